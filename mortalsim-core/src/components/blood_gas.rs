@@ -0,0 +1,200 @@
+use crate::event::ArterialBloodGas;
+use crate::sim::component::{ComponentRegistry, SimComponent};
+use crate::sim::layer::circulation::{CirculationComponent, CirculationConnector, CirculationInitializer};
+use crate::sim::layer::core::{CoreComponent, CoreConnector, CoreInitializer};
+use crate::sim::Organism;
+use crate::substance::{Substance, SubstanceConcentration};
+use crate::units::mechanical::Pressure;
+use crate::SimTimeSpan;
+
+/// Solubility of O2 in plasma (mM/mmHg)
+pub const O2_SOLUBILITY: f64 = 0.00134;
+
+/// Solubility of CO2 in plasma (mM/mmHg)
+pub const CO2_SOLUBILITY: f64 = 0.0307;
+
+/// pKa of the bicarbonate buffer system in plasma
+pub const BICARBONATE_PK: f64 = 6.1;
+
+/// Default plasma bicarbonate concentration (mM)
+const DEFAULT_BICARBONATE: f64 = 24.0;
+
+/// Change in O2 or CO2 concentration which triggers a new panel (mM)
+const CHANGE_THRESHOLD: f64 = 0.001;
+
+/// Reports an `ArterialBloodGas` panel derived from the dissolved O2 and
+/// CO2 concentrations of an arterial vessel. Partial pressures follow
+/// Henry's law, and pH follows the Henderson-Hasselbalch relation for
+/// the bicarbonate buffer at a fixed bicarbonate concentration.
+pub struct BloodGasComponent<O: Organism> {
+    vessel: O::VesselType,
+    bicarbonate: SubstanceConcentration,
+    core_connector: CoreConnector<O>,
+    circ_connector: CirculationConnector<O>,
+}
+
+impl<O: Organism> BloodGasComponent<O> {
+    /// Creates a new BloodGasComponent
+    ///
+    /// ### Arguments
+    /// * `vessel` - Arterial vessel to sample
+    pub fn new(vessel: O::VesselType) -> Self {
+        Self {
+            vessel,
+            bicarbonate: SubstanceConcentration::from_mM(DEFAULT_BICARBONATE),
+            core_connector: CoreConnector::new(),
+            circ_connector: CirculationConnector::new(),
+        }
+    }
+
+    /// Sets the plasma bicarbonate concentration used for pH
+    ///
+    /// ### Arguments
+    /// * `bicarbonate` - Bicarbonate concentration, typically 22-26 mM
+    pub fn with_bicarbonate(mut self, bicarbonate: SubstanceConcentration) -> Self {
+        self.bicarbonate = bicarbonate;
+        self
+    }
+
+    /// Derives a blood gas panel from dissolved gas concentrations
+    ///
+    /// ### Arguments
+    /// * `o2`  - Dissolved O2 concentration
+    /// * `co2` - Dissolved CO2 concentration
+    ///
+    /// Returns the blood gas panel
+    pub fn blood_gas(&self, o2: SubstanceConcentration, co2: SubstanceConcentration) -> ArterialBloodGas {
+        ArterialBloodGas {
+            pa_o2: Pressure::from_mmHg(o2.to_mM() / O2_SOLUBILITY),
+            pa_co2: Pressure::from_mmHg(co2.to_mM() / CO2_SOLUBILITY),
+            ph: BICARBONATE_PK + (self.bicarbonate.to_mM() / co2.to_mM()).log10(),
+        }
+    }
+}
+
+impl<O: Organism> CoreComponent<O> for BloodGasComponent<O> {
+    fn core_init(&mut self, initializer: &mut CoreInitializer<O>) {
+        initializer.produces::<ArterialBloodGas>();
+    }
+
+    fn core_connector(&mut self) -> &mut CoreConnector<O> {
+        &mut self.core_connector
+    }
+}
+
+impl<O: Organism> CirculationComponent<O> for BloodGasComponent<O> {
+    fn circulation_init(&mut self, initializer: &mut CirculationInitializer<O>) {
+        let threshold = SubstanceConcentration::from_mM(CHANGE_THRESHOLD);
+        initializer.notify_composition_change(self.vessel, Substance::O2, threshold);
+        initializer.notify_composition_change(self.vessel, Substance::CO2, threshold);
+    }
+
+    fn circulation_connector(&mut self) -> &mut CirculationConnector<O> {
+        &mut self.circ_connector
+    }
+}
+
+impl<O: Organism> SimComponent<O> for BloodGasComponent<O> {
+    fn id(&self) -> &'static str {
+        "BloodGasComponent"
+    }
+
+    fn attach(self, registry: &mut ComponentRegistry<O>) {
+        registry.add_core_circulation_component(self)
+    }
+
+    fn run(&mut self) {
+        let (o2, co2) = match self.circ_connector.blood_store(&self.vessel) {
+            Some(store) => (store.concentration_of(&Substance::O2), store.concentration_of(&Substance::CO2)),
+            None => return,
+        };
+
+        // pH is undefined without any CO2
+        let abg = self.blood_gas(o2, co2);
+        if abg.ph.is_finite() {
+            self.core_connector.schedule_event(SimTimeSpan::from_s(0.0), abg);
+        }
+    }
+}
+
+mod tests {
+    use crate::event::ArterialBloodGas;
+    use crate::sim::component::{ComponentRegistry, SimComponent};
+    use crate::sim::layer::circulation::{CirculationComponent, CirculationConnector, CirculationInitializer};
+    use crate::sim::organism::test::{TestBloodVessel, TestOrganism, TestSim};
+    use crate::sim::Sim;
+    use crate::substance::{Substance, SubstanceConcentration};
+    use crate::SimTimeSpan;
+
+    use super::BloodGasComponent;
+
+    /// Raises the Aorta's CO2 concentration by 0.3 mM each time it runs,
+    /// after filling it with 8 mM of O2
+    struct CO2StepComponent {
+        circ_connector: CirculationConnector<TestOrganism>,
+    }
+
+    impl CirculationComponent<TestOrganism> for CO2StepComponent {
+        fn circulation_init(&mut self, initializer: &mut CirculationInitializer<TestOrganism>) {
+            initializer.attach_vessel(TestBloodVessel::Aorta);
+        }
+
+        fn circulation_connector(&mut self) -> &mut CirculationConnector<TestOrganism> {
+            &mut self.circ_connector
+        }
+    }
+
+    impl SimComponent<TestOrganism> for CO2StepComponent {
+        fn id(&self) -> &'static str {
+            "CO2StepComponent"
+        }
+
+        fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+            registry.add_circulation_component(self)
+        }
+
+        fn run(&mut self) {
+            let mut store = self.circ_connector.blood_store(&TestBloodVessel::Aorta).unwrap();
+            if store.concentration_of(&Substance::O2).to_mM() == 0.0 {
+                store.schedule_change(Substance::O2, SubstanceConcentration::from_mM(8.0), SimTimeSpan::from_s(0.1));
+            }
+            store.schedule_change(Substance::CO2, SubstanceConcentration::from_mM(0.3), SimTimeSpan::from_s(0.1));
+        }
+    }
+
+    #[test]
+    fn ph_falls_with_co2() {
+        let mut sim = TestSim::new();
+        sim.add_component(CO2StepComponent {
+            circ_connector: CirculationConnector::new(),
+        }).unwrap();
+        sim.add_component(BloodGasComponent::new(TestBloodVessel::Aorta)).unwrap();
+
+        let mut panels = Vec::new();
+        for _ in 0..8 {
+            let mut step = Vec::new();
+            for _ in 0..2 {
+                sim.advance_by(SimTimeSpan::from_s(1.0));
+                step.extend(sim.drain_active().filter_map(|e| e.downcast_ref::<ArterialBloodGas>().copied()));
+            }
+            panels.extend(step);
+            sim.force_layer_run(None);
+        }
+
+        // Forced runs re-emit the current panel before the next step applies
+        panels.dedup();
+
+        assert!(panels.len() >= 6, "{:?}", panels);
+        assert!(panels.iter().all(|abg| (abg.pa_o2.to_mmHg() - 8.0 / super::O2_SOLUBILITY).abs() < 1.0));
+        for pair in panels.windows(2) {
+            assert!(pair[1].pa_co2.to_mmHg() > pair[0].pa_co2.to_mmHg(), "{:?}", pair);
+            assert!(pair[1].ph < pair[0].ph, "{:?}", pair);
+        }
+
+        // 1.2 mM of dissolved CO2 is ~39 mmHg, a normal pH of 7.4
+        let component = BloodGasComponent::<TestOrganism>::new(TestBloodVessel::Aorta);
+        let normal = component.blood_gas(SubstanceConcentration::from_mM(12.6), SubstanceConcentration::from_mM(1.2));
+        assert!((normal.ph - 7.4).abs() < 0.01, "{}", normal.ph);
+        assert!((normal.pa_co2.to_mmHg() - 39.1).abs() < 0.1, "{}", normal.pa_co2.to_mmHg());
+    }
+}
@@ -0,0 +1,107 @@
+use std::any::type_name;
+use std::marker::PhantomData;
+
+use crate::event::Event;
+use crate::sim::component::{ComponentRegistry, SimComponent};
+use crate::sim::layer::core::{CoreComponent, CoreConnector, CoreInitializer};
+use crate::sim::Organism;
+use crate::SimTimeSpan;
+
+/// Copy of an `Event` re-emitted by a `DelayLineComponent`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Delayed<E>(pub E);
+
+impl<E: Event> Event for Delayed<E> {
+    fn transient(&self) -> bool {
+        self.0.transient()
+    }
+}
+
+/// Pure transport delay, which re-emits each `Event` of type `E` as a
+/// `Delayed<E>` a fixed time later, e.g. to model the lag between a
+/// sensor and its effector. Each instance's id is derived from `E`.
+pub struct DelayLineComponent<O: Organism, E: Event + Clone> {
+    delay: SimTimeSpan,
+    connector: CoreConnector<O>,
+    _event: PhantomData<E>,
+}
+
+impl<O: Organism, E: Event + Clone> DelayLineComponent<O, E> {
+    /// Creates a new DelayLineComponent
+    ///
+    /// ### Arguments
+    /// * `delay` - Time between each input `Event` and its output
+    pub fn new(delay: SimTimeSpan) -> Self {
+        let mut connector = CoreConnector::new();
+
+        // Outputs in the line are kept when new inputs arrive
+        connector.unschedule_all(false);
+
+        Self {
+            delay,
+            connector,
+            _event: PhantomData,
+        }
+    }
+}
+
+impl<O: Organism, E: Event + Clone> CoreComponent<O> for DelayLineComponent<O, E> {
+    fn core_init(&mut self, initializer: &mut CoreInitializer<O>) {
+        initializer.notify::<E>();
+        initializer.produces::<Delayed<E>>();
+    }
+
+    fn core_connector(&mut self) -> &mut CoreConnector<O> {
+        &mut self.connector
+    }
+}
+
+impl<O: Organism, E: Event + Clone> SimComponent<O> for DelayLineComponent<O, E> {
+    fn id(&self) -> &'static str {
+        type_name::<Self>()
+    }
+
+    fn attach(self, registry: &mut ComponentRegistry<O>) {
+        registry.add_core_component(self)
+    }
+
+    fn run(&mut self) {
+        let inputs: Vec<E> = self.connector.get_active::<E>().cloned().collect();
+        for input in inputs {
+            self.connector.schedule_event(self.delay, Delayed(input));
+        }
+    }
+}
+
+mod tests {
+    use crate::event::HeartRate;
+    use crate::sim::organism::test::{TestOrganism, TestSim};
+    use crate::sim::{Sim, SimTime};
+    use crate::units::mechanical::Frequency;
+    use crate::SimTimeSpan;
+
+    use super::{Delayed, DelayLineComponent};
+
+    #[test]
+    fn transport_delay() {
+        let mut sim = TestSim::new();
+        sim.add_component(DelayLineComponent::<TestOrganism, HeartRate>::new(SimTimeSpan::from_s(2.5))).unwrap();
+
+        sim.schedule_event(SimTimeSpan::from_s(1.0), Box::new(HeartRate(Frequency::from_Hz(1.0))));
+        sim.schedule_event(SimTimeSpan::from_s(2.0), Box::new(HeartRate(Frequency::from_Hz(1.5))));
+
+        // Each input appears at the output exactly 2.5 s later, unchanged
+        let mut outputs = Vec::new();
+        for _ in 0..10 {
+            sim.advance();
+            let time = sim.time();
+            outputs.extend(
+                sim.drain_active().filter_map(|evt| evt.downcast_ref::<Delayed<HeartRate>>().map(|d| (time, *d)))
+            );
+        }
+        assert_eq!(outputs, vec![
+            (SimTime::from_s(3.5), Delayed(HeartRate(Frequency::from_Hz(1.0)))),
+            (SimTime::from_s(4.5), Delayed(HeartRate(Frequency::from_Hz(1.5)))),
+        ]);
+    }
+}
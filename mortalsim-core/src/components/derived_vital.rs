@@ -0,0 +1,132 @@
+use crate::event::{DerivedVital, Event};
+use crate::sim::component::{ComponentRegistry, SimComponent};
+use crate::sim::layer::core::{CoreComponent, CoreConnector, CoreInitializer};
+use crate::sim::{Organism, SimState};
+use crate::SimTimeSpan;
+
+type DeriveFn = Box<dyn Fn(&SimState) -> f64 + Send>;
+
+/// Publishes a metric derived from the current state, such as a clinical
+/// composite score, as a `DerivedVital` event. The metric is recalculated
+/// and emitted only when one of its inputs changes, rather than on every
+/// advance, since it can't change otherwise. `DerivedVital` events are
+/// transient, so consumers needing the value later should keep the latest.
+pub struct DerivedVitalComponent<O: Organism> {
+    name: &'static str,
+    derive: DeriveFn,
+    /// Registers notifications for each input `Event` type
+    inputs: Vec<fn(&mut CoreInitializer<O>)>,
+    connector: CoreConnector<O>,
+}
+
+impl<O: Organism> DerivedVitalComponent<O> {
+    /// Creates a new DerivedVitalComponent. Non-finite values, e.g. from
+    /// missing inputs, are not emitted.
+    ///
+    /// ### Arguments
+    /// * `name`   - Name of the derived vital, also used as the component id
+    /// * `derive` - Function calculating the value from the current state
+    pub fn new(name: &'static str, derive: impl Fn(&SimState) -> f64 + Send + 'static) -> Self {
+        Self {
+            name,
+            derive: Box::new(derive),
+            inputs: Vec::new(),
+            connector: CoreConnector::new(),
+        }
+    }
+
+    /// Recalculates the derived vital whenever an `Event` of type `E` is emitted
+    pub fn with_input<E: Event>(mut self) -> Self {
+        self.inputs.push(|initializer| initializer.notify::<E>());
+        self
+    }
+}
+
+impl<O: Organism> CoreComponent<O> for DerivedVitalComponent<O> {
+    fn core_init(&mut self, initializer: &mut CoreInitializer<O>) {
+        for input in self.inputs.iter() {
+            input(initializer);
+        }
+    }
+
+    fn core_connector(&mut self) -> &mut CoreConnector<O> {
+        &mut self.connector
+    }
+}
+
+impl<O: Organism> SimComponent<O> for DerivedVitalComponent<O> {
+    fn id(&self) -> &'static str {
+        self.name
+    }
+
+    fn attach(self, registry: &mut ComponentRegistry<O>) {
+        registry.add_core_component(self)
+    }
+
+    fn run(&mut self) {
+        let value = (self.derive)(&self.connector.current_state());
+        if value.is_finite() {
+            self.connector.schedule_event(SimTimeSpan::from_s(0.0), DerivedVital { name: self.name, value });
+        }
+    }
+}
+
+mod tests {
+    use crate::event::{AorticBloodPressure, DerivedVital, HeartRate};
+    use crate::sim::organism::test::TestSim;
+    use crate::sim::{Sim, SimState};
+    use crate::units::mechanical::{Frequency, Pressure};
+    use crate::SimTimeSpan;
+
+    use super::DerivedVitalComponent;
+
+    /// Heart rate (bpm) over systolic blood pressure (mmHg)
+    fn shock_index(state: &SimState) -> f64 {
+        match (state.get_state::<HeartRate>(), state.get_state::<AorticBloodPressure>()) {
+            (Some(hr), Some(bp)) => hr.0.Hz * 60.0 / bp.systolic.to_mmHg(),
+            _ => f64::NAN,
+        }
+    }
+
+    #[test]
+    fn shock_index_vital() {
+        let mut sim = TestSim::new();
+        sim.add_component(
+            DerivedVitalComponent::new("ShockIndex", shock_index)
+                .with_input::<HeartRate>()
+                .with_input::<AorticBloodPressure>(),
+        ).unwrap();
+
+        // Derived vitals are emitted on the update after their inputs change
+        let latest = |sim: &mut TestSim| {
+            let mut vitals = Vec::new();
+            for _ in 0..2 {
+                sim.advance_by(SimTimeSpan::from_s(1.0));
+                vitals.extend(sim.drain_active().filter_map(|e| e.downcast_ref::<DerivedVital>().copied()));
+            }
+            vitals.pop()
+        };
+
+        // Nothing is emitted until both inputs are available
+        sim.schedule_event(SimTimeSpan::from_s(0.0), Box::new(HeartRate(Frequency::from_Hz(1.5))));
+        assert!(latest(&mut sim).is_none());
+
+        sim.schedule_event(SimTimeSpan::from_s(0.0), Box::new(AorticBloodPressure {
+            systolic: Pressure::from_mmHg(120.0),
+            diastolic: Pressure::from_mmHg(80.0),
+        }));
+        let vital = latest(&mut sim).unwrap();
+        assert_eq!(vital.name, "ShockIndex");
+        assert!((vital.value - 0.75).abs() < 1e-9, "{}", vital.value);
+
+        // Tachycardia raises the index
+        sim.schedule_event(SimTimeSpan::from_s(0.0), Box::new(HeartRate(Frequency::from_Hz(2.0))));
+        assert!((latest(&mut sim).unwrap().value - 1.0).abs() < 1e-9);
+
+        // Nothing is emitted while the inputs stay the same
+        for _ in 0..5 {
+            assert!(latest(&mut sim).is_none());
+        }
+        assert!(sim.state().get_state::<DerivedVital>().is_none());
+    }
+}
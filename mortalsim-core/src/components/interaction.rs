@@ -0,0 +1,296 @@
+use std::collections::HashMap;
+
+use crate::event::Event;
+use crate::sim::component::{ComponentRegistry, SimComponent};
+use crate::sim::layer::circulation::{CirculationComponent, CirculationConnector, CirculationInitializer};
+use crate::sim::layer::core::{CoreComponent, CoreConnector, CoreInitializer};
+use crate::sim::Organism;
+use crate::substance::{Substance, SubstanceConcentration};
+use crate::SimTimeSpan;
+
+use super::periodic::PeriodicUpdate;
+
+/// Factors which interacting substances currently apply to the clearance
+/// or effect of other substances, emitted by an `InteractionComponent`
+/// whenever they change. Components modelling the clearance or effect of
+/// a Substance scale their rates by its factor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InteractionFactors(pub HashMap<Substance, f64>);
+
+impl InteractionFactors {
+    /// Factor currently applied to the given Substance
+    ///
+    /// ### Arguments
+    /// * `substance` - Substance affected by interactions
+    ///
+    /// Returns the combined factor, which is 1 if no interactions apply
+    pub fn factor(&self, substance: &Substance) -> f64 {
+        self.0.get(substance).copied().unwrap_or(1.0)
+    }
+}
+
+impl Event for InteractionFactors {
+    fn transient(&self) -> bool {
+        false
+    }
+}
+
+/// Rule by which one Substance modulates another
+struct InteractionRule {
+    modulator: Substance,
+    threshold: SubstanceConcentration,
+    target: Substance,
+    factor: f64,
+}
+
+/// Models interactions between substances, such as a drug inhibiting the
+/// enzyme which metabolizes another. Each rule scales the clearance or
+/// effect of a target Substance while a modulating Substance is at or
+/// above a threshold concentration in the sampled vessel. Multiple active
+/// rules on the same target combine multiplicatively. The resulting
+/// factors are emitted as `InteractionFactors` for other components to
+/// apply.
+pub struct InteractionComponent<O: Organism> {
+    vessel: O::VesselType,
+    rules: Vec<InteractionRule>,
+    updates: PeriodicUpdate,
+    /// Factors most recently emitted
+    factors: Option<InteractionFactors>,
+    core_connector: CoreConnector<O>,
+    circ_connector: CirculationConnector<O>,
+}
+
+impl<O: Organism> InteractionComponent<O> {
+    /// Creates a new InteractionComponent without any rules, which
+    /// samples the given vessel every 10 seconds
+    ///
+    /// ### Arguments
+    /// * `vessel` - Vessel to sample modulating substances from
+    pub fn new(vessel: O::VesselType) -> Self {
+        Self {
+            vessel,
+            rules: Vec::new(),
+            updates: PeriodicUpdate::new(SimTimeSpan::from_s(10.0)),
+            factors: None,
+            core_connector: CoreConnector::new(),
+            circ_connector: CirculationConnector::new(),
+        }
+    }
+
+    /// Adds a rule scaling the clearance or effect of `target` by `factor`
+    /// while `modulator` is at or above `threshold`
+    ///
+    /// Panics if `factor` is negative
+    ///
+    /// ### Arguments
+    /// * `modulator` - Substance causing the interaction
+    /// * `threshold` - Concentration of `modulator` at which the interaction applies
+    /// * `target`    - Substance affected by the interaction
+    /// * `factor`    - Scale applied to `target`, where values below 1 inhibit
+    pub fn with_rule(
+        mut self,
+        modulator: Substance,
+        threshold: SubstanceConcentration,
+        target: Substance,
+        factor: f64,
+    ) -> Self {
+        assert!(factor >= 0.0, "Interaction factor must not be negative");
+        self.rules.push(InteractionRule {
+            modulator,
+            threshold,
+            target,
+            factor,
+        });
+        self
+    }
+
+    /// Sets the time between samples of the modulating substances
+    ///
+    /// Panics if `update_period` isn't positive
+    ///
+    /// ### Arguments
+    /// * `update_period` - Time between samples
+    pub fn with_update_period(mut self, update_period: SimTimeSpan) -> Self {
+        self.updates = PeriodicUpdate::new(update_period);
+        self
+    }
+
+    /// Combines the factors of all rules which currently apply
+    fn current_factors(&self) -> InteractionFactors {
+        let mut factors = HashMap::new();
+        if let Some(store) = self.circ_connector.blood_store(&self.vessel) {
+            for rule in self.rules.iter() {
+                if store.concentration_of(&rule.modulator) >= rule.threshold {
+                    *factors.entry(rule.target).or_insert(1.0) *= rule.factor;
+                }
+            }
+        }
+        InteractionFactors(factors)
+    }
+}
+
+impl<O: Organism> CoreComponent<O> for InteractionComponent<O> {
+    fn core_init(&mut self, initializer: &mut CoreInitializer<O>) {
+        initializer.produces::<InteractionFactors>();
+    }
+
+    fn core_connector(&mut self) -> &mut CoreConnector<O> {
+        &mut self.core_connector
+    }
+}
+
+impl<O: Organism> CirculationComponent<O> for InteractionComponent<O> {
+    fn circulation_init(&mut self, initializer: &mut CirculationInitializer<O>) {
+        initializer.attach_vessel(self.vessel);
+    }
+
+    fn circulation_connector(&mut self) -> &mut CirculationConnector<O> {
+        &mut self.circ_connector
+    }
+}
+
+impl<O: Organism> SimComponent<O> for InteractionComponent<O> {
+    fn id(&self) -> &'static str {
+        "InteractionComponent"
+    }
+
+    fn attach(self, registry: &mut ComponentRegistry<O>) {
+        registry.add_core_circulation_component(self)
+    }
+
+    fn run(&mut self) {
+        if self.updates.due(&mut self.core_connector).is_none() {
+            return;
+        }
+
+        let factors = self.current_factors();
+        if self.factors.as_ref() != Some(&factors) {
+            self.core_connector.schedule_event(SimTimeSpan::from_s(0.0), factors.clone());
+            self.factors = Some(factors);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.factors = None;
+    }
+}
+
+mod tests {
+    use crate::math::BoundFn;
+    use crate::sim::component::{ComponentRegistry, SimComponent};
+    use crate::sim::layer::circulation::{CirculationComponent, CirculationConnector, CirculationInitializer};
+    use crate::sim::layer::core::{CoreComponent, CoreConnector, CoreInitializer};
+    use crate::sim::organism::test::{TestBloodVessel, TestOrganism, TestSim};
+    use crate::sim::Sim;
+    use crate::substance::{Substance, SubstanceChange, SubstanceConcentration};
+    use crate::SimTimeSpan;
+
+    use super::{InteractionComponent, InteractionFactors};
+
+    /// Fills the Aorta with lactate, and optionally ammonia, then clears
+    /// lactate each second with first order decay, scaled by its
+    /// interaction factor
+    struct DecayComponent {
+        ammonia: bool,
+        core_connector: CoreConnector<TestOrganism>,
+        circ_connector: CirculationConnector<TestOrganism>,
+    }
+
+    impl DecayComponent {
+        /// Rate constant of lactate decay (1/s)
+        const RATE: f64 = 0.01;
+    }
+
+    impl CoreComponent<TestOrganism> for DecayComponent {
+        fn core_init(&mut self, _initializer: &mut CoreInitializer<TestOrganism>) {}
+
+        fn core_connector(&mut self) -> &mut CoreConnector<TestOrganism> {
+            &mut self.core_connector
+        }
+    }
+
+    impl CirculationComponent<TestOrganism> for DecayComponent {
+        fn circulation_init(&mut self, initializer: &mut CirculationInitializer<TestOrganism>) {
+            initializer.attach_vessel(TestBloodVessel::Aorta);
+        }
+
+        fn circulation_connector(&mut self) -> &mut CirculationConnector<TestOrganism> {
+            &mut self.circ_connector
+        }
+    }
+
+    impl SimComponent<TestOrganism> for DecayComponent {
+        fn id(&self) -> &'static str {
+            "DecayComponent"
+        }
+
+        fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+            registry.add_core_circulation_component(self)
+        }
+
+        fn run(&mut self) {
+            let first_run = self.core_connector.is_first_run();
+            if !first_run && !self.core_connector.expired_timers().any(|token| token == 0) {
+                return;
+            }
+            self.core_connector.set_timer(SimTimeSpan::from_s(1.0), 0);
+
+            let factor = self.core_connector.get::<InteractionFactors>().map_or(1.0, |f| f.factor(&Substance::LAC));
+            let sim_time = self.core_connector.sim_time();
+            let mut store = self.circ_connector.blood_store(&TestBloodVessel::Aorta).unwrap();
+            if first_run {
+                store.schedule_change(Substance::LAC, SubstanceConcentration::from_mM(2.0), SimTimeSpan::from_s(0.1));
+                if self.ammonia {
+                    store.schedule_change(Substance::NH3, SubstanceConcentration::from_mM(0.1), SimTimeSpan::from_s(0.1));
+                }
+                return;
+            }
+
+            let cleared = store.concentration_of(&Substance::LAC) * (1.0 - (-Self::RATE * factor).exp());
+            store.schedule_custom_change(
+                Substance::LAC,
+                SubstanceChange::new(sim_time, -cleared, SimTimeSpan::from_s(1.0), BoundFn::Linear),
+            );
+        }
+    }
+
+    /// Lactate remaining in the Aorta after 2 minutes
+    fn remaining_lactate(ammonia: bool) -> f64 {
+        let mut sim = TestSim::new();
+        sim.add_component(
+            InteractionComponent::new(TestBloodVessel::Aorta)
+                .with_rule(Substance::NH3, SubstanceConcentration::from_uM(50.0), Substance::LAC, 0.25)
+                .with_update_period(SimTimeSpan::from_s(1.0)),
+        ).unwrap();
+        sim.add_component(DecayComponent {
+            ammonia,
+            core_connector: CoreConnector::new(),
+            circ_connector: CirculationConnector::new(),
+        }).unwrap();
+
+        for _ in 0..120 {
+            sim.advance_by(SimTimeSpan::from_s(1.0));
+        }
+
+        let mut remaining = f64::NAN;
+        sim.inspect_circulation("DecayComponent", |connector| {
+            remaining = connector
+                .blood_store(&TestBloodVessel::Aorta)
+                .unwrap()
+                .concentration_of(&Substance::LAC)
+                .to_mM();
+        }).unwrap();
+        remaining
+    }
+
+    #[test]
+    fn inhibitor_slows_decay() {
+        let uninhibited = remaining_lactate(false);
+        let inhibited = remaining_lactate(true);
+
+        // ~30% remains after 2 minutes at the full rate, but ~74% when
+        // the rate is cut to a quarter
+        assert!((uninhibited / 2.0 - 0.30).abs() < 0.05, "{}", uninhibited);
+        assert!((inhibited / 2.0 - 0.74).abs() < 0.05, "{}", inhibited);
+    }
+}
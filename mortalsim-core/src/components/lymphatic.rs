@@ -0,0 +1,321 @@
+use std::collections::HashMap;
+
+use crate::math::BoundFn;
+use crate::sim::component::{ComponentRegistry, SimComponent};
+use crate::sim::layer::circulation::{BloodVessel, CirculationComponent, CirculationConnector, CirculationInitializer};
+use crate::sim::layer::core::{CoreComponent, CoreConnector, CoreInitializer};
+use crate::sim::{Organism, SimTime};
+use crate::substance::{Substance, SubstanceChange, SubstanceConcentration};
+use crate::units::base::Amount;
+use crate::SimTimeSpan;
+
+use super::periodic::PeriodicUpdate;
+
+/// Default time constant for extravasation from capillaries into tissue (s)
+const DEFAULT_EXTRAVASATION_TIME: f64 = 600.0;
+
+/// Default time constant for lymphatic return from tissue (s)
+const DEFAULT_RETURN_TIME: f64 = 3600.0;
+
+/// Tissue compartment fed by a capillary vessel and drained by lymph
+struct LymphaticCompartment<O: Organism> {
+    capillary: O::VesselType,
+    drain: O::VesselType,
+    extravasation_time: SimTimeSpan,
+    return_time: SimTimeSpan,
+    /// Substances accumulated in the tissue, as concentrations in the capillary blood
+    tissue: HashMap<Substance, SubstanceConcentration>,
+}
+
+/// Slow lymphatic path in parallel with the circulation. For each tissue
+/// compartment, substances extravasate from a capillary vessel into the
+/// tissue, where they accumulate before returning to a designated venous
+/// vessel. Both transfers are first order, with the lymphatic return
+/// typically much slower than blood flow. Tissue contents are tracked as
+/// the concentrations they would have in the capillary blood. Where both
+/// vessels have known volumes, returned substances are diluted into the
+/// volume of the drain, and the amounts held in the tissue are reported
+/// to the circulation, so that the total amount of each Substance is
+/// conserved.
+pub struct LymphaticComponent<O: Organism> {
+    substances: Vec<Substance>,
+    compartments: Vec<LymphaticCompartment<O>>,
+    updates: PeriodicUpdate,
+    core_connector: CoreConnector<O>,
+    circ_connector: CirculationConnector<O>,
+}
+
+impl<O: Organism> LymphaticComponent<O> {
+    /// Creates a new LymphaticComponent without any compartments, which
+    /// updates every 10 seconds
+    ///
+    /// ### Arguments
+    /// * `substances` - Substances carried along the lymphatic path
+    pub fn new(substances: Vec<Substance>) -> Self {
+        let mut circ_connector = CirculationConnector::new();
+
+        // Transfers accumulate rather than being replaced each run
+        circ_connector.unschedule_all(false);
+
+        Self {
+            substances,
+            compartments: Vec::new(),
+            updates: PeriodicUpdate::new(SimTimeSpan::from_s(10.0)),
+            core_connector: CoreConnector::new(),
+            circ_connector,
+        }
+    }
+
+    /// Adds a tissue compartment with default time constants of 10 minutes
+    /// for extravasation and 1 hour for lymphatic return
+    ///
+    /// ### Arguments
+    /// * `capillary` - Vessel substances extravasate from into the tissue
+    /// * `drain`     - Venous vessel the lymph returns substances to
+    pub fn with_compartment(self, capillary: O::VesselType, drain: O::VesselType) -> Self {
+        self.with_compartment_times(
+            capillary,
+            drain,
+            SimTimeSpan::from_s(DEFAULT_EXTRAVASATION_TIME),
+            SimTimeSpan::from_s(DEFAULT_RETURN_TIME),
+        )
+    }
+
+    /// Adds a tissue compartment with the given time constants
+    ///
+    /// Panics if either time constant isn't positive
+    ///
+    /// ### Arguments
+    /// * `capillary`          - Vessel substances extravasate from into the tissue
+    /// * `drain`              - Venous vessel the lymph returns substances to
+    /// * `extravasation_time` - Time constant of extravasation into the tissue
+    /// * `return_time`        - Time constant of lymphatic return from the tissue
+    pub fn with_compartment_times(
+        mut self,
+        capillary: O::VesselType,
+        drain: O::VesselType,
+        extravasation_time: SimTimeSpan,
+        return_time: SimTimeSpan,
+    ) -> Self {
+        assert!(extravasation_time.to_s() > 0.0, "Extravasation time must be greater than 0");
+        assert!(return_time.to_s() > 0.0, "Return time must be greater than 0");
+        self.compartments.push(LymphaticCompartment {
+            capillary,
+            drain,
+            extravasation_time,
+            return_time,
+            tissue: HashMap::new(),
+        });
+        self
+    }
+
+    /// Sets the time between updates of the lymphatic transfers
+    ///
+    /// Panics if `update_period` isn't positive
+    ///
+    /// ### Arguments
+    /// * `update_period` - Time between updates
+    pub fn with_update_period(mut self, update_period: SimTimeSpan) -> Self {
+        self.updates = PeriodicUpdate::new(update_period);
+        self
+    }
+
+    /// Amount of a Substance currently held in the tissue of a compartment
+    ///
+    /// ### Arguments
+    /// * `capillary` - Capillary vessel of the compartment
+    /// * `substance` - Substance to retrieve
+    ///
+    /// Returns the blood-equivalent concentration, summed over all
+    /// compartments fed by the vessel
+    pub fn tissue_concentration(&self, capillary: O::VesselType, substance: &Substance) -> SubstanceConcentration {
+        self.compartments
+            .iter()
+            .filter(|compartment| compartment.capillary == capillary)
+            .filter_map(|compartment| compartment.tissue.get(substance).copied())
+            .fold(SubstanceConcentration::from_M(0.0), |total, concentration| total + concentration)
+    }
+
+    /// Schedules extravasation and lymphatic return for each compartment
+    /// over the next update period
+    ///
+    /// ### Arguments
+    /// * `sim_time` - Time of the update
+    /// * `dt`       - Time since the previous update
+    fn transfer(&mut self, sim_time: SimTime, dt: SimTimeSpan) {
+        let update_period = self.updates.period();
+
+        // Transfers apply to the blood over the coming update period, so
+        // the tissue currently holds what it did before them
+        for substance in self.substances.iter() {
+            let held = self
+                .compartments
+                .iter()
+                .filter_map(|compartment| {
+                    let concentration = compartment.tissue.get(substance)?;
+                    Some(*concentration * compartment.capillary.volume()?)
+                })
+                .fold(Amount::from_mol(0.0), |total, amount| total + amount);
+            self.circ_connector.set_held_amount(*substance, held);
+        }
+
+        for compartment in self.compartments.iter_mut() {
+            let leak_fraction = 1.0 - (-dt.to_s() / compartment.extravasation_time.to_s()).exp();
+            let return_fraction = 1.0 - (-dt.to_s() / compartment.return_time.to_s()).exp();
+
+            // Dilution of returned substances into the drain's volume
+            let dilution = match (compartment.capillary.volume(), compartment.drain.volume()) {
+                (Some(capillary), Some(drain)) => capillary.to_L() / drain.to_L(),
+                _ => 1.0,
+            };
+
+            for substance in self.substances.iter() {
+                let held = compartment.tissue.get(substance).copied().unwrap_or(SubstanceConcentration::from_M(0.0));
+                let returned = held * return_fraction;
+
+                let mut leaked = SubstanceConcentration::from_M(0.0);
+                if let Some(mut store) = self.circ_connector.blood_store(&compartment.capillary) {
+                    leaked = store.concentration_of(substance) * leak_fraction;
+                    if leaked.to_M() > 0.0 {
+                        store.schedule_custom_change(
+                            *substance,
+                            SubstanceChange::new(sim_time, -leaked, update_period, BoundFn::Linear),
+                        );
+                    } else {
+                        leaked = SubstanceConcentration::from_M(0.0);
+                    }
+                }
+
+                if returned.to_M() > 0.0 {
+                    if let Some(mut store) = self.circ_connector.blood_store(&compartment.drain) {
+                        store.schedule_custom_change(
+                            *substance,
+                            SubstanceChange::new(sim_time, returned * dilution, update_period, BoundFn::Linear),
+                        );
+                    }
+                }
+
+                compartment.tissue.insert(*substance, held - returned + leaked);
+            }
+        }
+
+    }
+}
+
+impl<O: Organism> CoreComponent<O> for LymphaticComponent<O> {
+    fn core_init(&mut self, _initializer: &mut CoreInitializer<O>) {}
+
+    fn core_connector(&mut self) -> &mut CoreConnector<O> {
+        &mut self.core_connector
+    }
+}
+
+impl<O: Organism> CirculationComponent<O> for LymphaticComponent<O> {
+    fn circulation_init(&mut self, initializer: &mut CirculationInitializer<O>) {
+        for compartment in self.compartments.iter() {
+            initializer.attach_vessel(compartment.capillary);
+            initializer.attach_vessel(compartment.drain);
+        }
+        for substance in self.substances.iter() {
+            initializer.manage_substance(*substance);
+        }
+    }
+
+    fn circulation_connector(&mut self) -> &mut CirculationConnector<O> {
+        &mut self.circ_connector
+    }
+}
+
+impl<O: Organism> SimComponent<O> for LymphaticComponent<O> {
+    fn id(&self) -> &'static str {
+        "LymphaticComponent"
+    }
+
+    fn attach(self, registry: &mut ComponentRegistry<O>) {
+        registry.add_core_circulation_component(self)
+    }
+
+    fn run(&mut self) {
+        // Transfers are scheduled over the update period, at rates from
+        // the time elapsed since the previous update
+        let Some((sim_time, dt)) = self.updates.due(&mut self.core_connector) else {
+            return;
+        };
+        self.transfer(sim_time, dt);
+    }
+
+    fn reset(&mut self) {
+        self.updates.reset();
+        for compartment in self.compartments.iter_mut() {
+            compartment.tissue.clear();
+        }
+        for substance in self.substances.iter() {
+            self.circ_connector.set_held_amount(*substance, Amount::from_mol(0.0));
+        }
+    }
+}
+
+mod tests {
+    use crate::sim::layer::circulation::component::test::TestBolusComponent;
+    use crate::sim::organism::test::{TestBloodVessel, TestOrganism, TestSim};
+    use crate::sim::Sim;
+    use crate::substance::{Substance, SubstanceConcentration};
+    use crate::SimTimeSpan;
+
+    use super::LymphaticComponent;
+
+    #[test]
+    fn returns_extravasated_substance() {
+        let mut sim = TestSim::new();
+        sim.add_component(
+            TestBolusComponent::new(
+                TestBloodVessel::LeftFemoralArtery,
+                Substance::GLC,
+                SubstanceConcentration::from_mM(1.0),
+                SimTimeSpan::from_s(1.0),
+            )
+            .inspecting(TestBloodVessel::LeftFemoralVein),
+        ).unwrap();
+        sim.add_component(
+            LymphaticComponent::new(vec![Substance::GLC]).with_compartment_times(
+                TestBloodVessel::LeftFemoralArtery,
+                TestBloodVessel::LeftFemoralVein,
+                SimTimeSpan::from_s(60.0),
+                SimTimeSpan::from_s(1200.0),
+            ),
+        ).unwrap();
+
+        let glc = |sim: &mut TestSim, vessel| {
+            let mut value = f64::NAN;
+            sim.inspect_circulation("TestBolusComponent", |connector| {
+                value = connector.blood_store(&vessel).unwrap().concentration_of(&Substance::GLC).to_mM();
+            }).unwrap();
+            value
+        };
+
+        // 1 mM in the 20 mL capillary, which is held between the blood
+        // and the tissue throughout
+        let total_umol = |sim: &TestSim| sim.total_substance(&Substance::GLC).to_umol();
+        for _ in 0..60 {
+            sim.advance_by(SimTimeSpan::from_s(10.0));
+            assert!((total_umol(&sim) - 20.0).abs() < 0.2, "Unexpected total {}", total_umol(&sim));
+        }
+
+        // Nearly all of the bolus has left the capillary, but only a
+        // small amount has returned through the lymph
+        let capillary = glc(&mut sim, TestBloodVessel::LeftFemoralArtery);
+        let early = glc(&mut sim, TestBloodVessel::LeftFemoralVein);
+        assert!(capillary < 0.01, "{}", capillary);
+        assert!(early > 0.025 && early < 0.25, "{}", early);
+
+        for _ in 0..540 {
+            sim.advance_by(SimTimeSpan::from_s(10.0));
+        }
+
+        // which continues to slowly reappear in venous blood, diluted
+        // into the 40 mL vein
+        let late = glc(&mut sim, TestBloodVessel::LeftFemoralVein);
+        assert!(late > 0.45 && late <= 0.5, "{}", late);
+        assert!((total_umol(&sim) - 20.0).abs() < 0.2, "Unexpected total {}", total_umol(&sim));
+    }
+}
@@ -0,0 +1,253 @@
+use crate::event::{Event, MetabolicRate};
+use crate::math::BoundFn;
+use crate::sim::component::{ComponentRegistry, SimComponent};
+use crate::sim::layer::circulation::{CirculationComponent, CirculationConnector, CirculationInitializer};
+use crate::sim::layer::core::{CoreComponent, CoreConnector, CoreInitializer};
+use crate::sim::{Organism, SimTime};
+use crate::substance::{Substance, SubstanceChange, SubstanceConcentration};
+use crate::units::geometry::Volume;
+use crate::SimTimeSpan;
+
+/// Energy released per mole of O2 consumed by aerobic metabolism (J/mol),
+/// from the ~20.1 kJ per liter of O2 at standard conditions
+pub const O2_ENERGY_EQUIVALENT: f64 = 450.0e3;
+
+/// Default ratio of CO2 produced to O2 consumed, for a mixed diet
+pub const DEFAULT_RESPIRATORY_QUOTIENT: f64 = 0.8;
+
+/// Internal event used to schedule periodic consumption
+#[derive(Debug, Clone, Copy)]
+struct MetabolismTick;
+
+impl Event for MetabolismTick {}
+
+/// Coarse whole-body metabolism which consumes O2 and produces CO2 in
+/// proportion to the current `MetabolicRate`, spread evenly across the
+/// attached tissue or capillary vessels. Gas exchange components are
+/// expected to resupply O2 and remove CO2.
+pub struct MetabolismComponent<O: Organism> {
+    vessels: Vec<O::VesselType>,
+    /// Volume of blood over which the exchange is distributed
+    distribution_volume: Volume<f64>,
+    respiratory_quotient: f64,
+    update_period: SimTimeSpan,
+    /// Metabolic rate in effect since the last run (W)
+    rate: f64,
+    last_run: Option<SimTime>,
+    /// Time of the next scheduled update
+    next_update: Option<SimTime>,
+    core_connector: CoreConnector<O>,
+    circ_connector: CirculationConnector<O>,
+}
+
+impl<O: Organism> MetabolismComponent<O> {
+    /// Creates a new MetabolismComponent
+    ///
+    /// ### Arguments
+    /// * `vessels`             - Tissue or capillary vessels where exchange takes place
+    /// * `distribution_volume` - Blood volume over which the exchange is distributed
+    /// * `update_period`       - Time between consumption updates
+    pub fn new(vessels: Vec<O::VesselType>, distribution_volume: Volume<f64>, update_period: SimTimeSpan) -> Self {
+        let mut core_connector = CoreConnector::new();
+        let mut circ_connector = CirculationConnector::new();
+
+        // Update ticks and changes accumulate rather than being replaced each run
+        core_connector.unschedule_all(false);
+        circ_connector.unschedule_all(false);
+
+        Self {
+            vessels,
+            distribution_volume,
+            respiratory_quotient: DEFAULT_RESPIRATORY_QUOTIENT,
+            update_period,
+            rate: 0.0,
+            last_run: None,
+            next_update: None,
+            core_connector,
+            circ_connector,
+        }
+    }
+
+    /// Creates a new MetabolismComponent which distributes the exchange
+    /// over the organism's total blood volume
+    ///
+    /// ### Arguments
+    /// * `vessels`       - Tissue or capillary vessels where exchange takes place
+    /// * `update_period` - Time between consumption updates
+    pub fn whole_body(vessels: Vec<O::VesselType>, update_period: SimTimeSpan) -> Self {
+        Self::new(vessels, O::total_blood_volume(), update_period)
+    }
+
+    /// Sets the ratio of CO2 produced to O2 consumed
+    ///
+    /// ### Arguments
+    /// * `quotient` - Respiratory quotient, typically between 0.7 and 1.0
+    pub fn with_respiratory_quotient(mut self, quotient: f64) -> Self {
+        self.respiratory_quotient = quotient;
+        self
+    }
+}
+
+impl<O: Organism> CoreComponent<O> for MetabolismComponent<O> {
+    fn core_init(&mut self, initializer: &mut CoreInitializer<O>) {
+        initializer.notify::<MetabolicRate>();
+        initializer.notify::<MetabolismTick>();
+    }
+
+    fn core_connector(&mut self) -> &mut CoreConnector<O> {
+        &mut self.core_connector
+    }
+}
+
+impl<O: Organism> CirculationComponent<O> for MetabolismComponent<O> {
+    fn circulation_init(&mut self, initializer: &mut CirculationInitializer<O>) {
+        for vessel in self.vessels.iter() {
+            initializer.attach_vessel(*vessel);
+        }
+    }
+
+    fn circulation_connector(&mut self) -> &mut CirculationConnector<O> {
+        &mut self.circ_connector
+    }
+}
+
+impl<O: Organism> SimComponent<O> for MetabolismComponent<O> {
+    fn id(&self) -> &'static str {
+        "MetabolismComponent"
+    }
+
+    fn attach(self, registry: &mut ComponentRegistry<O>) {
+        registry.add_core_circulation_component(self)
+    }
+
+    fn run(&mut self) {
+        let sim_time = self.core_connector.sim_time();
+
+        // Account for consumption at the previous rate since the last run,
+        // spreading the change out over the next update period
+        let elapsed = self.last_run.map_or(0.0, |t| (sim_time - t).to_s());
+        if elapsed > 0.0 && self.rate > 0.0 {
+            let o2_mol = self.rate * elapsed / O2_ENERGY_EQUIVALENT;
+            let o2_change = SubstanceConcentration::from_mM(o2_mol / self.distribution_volume.m3);
+            let co2_change = o2_change * self.respiratory_quotient;
+
+            for vessel in self.vessels.iter() {
+                if let Some(mut store) = self.circ_connector.blood_store(vessel) {
+                    for (substance, amount) in [(Substance::O2, -o2_change), (Substance::CO2, co2_change)] {
+                        store.schedule_custom_change(
+                            substance,
+                            SubstanceChange::new(sim_time, amount, self.update_period, BoundFn::Linear),
+                        );
+                    }
+                }
+            }
+        }
+        self.last_run = Some(sim_time);
+
+        if let Some(rate) = self.core_connector.get::<MetabolicRate>() {
+            self.rate = rate.0.W;
+        }
+
+        if self.next_update.map_or(true, |t| sim_time >= t) {
+            self.core_connector.schedule_event(self.update_period, MetabolismTick);
+            self.next_update = Some(sim_time + self.update_period);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.rate = 0.0;
+        self.last_run = None;
+        self.next_update = None;
+    }
+}
+
+mod tests {
+    use crate::event::MetabolicRate;
+    use crate::sim::component::{ComponentRegistry, SimComponent};
+    use crate::sim::layer::circulation::{CirculationComponent, CirculationConnector, CirculationInitializer};
+    use crate::sim::organism::test::{TestBloodVessel, TestOrganism, TestSim};
+    use crate::sim::Sim;
+    use crate::substance::{Substance, SubstanceConcentration};
+    use crate::components::{WindowedStatComponent, WindowedStats};
+    use crate::units::mechanical::Power;
+    use crate::SimTimeSpan;
+
+    use super::MetabolismComponent;
+
+    /// Fills the Vena Cava with 8 mM of O2 on the first run
+    struct O2FillComponent {
+        circ_connector: CirculationConnector<TestOrganism>,
+        filled: bool,
+    }
+
+    impl CirculationComponent<TestOrganism> for O2FillComponent {
+        fn circulation_init(&mut self, initializer: &mut CirculationInitializer<TestOrganism>) {
+            initializer.attach_vessel(TestBloodVessel::VenaCava);
+        }
+
+        fn circulation_connector(&mut self) -> &mut CirculationConnector<TestOrganism> {
+            &mut self.circ_connector
+        }
+    }
+
+    impl SimComponent<TestOrganism> for O2FillComponent {
+        fn id(&self) -> &'static str {
+            "O2FillComponent"
+        }
+
+        fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+            registry.add_circulation_component(self)
+        }
+
+        fn run(&mut self) {
+            if !self.filled {
+                self.filled = true;
+                self.circ_connector.unschedule_all(false);
+                self.circ_connector
+                    .blood_store(&TestBloodVessel::VenaCava)
+                    .unwrap()
+                    .schedule_change(Substance::O2, SubstanceConcentration::from_mM(8.0), SimTimeSpan::from_s(1.0));
+            }
+        }
+    }
+
+    /// Venous O2 (mM) after a minute at the given metabolic rate
+    fn venous_o2(watts: f64) -> f64 {
+        let mut sim = TestSim::new();
+        sim.add_component(O2FillComponent {
+            circ_connector: CirculationConnector::new(),
+            filled: false,
+        }).unwrap();
+        sim.add_component(MetabolismComponent::whole_body(
+            vec![TestBloodVessel::VenaCava],
+            SimTimeSpan::from_s(1.0),
+        )).unwrap();
+        sim.add_component(WindowedStatComponent::new(
+            TestBloodVessel::VenaCava,
+            Substance::O2,
+            SimTimeSpan::from_s(1.0),
+            SimTimeSpan::from_s(60.0),
+        )).unwrap();
+
+        sim.schedule_event(SimTimeSpan::from_s(0.0), Box::new(MetabolicRate(Power::from_W(watts))));
+
+        let mut last = None;
+        for _ in 0..60 {
+            sim.advance_by(SimTimeSpan::from_s(1.0));
+            if let Some(stats) = sim.drain_active().find_map(|e| e.downcast_ref::<WindowedStats>().copied()) {
+                last = Some(stats.mean.to_mM());
+            }
+        }
+        last.unwrap()
+    }
+
+    #[test]
+    fn metabolic_rate_lowers_venous_o2() {
+        let resting = venous_o2(80.0);
+        let exercising = venous_o2(400.0);
+
+        // 80 W consumes ~1.78e-4 mol/s of O2, ~2.1 mM over a minute in 5 L
+        assert!(resting > 5.5 && resting < 6.5, "{}", resting);
+        assert!(exercising < resting - 4.0, "{} vs {}", exercising, resting);
+    }
+}
@@ -0,0 +1,34 @@
+//! General purpose components which can be used with any `Organism`
+
+mod blood_gas;
+mod delay_line;
+mod interaction;
+mod lymphatic;
+mod derived_vital;
+mod metabolism;
+mod noisy;
+mod periodic;
+mod pid;
+mod renal;
+mod replay;
+mod sa_node;
+mod sequential;
+mod smoothing;
+mod ventilation;
+mod windowed_stat;
+
+pub use blood_gas::{BloodGasComponent, BICARBONATE_PK, CO2_SOLUBILITY, O2_SOLUBILITY};
+pub use delay_line::{Delayed, DelayLineComponent};
+pub use derived_vital::DerivedVitalComponent;
+pub use interaction::{InteractionComponent, InteractionFactors};
+pub use lymphatic::LymphaticComponent;
+pub use metabolism::{MetabolismComponent, DEFAULT_RESPIRATORY_QUOTIENT, O2_ENERGY_EQUIVALENT};
+pub use noisy::{NoiseConfig, NoisyComponent};
+pub use pid::{PidControllerComponent, PidGains};
+pub use renal::{RenalClearanceComponent, RenalExcretion};
+pub use replay::ReplayComponent;
+pub use sa_node::SinoatrialNodeComponent;
+pub use sequential::SequentialComponent;
+pub use smoothing::{Smoothed, SmoothingComponent, SmoothingFilter};
+pub use ventilation::{VentilationComponent, INSPIRED_PO2, NORMAL_PA_CO2};
+pub use windowed_stat::{SlidingWindow, WindowStats, WindowedStatComponent, WindowedStats};
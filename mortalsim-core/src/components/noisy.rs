@@ -0,0 +1,236 @@
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::event::Event;
+use crate::sim::component::{ComponentRegistry, Phase, SimComponent};
+use crate::sim::layer::core::{CoreComponent, CoreConnector, CoreInitializer};
+use crate::sim::Organism;
+
+type PerturbFn = Box<dyn Fn(&mut dyn Event, f64) + Send>;
+
+/// Standard normal sample using the Box-Muller transform
+fn standard_normal(rng: &mut StdRng) -> f64 {
+    let u1: f64 = 1.0 - rng.gen::<f64>();
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+}
+
+/// Describes how the events emitted by a `NoisyComponent` are perturbed
+pub struct NoiseConfig {
+    /// Seed for the random number generator, so perturbations are reproducible
+    seed: u64,
+    /// Probability of dropping any emitted event
+    drop_probability: f64,
+    /// Standard deviation and perturbation function for each event type
+    perturbations: HashMap<TypeId, (f64, PerturbFn)>,
+}
+
+impl NoiseConfig {
+    /// Creates a new config which leaves all events untouched
+    ///
+    /// ### Arguments
+    /// * `seed` - Seed for the random number generator
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            drop_probability: 0.0,
+            perturbations: HashMap::new(),
+        }
+    }
+
+    /// Drops emitted events with the given probability, to simulate
+    /// sensor dropouts
+    ///
+    /// ### Arguments
+    /// * `probability` - Probability of dropping each event, between 0 and 1
+    pub fn with_drop_probability(mut self, probability: f64) -> Self {
+        self.drop_probability = probability.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Adds Gaussian noise to each emitted event of type `E`
+    ///
+    /// ### Arguments
+    /// * `stddev`  - Standard deviation of the noise
+    /// * `perturb` - Function applying a noise sample to the event
+    pub fn with_noise<E: Event>(mut self, stddev: f64, perturb: impl Fn(&mut E, f64) + Send + 'static) -> Self {
+        self.perturbations.insert(
+            TypeId::of::<E>(),
+            (stddev, Box::new(move |evt: &mut dyn Event, noise| {
+                if let Some(evt) = evt.downcast_mut::<E>() {
+                    perturb(evt, noise)
+                }
+            })),
+        );
+        self
+    }
+}
+
+/// Wraps a `CoreComponent` to perturb the events it emits, in order to
+/// test that downstream components tolerate noisy or missing inputs.
+/// The wrapper is registered on the Core layer only.
+pub struct NoisyComponent<O: Organism, C: CoreComponent<O> + 'static> {
+    inner: C,
+    config: NoiseConfig,
+    rng: StdRng,
+    _organism: std::marker::PhantomData<O>,
+}
+
+impl<O: Organism, C: CoreComponent<O> + 'static> NoisyComponent<O, C> {
+    /// Creates a new NoisyComponent
+    ///
+    /// ### Arguments
+    /// * `inner`        - Component to wrap
+    /// * `noise_config` - How to perturb the component's events
+    pub fn new(inner: C, noise_config: NoiseConfig) -> Self {
+        Self {
+            inner,
+            rng: StdRng::seed_from_u64(noise_config.seed),
+            config: noise_config,
+            _organism: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<O: Organism, C: CoreComponent<O> + 'static> CoreComponent<O> for NoisyComponent<O, C> {
+    fn core_init(&mut self, initializer: &mut CoreInitializer<O>) {
+        self.inner.core_init(initializer)
+    }
+
+    fn core_connector(&mut self) -> &mut CoreConnector<O> {
+        self.inner.core_connector()
+    }
+}
+
+impl<O: Organism, C: CoreComponent<O> + 'static> SimComponent<O> for NoisyComponent<O, C> {
+    fn id(&self) -> &'static str {
+        self.inner.id()
+    }
+
+    fn attach(self, registry: &mut ComponentRegistry<O>) {
+        registry.add_core_component(self)
+    }
+
+    fn run(&mut self) {
+        self.inner.run();
+
+        let pending = std::mem::take(&mut self.inner.core_connector().pending_schedules);
+        let mut kept = Vec::with_capacity(pending.len());
+
+        for (wait_time, (local_id, mut evt)) in pending {
+            if self.config.drop_probability > 0.0 && self.rng.gen::<f64>() < self.config.drop_probability {
+                log::debug!("{} dropping event {:?}", self.id(), evt);
+                continue;
+            }
+            let type_id = evt.as_ref().as_any().type_id();
+            if let Some((stddev, perturb)) = self.config.perturbations.get(&type_id) {
+                perturb(evt.as_mut(), stddev * standard_normal(&mut self.rng));
+            }
+            kept.push((wait_time, (local_id, evt)));
+        }
+
+        self.inner.core_connector().pending_schedules = kept;
+    }
+
+    fn reset(&mut self) {
+        // Restart the noise sequence along with the inner component
+        self.rng = StdRng::seed_from_u64(self.config.seed);
+        self.inner.reset();
+    }
+
+    fn phase(&self) -> Phase {
+        self.inner.phase()
+    }
+}
+
+mod tests {
+    use std::any::TypeId;
+
+    use crate::event::test::{TestEventA, TestEventB};
+    use crate::sim::component::{ComponentRegistry, Phase, SimComponent};
+    use crate::sim::layer::core::{CoreComponent, CoreConnector, CoreInitializer};
+    use crate::sim::organism::test::{TestOrganism, TestSim};
+    use crate::sim::Sim;
+    use crate::units::base::{Amount, Distance};
+    use crate::SimTimeSpan;
+
+    use super::{NoiseConfig, NoisyComponent};
+
+    /// Emits a 1 m `TestEventA` each time it receives a `TestEventB`
+    struct SensorComponent {
+        connector: CoreConnector<TestOrganism>,
+    }
+
+    impl CoreComponent<TestOrganism> for SensorComponent {
+        fn core_init(&mut self, initializer: &mut CoreInitializer<TestOrganism>) {
+            initializer.notify::<TestEventB>();
+        }
+        fn core_connector(&mut self) -> &mut CoreConnector<TestOrganism> {
+            &mut self.connector
+        }
+    }
+
+    impl SimComponent<TestOrganism> for SensorComponent {
+        fn id(&self) -> &'static str {
+            "SensorComponent"
+        }
+        fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+            registry.add_core_component(self);
+        }
+        fn run(&mut self) {
+            if self.connector.trigger_events().any(|t| *t == TypeId::of::<TestEventB>()) {
+                self.connector.schedule_event(SimTimeSpan::from_s(0.0), TestEventA::new(Distance::from_m(1.0)));
+            }
+        }
+        fn phase(&self) -> Phase {
+            Phase::Sense
+        }
+    }
+
+    fn sensor_readings(seed: u64) -> Vec<Option<f64>> {
+        let mut connector = CoreConnector::new();
+        connector.unschedule_all(false);
+        let config = NoiseConfig::new(seed)
+            .with_drop_probability(0.2)
+            .with_noise::<TestEventA>(0.1, |evt, noise| evt.len += Distance::from_m(noise));
+
+        let mut sim = TestSim::new();
+        sim.add_component(NoisyComponent::new(SensorComponent { connector }, config)).unwrap();
+
+        let mut readings = Vec::new();
+        for _ in 0..50 {
+            sim.schedule_event(SimTimeSpan::from_s(0.5), Box::new(TestEventB::new(Amount::from_mol(1.0))));
+            sim.advance_by(SimTimeSpan::from_s(1.0));
+            readings.push(
+                sim.drain_active()
+                    .find_map(|evt| evt.downcast_ref::<TestEventA>().map(|a| a.len.m))
+            );
+        }
+        readings
+    }
+
+    #[test]
+    fn noisy_component() {
+        let readings = sensor_readings(7);
+        assert_eq!(readings.len(), 50);
+
+        // Some readings are dropped, the rest are perturbed
+        let kept: Vec<f64> = readings.iter().flatten().copied().collect();
+        assert!(kept.len() < 50 && kept.len() > 25);
+        assert!(kept.iter().all(|len| (len - 1.0).abs() > 0.0 && (len - 1.0).abs() < 0.6));
+
+        // The same seed reproduces the same perturbations
+        assert_eq!(readings, sensor_readings(7));
+        assert_ne!(readings, sensor_readings(8));
+    }
+
+    #[test]
+    fn keeps_phase() {
+        let noisy = NoisyComponent::new(SensorComponent { connector: CoreConnector::new() }, NoiseConfig::new(7));
+        assert_eq!(noisy.phase(), Phase::Sense);
+    }
+}
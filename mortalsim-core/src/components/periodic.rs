@@ -0,0 +1,61 @@
+use crate::sim::layer::core::CoreConnector;
+use crate::sim::{Organism, SimTime};
+use crate::SimTimeSpan;
+
+/// Timer token reserved for periodic updates
+pub(crate) const UPDATE_TIMER: u64 = 0;
+
+/// Timer driven schedule for components which update at a fixed period,
+/// tracking the time elapsed between updates
+pub(crate) struct PeriodicUpdate {
+    period: SimTimeSpan,
+    /// Time of the previous update
+    last_update: Option<SimTime>,
+}
+
+impl PeriodicUpdate {
+    /// Creates a new PeriodicUpdate
+    ///
+    /// Panics if `period` isn't positive
+    ///
+    /// ### Arguments
+    /// * `period` - Time between updates
+    pub fn new(period: SimTimeSpan) -> Self {
+        assert!(period.to_s() > 0.0, "Update period must be greater than 0");
+        Self {
+            period,
+            last_update: None,
+        }
+    }
+
+    /// Time between updates
+    pub fn period(&self) -> SimTimeSpan {
+        self.period
+    }
+
+    /// Checks whether an update is due, which is on the first run and
+    /// whenever the update timer expires, and sets the timer for the
+    /// next update if so
+    ///
+    /// ### Arguments
+    /// * `connector` - Core connector of the component
+    ///
+    /// Returns the time of the update and the time since the previous
+    /// one, which is the update period for the first update
+    pub fn due<O: Organism>(&mut self, connector: &mut CoreConnector<O>) -> Option<(SimTime, SimTimeSpan)> {
+        if !connector.is_first_run() && !connector.expired_timers().any(|token| token == UPDATE_TIMER) {
+            return None;
+        }
+        connector.set_timer(self.period, UPDATE_TIMER);
+
+        let sim_time = connector.sim_time();
+        let dt = self.last_update.map_or(self.period, |time| SimTimeSpan::from_s((sim_time - time).to_s()));
+        self.last_update = Some(sim_time);
+        Some((sim_time, dt))
+    }
+
+    /// Forgets the previous update, so the next one is treated as the first
+    pub fn reset(&mut self) {
+        self.last_update = None;
+    }
+}
@@ -0,0 +1,259 @@
+use crate::event::Event;
+use crate::sim::component::{ComponentRegistry, SimComponent};
+use crate::sim::layer::core::{CoreComponent, CoreConnector, CoreInitializer};
+use crate::sim::{Organism, SimState, SimTime};
+use crate::SimTimeSpan;
+
+type InputFn = Box<dyn Fn(&SimState) -> f64 + Send>;
+type OutputFn = Box<dyn Fn(f64) -> Box<dyn Event> + Send>;
+
+/// Timer token for periodic samples
+const SAMPLE_TIMER: u64 = 0;
+
+/// Proportional, integral and derivative gains of a `PidControllerComponent`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PidGains {
+    pub kp: f64,
+    pub ki: f64,
+    pub kd: f64,
+}
+
+/// Regulates a measured value toward a setpoint for closed-loop control
+/// experiments, e.g. titrating a vasopressor toward a target blood
+/// pressure. The measurement is sampled from the current state
+/// periodically, and whenever one of its inputs changes, and the control
+/// signal is emitted as an actuating `Event`. The integral and derivative
+/// terms use the simulation time elapsed since the previous sample.
+pub struct PidControllerComponent<O: Organism> {
+    name: &'static str,
+    input: InputFn,
+    setpoint: f64,
+    gains: PidGains,
+    output: OutputFn,
+    sample_period: SimTimeSpan,
+    /// Registers notifications for each input `Event` type
+    inputs: Vec<fn(&mut CoreInitializer<O>)>,
+    integral: f64,
+    /// Time and error of the previous sample
+    last_sample: Option<(SimTime, f64)>,
+    connector: CoreConnector<O>,
+}
+
+impl<O: Organism> PidControllerComponent<O> {
+    /// Creates a new PidControllerComponent which samples once a second.
+    /// Non-finite measurements, e.g. from missing inputs, are skipped.
+    ///
+    /// ### Arguments
+    /// * `name`     - Name of the controller, also used as the component id
+    /// * `input`    - Function extracting the measured value from the current state
+    /// * `setpoint` - Value to regulate the measurement toward
+    /// * `gains`    - Gains of the controller
+    /// * `output`   - Function creating the actuating `Event` for a control signal
+    pub fn new(
+        name: &'static str,
+        input: impl Fn(&SimState) -> f64 + Send + 'static,
+        setpoint: f64,
+        gains: PidGains,
+        output: impl Fn(f64) -> Box<dyn Event> + Send + 'static,
+    ) -> Self {
+        Self {
+            name,
+            input: Box::new(input),
+            setpoint,
+            gains,
+            output: Box::new(output),
+            sample_period: SimTimeSpan::from_s(1.0),
+            inputs: Vec::new(),
+            integral: 0.0,
+            last_sample: None,
+            connector: CoreConnector::new(),
+        }
+    }
+
+    /// Sets the time between periodic samples of the measurement
+    ///
+    /// Panics if `sample_period` isn't positive
+    ///
+    /// ### Arguments
+    /// * `sample_period` - Time between samples
+    pub fn with_sample_period(mut self, sample_period: SimTimeSpan) -> Self {
+        assert!(sample_period.to_s() > 0.0, "Sample period must be greater than 0");
+        self.sample_period = sample_period;
+        self
+    }
+
+    /// Also samples the measurement whenever an `Event` of type `E` is emitted
+    pub fn with_input<E: Event>(mut self) -> Self {
+        self.inputs.push(|initializer| initializer.notify::<E>());
+        self
+    }
+
+    /// Calculates the control signal for the given error, updating the
+    /// integral term over the time since the previous sample
+    ///
+    /// ### Arguments
+    /// * `sim_time` - Time of the sample
+    /// * `error`    - Setpoint minus the measured value
+    ///
+    /// Returns the control signal
+    fn control(&mut self, sim_time: SimTime, error: f64) -> f64 {
+        let derivative = match self.last_sample {
+            Some((time, last_error)) if sim_time > time => {
+                let dt = (sim_time - time).to_s();
+                self.integral += error * dt;
+                (error - last_error) / dt
+            }
+            _ => 0.0,
+        };
+        self.last_sample = Some((sim_time, error));
+
+        self.gains.kp * error + self.gains.ki * self.integral + self.gains.kd * derivative
+    }
+}
+
+impl<O: Organism> CoreComponent<O> for PidControllerComponent<O> {
+    fn core_init(&mut self, initializer: &mut CoreInitializer<O>) {
+        for input in self.inputs.iter() {
+            input(initializer);
+        }
+    }
+
+    fn core_connector(&mut self) -> &mut CoreConnector<O> {
+        &mut self.connector
+    }
+}
+
+impl<O: Organism> SimComponent<O> for PidControllerComponent<O> {
+    fn id(&self) -> &'static str {
+        self.name
+    }
+
+    fn attach(self, registry: &mut ComponentRegistry<O>) {
+        registry.add_core_component(self)
+    }
+
+    fn run(&mut self) {
+        if self.connector.is_first_run() || self.connector.expired_timers().any(|token| token == SAMPLE_TIMER) {
+            self.connector.set_timer(self.sample_period, SAMPLE_TIMER);
+        }
+
+        let sim_time = self.connector.sim_time();
+        let measurement = (self.input)(&self.connector.current_state());
+        if !measurement.is_finite() || self.last_sample.is_some_and(|(time, _)| time == sim_time) {
+            return;
+        }
+
+        let signal = self.control(sim_time, self.setpoint - measurement);
+        self.connector.schedule_boxed_event(SimTimeSpan::from_s(0.0), (self.output)(signal));
+    }
+
+    fn reset(&mut self) {
+        self.integral = 0.0;
+        self.last_sample = None;
+    }
+}
+
+mod tests {
+    use crate::event::Event;
+    use crate::sim::component::{ComponentRegistry, SimComponent};
+    use crate::sim::layer::core::{CoreComponent, CoreConnector, CoreInitializer};
+    use crate::sim::organism::test::{TestOrganism, TestSim};
+    use crate::sim::{Sim, SimState, SimTime};
+    use crate::SimTimeSpan;
+
+    use super::{PidControllerComponent, PidGains};
+
+    /// Actuating input of the plant
+    #[derive(Debug, Clone, Copy)]
+    struct PlantInput(f64);
+
+    impl Event for PlantInput {}
+
+    /// Measured output of the plant
+    #[derive(Debug, Clone, Copy)]
+    struct PlantOutput(f64);
+
+    impl Event for PlantOutput {
+        fn transient(&self) -> bool {
+            false
+        }
+    }
+
+    /// First order plant with a 5 s time constant, whose output follows
+    /// its input, updated every 100 ms
+    struct FirstOrderPlant {
+        output: f64,
+        input: f64,
+        last_run: Option<SimTime>,
+        connector: CoreConnector<TestOrganism>,
+    }
+
+    impl CoreComponent<TestOrganism> for FirstOrderPlant {
+        fn core_init(&mut self, initializer: &mut CoreInitializer<TestOrganism>) {
+            initializer.notify::<PlantInput>();
+        }
+
+        fn core_connector(&mut self) -> &mut CoreConnector<TestOrganism> {
+            &mut self.connector
+        }
+    }
+
+    impl SimComponent<TestOrganism> for FirstOrderPlant {
+        fn id(&self) -> &'static str {
+            "FirstOrderPlant"
+        }
+
+        fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+            registry.add_core_component(self)
+        }
+
+        fn run(&mut self) {
+            let sim_time = self.connector.sim_time();
+            if let Some(last_run) = self.last_run {
+                self.output += (self.input - self.output) * (sim_time - last_run).to_s() / 5.0;
+            }
+            self.last_run = Some(sim_time);
+            if let Some(input) = self.connector.get::<PlantInput>() {
+                self.input = input.0;
+            }
+
+            self.connector.set_timer(SimTimeSpan::from_s(0.1), 0);
+            self.connector.schedule_event(SimTimeSpan::from_s(0.0), PlantOutput(self.output));
+        }
+    }
+
+    #[test]
+    fn regulates_first_order_plant() {
+        let mut sim = TestSim::new();
+        sim.add_component(FirstOrderPlant {
+            output: 0.0,
+            input: 0.0,
+            last_run: None,
+            connector: CoreConnector::new(),
+        }).unwrap();
+        sim.add_component(
+            PidControllerComponent::new(
+                "PlantController",
+                |state: &SimState| state.get_state::<PlantOutput>().map_or(f64::NAN, |out| out.0),
+                10.0,
+                PidGains { kp: 0.5, ki: 0.2, kd: 0.1 },
+                |signal| Box::new(PlantInput(signal)),
+            ).with_sample_period(SimTimeSpan::from_s(0.5)),
+        ).unwrap();
+
+        let output = |sim: &TestSim| sim.state().get_state::<PlantOutput>().map_or(f64::NAN, |out| out.0);
+
+        let mut outputs = Vec::new();
+        for _ in 0..120 {
+            sim.advance_by(SimTimeSpan::from_s(1.0));
+            outputs.push(output(&sim));
+        }
+
+        // Rises toward the setpoint, and the integral term eliminates
+        // any steady state error
+        assert!(outputs[10] > 1.0, "{:?}", outputs);
+        for value in outputs[90..].iter() {
+            assert!((value - 10.0).abs() < 0.1, "{:?}", outputs);
+        }
+    }
+}
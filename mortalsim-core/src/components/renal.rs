@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+
+use crate::event::Event;
+use crate::math::BoundFn;
+use crate::sim::component::{ComponentRegistry, SimComponent};
+use crate::sim::layer::circulation::{BloodVessel, CirculationComponent, CirculationConnector, CirculationInitializer};
+use crate::sim::layer::core::{CoreComponent, CoreConnector, CoreInitializer};
+use crate::sim::{Organism, SimTime};
+use crate::substance::{Substance, SubstanceChange};
+use crate::units::base::Amount;
+use crate::SimTimeSpan;
+
+use super::periodic::PeriodicUpdate;
+
+/// Amount of a Substance excreted in the urine, emitted by a
+/// `RenalClearanceComponent` each update the Substance is cleared
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenalExcretion {
+    pub substance: Substance,
+    pub amount: Amount<f64>,
+}
+
+impl Event for RenalExcretion {}
+
+/// Clears substances from the blood through the kidneys at a rate
+/// proportional to renal perfusion. Each configured Substance has an
+/// extraction ratio, the fraction removed from the blood on each pass
+/// through the kidneys, so the renal clearance is the extraction ratio
+/// times the blood flow into the renal arteries. Clearance is first order
+/// across the total blood volume of the `Organism`, and is applied to the
+/// renal arteries, from where a blood flow component can propagate it.
+pub struct RenalClearanceComponent<O: Organism> {
+    renal_arteries: Vec<O::VesselType>,
+    extraction_ratios: HashMap<Substance, f64>,
+    updates: PeriodicUpdate,
+    core_connector: CoreConnector<O>,
+    circ_connector: CirculationConnector<O>,
+}
+
+impl<O: Organism> RenalClearanceComponent<O> {
+    /// Creates a new RenalClearanceComponent without any cleared
+    /// substances, which updates every 10 seconds
+    ///
+    /// ### Arguments
+    /// * `renal_arteries` - Arteries perfusing the kidneys, which must have upstream vessels
+    pub fn new(renal_arteries: Vec<O::VesselType>) -> Self {
+        let mut circ_connector = CirculationConnector::new();
+
+        // Clearance accumulates rather than being replaced each run
+        circ_connector.unschedule_all(false);
+
+        Self {
+            renal_arteries,
+            extraction_ratios: HashMap::new(),
+            updates: PeriodicUpdate::new(SimTimeSpan::from_s(10.0)),
+            core_connector: CoreConnector::new(),
+            circ_connector,
+        }
+    }
+
+    /// Clears the given Substance with a fixed extraction ratio. Tracers
+    /// pass through the kidneys unchanged, so they're never cleared.
+    ///
+    /// Panics if `extraction_ratio` is not between 0 and 1
+    ///
+    /// ### Arguments
+    /// * `substance`        - Substance to clear
+    /// * `extraction_ratio` - Fraction of the Substance removed per pass through the kidneys
+    pub fn with_substance(mut self, substance: Substance, extraction_ratio: f64) -> Self {
+        assert!((0.0..=1.0).contains(&extraction_ratio), "Extraction ratio must be between 0 and 1");
+        if !substance.is_tracer() {
+            self.extraction_ratios.insert(substance, extraction_ratio);
+        }
+        self
+    }
+
+    /// Sets the time between updates of the clearance
+    ///
+    /// Panics if `update_period` isn't positive
+    ///
+    /// ### Arguments
+    /// * `update_period` - Time between updates
+    pub fn with_update_period(mut self, update_period: SimTimeSpan) -> Self {
+        self.updates = PeriodicUpdate::new(update_period);
+        self
+    }
+
+    /// Blood flow into a renal artery from its upstream vessels (m^3/s)
+    ///
+    /// ### Arguments
+    /// * `artery` - Renal artery to retrieve the perfusion of
+    fn perfusion(&self, artery: O::VesselType) -> f64 {
+        artery.upstream()
+            .map(|upstream| self.circ_connector.flow_rate(upstream, artery).m3ps)
+            .sum()
+    }
+
+    /// Schedules the clearance of each Substance from each renal artery
+    /// over the next update period
+    ///
+    /// ### Arguments
+    /// * `sim_time` - Time of the update
+    /// * `dt`       - Time since the previous update
+    fn clear(&mut self, sim_time: SimTime, dt: SimTimeSpan) {
+        let blood_volume = O::total_blood_volume();
+        let mut excreted: HashMap<Substance, Amount<f64>> = HashMap::new();
+
+        for artery in self.renal_arteries.iter() {
+            let perfusion = self.perfusion(*artery);
+            let Some(mut store) = self.circ_connector.blood_store(artery) else {
+                continue;
+            };
+
+            for (substance, extraction_ratio) in self.extraction_ratios.iter() {
+                let rate_constant = extraction_ratio * perfusion / blood_volume.m3;
+                let fraction = 1.0 - (-rate_constant * dt.to_s()).exp();
+                let cleared = store.concentration_of(substance) * fraction;
+                if cleared.to_M() <= 0.0 {
+                    continue;
+                }
+
+                store.schedule_custom_change(
+                    *substance,
+                    SubstanceChange::new(sim_time, -cleared, self.updates.period(), BoundFn::Linear),
+                );
+                let total = excreted.entry(*substance).or_insert(Amount::from_mol(0.0));
+                *total += cleared * blood_volume;
+            }
+        }
+
+        for (substance, amount) in excreted {
+            self.core_connector.schedule_event(SimTimeSpan::from_s(0.0), RenalExcretion { substance, amount });
+        }
+    }
+}
+
+impl<O: Organism> CoreComponent<O> for RenalClearanceComponent<O> {
+    fn core_init(&mut self, initializer: &mut CoreInitializer<O>) {
+        initializer.produces::<RenalExcretion>();
+    }
+
+    fn core_connector(&mut self) -> &mut CoreConnector<O> {
+        &mut self.core_connector
+    }
+}
+
+impl<O: Organism> CirculationComponent<O> for RenalClearanceComponent<O> {
+    fn circulation_init(&mut self, initializer: &mut CirculationInitializer<O>) {
+        for artery in self.renal_arteries.iter() {
+            initializer.attach_vessel(*artery);
+        }
+        for substance in self.extraction_ratios.keys() {
+            initializer.manage_substance(*substance);
+        }
+    }
+
+    fn circulation_connector(&mut self) -> &mut CirculationConnector<O> {
+        &mut self.circ_connector
+    }
+}
+
+impl<O: Organism> SimComponent<O> for RenalClearanceComponent<O> {
+    fn id(&self) -> &'static str {
+        "RenalClearanceComponent"
+    }
+
+    fn attach(self, registry: &mut ComponentRegistry<O>) {
+        registry.add_core_circulation_component(self)
+    }
+
+    fn run(&mut self) {
+        // Clearance is scheduled over the update period, at rates from
+        // the time elapsed since the previous update
+        let Some((sim_time, dt)) = self.updates.due(&mut self.core_connector) else {
+            return;
+        };
+        self.clear(sim_time, dt);
+    }
+
+    fn reset(&mut self) {
+        self.updates.reset();
+    }
+}
+
+mod tests {
+    use crate::event::CardiacOutput;
+    use crate::sim::layer::circulation::component::test::TestBolusComponent;
+    use crate::sim::organism::test::{TestBloodVessel, TestOrganism, TestSim};
+    use crate::sim::Sim;
+    use crate::substance::{Substance, SubstanceConcentration};
+    use crate::SimTimeSpan;
+
+    use super::{RenalClearanceComponent, RenalExcretion};
+
+    /// Remaining concentration (mM) in the renal artery and total
+    /// excreted amount (mmol) of a 5 mM bolus of the given Substance after
+    /// 10 minutes at the given cardiac output
+    fn clear(substance: Substance, cardiac_output: f64) -> (f64, f64) {
+        let mut sim = TestSim::new();
+        sim.schedule_event(SimTimeSpan::from_s(0.0), Box::new(CardiacOutput(cardiac_output)));
+        sim.add_component(TestBolusComponent::new(
+            TestBloodVessel::LeftFemoralArtery,
+            substance,
+            SubstanceConcentration::from_mM(5.0),
+            SimTimeSpan::from_s(1.0),
+        )).unwrap();
+        sim.add_component(
+            RenalClearanceComponent::new(vec![TestBloodVessel::LeftFemoralArtery]).with_substance(substance, 0.8),
+        ).unwrap();
+
+        let mut excreted = 0.0;
+        for _ in 0..60 {
+            sim.advance_by(SimTimeSpan::from_s(10.0));
+            excreted += sim
+                .drain_active()
+                .filter_map(|e| e.downcast_ref::<RenalExcretion>().map(|x| x.amount.mol * 1000.0))
+                .sum::<f64>();
+        }
+
+        let mut remaining = f64::NAN;
+        sim.inspect_circulation("TestBolusComponent", |connector| {
+            remaining = connector
+                .blood_store(&TestBloodVessel::LeftFemoralArtery)
+                .unwrap()
+                .concentration_of(&substance)
+                .to_mM();
+        }).unwrap();
+        (remaining, excreted)
+    }
+
+    #[test]
+    fn reduced_perfusion_slows_clearance() {
+        let (normal, normal_excreted) = clear(Substance::GLC, 5.0);
+        let (reduced, reduced_excreted) = clear(Substance::GLC, 2.0);
+
+        assert!(normal < 5.0 && normal > 0.0, "{}", normal);
+        assert!(reduced > normal && reduced < 5.0, "{} <= {}", reduced, normal);
+        assert!(reduced_excreted < normal_excreted, "{} >= {}", reduced_excreted, normal_excreted);
+        assert!(normal_excreted > 0.0);
+    }
+
+    #[test]
+    fn tracers_pass_through() {
+        let (remaining, excreted) = clear(Substance::Tracer(1), 5.0);
+        assert!((remaining - 5.0).abs() < 1e-9, "{}", remaining);
+        assert_eq!(excreted, 0.0);
+    }
+}
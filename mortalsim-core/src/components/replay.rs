@@ -0,0 +1,113 @@
+use std::any::type_name;
+
+use crate::event::Event;
+use crate::sim::component::{ComponentRegistry, SimComponent};
+use crate::sim::layer::core::{CoreComponent, CoreConnector, CoreInitializer};
+use crate::sim::{Organism, SimTime};
+
+/// Replays a recorded time series of `Event`s of type `E`, such as a
+/// patient's vital signs, by scheduling each recorded value at its
+/// time. Useful for driving downstream components with real data, e.g.
+/// feeding a recorded `HeartRate` trace to a cardiovascular model.
+/// Each instance's id is derived from `E`.
+pub struct ReplayComponent<O: Organism, E: Event + Clone> {
+    /// Recorded values, in order of time
+    recording: Vec<(SimTime, E)>,
+    /// Whether the recording has been scheduled
+    scheduled: bool,
+    connector: CoreConnector<O>,
+}
+
+impl<O: Organism, E: Event + Clone> ReplayComponent<O, E> {
+    /// Creates a new ReplayComponent
+    ///
+    /// ### Arguments
+    /// * `recording` - Recorded values and the simulation times to emit them at
+    pub fn new(mut recording: Vec<(SimTime, E)>) -> Self {
+        let mut connector = CoreConnector::new();
+
+        // The whole recording is scheduled up front
+        connector.unschedule_all(false);
+
+        recording.sort_by_key(|(time, _)| *time);
+
+        Self {
+            recording,
+            scheduled: false,
+            connector,
+        }
+    }
+}
+
+impl<O: Organism, E: Event + Clone> CoreComponent<O> for ReplayComponent<O, E> {
+    fn core_init(&mut self, initializer: &mut CoreInitializer<O>) {
+        initializer.produces::<E>();
+    }
+
+    fn core_connector(&mut self) -> &mut CoreConnector<O> {
+        &mut self.connector
+    }
+}
+
+impl<O: Organism, E: Event + Clone> SimComponent<O> for ReplayComponent<O, E> {
+    fn id(&self) -> &'static str {
+        type_name::<Self>()
+    }
+
+    fn attach(self, registry: &mut ComponentRegistry<O>) {
+        registry.add_core_component(self)
+    }
+
+    fn run(&mut self) {
+        if self.scheduled {
+            return;
+        }
+        self.scheduled = true;
+
+        // Values recorded before the component first runs are skipped
+        let sim_time = self.connector.sim_time();
+        for (time, value) in self.recording.iter().filter(|(time, _)| *time >= sim_time) {
+            self.connector.schedule_event(sim_time.span_to(time), value.clone());
+        }
+    }
+
+    fn reset(&mut self) {
+        self.scheduled = false;
+    }
+}
+
+mod tests {
+    use crate::event::HeartRate;
+    use crate::sim::organism::test::{TestOrganism, TestSim};
+    use crate::sim::{Sim, SimTime};
+    use crate::units::mechanical::Frequency;
+    use crate::SimTimeSpan;
+
+    use super::ReplayComponent;
+
+    #[test]
+    fn replay_recording() {
+        let recording: Vec<(SimTime, HeartRate)> = [(4.0, 1.4), (1.0, 1.2), (2.5, 1.1)]
+            .into_iter()
+            .map(|(s, hz)| (SimTime::from_s(s), HeartRate(Frequency::from_Hz(hz))))
+            .collect();
+
+        let mut sim = TestSim::new();
+        sim.add_component(ReplayComponent::<TestOrganism, HeartRate>::new(recording)).unwrap();
+
+        let mut values = Vec::new();
+        for _ in 0..10 {
+            sim.advance_by(SimTimeSpan::from_s(0.5));
+            values.push((sim.time(), sim.state().get_state::<HeartRate>().map(|hr| hr.0.Hz)));
+        }
+
+        let at = |s: f64| values.iter().find(|(t, _)| *t == SimTime::from_s(s)).unwrap().1;
+        assert_eq!(at(0.5), None);
+        assert_eq!(at(1.0), Some(1.2));
+        assert_eq!(at(2.0), Some(1.2));
+        assert_eq!(at(2.5), Some(1.1));
+        assert_eq!(at(3.5), Some(1.1));
+        assert_eq!(at(4.0), Some(1.4));
+        assert_eq!(at(5.0), Some(1.4));
+    }
+}
@@ -0,0 +1,124 @@
+use crate::event::{CoreBodyTemp, HeartRate};
+use crate::sim::component::{ComponentRegistry, SimComponent};
+use crate::sim::layer::core::{CoreComponent, CoreConnector, CoreInitializer};
+use crate::sim::Organism;
+use crate::units::mechanical::Frequency;
+use crate::SimTimeSpan;
+
+/// Core body temperature at which the resting rate applies (degrees C)
+const REFERENCE_TEMP: f64 = 37.0;
+
+/// Default increase in heart rate per degree C above the reference
+/// temperature (beats per minute)
+const DEFAULT_TEMP_SENSITIVITY: f64 = 10.0;
+
+/// Sinoatrial node pacemaker, which sets the `HeartRate`. The node fires
+/// at a resting rate which rises with `CoreBodyTemp`, by around 10 beats
+/// per minute for each degree above 37 C, and falls below it. The
+/// `HeartRate` is only emitted when it changes.
+pub struct SinoatrialNodeComponent<O: Organism> {
+    resting_rate: HeartRate,
+    /// Change in rate per degree C (bpm)
+    temp_sensitivity: f64,
+    /// Rate most recently emitted
+    rate: Option<HeartRate>,
+    connector: CoreConnector<O>,
+}
+
+impl<O: Organism> SinoatrialNodeComponent<O> {
+    /// Creates a new SinoatrialNodeComponent
+    ///
+    /// ### Arguments
+    /// * `resting_rate` - Heart rate at a core body temperature of 37 C
+    pub fn new(resting_rate: HeartRate) -> Self {
+        Self {
+            resting_rate,
+            temp_sensitivity: DEFAULT_TEMP_SENSITIVITY,
+            rate: None,
+            connector: CoreConnector::new(),
+        }
+    }
+
+    /// Sets the change in heart rate per degree C of core body temperature
+    ///
+    /// ### Arguments
+    /// * `bpm_per_degree` - Change in beats per minute for each degree C
+    pub fn with_temp_sensitivity(mut self, bpm_per_degree: f64) -> Self {
+        self.temp_sensitivity = bpm_per_degree;
+        self
+    }
+
+    /// Heart rate at the given core body temperature, which never falls
+    /// below zero
+    ///
+    /// ### Arguments
+    /// * `temp` - Core body temperature, or `None` for the resting rate
+    pub fn rate_at(&self, temp: Option<&CoreBodyTemp>) -> HeartRate {
+        let delta = temp.map_or(0.0, |t| t.as_ref().to_celsius() - REFERENCE_TEMP);
+        let bpm = self.resting_rate.as_ref().Hz * 60.0 + self.temp_sensitivity * delta;
+        HeartRate(Frequency::from_Hz(bpm.max(0.0) / 60.0))
+    }
+}
+
+impl<O: Organism> CoreComponent<O> for SinoatrialNodeComponent<O> {
+    fn core_init(&mut self, initializer: &mut CoreInitializer<O>) {
+        initializer.notify::<CoreBodyTemp>();
+        initializer.set_output(self.resting_rate);
+    }
+
+    fn core_connector(&mut self) -> &mut CoreConnector<O> {
+        &mut self.connector
+    }
+}
+
+impl<O: Organism> SimComponent<O> for SinoatrialNodeComponent<O> {
+    fn id(&self) -> &'static str {
+        "SinoatrialNodeComponent"
+    }
+
+    fn attach(self, registry: &mut ComponentRegistry<O>) {
+        registry.add_core_component(self)
+    }
+
+    fn run(&mut self) {
+        let rate = self.rate_at(self.connector.get::<CoreBodyTemp>());
+        if self.rate != Some(rate) {
+            self.connector.schedule_event(SimTimeSpan::from_s(0.0), rate);
+            self.rate = Some(rate);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.rate = None;
+    }
+}
+
+mod tests {
+    use crate::event::{CoreBodyTemp, HeartRate};
+    use crate::sim::organism::test::TestSim;
+    use crate::sim::Sim;
+    use crate::units::base::Temperature;
+    use crate::units::mechanical::Frequency;
+    use crate::SimTimeSpan;
+
+    use super::SinoatrialNodeComponent;
+
+    #[test]
+    fn fever_raises_heart_rate() {
+        let mut sim = TestSim::new();
+        sim.add_component(SinoatrialNodeComponent::new(HeartRate(Frequency::from_Hz(70.0 / 60.0)))).unwrap();
+
+        let bpm = |sim: &TestSim| sim.state().get_state::<HeartRate>().unwrap().as_ref().Hz * 60.0;
+
+        sim.advance_by(SimTimeSpan::from_s(1.0));
+        assert!((bpm(&sim) - 70.0).abs() < 1e-9, "{}", bpm(&sim));
+
+        sim.schedule_event(SimTimeSpan::from_s(0.0), Box::new(CoreBodyTemp(Temperature::from_celsius(39.0))));
+        sim.advance_by(SimTimeSpan::from_s(1.0));
+        assert!((bpm(&sim) - 90.0).abs() < 1e-9, "{}", bpm(&sim));
+
+        sim.schedule_event(SimTimeSpan::from_s(0.0), Box::new(CoreBodyTemp(Temperature::from_celsius(36.0))));
+        sim.advance_by(SimTimeSpan::from_s(1.0));
+        assert!((bpm(&sim) - 60.0).abs() < 1e-9, "{}", bpm(&sim));
+    }
+}
@@ -0,0 +1,315 @@
+use std::mem::swap;
+
+use crate::sim::component::registry::ComponentWrapper;
+use crate::sim::component::{ComponentRegistry, Phase, SimComponent};
+use crate::sim::layer::circulation::{CirculationComponent, CirculationConnector, CirculationInitializer};
+use crate::sim::layer::core::{CoreComponent, CoreConnector, CoreInitializer};
+use crate::sim::layer::digestion::{DigestionComponent, DigestionConnector, DigestionInitializer};
+use crate::sim::layer::nervous::{NervousComponent, NervousConnector, NervousInitializer};
+use crate::sim::layer::LayerType;
+use crate::sim::Organism;
+
+/// Runs a series of sub-components in order as a single logical component,
+/// e.g. metabolism followed by gas exchange followed by saturation.
+///
+/// The sequence is registered on every layer any of its sub-components use,
+/// and each sub-component initializes against the sequence's initializers.
+/// Whenever the sequence is triggered, every sub-component runs in the order
+/// added. The sequence owns one connector per layer, which is swapped into
+/// each sub-component for the duration of its run, so later sub-components
+/// see the changes made by earlier ones. Connector settings such as
+/// `unschedule_all` are taken from the sequence's connectors rather than
+/// those of the sub-components. The sequence runs in the earliest phase
+/// of its sub-components.
+pub struct SequentialComponent<O: Organism> {
+    id: &'static str,
+    components: Vec<Box<dyn ComponentWrapper<O>>>,
+    core_connector: CoreConnector<O>,
+    circ_connector: CirculationConnector<O>,
+    digestion_connector: DigestionConnector<O>,
+    nervous_connector: NervousConnector<O>,
+}
+
+impl<O: Organism> SequentialComponent<O> {
+    /// Creates a new, empty SequentialComponent
+    ///
+    /// ### Arguments
+    /// * `id` - Unique id of the sequence as a whole
+    pub fn new(id: &'static str) -> Self {
+        Self {
+            id,
+            components: Vec::new(),
+            core_connector: CoreConnector::new(),
+            circ_connector: CirculationConnector::new(),
+            digestion_connector: DigestionConnector::new(),
+            nervous_connector: NervousConnector::new(),
+        }
+    }
+
+    /// Adds a component to the end of the sequence
+    ///
+    /// ### Arguments
+    /// * `component` - Component to run after those already added
+    pub fn then(mut self, component: impl SimComponent<O>) -> Self {
+        let id = component.id();
+        let mut registry = ComponentRegistry::new();
+        registry.add_component(component).unwrap();
+        self.components.push(registry.remove_component(id).unwrap());
+        self
+    }
+
+    fn swap_connectors(&mut self, component: &mut Box<dyn ComponentWrapper<O>>) {
+        if component.is_core_component() {
+            swap(&mut self.core_connector, component.core_connector());
+        }
+        if component.is_circulation_component() {
+            swap(&mut self.circ_connector, component.circulation_connector());
+        }
+        if component.is_digestion_component() {
+            swap(&mut self.digestion_connector, component.digestion_connector());
+        }
+        if component.is_nervous_component() {
+            swap(&mut self.nervous_connector, component.nervous_connector());
+        }
+    }
+}
+
+impl<O: Organism> CoreComponent<O> for SequentialComponent<O> {
+    fn core_init(&mut self, initializer: &mut CoreInitializer<O>) {
+        for component in self.components.iter_mut().filter(|c| c.is_core_component()) {
+            component.core_init(initializer);
+        }
+    }
+
+    fn core_connector(&mut self) -> &mut CoreConnector<O> {
+        &mut self.core_connector
+    }
+}
+
+impl<O: Organism> CirculationComponent<O> for SequentialComponent<O> {
+    fn circulation_init(&mut self, initializer: &mut CirculationInitializer<O>) {
+        for component in self.components.iter_mut().filter(|c| c.is_circulation_component()) {
+            component.circulation_init(initializer);
+        }
+    }
+
+    fn circulation_connector(&mut self) -> &mut CirculationConnector<O> {
+        &mut self.circ_connector
+    }
+}
+
+impl<O: Organism> DigestionComponent<O> for SequentialComponent<O> {
+    fn digestion_init(&mut self, initializer: &mut DigestionInitializer<O>) {
+        for component in self.components.iter_mut().filter(|c| c.is_digestion_component()) {
+            component.digestion_init(initializer);
+        }
+    }
+
+    fn digestion_connector(&mut self) -> &mut DigestionConnector<O> {
+        &mut self.digestion_connector
+    }
+}
+
+impl<O: Organism> NervousComponent<O> for SequentialComponent<O> {
+    fn nervous_init(&mut self, initializer: &mut NervousInitializer<O>) {
+        for component in self.components.iter_mut().filter(|c| c.is_nervous_component()) {
+            component.nervous_init(initializer);
+        }
+    }
+
+    fn nervous_connector(&mut self) -> &mut NervousConnector<O> {
+        &mut self.nervous_connector
+    }
+}
+
+impl<O: Organism> SimComponent<O> for SequentialComponent<O> {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn attach(self, registry: &mut ComponentRegistry<O>) {
+        registry.add_wrapped_component(Box::new(self))
+    }
+
+    fn run(&mut self) {
+        let mut components = std::mem::take(&mut self.components);
+        for component in components.iter_mut() {
+            // Lend the shared connectors to the sub-component for its run,
+            // then take them back along with any changes it made
+            self.swap_connectors(component);
+            component.run();
+            self.swap_connectors(component);
+        }
+        self.components = components;
+    }
+
+    fn reset(&mut self) {
+        for component in self.components.iter_mut() {
+            component.reset();
+        }
+    }
+
+    fn phase(&self) -> Phase {
+        self.components.iter().map(|component| component.phase()).min().unwrap_or_default()
+    }
+}
+
+impl<O: Organism> ComponentWrapper<O> for SequentialComponent<O> {
+    fn is_core_component(&self) -> bool {
+        self.has_layer(&LayerType::Core)
+    }
+
+    fn is_circulation_component(&self) -> bool {
+        self.has_layer(&LayerType::Circulation)
+    }
+
+    fn is_digestion_component(&self) -> bool {
+        self.has_layer(&LayerType::Digestion)
+    }
+
+    fn is_nervous_component(&self) -> bool {
+        self.has_layer(&LayerType::Nervous)
+    }
+
+    fn has_layer(&self, layer_type: &LayerType) -> bool {
+        self.components.iter().any(|c| c.has_layer(layer_type))
+    }
+}
+
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::event::test::TestEventA;
+    use crate::sim::component::{ComponentRegistry, Phase, SimComponent};
+    use crate::sim::layer::circulation::{CirculationComponent, CirculationConnector, CirculationInitializer};
+    use crate::sim::layer::core::{CoreComponent, CoreConnector, CoreInitializer};
+    use crate::sim::organism::test::{TestBloodVessel, TestOrganism, TestSim};
+    use crate::sim::Sim;
+    use crate::units::base::Distance;
+    use crate::SimTimeSpan;
+
+    use super::SequentialComponent;
+
+    type RunLog = Arc<Mutex<Vec<&'static str>>>;
+
+    /// Records its id each time it runs
+    struct CoreStep {
+        id: &'static str,
+        log: RunLog,
+        connector: CoreConnector<TestOrganism>,
+    }
+
+    impl CoreComponent<TestOrganism> for CoreStep {
+        fn core_init(&mut self, initializer: &mut CoreInitializer<TestOrganism>) {
+            initializer.notify::<TestEventA>();
+        }
+
+        fn core_connector(&mut self) -> &mut CoreConnector<TestOrganism> {
+            &mut self.connector
+        }
+    }
+
+    impl SimComponent<TestOrganism> for CoreStep {
+        fn id(&self) -> &'static str {
+            self.id
+        }
+
+        fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+            registry.add_core_component(self)
+        }
+
+        fn run(&mut self) {
+            self.log.lock().unwrap().push(self.id);
+        }
+    }
+
+    /// Records its id each time it runs, in the Sense phase
+    struct CirculationStep {
+        log: RunLog,
+        connector: CirculationConnector<TestOrganism>,
+    }
+
+    impl CirculationComponent<TestOrganism> for CirculationStep {
+        fn circulation_init(&mut self, initializer: &mut CirculationInitializer<TestOrganism>) {
+            initializer.attach_vessel(TestBloodVessel::VenaCava);
+        }
+
+        fn circulation_connector(&mut self) -> &mut CirculationConnector<TestOrganism> {
+            &mut self.connector
+        }
+    }
+
+    impl SimComponent<TestOrganism> for CirculationStep {
+        fn id(&self) -> &'static str {
+            "CirculationStep"
+        }
+
+        fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+            registry.add_circulation_component(self)
+        }
+
+        fn run(&mut self) {
+            assert!(self.connector.blood_store(&TestBloodVessel::VenaCava).is_some());
+            self.log.lock().unwrap().push("CirculationStep");
+        }
+
+        fn phase(&self) -> Phase {
+            Phase::Sense
+        }
+    }
+
+    #[test]
+    fn runs_in_order() {
+        let log = RunLog::default();
+        let core_step = |id| CoreStep {
+            id,
+            log: log.clone(),
+            connector: CoreConnector::new(),
+        };
+
+        let sequence = SequentialComponent::new("Sequence")
+            .then(core_step("Metabolism"))
+            .then(CirculationStep {
+                log: log.clone(),
+                connector: CirculationConnector::new(),
+            })
+            .then(core_step("Saturation"));
+
+        let mut sim = TestSim::new();
+        sim.add_component(sequence).unwrap();
+
+        // Skip past the initial run of each component
+        sim.advance_by(SimTimeSpan::from_s(1.0));
+        log.lock().unwrap().clear();
+
+        for _ in 0..3 {
+            sim.schedule_event(SimTimeSpan::from_s(0.5), Box::new(TestEventA::new(Distance::from_m(1.0))));
+            sim.advance_by(SimTimeSpan::from_s(1.0));
+            assert_eq!(
+                std::mem::take(&mut *log.lock().unwrap()),
+                vec!["Metabolism", "CirculationStep", "Saturation"]
+            );
+        }
+    }
+
+    #[test]
+    fn earliest_phase() {
+        let log = RunLog::default();
+        let core_step = |id| CoreStep {
+            id,
+            log: log.clone(),
+            connector: CoreConnector::new(),
+        };
+
+        assert_eq!(SequentialComponent::<TestOrganism>::new("Empty").phase(), Phase::Decide);
+        assert_eq!(SequentialComponent::new("Core").then(core_step("Metabolism")).phase(), Phase::Decide);
+
+        let sequence = SequentialComponent::new("Sequence")
+            .then(core_step("Metabolism"))
+            .then(CirculationStep {
+                log: log.clone(),
+                connector: CirculationConnector::new(),
+            });
+        assert_eq!(sequence.phase(), Phase::Sense);
+    }
+}
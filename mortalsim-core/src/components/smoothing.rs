@@ -0,0 +1,226 @@
+use std::marker::PhantomData;
+
+use crate::event::Event;
+use crate::sim::component::{ComponentRegistry, SimComponent};
+use crate::sim::layer::core::{CoreComponent, CoreConnector, CoreInitializer};
+use crate::sim::{Organism, SimTime};
+use crate::SimTimeSpan;
+
+use super::SlidingWindow;
+
+type ExtractFn<E> = Box<dyn Fn(&E) -> f64 + Send>;
+type ApplyFn<E> = Box<dyn Fn(&E, f64) -> E + Send>;
+
+/// Smoothed variant of an `Event`, emitted by a `SmoothingComponent`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Smoothed<E>(pub E);
+
+impl<E: Event> Event for Smoothed<E> {
+    fn transient(&self) -> bool {
+        false
+    }
+}
+
+/// Internal event used to schedule periodic updates
+#[derive(Debug, Clone, Copy)]
+struct SmoothingTick<E>(PhantomData<E>);
+
+impl<E: Event> Event for SmoothingTick<E> {}
+
+/// Filter applied by a `SmoothingComponent`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SmoothingFilter {
+    /// Exponential moving average, which responds to a step change
+    /// by closing ~63% of the gap every time constant
+    Exponential { time_constant: SimTimeSpan },
+    /// Time-weighted mean over a trailing window
+    MovingAverage { window: SimTimeSpan },
+}
+
+/// Smooths a scalar value of the latest `Event` of type `E`, such as
+/// a vital sign from an ODE solution or a noisy producer, and emits
+/// the result as a `Smoothed<E>` event. The value is resampled
+/// periodically so the output keeps converging between inputs.
+pub struct SmoothingComponent<O: Organism, E: Event> {
+    id: &'static str,
+    filter: SmoothingFilter,
+    update_period: SimTimeSpan,
+    extract: ExtractFn<E>,
+    apply: ApplyFn<E>,
+    /// Input value and time of the last run
+    last_input: Option<(SimTime, f64)>,
+    /// Current exponential moving average
+    average: f64,
+    window: SlidingWindow,
+    /// Time of the next scheduled update
+    next_update: Option<SimTime>,
+    connector: CoreConnector<O>,
+}
+
+impl<O: Organism, E: Event> SmoothingComponent<O, E> {
+    /// Creates a new SmoothingComponent
+    ///
+    /// ### Arguments
+    /// * `id`            - Component id
+    /// * `filter`        - Filter to apply
+    /// * `update_period` - Time between updates of the smoothed value
+    /// * `extract`       - Function retrieving the value to smooth from the `Event`
+    /// * `apply`         - Function producing a copy of the `Event` with the smoothed value
+    pub fn new(
+        id: &'static str,
+        filter: SmoothingFilter,
+        update_period: SimTimeSpan,
+        extract: impl Fn(&E) -> f64 + Send + 'static,
+        apply: impl Fn(&E, f64) -> E + Send + 'static,
+    ) -> Self {
+        let mut connector = CoreConnector::new();
+
+        // Update ticks are managed here rather than rescheduled each run
+        connector.unschedule_all(false);
+
+        let window = match filter {
+            SmoothingFilter::MovingAverage { window } => window,
+            SmoothingFilter::Exponential { .. } => SimTimeSpan::from_s(0.0),
+        };
+
+        Self {
+            id,
+            filter,
+            update_period,
+            extract: Box::new(extract),
+            apply: Box::new(apply),
+            last_input: None,
+            average: 0.0,
+            window: SlidingWindow::new(window),
+            next_update: None,
+            connector,
+        }
+    }
+}
+
+impl<O: Organism, E: Event> CoreComponent<O> for SmoothingComponent<O, E> {
+    fn core_init(&mut self, initializer: &mut CoreInitializer<O>) {
+        initializer.notify::<E>();
+        initializer.notify::<SmoothingTick<E>>();
+        initializer.produces::<Smoothed<E>>();
+    }
+
+    fn core_connector(&mut self) -> &mut CoreConnector<O> {
+        &mut self.connector
+    }
+}
+
+impl<O: Organism, E: Event> SimComponent<O> for SmoothingComponent<O, E> {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn attach(self, registry: &mut ComponentRegistry<O>) {
+        registry.add_core_component(self)
+    }
+
+    fn run(&mut self) {
+        let sim_time = self.connector.sim_time();
+        let Some(input) = self.connector.get::<E>() else {
+            return;
+        };
+        let value = (self.extract)(input);
+        if !value.is_finite() {
+            return;
+        }
+
+        let output = match self.filter {
+            SmoothingFilter::Exponential { time_constant } => {
+                // The previous input was in effect since the last run
+                self.average = match self.last_input {
+                    Some((time, last)) => {
+                        let alpha = 1.0 - (-(sim_time - time).to_s() / time_constant.to_s()).exp();
+                        self.average + (last - self.average) * alpha
+                    }
+                    None => value,
+                };
+                self.average
+            }
+            SmoothingFilter::MovingAverage { .. } => {
+                self.window.push(sim_time, value);
+                self.window.stats().map_or(value, |stats| stats.mean)
+            }
+        };
+        self.last_input = Some((sim_time, value));
+
+        let smoothed = Smoothed((self.apply)(input, output));
+        self.connector.schedule_event(SimTimeSpan::from_s(0.0), smoothed);
+
+        if self.next_update.map_or(true, |t| sim_time >= t) {
+            self.connector.schedule_event(self.update_period, SmoothingTick::<E>(PhantomData));
+            self.next_update = Some(sim_time + self.update_period);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.last_input = None;
+        self.average = 0.0;
+        self.window.clear();
+        self.next_update = None;
+    }
+}
+
+mod tests {
+    use crate::event::HeartRate;
+    use crate::sim::organism::test::{TestOrganism, TestSim};
+    use crate::sim::{Sim, SimTime};
+    use crate::units::mechanical::Frequency;
+    use crate::SimTimeSpan;
+
+    use super::{Smoothed, SmoothingComponent, SmoothingFilter};
+
+    /// Smoothed heart rate (Hz) each second, with a step from 1 Hz to
+    /// 2 Hz at 10 s
+    fn smoothed_heart_rate(filter: SmoothingFilter) -> Vec<(SimTime, f64)> {
+        let mut sim = TestSim::new();
+        sim.add_component(SmoothingComponent::<TestOrganism, HeartRate>::new(
+            "HeartRateSmoothing",
+            filter,
+            SimTimeSpan::from_s(0.5),
+            |hr| hr.0.Hz,
+            |_, hz| HeartRate(Frequency::from_Hz(hz)),
+        )).unwrap();
+
+        sim.schedule_event(SimTimeSpan::from_s(0.0), Box::new(HeartRate(Frequency::from_Hz(1.0))));
+        sim.schedule_event(SimTimeSpan::from_s(10.0), Box::new(HeartRate(Frequency::from_Hz(2.0))));
+
+        let mut values = Vec::new();
+        for _ in 0..30 {
+            sim.advance_by(SimTimeSpan::from_s(1.0));
+            let smoothed = sim.state().get_state::<Smoothed<HeartRate>>().unwrap();
+            values.push((sim.time(), smoothed.0.0.Hz));
+        }
+        values
+    }
+
+    #[test]
+    fn exponential_step_response() {
+        let time_constant = 2.0;
+        let values = smoothed_heart_rate(SmoothingFilter::Exponential {
+            time_constant: SimTimeSpan::from_s(time_constant),
+        });
+
+        for (time, hz) in values {
+            let elapsed = time.to_s() - 10.0;
+            let expected = if elapsed <= 0.0 { 1.0 } else { 2.0 - (-elapsed / time_constant).exp() };
+            assert!((hz - expected).abs() < 1e-9, "{} at {:?}, expected {}", hz, time, expected);
+        }
+    }
+
+    #[test]
+    fn moving_average_step_response() {
+        let values = smoothed_heart_rate(SmoothingFilter::MovingAverage { window: SimTimeSpan::from_s(4.0) });
+
+        let at = |s: f64| values.iter().find(|(t, _)| *t == SimTime::from_s(s)).unwrap().1;
+        assert_eq!(at(9.0), 1.0);
+        // Halfway through the window, plus the interpolated ramp
+        // from the last sample before the step
+        assert!(at(12.0) > 1.5 && at(12.0) < 1.7, "{}", at(12.0));
+        assert_eq!(at(15.0), 2.0);
+    }
+}
@@ -0,0 +1,252 @@
+use crate::event::RespiratoryRate;
+use crate::math::BoundFn;
+use crate::sim::component::{ComponentRegistry, SimComponent};
+use crate::sim::layer::circulation::{BloodVessel, CirculationComponent, CirculationConnector, CirculationInitializer};
+use crate::sim::layer::core::{CoreComponent, CoreConnector, CoreInitializer};
+use crate::sim::{Organism, SimTime};
+use crate::substance::{Substance, SubstanceChange, SubstanceConcentration};
+use crate::SimTimeSpan;
+
+use super::periodic::PeriodicUpdate;
+use super::{CO2_SOLUBILITY, DEFAULT_RESPIRATORY_QUOTIENT, O2_SOLUBILITY};
+
+/// Normal arterial CO2 partial pressure at the baseline respiratory rate (mmHg)
+pub const NORMAL_PA_CO2: f64 = 40.0;
+
+/// Partial pressure of O2 in humidified inspired air at sea level (mmHg)
+pub const INSPIRED_PO2: f64 = 150.0;
+
+/// Default time constant for CO2 to approach its equilibrium (s)
+const DEFAULT_CLEARANCE_TIME: f64 = 10.0;
+
+/// Coarse ventilation which clears CO2 at the pulmonary loopback, i.e.
+/// each terminal vessel where the systemic circulation returns through
+/// the lungs. Alveolar ventilation is taken to be proportional to the
+/// `RespiratoryRate`, so the equilibrium CO2 concentration is inversely
+/// proportional to the rate relative to the baseline, at which it is
+/// `NORMAL_PA_CO2`. CO2 above the equilibrium is cleared exponentially,
+/// while CO2 below it is left for metabolism to replenish. Optionally,
+/// O2 is also loaded toward its alveolar equilibrium, with its own time
+/// constant, so each gas approaches its target without overshooting.
+pub struct VentilationComponent<O: Organism> {
+    baseline_rate: RespiratoryRate,
+    /// Respiratory rate currently in effect
+    rate: RespiratoryRate,
+    clearance_time: SimTimeSpan,
+    /// Time constant for O2 to approach its equilibrium, if O2 is exchanged
+    o2_exchange_time: Option<SimTimeSpan>,
+    updates: PeriodicUpdate,
+    core_connector: CoreConnector<O>,
+    circ_connector: CirculationConnector<O>,
+}
+
+impl<O: Organism> VentilationComponent<O> {
+    /// Creates a new VentilationComponent
+    ///
+    /// ### Arguments
+    /// * `baseline_rate` - Respiratory rate at which arterial CO2 is normal
+    pub fn new(baseline_rate: RespiratoryRate) -> Self {
+        let mut circ_connector = CirculationConnector::new();
+
+        // Changes accumulate rather than being replaced each run
+        circ_connector.unschedule_all(false);
+
+        Self {
+            baseline_rate,
+            rate: baseline_rate,
+            clearance_time: SimTimeSpan::from_s(DEFAULT_CLEARANCE_TIME),
+            o2_exchange_time: None,
+            updates: PeriodicUpdate::new(SimTimeSpan::from_s(1.0)),
+            core_connector: CoreConnector::new(),
+            circ_connector,
+        }
+    }
+
+    /// Sets the time constant for CO2 to approach its equilibrium
+    ///
+    /// ### Arguments
+    /// * `clearance_time` - Time constant of CO2 clearance
+    pub fn with_clearance_time(mut self, clearance_time: SimTimeSpan) -> Self {
+        self.clearance_time = clearance_time;
+        self
+    }
+
+    /// Exchanges both CO2 and O2 at the pulmonary loopback, each
+    /// approaching its equilibrium with its own time constant
+    ///
+    /// Panics if either time constant isn't positive
+    ///
+    /// ### Arguments
+    /// * `co2_time` - Time constant of CO2 clearance
+    /// * `o2_time`  - Time constant of O2 loading
+    pub fn with_pulmonary_exchange(mut self, co2_time: SimTimeSpan, o2_time: SimTimeSpan) -> Self {
+        assert!(co2_time.to_s() > 0.0, "CO2 time constant must be greater than 0");
+        assert!(o2_time.to_s() > 0.0, "O2 time constant must be greater than 0");
+        self.clearance_time = co2_time;
+        self.o2_exchange_time = Some(o2_time);
+        self
+    }
+
+    /// Equilibrium CO2 concentration at the current respiratory rate.
+    /// Invalid rates, including a baseline rate which isn't positive,
+    /// yield the normal concentration.
+    pub fn equilibrium_co2(&self) -> SubstanceConcentration {
+        let normal = NORMAL_PA_CO2 * CO2_SOLUBILITY;
+        let ratio = self.rate.as_ref().Hz / self.baseline_rate.as_ref().Hz;
+        if ratio.is_finite() && ratio > 0.0 {
+            SubstanceConcentration::from_mM(normal / ratio)
+        } else {
+            SubstanceConcentration::from_mM(normal)
+        }
+    }
+
+    /// Equilibrium O2 concentration at the current respiratory rate, from
+    /// the alveolar gas equation with the equilibrium CO2
+    pub fn equilibrium_o2(&self) -> SubstanceConcentration {
+        let pa_co2 = self.equilibrium_co2().to_mM() / CO2_SOLUBILITY;
+        let pa_o2 = (INSPIRED_PO2 - pa_co2 / DEFAULT_RESPIRATORY_QUOTIENT).max(0.0);
+        SubstanceConcentration::from_mM(pa_o2 * O2_SOLUBILITY)
+    }
+
+    /// Schedules clearance of CO2 above the equilibrium, and loading of
+    /// O2 below it if enabled, on each terminal vessel over the next
+    /// update period
+    fn exchange(&mut self, sim_time: SimTime) {
+        let co2_equilibrium = self.equilibrium_co2();
+        let update_period = self.updates.period();
+        let co2_fraction = 1.0 - (-update_period.to_s() / self.clearance_time.to_s()).exp();
+        let o2_exchange = self.o2_exchange_time.map(|o2_time| {
+            (self.equilibrium_o2(), 1.0 - (-update_period.to_s() / o2_time.to_s()).exp())
+        });
+
+        self.circ_connector.with_blood_stores(|vessel, store| {
+            if vessel.downstream().len() > 0 {
+                return;
+            }
+            let excess = store.concentration_of(&Substance::CO2) - co2_equilibrium;
+            if excess.to_mM() > 0.0 {
+                store.schedule_custom_change(
+                    Substance::CO2,
+                    SubstanceChange::new(sim_time, -excess * co2_fraction, update_period, BoundFn::Linear),
+                );
+            }
+            if let Some((o2_equilibrium, o2_fraction)) = o2_exchange {
+                let deficit = o2_equilibrium - store.concentration_of(&Substance::O2);
+                if deficit.to_mM() > 0.0 {
+                    store.schedule_custom_change(
+                        Substance::O2,
+                        SubstanceChange::new(sim_time, deficit * o2_fraction, update_period, BoundFn::Linear),
+                    );
+                }
+            }
+        });
+    }
+}
+
+impl<O: Organism> CoreComponent<O> for VentilationComponent<O> {
+    fn core_init(&mut self, initializer: &mut CoreInitializer<O>) {
+        initializer.notify::<RespiratoryRate>();
+    }
+
+    fn core_connector(&mut self) -> &mut CoreConnector<O> {
+        &mut self.core_connector
+    }
+}
+
+impl<O: Organism> CirculationComponent<O> for VentilationComponent<O> {
+    fn circulation_init(&mut self, initializer: &mut CirculationInitializer<O>) {
+        initializer.attach_all_vessels();
+        initializer.manage_substance(Substance::CO2);
+        if self.o2_exchange_time.is_some() {
+            initializer.manage_substance(Substance::O2);
+        }
+    }
+
+    fn circulation_connector(&mut self) -> &mut CirculationConnector<O> {
+        &mut self.circ_connector
+    }
+}
+
+impl<O: Organism> SimComponent<O> for VentilationComponent<O> {
+    fn id(&self) -> &'static str {
+        "VentilationComponent"
+    }
+
+    fn attach(self, registry: &mut ComponentRegistry<O>) {
+        registry.add_core_circulation_component(self)
+    }
+
+    fn run(&mut self) {
+        if let Some(rate) = self.core_connector.get::<RespiratoryRate>() {
+            self.rate = *rate;
+        }
+
+        // Clearance is only applied when the update timer expires, so
+        // that runs triggered by rate changes don't clear more than once
+        if let Some((sim_time, _)) = self.updates.due(&mut self.core_connector) {
+            self.exchange(sim_time);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.rate = self.baseline_rate;
+        self.updates.reset();
+    }
+}
+
+mod tests {
+    use crate::event::RespiratoryRate;
+    use crate::sim::layer::circulation::component::test::TestBolusComponent;
+    use crate::sim::organism::test::{TestBloodVessel, TestOrganism, TestSim};
+    use crate::sim::Sim;
+    use crate::substance::{Substance, SubstanceConcentration};
+    use crate::units::mechanical::Frequency;
+    use crate::SimTimeSpan;
+
+    use super::VentilationComponent;
+
+    #[test]
+    fn gases_equilibrate_at_their_own_rates() {
+        let ventilation = VentilationComponent::<TestOrganism>::new(RespiratoryRate(Frequency::from_Hz(0.2)))
+            .with_pulmonary_exchange(SimTimeSpan::from_s(5.0), SimTimeSpan::from_s(20.0));
+        let co2_target = ventilation.equilibrium_co2().to_mM();
+        let o2_target = ventilation.equilibrium_o2().to_mM();
+
+        // Around 100 mmHg of O2 at a normal PaCO2
+        assert!((o2_target / super::O2_SOLUBILITY - 100.0).abs() < 1.0, "{}", o2_target);
+
+        let mut sim = TestSim::new();
+        sim.add_component(TestBolusComponent::new(
+            TestBloodVessel::VenaCava,
+            Substance::CO2,
+            SubstanceConcentration::from_mM(2.0),
+            SimTimeSpan::from_s(0.1),
+        )).unwrap();
+        sim.add_component(ventilation).unwrap();
+
+        // Fraction of the initial gap to the target which has been closed
+        let mut progress = Vec::new();
+        for _ in 0..60 {
+            sim.advance_by(SimTimeSpan::from_s(1.0));
+            sim.inspect_circulation("TestBolusComponent", |connector| {
+                let store = connector.blood_store(&TestBloodVessel::VenaCava).unwrap();
+                let co2 = store.concentration_of(&Substance::CO2).to_mM();
+                let o2 = store.concentration_of(&Substance::O2).to_mM();
+                progress.push(((2.0 - co2) / (2.0 - co2_target), o2 / o2_target));
+            }).unwrap();
+        }
+
+        // Updates are driven by a timer, so nothing is emitted on the Sim
+        assert_eq!(sim.drain_active().count(), 0);
+
+        // CO2 equilibrates faster than O2
+        let (co2_progress, o2_progress) = progress[10];
+        assert!(co2_progress > 0.75 && o2_progress < 0.5, "{:?}", progress[10]);
+        assert!(progress[59].0 > 0.99 && progress[59].1 > 0.9, "{:?}", progress[59]);
+
+        // and neither overshoots its target
+        for (co2_progress, o2_progress) in progress.iter() {
+            assert!(*co2_progress <= 1.0 && *o2_progress <= 1.0, "{:?}", progress);
+        }
+    }
+}
@@ -0,0 +1,360 @@
+use std::collections::VecDeque;
+
+use crate::event::Event;
+use crate::sim::component::{ComponentRegistry, SimComponent};
+use crate::sim::layer::circulation::{CirculationComponent, CirculationConnector, CirculationInitializer};
+use crate::sim::layer::core::{CoreComponent, CoreConnector, CoreInitializer};
+use crate::sim::{Organism, SimTime};
+use crate::substance::{Substance, SubstanceConcentration};
+use crate::SimTimeSpan;
+
+/// Summary statistics of a value over a window of time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowStats {
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    pub stddev: f64,
+}
+
+/// Sliding window of timestamped samples. Values are linearly
+/// interpolated between samples, so the mean and standard deviation
+/// are weighted by time rather than by the number of samples.
+#[derive(Debug, Clone)]
+pub struct SlidingWindow {
+    /// Length of the window
+    window: SimTimeSpan,
+    /// Samples within the window, plus the last one preceding it
+    samples: VecDeque<(SimTime, f64)>,
+}
+
+impl SlidingWindow {
+    pub fn new(window: SimTimeSpan) -> Self {
+        Self {
+            window,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Adds a sample to the window, dropping any samples which have
+    /// moved out of it. Samples must be added in time order.
+    ///
+    /// ### Arguments
+    /// * `time`  - Simulation time of the sample
+    /// * `value` - Sampled value
+    pub fn push(&mut self, time: SimTime, value: f64) {
+        self.samples.push_back((time, value));
+
+        // Keep one sample at or before the window start for interpolation
+        let start = time - self.window;
+        while self.samples.len() > 1 && self.samples[1].0 <= start {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Removes all samples from the window
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Statistics over the window ending at the most recent sample
+    ///
+    /// Returns `None` if no samples have been added
+    pub fn stats(&self) -> Option<WindowStats> {
+        let (end, last) = *self.samples.back()?;
+        let start = end - self.window;
+
+        let mut points: Vec<(f64, f64)> = Vec::with_capacity(self.samples.len());
+        for (i, (t, v)) in self.samples.iter().enumerate() {
+            if *t >= start {
+                points.push((t.to_s(), *v));
+            } else if let Some((next_t, next_v)) = self.samples.get(i + 1) {
+                // Interpolate the value at the window start
+                if *next_t > start {
+                    let frac = (start.to_s() - t.to_s()) / (next_t.to_s() - t.to_s());
+                    points.push((start.to_s(), v + (next_v - v) * frac));
+                }
+            }
+        }
+
+        let duration = points.last().unwrap().0 - points[0].0;
+        let min = points.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+        let max = points.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max);
+
+        if duration <= 0.0 {
+            return Some(WindowStats { mean: last, min, max, stddev: 0.0 });
+        }
+
+        // Integrate each linear segment for the first and second moments
+        let (mut sum, mut sum_sq) = (0.0, 0.0);
+        for pair in points.windows(2) {
+            let ((t0, a), (t1, b)) = (pair[0], pair[1]);
+            sum += (t1 - t0) * (a + b) / 2.0;
+            sum_sq += (t1 - t0) * (a * a + a * b + b * b) / 3.0;
+        }
+
+        let mean = sum / duration;
+        let variance = (sum_sq / duration - mean * mean).max(0.0);
+
+        Some(WindowStats {
+            mean,
+            min,
+            max,
+            stddev: variance.sqrt(),
+        })
+    }
+}
+
+/// Sliding window statistics of a `Substance` in a `BloodVessel`,
+/// emitted by a `WindowedStatComponent` each time it samples
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowedStats {
+    pub vessel: &'static str,
+    pub substance: Substance,
+    pub mean: SubstanceConcentration,
+    pub min: SubstanceConcentration,
+    pub max: SubstanceConcentration,
+    pub stddev: SubstanceConcentration,
+}
+
+impl Event for WindowedStats {}
+
+/// Internal event used to schedule periodic sampling
+#[derive(Debug, Clone, Copy)]
+struct WindowedStatTick;
+
+impl Event for WindowedStatTick {}
+
+/// Tracks the concentration of a `Substance` in a `BloodVessel`,
+/// periodically sampling it and emitting `WindowedStats` over a
+/// sliding window of time.
+pub struct WindowedStatComponent<O: Organism> {
+    id: &'static str,
+    vessel: O::VesselType,
+    substance: Substance,
+    sample_period: SimTimeSpan,
+    window: SlidingWindow,
+    /// Time of the next scheduled sample
+    next_sample: Option<SimTime>,
+    core_connector: CoreConnector<O>,
+    circ_connector: CirculationConnector<O>,
+}
+
+impl<O: Organism> WindowedStatComponent<O> {
+    /// Creates a new component tracking the given substance
+    ///
+    /// ### Arguments
+    /// * `vessel`        - Vessel to sample
+    /// * `substance`     - Substance to sample
+    /// * `window`        - Length of the sliding window
+    /// * `sample_period` - Time between samples
+    pub fn new(
+        vessel: O::VesselType,
+        substance: Substance,
+        window: SimTimeSpan,
+        sample_period: SimTimeSpan,
+    ) -> Self {
+        let vessel_name: &'static str = vessel.into();
+        let mut core_connector = CoreConnector::new();
+
+        // Sampling ticks are managed here rather than rescheduled each run
+        core_connector.unschedule_all(false);
+
+        Self {
+            id: format!("WindowedStatComponent({}, {})", vessel_name, substance).leak(),
+            vessel,
+            substance,
+            sample_period,
+            window: SlidingWindow::new(window),
+            next_sample: None,
+            core_connector,
+            circ_connector: CirculationConnector::new(),
+        }
+    }
+}
+
+impl<O: Organism> CoreComponent<O> for WindowedStatComponent<O> {
+    fn core_init(&mut self, initializer: &mut CoreInitializer<O>) {
+        initializer.notify::<WindowedStatTick>();
+        initializer.produces::<WindowedStats>();
+    }
+
+    fn core_connector(&mut self) -> &mut CoreConnector<O> {
+        &mut self.core_connector
+    }
+}
+
+impl<O: Organism> CirculationComponent<O> for WindowedStatComponent<O> {
+    fn circulation_init(&mut self, initializer: &mut CirculationInitializer<O>) {
+        initializer.attach_vessel(self.vessel);
+    }
+
+    fn circulation_connector(&mut self) -> &mut CirculationConnector<O> {
+        &mut self.circ_connector
+    }
+}
+
+impl<O: Organism> SimComponent<O> for WindowedStatComponent<O> {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn attach(self, registry: &mut ComponentRegistry<O>) {
+        registry.add_core_circulation_component(self)
+    }
+
+    fn run(&mut self) {
+        let sim_time = self.circ_connector.sim_time();
+        let concentration = match self.circ_connector.blood_store(&self.vessel) {
+            Some(store) => store.concentration_of(&self.substance),
+            None => return,
+        };
+        self.window.push(sim_time, concentration.to_mM());
+
+        if self.next_sample.map_or(true, |t| sim_time >= t) {
+            self.core_connector.schedule_event(self.sample_period, WindowedStatTick);
+            self.next_sample = Some(sim_time + self.sample_period);
+        }
+
+        if let Some(stats) = self.window.stats() {
+            self.core_connector.schedule_event(
+                SimTimeSpan::from_s(0.0),
+                WindowedStats {
+                    vessel: self.vessel.into(),
+                    substance: self.substance,
+                    mean: SubstanceConcentration::from_mM(stats.mean),
+                    min: SubstanceConcentration::from_mM(stats.min),
+                    max: SubstanceConcentration::from_mM(stats.max),
+                    stddev: SubstanceConcentration::from_mM(stats.stddev),
+                },
+            );
+        }
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+        self.next_sample = None;
+    }
+}
+
+mod tests {
+    use crate::event::Event;
+    use crate::math::BoundFn;
+    use crate::sim::component::{ComponentRegistry, SimComponent};
+    use crate::sim::layer::circulation::{CirculationComponent, CirculationConnector, CirculationInitializer};
+    use crate::sim::organism::test::{TestBloodVessel, TestOrganism, TestSim};
+    use crate::sim::{Sim, SimTime};
+    use crate::substance::{Substance, SubstanceChange, SubstanceConcentration};
+    use crate::SimTimeSpan;
+
+    use super::{SlidingWindow, WindowedStatComponent, WindowedStats};
+
+    /// Drives a square wave of O2 in the Aorta, alternating
+    /// between 0 and 100 uM every 10 seconds
+    struct SquareWaveComponent {
+        circ_connector: CirculationConnector<TestOrganism>,
+        scheduled: bool,
+    }
+
+    impl CirculationComponent<TestOrganism> for SquareWaveComponent {
+        fn circulation_init(&mut self, initializer: &mut CirculationInitializer<TestOrganism>) {
+            initializer.attach_vessel(TestBloodVessel::Aorta);
+        }
+
+        fn circulation_connector(&mut self) -> &mut CirculationConnector<TestOrganism> {
+            &mut self.circ_connector
+        }
+    }
+
+    impl SimComponent<TestOrganism> for SquareWaveComponent {
+        fn id(&self) -> &'static str {
+            "SquareWaveComponent"
+        }
+
+        fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+            registry.add_circulation_component(self)
+        }
+
+        fn run(&mut self) {
+            if self.scheduled {
+                return;
+            }
+            self.scheduled = true;
+            self.circ_connector.unschedule_all(false);
+
+            let mut store = self.circ_connector.blood_store(&TestBloodVessel::Aorta).unwrap();
+            for i in 1..10 {
+                let amount = if i % 2 == 1 { 100.0 } else { -100.0 };
+                store.schedule_custom_change(
+                    Substance::O2,
+                    SubstanceChange::new(
+                        SimTime::from_s(10.0 * i as f64),
+                        SubstanceConcentration::from_uM(amount),
+                        SimTimeSpan::from_s(0.1),
+                        BoundFn::Linear,
+                    ),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn sliding_window() {
+        let mut window = SlidingWindow::new(SimTimeSpan::from_s(10.0));
+        assert!(window.stats().is_none());
+
+        window.push(SimTime::from_s(0.0), 4.0);
+        let stats = window.stats().unwrap();
+        assert_eq!((stats.mean, stats.min, stats.max, stats.stddev), (4.0, 4.0, 4.0, 0.0));
+
+        // Linear ramp from 0 to 10
+        let mut window = SlidingWindow::new(SimTimeSpan::from_s(10.0));
+        window.push(SimTime::from_s(0.0), 0.0);
+        window.push(SimTime::from_s(10.0), 10.0);
+        let stats = window.stats().unwrap();
+        assert!((stats.mean - 5.0).abs() < 1e-9);
+        assert_eq!((stats.min, stats.max), (0.0, 10.0));
+        assert!((stats.stddev - (100.0_f64 / 12.0).sqrt()).abs() < 1e-9);
+
+        // Older samples fall out, interpolating at the window start
+        window.push(SimTime::from_s(15.0), 10.0);
+        let stats = window.stats().unwrap();
+        assert!((stats.mean - 8.75).abs() < 1e-9);
+        assert_eq!((stats.min, stats.max), (5.0, 10.0));
+    }
+
+    #[test]
+    fn windowed_stats() {
+        let mut sim = TestSim::new();
+        sim.add_component(SquareWaveComponent {
+            circ_connector: CirculationConnector::new(),
+            scheduled: false,
+        }).unwrap();
+        sim.add_component(WindowedStatComponent::<TestOrganism>::new(
+            TestBloodVessel::Aorta,
+            Substance::O2,
+            SimTimeSpan::from_s(20.0),
+            SimTimeSpan::from_s(1.0),
+        )).unwrap();
+
+        let mut latest = None;
+        for _ in 0..60 {
+            sim.advance_by(SimTimeSpan::from_s(1.0));
+            for evt in sim.drain_active() {
+                if let Some(stats) = evt.downcast_ref::<WindowedStats>() {
+                    latest = Some(*stats);
+                }
+            }
+        }
+
+        // Window spans one full period of the wave
+        let stats = latest.unwrap();
+        assert_eq!(stats.vessel, "Aorta");
+        assert_eq!(stats.substance, Substance::O2);
+        assert!((stats.mean.to_uM() - 50.0).abs() < 0.01, "mean {}", stats.mean);
+        assert!(stats.min.to_uM().abs() < 0.01, "min {}", stats.min);
+        assert!((stats.max.to_uM() - 100.0).abs() < 0.01, "max {}", stats.max);
+        // Sampling smooths the edges of the wave slightly
+        assert!((stats.stddev.to_uM() - 48.3).abs() < 0.1, "stddev {}", stats.stddev);
+    }
+}
@@ -0,0 +1,160 @@
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+use anyhow::Result;
+
+use super::Event;
+
+/// Serialization functions for a registered `Event` type
+struct EventCodec {
+    type_name: &'static str,
+    encode: fn(&dyn Event) -> Result<String>,
+    decode: fn(&str) -> Result<Box<dyn Event>>,
+}
+
+static CODECS: OnceLock<RwLock<HashMap<TypeId, EventCodec>>> = OnceLock::new();
+
+fn codecs() -> &'static RwLock<HashMap<TypeId, EventCodec>> {
+    CODECS.get_or_init(|| {
+        let codecs = RwLock::new(HashMap::new());
+        #[cfg(feature = "serde")]
+        {
+            use super::*;
+            let mut map = codecs.write().unwrap();
+            insert_codec::<HeartRate>(&mut map);
+            insert_codec::<CoreBodyTemp>(&mut map);
+            insert_codec::<AorticBloodPressure>(&mut map);
+            insert_codec::<PulmonaryBloodPressure>(&mut map);
+            insert_codec::<CardiacOutput>(&mut map);
+            insert_codec::<Hemodynamics>(&mut map);
+            insert_codec::<ArterialBloodGas>(&mut map);
+            insert_codec::<RespiratoryRate>(&mut map);
+            insert_codec::<Height>(&mut map);
+            insert_codec::<BodyMass>(&mut map);
+            insert_codec::<MetabolicRate>(&mut map);
+            insert_codec::<ConciousLevel>(&mut map);
+            insert_codec::<Arrhythmia>(&mut map);
+            insert_codec::<test::TestEventA>(&mut map);
+            insert_codec::<test::TestEventB>(&mut map);
+        }
+        codecs
+    })
+}
+
+#[cfg(feature = "serde")]
+fn insert_codec<E: Event + serde::Serialize + serde::de::DeserializeOwned>(
+    map: &mut HashMap<TypeId, EventCodec>,
+) {
+    map.insert(
+        TypeId::of::<E>(),
+        EventCodec {
+            type_name: std::any::type_name::<E>(),
+            encode: |event| {
+                let event = event
+                    .downcast_ref::<E>()
+                    .ok_or_else(|| anyhow!("Event isn't a {}", std::any::type_name::<E>()))?;
+                Ok(serde_json::to_string(event)?)
+            },
+            decode: |value| Ok(Box::new(serde_json::from_str::<E>(value)?)),
+        },
+    );
+}
+
+/// Registers an `Event` type for serialization, so that `Event`s of the
+/// type scheduled on a Sim can be saved with `Sim::save_to_file`. The
+/// built-in vital `Event`s are registered already.
+#[cfg(feature = "serde")]
+pub fn register_event<E: Event + serde::Serialize + serde::de::DeserializeOwned>() {
+    insert_codec::<E>(&mut codecs().write().unwrap());
+}
+
+/// Looks up the `TypeId` of a registered `Event` type
+///
+/// ### Arguments
+/// * `type_name` - Name of the type, as given by `std::any::type_name`
+pub fn registered_type_id(type_name: &str) -> Option<TypeId> {
+    codecs()
+        .read()
+        .unwrap()
+        .iter()
+        .find(|(_, codec)| codec.type_name == type_name)
+        .map(|(type_id, _)| *type_id)
+}
+
+/// Looks up the name of a registered `Event` type
+///
+/// ### Arguments
+/// * `type_id` - `TypeId` of the type
+pub fn registered_type_name(type_id: TypeId) -> Option<&'static str> {
+    codecs().read().unwrap().get(&type_id).map(|codec| codec.type_name)
+}
+
+/// `Event` serialized as JSON, along with the name of its type
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EncodedEvent {
+    pub type_name: String,
+    pub value: String,
+}
+
+impl EncodedEvent {
+    /// Serializes an `Event`
+    ///
+    /// ### Arguments
+    /// * `event` - `Event` to serialize
+    ///
+    /// Returns `None` if the type of the `Event` isn't registered or it
+    /// can't be serialized
+    pub fn encode(event: &dyn Event) -> Option<Self> {
+        let codecs = codecs().read().unwrap();
+        let codec = codecs.get(&event.as_any().type_id())?;
+        Some(Self {
+            type_name: codec.type_name.to_string(),
+            value: (codec.encode)(event).ok()?,
+        })
+    }
+
+    /// Deserializes a new instance of the `Event`
+    ///
+    /// Returns an Err Result if the type isn't registered or the value
+    /// is invalid
+    pub fn decode(&self) -> Result<Box<dyn Event>> {
+        let codecs = codecs().read().unwrap();
+        let codec = codecs
+            .values()
+            .find(|codec| codec.type_name == self.type_name)
+            .ok_or_else(|| anyhow!("Event type {} isn't registered for serialization", self.type_name))?;
+        (codec.decode)(&self.value)
+    }
+}
+
+#[cfg(feature = "serde")]
+mod tests {
+    use crate::event::test::TestEventA;
+    use crate::event::{Event, HeartRate};
+    use crate::units::base::Distance;
+    use crate::units::mechanical::Frequency;
+
+    use super::{registered_type_id, EncodedEvent};
+
+    #[test]
+    fn encode_decode() {
+        let encoded = EncodedEvent::encode(&HeartRate(Frequency::from_Hz(1.2))).unwrap();
+        assert_eq!(registered_type_id(&encoded.type_name), Some(std::any::TypeId::of::<HeartRate>()));
+        let decoded = encoded.decode().unwrap();
+        assert_eq!(decoded.downcast_ref::<HeartRate>(), Some(&HeartRate(Frequency::from_Hz(1.2))));
+    }
+
+    #[test]
+    fn unregistered_event() {
+        #[derive(Debug)]
+        struct Unregistered;
+        impl Event for Unregistered {}
+
+        assert!(EncodedEvent::encode(&Unregistered).is_none());
+        let encoded = EncodedEvent::encode(&TestEventA::new(Distance::from_m(1.0))).unwrap();
+        let renamed = EncodedEvent { type_name: "Unregistered".to_string(), ..encoded };
+        assert!(renamed.decode().is_err());
+    }
+}
@@ -6,6 +6,7 @@ use std::vec::Drain;
 mod vital;
 mod infection;
 mod wound;
+pub mod codec;
 
 pub use vital::*;
 pub use infection::*;
@@ -42,6 +43,7 @@ pub mod test {
     use crate::units::base::Distance;
 
     #[derive(Debug, Clone, Copy)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct TestEventA {
         pub len: Distance<f64>,
     }
@@ -59,6 +61,7 @@ pub mod test {
     }
 
     #[derive(Debug, Clone, Copy)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct TestEventB {
         pub amt: Amount<f64>,
     }
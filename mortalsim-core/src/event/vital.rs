@@ -3,7 +3,7 @@ use either::Either;
 
 use crate::sim::Organism;
 use crate::units::base::{Distance, Mass, Temperature};
-use crate::units::mechanical::{Frequency, Force, Pressure};
+use crate::units::mechanical::{Frequency, Force, Power, Pressure};
 
 use super::{Event, NumType};
 
@@ -37,16 +37,19 @@ macro_rules! unit_wrapper {
 
 /// Event indicating a change of heart contraction rate or pulse
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HeartRate(pub Frequency<NumType>);
 unit_wrapper!(HeartRate, Frequency<NumType>);
 
 /// Event indicating a change of core body temperature
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CoreBodyTemp(pub Temperature<NumType>);
 unit_wrapper!(CoreBodyTemp, Temperature<NumType>);
 
 /// Event indicating a change of aortic blood pressure
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AorticBloodPressure {
     pub systolic: Pressure<NumType>,
     pub diastolic: Pressure<NumType>,
@@ -60,6 +63,7 @@ impl Event for AorticBloodPressure {
 
 /// Event indicating a change of aortic blood pressure
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PulmonaryBloodPressure {
     pub systolic: Pressure<NumType>,
     pub diastolic: Pressure<NumType>,
@@ -71,24 +75,90 @@ impl Event for PulmonaryBloodPressure {
     }
 }
 
+/// Event indicating a change of cardiac output, in L/min
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CardiacOutput(pub NumType);
+
+impl Event for CardiacOutput {
+    fn transient(&self) -> bool {
+        false
+    }
+}
+
+/// Event bundling the hemodynamic vitals produced together, so that
+/// consumers get a coherent snapshot from a single read rather than
+/// reading each of the individual events
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hemodynamics {
+    pub aortic: AorticBloodPressure,
+    pub pulmonary: PulmonaryBloodPressure,
+    pub cardiac_output: CardiacOutput,
+}
+
+impl Event for Hemodynamics {
+    fn transient(&self) -> bool {
+        false
+    }
+}
+
+/// Event carrying an arterial blood gas panel
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ArterialBloodGas {
+    /// Partial pressure of O2
+    pub pa_o2: Pressure<NumType>,
+    /// Partial pressure of CO2
+    pub pa_co2: Pressure<NumType>,
+    /// Arterial pH
+    pub ph: NumType,
+}
+
+impl Event for ArterialBloodGas {
+    fn transient(&self) -> bool {
+        false
+    }
+}
+
+/// Event carrying the value of a named metric derived from other vitals,
+/// such as shock index
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DerivedVital {
+    pub name: &'static str,
+    pub value: NumType,
+}
+
+impl Event for DerivedVital {}
+
 /// Event indicating a change of respiration rate
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RespiratoryRate(pub Frequency<NumType>);
 unit_wrapper!(RespiratoryRate, Frequency<NumType>);
 
 /// Event indicating a change in height
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Height(pub Distance<NumType>);
 unit_wrapper!(Height, Distance<NumType>);
 
 /// Event indicating a change in total body mass
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BodyMass(pub Mass<NumType>);
 unit_wrapper!(BodyMass, Mass<NumType>);
 
+/// Event indicating a change of whole-body metabolic rate
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MetabolicRate(pub Power<NumType>);
+unit_wrapper!(MetabolicRate, Power<NumType>);
+
 /// Event indicating a change in level of consciousness
 /// See https://www.ncbi.nlm.nih.gov/books/NBK380/
 #[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, EnumCount, EnumIs, EnumIter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ConciousLevel {
     Alert,
     Clouded,
@@ -106,6 +176,7 @@ impl Event for ConciousLevel {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Arrhythmia {
     None,
     AtrialFibrillation,
@@ -16,6 +16,7 @@ pub use id_gen::{IdGenerator, IdType};
 pub use quantity::*;
 pub(crate) use util::*;
 
+pub mod components;
 pub mod event;
 pub mod hub;
 pub mod sim;
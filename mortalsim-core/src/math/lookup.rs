@@ -0,0 +1,335 @@
+use anyhow::Result;
+
+/// Interpolation method used between tabulated points
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    Linear,
+    /// Natural cubic spline, with zero curvature at either end
+    Cubic,
+}
+
+/// One dimensional table of tabulated points, such as a dose response
+/// or dissociation curve, interpolated between points. Lookups outside
+/// of the tabulated range are clamped to the end values unless
+/// extrapolation is enabled.
+#[derive(Debug, Clone)]
+pub struct LookupTable1D {
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    /// Second derivatives at each point, for cubic interpolation
+    curvature: Vec<f64>,
+    interpolation: Interpolation,
+    extrapolate: bool,
+}
+
+impl LookupTable1D {
+    /// Creates a new linearly interpolated LookupTable1D
+    ///
+    /// ### Arguments
+    /// * `points` - `(x, y)` pairs in any order
+    ///
+    /// Returns an Err Result if fewer than two points are provided, or
+    /// if any values are not finite or any x value is repeated
+    pub fn new(points: impl IntoIterator<Item = (f64, f64)>) -> Result<Self> {
+        let mut points: Vec<(f64, f64)> = points.into_iter().collect();
+
+        if points.len() < 2 {
+            return Err(anyhow!("LookupTable1D requires at least two points, got {}", points.len()));
+        }
+        if let Some((x, y)) = points.iter().find(|(x, y)| !x.is_finite() || !y.is_finite()) {
+            return Err(anyhow!("LookupTable1D point ({}, {}) is not finite", x, y));
+        }
+
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        if let Some(pair) = points.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+            return Err(anyhow!("LookupTable1D has repeated x value {}", pair[0].0));
+        }
+
+        let (xs, ys): (Vec<f64>, Vec<f64>) = points.into_iter().unzip();
+        Ok(Self {
+            curvature: vec![0.0; xs.len()],
+            xs,
+            ys,
+            interpolation: Interpolation::Linear,
+            extrapolate: false,
+        })
+    }
+
+    /// Sets the interpolation method between points
+    ///
+    /// ### Arguments
+    /// * `interpolation` - Interpolation method
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self.curvature = match interpolation {
+            Interpolation::Linear => vec![0.0; self.xs.len()],
+            Interpolation::Cubic => natural_spline(&self.xs, &self.ys),
+        };
+        self
+    }
+
+    /// Extrapolates lookups outside of the tabulated range along the
+    /// slope at the nearest end, rather than clamping to the end values
+    pub fn with_extrapolation(mut self) -> Self {
+        self.extrapolate = true;
+        self
+    }
+
+    /// Returns the tabulated range of x values
+    pub fn domain(&self) -> (f64, f64) {
+        (self.xs[0], self.xs[self.xs.len() - 1])
+    }
+
+    /// Interpolates the table at the given value
+    ///
+    /// ### Arguments
+    /// * `x` - Value to look up
+    ///
+    /// Returns the interpolated value
+    pub fn lookup(&self, x: f64) -> f64 {
+        let last = self.xs.len() - 1;
+        let (min, max) = self.domain();
+
+        if x < min {
+            return if self.extrapolate {
+                self.ys[0] + self.slope(0, 0.0) * (x - min)
+            } else {
+                self.ys[0]
+            };
+        }
+        if x > max {
+            return if self.extrapolate {
+                self.ys[last] + self.slope(last - 1, 1.0) * (x - max)
+            } else {
+                self.ys[last]
+            };
+        }
+
+        let hi = self.xs.partition_point(|v| *v <= x).clamp(1, last);
+        let lo = hi - 1;
+        let h = self.xs[hi] - self.xs[lo];
+        let b = (x - self.xs[lo]) / h;
+        let a = 1.0 - b;
+
+        a * self.ys[lo]
+            + b * self.ys[hi]
+            + ((a.powi(3) - a) * self.curvature[lo] + (b.powi(3) - b) * self.curvature[hi]) * h * h / 6.0
+    }
+
+    /// Slope of the interpolant within the segment starting at point `lo`
+    ///
+    /// ### Arguments
+    /// * `lo` - Index of the segment's first point
+    /// * `b`  - Fractional position within the segment
+    fn slope(&self, lo: usize, b: f64) -> f64 {
+        let hi = lo + 1;
+        let h = self.xs[hi] - self.xs[lo];
+        let a = 1.0 - b;
+
+        (self.ys[hi] - self.ys[lo]) / h
+            - (3.0 * a * a - 1.0) * h * self.curvature[lo] / 6.0
+            + (3.0 * b * b - 1.0) * h * self.curvature[hi] / 6.0
+    }
+}
+
+/// Second derivatives of a natural cubic spline through the given points
+///
+/// ### Arguments
+/// * `xs` - Sorted x values
+/// * `ys` - y values at each x
+fn natural_spline(xs: &[f64], ys: &[f64]) -> Vec<f64> {
+    let n = xs.len();
+    let mut curvature = vec![0.0; n];
+    let mut u = vec![0.0; n];
+
+    // Forward sweep of the tridiagonal system
+    for i in 1..n - 1 {
+        let sig = (xs[i] - xs[i - 1]) / (xs[i + 1] - xs[i - 1]);
+        let p = sig * curvature[i - 1] + 2.0;
+        curvature[i] = (sig - 1.0) / p;
+        let d = (ys[i + 1] - ys[i]) / (xs[i + 1] - xs[i]) - (ys[i] - ys[i - 1]) / (xs[i] - xs[i - 1]);
+        u[i] = (6.0 * d / (xs[i + 1] - xs[i - 1]) - sig * u[i - 1]) / p;
+    }
+
+    // Back substitution, with zero curvature at either end
+    curvature[n - 1] = 0.0;
+    for i in (0..n - 1).rev() {
+        curvature[i] = curvature[i] * curvature[i + 1] + u[i];
+    }
+    curvature
+}
+
+/// Two dimensional table of values tabulated on a grid, interpolated
+/// along each axis in turn. Clamping and extrapolation behave as for
+/// `LookupTable1D` along each axis.
+#[derive(Debug, Clone)]
+pub struct LookupTable2D {
+    /// Table along y for each x value
+    rows: Vec<LookupTable1D>,
+    /// Table along x for each x value, which is 1 at that value and 0 at
+    /// the others. Interpolation is linear in the tabulated values, so
+    /// these give the weight of each row at any x.
+    columns: Vec<LookupTable1D>,
+}
+
+impl LookupTable2D {
+    /// Creates a new linearly interpolated LookupTable2D
+    ///
+    /// ### Arguments
+    /// * `xs`     - Grid x values
+    /// * `ys`     - Grid y values
+    /// * `values` - Tabulated values, where `values[i][j]` is the value at `(xs[i], ys[j])`
+    ///
+    /// Returns an Err Result if the grid is smaller than 2x2, if the
+    /// dimensions don't match or if any values are invalid
+    pub fn new(xs: Vec<f64>, ys: Vec<f64>, values: Vec<Vec<f64>>) -> Result<Self> {
+        if values.len() != xs.len() {
+            return Err(anyhow!("LookupTable2D has {} x values but {} rows", xs.len(), values.len()));
+        }
+        if let Some(row) = values.iter().find(|row| row.len() != ys.len()) {
+            return Err(anyhow!("LookupTable2D has {} y values but a row of {}", ys.len(), row.len()));
+        }
+
+        let rows = values
+            .iter()
+            .map(|row| LookupTable1D::new(ys.iter().copied().zip(row.iter().copied())))
+            .collect::<Result<Vec<_>>>()?;
+        let columns = (0..xs.len())
+            .map(|i| LookupTable1D::new(xs.iter().enumerate().map(|(j, x)| (*x, if i == j { 1.0 } else { 0.0 }))))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { rows, columns })
+    }
+
+    /// Sets the interpolation method between grid points
+    ///
+    /// ### Arguments
+    /// * `interpolation` - Interpolation method
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.rows = self.rows.into_iter().map(|row| row.with_interpolation(interpolation)).collect();
+        self.columns = self.columns.into_iter().map(|column| column.with_interpolation(interpolation)).collect();
+        self
+    }
+
+    /// Extrapolates lookups outside of the tabulated grid rather than
+    /// clamping to the edge values
+    pub fn with_extrapolation(mut self) -> Self {
+        self.rows = self.rows.into_iter().map(|row| row.with_extrapolation()).collect();
+        self.columns = self.columns.into_iter().map(|column| column.with_extrapolation()).collect();
+        self
+    }
+
+    /// Interpolates the table at the given point
+    ///
+    /// ### Arguments
+    /// * `x` - x value to look up
+    /// * `y` - y value to look up
+    ///
+    /// Returns the interpolated value
+    pub fn lookup(&self, x: f64, y: f64) -> f64 {
+        self.rows
+            .iter()
+            .zip(self.columns.iter())
+            .map(|(row, column)| column.lookup(x) * row.lookup(y))
+            .sum()
+    }
+}
+
+mod tests {
+    use super::{Interpolation, LookupTable1D, LookupTable2D};
+
+    fn assert_close(result: f64, expected: f64) {
+        assert!((result - expected).abs() < 1e-9, "result: {}, expected: {}", result, expected);
+    }
+
+    #[test]
+    fn linear_1d() {
+        let table = LookupTable1D::new([(2.0, 4.0), (0.0, 0.0), (1.0, 2.0), (4.0, 0.0)]).unwrap();
+        assert_eq!(table.domain(), (0.0, 4.0));
+
+        for (x, expected) in [(0.0, 0.0), (0.5, 1.0), (1.0, 2.0), (1.25, 2.5), (3.0, 2.0), (4.0, 0.0)] {
+            assert_close(table.lookup(x), expected);
+        }
+
+        // Clamped by default, extrapolated along the end slopes if enabled
+        assert_close(table.lookup(-1.0), 0.0);
+        assert_close(table.lookup(5.0), 0.0);
+        let table = table.with_extrapolation();
+        assert_close(table.lookup(-1.0), -2.0);
+        assert_close(table.lookup(5.0), -2.0);
+    }
+
+    #[test]
+    fn cubic_1d() {
+        let table = LookupTable1D::new([(0.0, 0.0), (1.0, 1.0), (2.0, 0.0)])
+            .unwrap()
+            .with_interpolation(Interpolation::Cubic);
+
+        // The natural spline has a curvature of -3 at x = 1
+        for (x, expected) in [(0.0, 0.0), (0.5, 0.6875), (1.0, 1.0), (1.5, 0.6875), (2.0, 0.0)] {
+            assert_close(table.lookup(x), expected);
+        }
+
+        assert_close(table.lookup(3.0), 0.0);
+        let table = table.with_extrapolation();
+        assert_close(table.lookup(-1.0), -1.5);
+        assert_close(table.lookup(3.0), -1.5);
+
+        // Exact for linear data
+        let line = LookupTable1D::new((0..5).map(|i| (i as f64, 3.0 * i as f64 - 1.0)))
+            .unwrap()
+            .with_interpolation(Interpolation::Cubic);
+        assert_close(line.lookup(2.7), 7.1);
+    }
+
+    #[test]
+    fn invalid_1d() {
+        assert!(LookupTable1D::new([(0.0, 1.0)]).is_err());
+        assert!(LookupTable1D::new([(0.0, 1.0), (0.0, 2.0)]).is_err());
+        assert!(LookupTable1D::new([(0.0, 1.0), (1.0, f64::NAN)]).is_err());
+    }
+
+    #[test]
+    fn lookup_2d() {
+        let xs = vec![1.0, 0.0, 2.0];
+        let ys = vec![0.0, 1.0];
+        let values = xs.iter().map(|x| ys.iter().map(|y| x + 2.0 * y).collect()).collect();
+        let table = LookupTable2D::new(xs, ys, values).unwrap();
+
+        assert_close(table.lookup(0.5, 0.25), 1.0);
+        assert_close(table.lookup(2.0, 1.0), 4.0);
+        assert_close(table.lookup(-1.0, 2.0), 2.0);
+        assert_close(table.clone().with_extrapolation().lookup(-1.0, 2.0), 3.0);
+        assert!(table.lookup(f64::NAN, 0.5).is_nan());
+        assert_close(table.with_interpolation(Interpolation::Cubic).lookup(1.5, 0.5), 2.5);
+
+        assert!(LookupTable2D::new(vec![0.0, 1.0], vec![0.0, 1.0], vec![vec![0.0, 1.0]]).is_err());
+        assert!(LookupTable2D::new(vec![0.0, 1.0], vec![0.0, 1.0], vec![vec![0.0, 1.0], vec![0.0]]).is_err());
+        assert!(LookupTable2D::new(vec![0.0, 0.0], vec![0.0, 1.0], vec![vec![0.0, 1.0], vec![0.0, 1.0]]).is_err());
+    }
+
+    #[test]
+    fn cubic_2d() {
+        let xs: Vec<f64> = vec![0.0, 1.0, 3.0, 4.0];
+        let ys: Vec<f64> = vec![0.0, 2.0, 3.0];
+        let values: Vec<Vec<f64>> = xs.iter().map(|x| ys.iter().map(|y| (x * y).sin() + x * x).collect()).collect();
+        let table = LookupTable2D::new(xs.clone(), ys.clone(), values.clone())
+            .unwrap()
+            .with_interpolation(Interpolation::Cubic)
+            .with_extrapolation();
+
+        // Matches interpolating along y for each grid x, then along x
+        for (x, y) in [(0.5, 0.5), (2.2, 2.9), (3.9, 1.0), (-0.5, 3.5), (4.5, -1.0)] {
+            let column = xs.iter().zip(values.iter()).map(|(xi, row)| {
+                let row = LookupTable1D::new(ys.iter().copied().zip(row.iter().copied())).unwrap();
+                (*xi, row.with_interpolation(Interpolation::Cubic).with_extrapolation().lookup(y))
+            });
+            let expected = LookupTable1D::new(column)
+                .unwrap()
+                .with_interpolation(Interpolation::Cubic)
+                .with_extrapolation()
+                .lookup(x);
+            assert_close(table.lookup(x, y), expected);
+        }
+    }
+}
@@ -0,0 +1,235 @@
+use std::f64::consts::E;
+use std::hash::{Hash, Hasher};
+
+use ordered_float::OrderedFloat;
+
+mod lookup;
+pub use lookup::{Interpolation, LookupTable1D, LookupTable2D};
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BoundFn {
+    Linear,
+    Sigmoid,
+    /// Exponential approach to the full amplitude. Positive rates front-load
+    /// the change, negative rates back-load it.
+    Exponential { rate: f64 },
+    /// Logistic curve with the given steepness, centered at `midpoint`
+    /// as a fraction of the total duration.
+    Logistic { steepness: f64, midpoint: f64 },
+}
+
+impl BoundFn {
+    pub fn call(&self, t: f64, d: f64, a: f64) -> f64 {
+        match self {
+            BoundFn::Linear => bound_linear(t, d, a),
+            BoundFn::Sigmoid => bound_sigmoid(t, d, a),
+            BoundFn::Exponential { rate } => bound_exponential(t, d, a, *rate),
+            BoundFn::Logistic { steepness, midpoint } => bound_logistic(t, d, a, *steepness, *midpoint),
+        }
+    }
+
+    /// Largest slope of the function over its duration, for a unit
+    /// amplitude over a unit duration. Used to determine the peak rate
+    /// of a change with the given shape.
+    pub fn peak_slope(&self) -> f64 {
+        match self {
+            BoundFn::Linear => 1.0,
+            // Slope of the sigmoid at its midpoint, 4e / 4
+            BoundFn::Sigmoid => E,
+            BoundFn::Exponential { rate } => {
+                if rate.abs() < f64::EPSILON {
+                    return 1.0;
+                }
+                // Steepest at the start for positive rates, the end otherwise
+                let p = if *rate > 0.0 { 0.0 } else { 1.0 };
+                rate * f64::exp(-rate * p) / (1.0 - f64::exp(-rate))
+            }
+            BoundFn::Logistic { steepness, midpoint } => {
+                let logistic = |p: f64| 1.0 / (1.0 + f64::exp(-steepness * (p - midpoint)));
+                let (start, end) = (logistic(0.0), logistic(1.0));
+                if (end - start).abs() < f64::EPSILON {
+                    return 1.0;
+                }
+                let l = logistic(midpoint.clamp(0.0, 1.0));
+                (steepness * l * (1.0 - l) / (end - start)).abs()
+            }
+        }
+    }
+
+    fn params(&self) -> (u8, OrderedFloat<f64>, OrderedFloat<f64>) {
+        match self {
+            BoundFn::Linear => (0, OrderedFloat(0.0), OrderedFloat(0.0)),
+            BoundFn::Sigmoid => (1, OrderedFloat(0.0), OrderedFloat(0.0)),
+            BoundFn::Exponential { rate } => (2, OrderedFloat(*rate), OrderedFloat(0.0)),
+            BoundFn::Logistic { steepness, midpoint } => (3, OrderedFloat(*steepness), OrderedFloat(*midpoint)),
+        }
+    }
+}
+
+impl PartialEq for BoundFn {
+    fn eq(&self, other: &Self) -> bool {
+        self.params() == other.params()
+    }
+}
+
+impl Eq for BoundFn {}
+
+impl Hash for BoundFn {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.params().hash(state)
+    }
+}
+
+/// A mathematical sigmoid / logistic function with additional bounds
+/// to define function shape
+///
+/// ### Arguments
+/// * `t` - time
+/// * `d` - duration
+/// * `a` - amplitude
+pub fn bound_sigmoid(t: f64, d: f64, a: f64) -> f64 {
+    return a / (1.0 + f64::exp(-((4.0 * E / d) * t - 2.0 * E)));
+}
+
+/// A linear function with additional bounds to define function shape
+///
+/// ### Arguments
+/// * `t` - time
+/// * `d` - duration
+/// * `a` - amplitude
+pub fn bound_linear(t: f64, d: f64, a: f64) -> f64 {
+    if t < d {
+        return a * t / d;
+    }
+    return a;
+}
+
+/// An exponential function over the normalized progress `t / d`, scaled
+/// so that it starts at zero and reaches the full amplitude at `t = d`
+///
+/// ### Arguments
+/// * `t`    - time
+/// * `d`    - duration
+/// * `a`    - amplitude
+/// * `rate` - exponential rate over the normalized progress
+pub fn bound_exponential(t: f64, d: f64, a: f64, rate: f64) -> f64 {
+    if t <= 0.0 {
+        return 0.0;
+    }
+    if t >= d {
+        return a;
+    }
+    // Degenerates to a linear function as the rate approaches zero
+    if rate.abs() < f64::EPSILON {
+        return bound_linear(t, d, a);
+    }
+    a * (1.0 - f64::exp(-rate * t / d)) / (1.0 - f64::exp(-rate))
+}
+
+/// A logistic function over the normalized progress `t / d`, scaled
+/// so that it starts at zero and reaches the full amplitude at `t = d`
+///
+/// ### Arguments
+/// * `t`         - time
+/// * `d`         - duration
+/// * `a`         - amplitude
+/// * `steepness` - steepness of the curve over the normalized progress
+/// * `midpoint`  - normalized progress at the curve's inflection point
+pub fn bound_logistic(t: f64, d: f64, a: f64, steepness: f64, midpoint: f64) -> f64 {
+    if t <= 0.0 {
+        return 0.0;
+    }
+    if t >= d {
+        return a;
+    }
+    let logistic = |p: f64| 1.0 / (1.0 + f64::exp(-steepness * (p - midpoint)));
+    let (start, end) = (logistic(0.0), logistic(1.0));
+    // Degenerates to a linear function as the steepness approaches zero
+    if (end - start).abs() < f64::EPSILON {
+        return bound_linear(t, d, a);
+    }
+    a * (logistic(t / d) - start) / (end - start)
+}
+
+
+mod tests {
+    use super::{bound_exponential, bound_linear, bound_logistic, bound_sigmoid, BoundFn};
+
+    macro_rules! func_tests {
+        ($($name:ident: $func:ident, $value:expr,)*) => {
+        $(
+            #[test]
+            fn $name() {
+                let (t, d, a, expected) = $value;
+                let result = $func(t,d,a);
+                assert!((result - expected).abs() < 0.01, "t: {}, result: {}", t, result);
+            }
+        )*
+        }
+    }
+
+    func_tests! {
+        linear_0:    bound_linear, (0.0, 1.0, 1.0, 0.0),
+        linear_1q:   bound_linear, (0.25, 1.0, 1.0, 0.25),
+        linear_1h:   bound_linear, (0.5, 1.0, 1.0, 0.5),
+        linear_3q:   bound_linear, (0.75, 1.0, 1.0, 0.75),
+        linear_1:    bound_linear, (1.0, 1.0, 1.0, 1.0),
+        linear_1_1h: bound_linear, (1.5, 1.0, 1.0, 1.0),
+
+        sigmoid_0:    bound_sigmoid, (0.0, 1.0, 1.0, 0.0),
+        sigmoid_1q:   bound_sigmoid, (0.25, 1.0, 1.0, 0.0619),
+        sigmoid_1h:   bound_sigmoid, (0.5, 1.0, 1.0, 0.5),
+        sigmoid_3q:   bound_sigmoid, (0.75, 1.0, 1.0, 0.9381),
+        sigmoid_1:    bound_sigmoid, (1.0, 1.0, 1.0, 1.0),
+        sigmoid_1_1h: bound_sigmoid, (1.5, 1.0, 1.0, 1.0),
+    }
+
+    #[test]
+    fn exponential() {
+        let rate = 3.0;
+        for t in [0.1, 0.25, 0.5, 0.75, 0.9] {
+            let expected = (1.0 - f64::exp(-rate * t)) / (1.0 - f64::exp(-rate));
+            let result = bound_exponential(t * 10.0, 10.0, 1.0, rate);
+            assert!((result - expected).abs() < 1e-9, "t: {}, result: {}, expected: {}", t, result, expected);
+        }
+
+        // Front-loaded for a positive rate, back-loaded for a negative rate
+        assert!(bound_exponential(5.0, 10.0, 1.0, 3.0) > 0.5);
+        assert!(bound_exponential(5.0, 10.0, 1.0, -3.0) < 0.5);
+
+        // Bounded at the start and end
+        assert_eq!(bound_exponential(0.0, 10.0, 2.0, rate), 0.0);
+        assert_eq!(bound_exponential(15.0, 10.0, 2.0, rate), 2.0);
+
+        // Zero rate is linear
+        assert_eq!(bound_exponential(2.5, 10.0, 1.0, 0.0), 0.25);
+    }
+
+    #[test]
+    fn logistic() {
+        let (k, m) = (10.0, 0.3);
+        let g = |p: f64| 1.0 / (1.0 + f64::exp(-k * (p - m)));
+        for t in [0.1, 0.25, 0.5, 0.75, 0.9] {
+            let expected = (g(t) - g(0.0)) / (g(1.0) - g(0.0));
+            let result = bound_logistic(t * 10.0, 10.0, 1.0, k, m);
+            assert!((result - expected).abs() < 1e-9, "t: {}, result: {}, expected: {}", t, result, expected);
+        }
+
+        // Steepest change around the midpoint
+        let around_mid = bound_logistic(3.5, 10.0, 1.0, k, m) - bound_logistic(2.5, 10.0, 1.0, k, m);
+        let late = bound_logistic(8.5, 10.0, 1.0, k, m) - bound_logistic(7.5, 10.0, 1.0, k, m);
+        assert!(around_mid > late);
+
+        // Bounded at the start and end
+        assert_eq!(bound_logistic(0.0, 10.0, 2.0, k, m), 0.0);
+        assert_eq!(bound_logistic(15.0, 10.0, 2.0, k, m), 2.0);
+    }
+
+    #[test]
+    fn bound_fn_eq() {
+        assert_eq!(BoundFn::Exponential { rate: 2.0 }, BoundFn::Exponential { rate: 2.0 });
+        assert_ne!(BoundFn::Exponential { rate: 2.0 }, BoundFn::Exponential { rate: 3.0 });
+        assert_ne!(BoundFn::Linear, BoundFn::Sigmoid);
+    }
+}
@@ -4,6 +4,7 @@ use ordered_float::OrderedFloat;
 use std::cmp::Ordering;
 use std::fmt::Display;
 use std::ops::{Add, Sub, AddAssign, SubAssign, Mul, MulAssign, Div, DivAssign};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 type Time = crate::units::base::Time<f64>;
 
@@ -373,6 +374,56 @@ impl SimTime {
     pub fn span_to(&self, other: &Self) -> SimTimeSpan {
         SimTimeSpan(Time::from_s(other.0.s - self.0.s))
     }
+
+    /// Returns the calendar time of `self` as a `Duration` since the
+    /// Unix epoch, e.g. for logging timestamps
+    ///
+    /// ### Arguments
+    /// * `epoch` - calendar time corresponding to a simulation time of zero
+    ///
+    /// Returns an Err Result if `self` isn't finite or the calendar time
+    /// is out of range or before the Unix epoch
+    pub fn as_duration_since_epoch(&self, epoch: SystemTime) -> anyhow::Result<Duration> {
+        let offset = Duration::try_from_secs_f64(self.0.s.abs())
+            .map_err(|e| anyhow!("SimTime {} can't be converted to a Duration: {}", self.0.s, e))?;
+        let calendar_time = if self.0.s >= 0.0 {
+            epoch.checked_add(offset)
+        } else {
+            epoch.checked_sub(offset)
+        }
+        .ok_or_else(|| anyhow!("SimTime {} is out of range of the epoch", self.0.s))?;
+
+        Ok(calendar_time.duration_since(UNIX_EPOCH)?)
+    }
+}
+
+/// Converts a span to a `Duration`, rounded to the nearest nanosecond.
+/// As a `Duration` can't be negative, negative and NaN spans saturate to
+/// zero, and spans too long to represent saturate to `Duration::MAX`.
+impl From<SimTimeSpan> for Duration {
+    fn from(span: SimTimeSpan) -> Self {
+        let secs = span.0.s;
+        if secs.is_nan() || secs <= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::try_from_secs_f64(secs).unwrap_or(Duration::MAX)
+        }
+    }
+}
+
+/// Converts a `Duration` to a span. Fails if the `Duration` can't be
+/// represented exactly to the nanosecond, which can only happen for
+/// spans over ~104 days with sub-second precision.
+impl TryFrom<Duration> for SimTimeSpan {
+    type Error = anyhow::Error;
+
+    fn try_from(duration: Duration) -> Result<Self, Self::Error> {
+        let secs = duration.as_secs_f64();
+        if Duration::try_from_secs_f64(secs).ok() != Some(duration) {
+            return Err(anyhow!("Duration {:?} can't be represented exactly as a SimTimeSpan", duration));
+        }
+        Ok(SimTimeSpan::from_s(secs))
+    }
 }
 
 impl Add<SimTimeSpan> for SimTime {
@@ -450,3 +501,42 @@ impl SubAssign<SimTimeSpan> for SimTime {
 //         OrderedFloat(self.0.s).cmp(&OrderedFloat(other.0.s))
 //     }
 // }
+
+mod tests {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use super::{SimTime, SimTimeSpan};
+
+    #[test]
+    fn duration_round_trip() {
+        for duration in [Duration::from_secs(0), Duration::from_secs(1), Duration::from_secs(86_400)] {
+            let span = SimTimeSpan::try_from(duration).unwrap();
+            assert_eq!(span.to_s(), duration.as_secs_f64());
+            assert_eq!(Duration::from(span), duration);
+        }
+        for duration in [Duration::from_millis(1), Duration::from_millis(1_234), Duration::from_millis(3_600_001)] {
+            let span = SimTimeSpan::try_from(duration).unwrap();
+            assert_eq!(span.to_ms(), duration.as_millis() as f64);
+            assert_eq!(Duration::from(span), duration);
+        }
+
+        // Too precise for the span at this length
+        assert!(SimTimeSpan::try_from(Duration::new(10_000_000, 1)).is_err());
+
+        // Saturates at either end
+        assert_eq!(Duration::from(SimTimeSpan::from_s(-1.0)), Duration::ZERO);
+        assert_eq!(Duration::from(SimTimeSpan::from_s(f64::NAN)), Duration::ZERO);
+        assert_eq!(Duration::from(SimTimeSpan::from_s(f64::INFINITY)), Duration::MAX);
+    }
+
+    #[test]
+    fn duration_since_epoch() {
+        let epoch = UNIX_EPOCH + Duration::from_secs(1_000);
+        assert_eq!(SimTime::from_s(1.5).as_duration_since_epoch(epoch).unwrap(), Duration::from_millis(1_001_500));
+        assert_eq!(SimTime::from_s(-10.0).as_duration_since_epoch(epoch).unwrap(), Duration::from_secs(990));
+
+        assert!(SimTime::from_s(-1_001.0).as_duration_since_epoch(epoch).is_err());
+        assert!(SimTime::from_s(f64::NAN).as_duration_since_epoch(epoch).is_err());
+        assert!(SimTime::from_s(0.0).as_duration_since_epoch(SystemTime::now()).is_ok());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::sim::organism::OrganismInfo;
 use crate::sim::Organism;
 
 use super::{registry::ComponentWrapper, ComponentRegistry, SimComponent};
@@ -22,7 +23,87 @@ impl<'a, O: Organism> ComponentFactory<'a, O> {
         }
     }
 
+    /// Creates a factory whose function receives the constants of the
+    /// `Organism` the component is being created for, so components can
+    /// be configured for the organism rather than hardcoding assumptions.
+    ///
+    /// ### Arguments
+    /// * `factory` - Function creating a component for the given organism
+    pub fn new_for<T: SimComponent<O>>(mut factory: impl (FnMut(&OrganismInfo) -> T) + 'a + Send) -> Self {
+        let info = OrganismInfo::of::<O>();
+        Self::new(move || factory(&info))
+    }
+
     pub fn attach<'b>(&mut self, registry: &'b mut ComponentRegistry<O>) -> &'b mut Box<dyn ComponentWrapper<O>> {
         self.attach_fn.as_mut()(registry)
     }
-}
\ No newline at end of file
+}
+mod tests {
+    use std::marker::PhantomData;
+    use std::sync::{Arc, Mutex};
+
+    use crate::sim::component::{ComponentRegistry, SimComponent};
+    use crate::sim::layer::core::{CoreComponent, CoreConnector, CoreInitializer};
+    use crate::sim::organism::test::{FistulaOrganism, TestOrganism};
+    use crate::sim::organism::OrganismInfo;
+    use crate::sim::Organism;
+
+    use super::ComponentFactory;
+
+    /// Keeps a value for each blood vessel of the organism
+    struct VesselBufferComponent<O: Organism> {
+        buffer: Vec<f64>,
+        connector: CoreConnector<O>,
+    }
+
+    impl<O: Organism> VesselBufferComponent<O> {
+        fn new(info: &OrganismInfo) -> Self {
+            Self {
+                buffer: vec![0.0; info.vessel_count],
+                connector: CoreConnector::new(),
+            }
+        }
+    }
+
+    impl<O: Organism> CoreComponent<O> for VesselBufferComponent<O> {
+        fn core_init(&mut self, _initializer: &mut CoreInitializer<O>) {}
+        fn core_connector(&mut self) -> &mut CoreConnector<O> {
+            &mut self.connector
+        }
+    }
+
+    impl<O: Organism> SimComponent<O> for VesselBufferComponent<O> {
+        fn id(&self) -> &'static str {
+            "VesselBufferComponent"
+        }
+        fn attach(self, registry: &mut ComponentRegistry<O>) {
+            registry.add_core_component(self)
+        }
+        fn run(&mut self) {}
+    }
+
+    fn buffer_size<O: Organism>(_organism: PhantomData<O>) -> usize {
+        let size = Arc::new(Mutex::new(0));
+        let size_ref = size.clone();
+        let mut factory = ComponentFactory::<O>::new_for(move |info| {
+            let component = VesselBufferComponent::<O>::new(info);
+            *size_ref.lock().unwrap() = component.buffer.len();
+            component
+        });
+        factory.attach(&mut ComponentRegistry::new());
+        let result = *size.lock().unwrap();
+        result
+    }
+
+    #[test]
+    fn new_for() {
+        let base = OrganismInfo::of::<TestOrganism>();
+        let fistula = OrganismInfo::of::<FistulaOrganism>();
+        assert_eq!(base.vessel_count, base.artery_count + base.vein_count);
+        assert_eq!(fistula.artery_count, base.artery_count + 1);
+        assert_eq!(fistula.terminal_nerve_count, base.terminal_nerve_count);
+
+        assert_eq!(buffer_size(PhantomData::<TestOrganism>), base.vessel_count);
+        assert_eq!(buffer_size(PhantomData::<FistulaOrganism>), base.vessel_count + 1);
+    }
+}
@@ -7,6 +7,21 @@ use super::SimConnector;
 pub use registry::ComponentRegistry;
 pub use factory::ComponentFactory;
 
+/// Phase of an update in which a component runs. All components of
+/// an earlier phase run before any of a later phase, and `Event`s they
+/// emit for the current time are applied in between, so that e.g. an
+/// actuator sees a sensor's output within the same advance.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Phase {
+    /// Measures the current state of the organism
+    Sense,
+    /// Default phase for components which don't specify one
+    #[default]
+    Decide,
+    /// Acts on the organism based on earlier phases
+    Actuate,
+}
+
 /// Common trait for all simulation components
 pub trait SimComponent<O: Organism>: Send {
     /// The unique id of the component
@@ -15,6 +30,15 @@ pub trait SimComponent<O: Organism>: Send {
     fn attach(self, registry: &mut ComponentRegistry<O>);
     /// Runs an iteration of this module.
     fn run(&mut self);
+    /// Restores the component to its initial configuration, e.g. to
+    /// restart a dosing schedule. Layer initialization is re-run after
+    /// a reset. Does nothing by default.
+    fn reset(&mut self) {}
+    /// Phase of each update in which the component runs. Defaults to
+    /// `Phase::Decide`.
+    fn phase(&self) -> Phase {
+        Phase::Decide
+    }
 }
 
 /// Trait to outline common methods for all layers that
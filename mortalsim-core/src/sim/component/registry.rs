@@ -14,7 +14,7 @@ use crate::sim::layer::{
     digestion::{DigestionComponent, DigestionInitializer, DigestionConnector},
     nervous::{NervousComponent, NervousInitializer, NervousConnector},
 };
-use super::SimComponent;
+use super::{Phase, SimComponent};
 
 pub trait ComponentWrapper<O: Organism>: SimComponent<O> + CoreComponent<O> + CirculationComponent<O> + DigestionComponent<O> + NervousComponent<O> {
 
@@ -62,6 +62,12 @@ impl<O: Organism> SimComponent<O> for Box<dyn ComponentWrapper<O>> {
     fn run(&mut self) {
         self.as_mut().run()     
     }
+    fn reset(&mut self) {
+        self.as_mut().reset()
+    }
+    fn phase(&self) -> Phase {
+        self.as_ref().phase()
+    }
 }
 
 impl<O: Organism> CoreComponent<O> for Box<dyn ComponentWrapper<O>> {
@@ -109,6 +115,12 @@ impl<O: Organism, T: Send + CoreComponent<O> + CirculationComponent<O> + Digesti
     fn run(&mut self) {
         self.0.run();
     }
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+    fn phase(&self) -> Phase {
+        self.0.phase()
+    }
 }
 
 
@@ -196,6 +208,12 @@ impl<O: Organism, T: Send + CoreComponent<O> + CirculationComponent<O> + Nervous
     fn run(&mut self) {
         self.0.run();
     }
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+    fn phase(&self) -> Phase {
+        self.0.phase()
+    }
 }
 
 
@@ -283,6 +301,12 @@ impl<O: Organism, T: Send + CoreComponent<O> + CirculationComponent<O>> SimCompo
     fn run(&mut self) {
         self.0.run();
     }
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+    fn phase(&self) -> Phase {
+        self.0.phase()
+    }
 }
 
 
@@ -370,6 +394,12 @@ impl<O: Organism, T: Send + CoreComponent<O> + DigestionComponent<O> + NervousCo
     fn run(&mut self) {
         self.0.run();
     }
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+    fn phase(&self) -> Phase {
+        self.0.phase()
+    }
 }
 
 
@@ -457,6 +487,12 @@ impl<O: Organism, T: Send + CoreComponent<O> + DigestionComponent<O>> SimCompone
     fn run(&mut self) {
         self.0.run();
     }
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+    fn phase(&self) -> Phase {
+        self.0.phase()
+    }
 }
 
 
@@ -544,6 +580,12 @@ impl<O: Organism, T: Send + CoreComponent<O> + NervousComponent<O>> SimComponent
     fn run(&mut self) {
         self.0.run();
     }
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+    fn phase(&self) -> Phase {
+        self.0.phase()
+    }
 }
 
 
@@ -631,6 +673,12 @@ impl<O: Organism, T: Send + CoreComponent<O>> SimComponent<O> for CoreWrapper<O,
     fn run(&mut self) {
         self.0.run();
     }
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+    fn phase(&self) -> Phase {
+        self.0.phase()
+    }
 }
 
 
@@ -718,6 +766,12 @@ impl<O: Organism, T: Send + CirculationComponent<O> + DigestionComponent<O> + Ne
     fn run(&mut self) {
         self.0.run();
     }
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+    fn phase(&self) -> Phase {
+        self.0.phase()
+    }
 }
 
 
@@ -805,6 +859,12 @@ impl<O: Organism, T: Send + CirculationComponent<O> + DigestionComponent<O>> Sim
     fn run(&mut self) {
         self.0.run();
     }
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+    fn phase(&self) -> Phase {
+        self.0.phase()
+    }
 }
 
 
@@ -892,6 +952,12 @@ impl<O: Organism, T: Send + CirculationComponent<O> + NervousComponent<O>> SimCo
     fn run(&mut self) {
         self.0.run();
     }
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+    fn phase(&self) -> Phase {
+        self.0.phase()
+    }
 }
 
 
@@ -979,6 +1045,12 @@ impl<O: Organism, T: Send + CirculationComponent<O>> SimComponent<O> for Circula
     fn run(&mut self) {
         self.0.run();
     }
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+    fn phase(&self) -> Phase {
+        self.0.phase()
+    }
 }
 
 
@@ -1066,6 +1138,12 @@ impl<O: Organism, T: Send + DigestionComponent<O> + NervousComponent<O>> SimComp
     fn run(&mut self) {
         self.0.run();
     }
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+    fn phase(&self) -> Phase {
+        self.0.phase()
+    }
 }
 
 
@@ -1153,6 +1231,12 @@ impl<O: Organism, T: Send + DigestionComponent<O>> SimComponent<O> for Digestion
     fn run(&mut self) {
         self.0.run();
     }
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+    fn phase(&self) -> Phase {
+        self.0.phase()
+    }
 }
 
 
@@ -1240,6 +1324,12 @@ impl<O: Organism, T: Send + NervousComponent<O>> SimComponent<O> for NervousWrap
     fn run(&mut self) {
         self.0.run();
     }
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+    fn phase(&self) -> Phase {
+        self.0.phase()
+    }
 }
 
 
@@ -1340,6 +1430,7 @@ impl<O: Organism> ComponentRegistry<O> {
 
     pub(crate) fn remove_component(&mut self, component_id: &str) -> anyhow::Result<Box<dyn ComponentWrapper<O>>> {
         if let Some(index) = self.components.iter().position(|x| x.id() == component_id) {
+            self.id_set.remove(component_id);
             return Ok(self.components.remove(index))
         }
         Err(anyhow!("component not found"))
@@ -1355,6 +1446,10 @@ impl<O: Organism> ComponentRegistry<O> {
         self.components.iter_mut()
     }
 
+    pub(crate) fn add_wrapped_component(&mut self, component: Box<dyn ComponentWrapper<O>>) {
+        self.components.push(component)
+    }
+
     pub fn add_core_circulation_digestion_component(&mut self, component: impl CoreComponent<O> + CirculationComponent<O> + DigestionComponent<O> + 'static) {
         self.components.push(Box::new(CoreCirculationDigestionWrapper(component, PhantomData)))
     }
@@ -0,0 +1,38 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// Global parameters of a Sim, such as ambient temperature or altitude,
+/// which any component can read through any of its connectors without
+/// waiting on an `Event`. Clones share the same underlying values.
+#[derive(Clone, Default)]
+pub struct SimGlobals {
+    values: Arc<RwLock<HashMap<String, Arc<dyn Any + Send + Sync>>>>,
+}
+
+impl SimGlobals {
+    /// Creates an empty set of globals
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the value of a global, replacing any previous value
+    ///
+    /// ### Arguments
+    /// * `key`   - Name of the global
+    /// * `value` - Value to set, of any type
+    pub fn set<T: Any + Send + Sync>(&self, key: &str, value: T) {
+        self.values.write().unwrap().insert(key.to_string(), Arc::new(value));
+    }
+
+    /// Retrieves the value of a global
+    ///
+    /// ### Arguments
+    /// * `key` - Name of the global
+    ///
+    /// Returns a copy of the value, or `None` if the global isn't set or
+    /// holds a value of another type
+    pub fn get<T: Any + Clone>(&self, key: &str) -> Option<T> {
+        self.values.read().unwrap().get(key)?.downcast_ref::<T>().cloned()
+    }
+}
@@ -6,6 +6,11 @@ macro_rules! impl_sim {
             layer_manager: $crate::sim::layer::LayerManager<$organism>,
             id_gen: $crate::IdGenerator,
             hub: $crate::hub::EventHub<'static>,
+            paused: bool,
+            time_marks: $crate::sim::time_manager::TimeMarks,
+            auto_step: Option<$crate::sim::time_manager::AutoStep>,
+            real_time: $crate::sim::time_manager::RealTimeSync,
+            journal: $crate::sim::SimJournal,
         }
 
         static DEFAULT_ID_GEN: std::sync::OnceLock<std::sync::Mutex<$crate::IdGenerator>> =
@@ -56,6 +61,19 @@ macro_rules! impl_sim {
                 factory_id
             }
 
+            /// Attaches a default factory function which receives the constants of
+            /// this Sim's `Organism`, as with `set_default`.
+            pub fn set_default_for<T: $crate::sim::component::SimComponent<$organism>>(
+                factory: impl FnMut(&$crate::sim::OrganismInfo) -> T + 'static + Send,
+            ) -> $crate::IdType {
+                let factory_id = Self::default_id_gen().get_id();
+                Self::default_factories().push((
+                    factory_id,
+                    $crate::sim::component::ComponentFactory::new_for(factory),
+                ));
+                factory_id
+            }
+
             pub fn remove_default(
                 factory_id: &$crate::IdType,
             ) -> anyhow::Result<()> {
@@ -80,10 +98,112 @@ macro_rules! impl_sim {
                 &mut self,
                 component: impl $crate::sim::component::SimComponent<$organism>,
             ) -> anyhow::Result<()> {
-                self.layer_manager.add_component(&mut self.connector, component)?;
+                let id = self.layer_manager.add_component(&mut self.connector, component)?.id();
+                self.journal.record($crate::sim::JournalEntry::AddComponent { id: id.to_string() });
+                Ok(())
+            }
+
+            /// Adds a component created by the given factory, such as one
+            /// from a curated set of defaults for the `Organism`
+            ///
+            /// ### Arguments
+            /// * `factory` - factory creating the component to add
+            pub fn add_component_from_factory(
+                &mut self,
+                factory: &mut $crate::sim::component::ComponentFactory<'_, $organism>,
+            ) -> anyhow::Result<()> {
+                let id = self.layer_manager.add_component_from_factory(&mut self.connector, factory)?.id();
+                self.journal.record($crate::sim::JournalEntry::AddComponent { id: id.to_string() });
                 Ok(())
             }
             
+            /// Adds a component tagged with the given labels, which can later
+            /// be used to look up related components with `components_with_label`
+            ///
+            /// ### Arguments
+            /// * `component` - component to add
+            /// * `labels`    - labels to tag the component with, e.g. "cardiac"
+            pub fn add_component_with_labels(
+                &mut self,
+                component: impl $crate::sim::component::SimComponent<$organism>,
+                labels: &[&str],
+            ) -> anyhow::Result<()> {
+                let id = component.id();
+                self.layer_manager.add_component_with_labels(&mut self.connector, component, labels)?;
+                self.journal.record($crate::sim::JournalEntry::AddComponent { id: id.to_string() });
+                Ok(())
+            }
+
+            /// Adds a component to a mutual exclusion group. Under a threaded
+            /// Sim, components in the same group never run concurrently, such
+            /// as components which modify the same vessel stores.
+            ///
+            /// ### Arguments
+            /// * `group_id`  - id of the exclusion group
+            /// * `component` - component to add
+            pub fn add_component_in_exclusion_group(
+                &mut self,
+                group_id: &str,
+                component: impl $crate::sim::component::SimComponent<$organism>,
+            ) -> anyhow::Result<()> {
+                let id = component.id();
+                self.layer_manager.add_component_in_exclusion_group(&mut self.connector, group_id, component)?;
+                self.journal.record($crate::sim::JournalEntry::AddComponent { id: id.to_string() });
+                Ok(())
+            }
+
+            /// Sets a constant background `Event`, such as a vital sign, which
+            /// is always present on the Sim state without a producing component.
+            /// A later `Event` of the same type overrides it.
+            ///
+            /// ### Arguments
+            /// * `event` - `Event` to set
+            pub fn set_constant_event<E: $crate::event::Event + Clone>(&mut self, event: E) {
+                self.journal.record_constant(&event);
+                self.connector.set_constant_event(event)
+            }
+
+            /// Sets the maximum number of update passes executed for a single
+            /// advance, which caps feedback between components triggering each
+            /// other at the same simulation time. Components responding to
+            /// `Event`s scheduled without a delay run within the same advance,
+            /// up to this many passes, so a cap of 1 leaves them for the next
+            /// advance instead.
+            ///
+            /// ### Arguments
+            /// * `max_iterations` - maximum number of passes (at least 1)
+            pub fn set_max_iterations_per_advance(&mut self, max_iterations: usize) {
+                self.journal.record($crate::sim::JournalEntry::SetMaxIterationsPerAdvance { max_iterations });
+                self.layer_manager.set_max_iterations_per_advance(max_iterations)
+            }
+
+            /// Sets how components scheduling conflicting values of the same
+            /// `Event` type for the same time are reported
+            ///
+            /// ### Arguments
+            /// * `policy` - conflict policy to apply
+            pub fn set_event_conflict_policy(&mut self, policy: $crate::sim::layer::core::EventConflictPolicy) {
+                self.layer_manager.set_event_conflict_policy(policy)
+            }
+
+            /// Provides read-only access to a circulation component's
+            /// connector between advances, with the current stores of its
+            /// attached vessels, without running the component
+            ///
+            /// ### Arguments
+            /// * `component_id` - id of the component to inspect
+            /// * `inspect`      - function given the component's connector
+            ///
+            /// Returns an Err Result if the component isn't registered or
+            /// isn't a circulation component
+            pub fn inspect_circulation(
+                &mut self,
+                component_id: &str,
+                inspect: impl FnOnce(&$crate::sim::layer::circulation::CirculationConnector<$organism>),
+            ) -> anyhow::Result<()> {
+                self.layer_manager.inspect_circulation(component_id, inspect)
+            }
+
             fn init(mut layer_manager: $crate::sim::layer::LayerManager<$organism>) -> Self {
                 let mut connector = $crate::sim::SimConnector::new();
 
@@ -96,6 +216,11 @@ macro_rules! impl_sim {
                     connector: connector,
                     hub: $crate::hub::EventHub::new(),
                     layer_manager,
+                    paused: false,
+                    time_marks: $crate::sim::time_manager::TimeMarks::new(),
+                    auto_step: None,
+                    real_time: $crate::sim::time_manager::RealTimeSync::new(),
+                    journal: $crate::sim::SimJournal::new(),
                 }
             }
 
@@ -113,20 +238,229 @@ macro_rules! impl_sim {
                 self.connector.sim_time()
             }
 
+            fn set_epoch(&mut self, epoch: std::time::SystemTime) {
+                self.journal.record_epoch(epoch);
+                self.connector.time_manager.set_epoch(epoch)
+            }
+
+            fn wall_clock(&self) -> Option<std::time::SystemTime> {
+                self.connector.wall_clock()
+            }
+
+            fn set_global<T: std::any::Any + Send + Sync>(&mut self, key: &str, value: T) {
+                self.journal.record($crate::sim::JournalEntry::Unpersistable {
+                    reason: format!("global {} was set", key),
+                });
+                self.connector.globals.set(key, value)
+            }
+
+            fn get_global<T: std::any::Any + Clone>(&self, key: &str) -> Option<T> {
+                self.connector.globals.get(key)
+            }
+
+            fn pause(&mut self) {
+                self.paused = true;
+            }
+
+            fn resume(&mut self) {
+                self.paused = false;
+            }
+
+            fn is_paused(&self) -> bool {
+                self.paused
+            }
+
+            fn on_time_mark(
+                &mut self,
+                interval: $crate::SimTimeSpan,
+                callback: Box<dyn FnMut($crate::sim::SimTime) + Send>,
+            ) -> anyhow::Result<()> {
+                self.time_marks.add(self.time(), interval, callback)
+            }
+
+            fn set_auto_step(
+                &mut self,
+                min_span: $crate::SimTimeSpan,
+                max_span: $crate::SimTimeSpan,
+                activity_metric: Box<dyn FnMut(&$crate::sim::SimState) -> f64 + Send>,
+            ) -> anyhow::Result<()> {
+                self.auto_step = Some($crate::sim::time_manager::AutoStep::new(min_span, max_span, activity_metric)?);
+                self.journal.record($crate::sim::JournalEntry::Unpersistable {
+                    reason: "adaptive steps were set".to_string(),
+                });
+                Ok(())
+            }
+
+            fn clear_auto_step(&mut self) {
+                self.auto_step = None;
+            }
+
+            fn auto_step(&self) -> Option<$crate::SimTimeSpan> {
+                self.auto_step.as_ref().map(|auto_step| auto_step.step())
+            }
+
+            fn set_real_time_clock(&mut self, clock: Box<dyn FnMut() -> std::time::Duration + Send>) {
+                self.real_time = $crate::sim::time_manager::RealTimeSync::with_clock(clock);
+            }
+
+            fn advance_to_wall_clock(&mut self, target_factor: f64) -> anyhow::Result<$crate::SimTimeSpan> {
+                if !target_factor.is_finite() || target_factor < 0.0 {
+                    return Err(anyhow!("Real time factor must be a non-negative number, got {}", target_factor));
+                }
+
+                // Wall time passes while paused without being simulated
+                let elapsed = self.real_time.elapsed();
+                if self.paused {
+                    log::debug!("Ignoring advance while paused");
+                    return Ok($crate::SimTimeSpan::from_s(0.0));
+                }
+
+                // Split into equal steps no larger than the maximum
+                let span_s = elapsed.as_secs_f64() * target_factor;
+                let steps = (span_s / $crate::sim::time_manager::RealTimeSync::MAX_STEP).ceil() as u64;
+                for _ in 0..steps {
+                    self.advance_by($crate::SimTimeSpan::from_s(span_s / steps as f64));
+                }
+                Ok($crate::SimTimeSpan::from_s(span_s))
+            }
+
             fn advance(&mut self) {
+                if self.paused {
+                    log::debug!("Ignoring advance while paused");
+                    return;
+                }
+                self.journal.record($crate::sim::JournalEntry::Advance { times: 1 });
                 if !self.layer_manager.first_update() {
                     self.layer_manager.update(&mut self.connector);
                 }
                 self.connector.time_manager.advance();
                 self.layer_manager.update(&mut self.connector);
+                self.time_marks.fire(self.time());
             }
 
             fn advance_by(&mut self, time_step: $crate::SimTimeSpan) {
+                if self.paused {
+                    log::debug!("Ignoring advance while paused");
+                    return;
+                }
+                self.journal.record($crate::sim::JournalEntry::AdvanceBy { span_s: time_step.to_s(), times: 1 });
                 if !self.layer_manager.first_update() {
                     self.layer_manager.update(&mut self.connector);
                 }
-                self.connector.time_manager.advance_by(time_step);
-                self.layer_manager.update(&mut self.connector);
+
+                let Some(auto_step) = self.auto_step.as_mut().filter(|_| time_step > $crate::SimTimeSpan::from_s(0.0)) else {
+                    self.connector.time_manager.advance_by(time_step);
+                    self.layer_manager.update(&mut self.connector);
+                    self.time_marks.fire(self.time());
+                    return;
+                };
+
+                // Subdivide the advance into adaptive steps
+                let end_time = self.connector.sim_time() + time_step;
+                while self.connector.sim_time() < end_time {
+                    let step_time = (self.connector.sim_time() + auto_step.step()).min(end_time);
+                    self.connector.time_manager.advance_to(step_time);
+                    self.layer_manager.update(&mut self.connector);
+                    self.time_marks.fire(step_time);
+                    auto_step.observe(&self.connector.state);
+                }
+            }
+
+            fn advance_by_stepping(
+                &mut self,
+                time_step: $crate::SimTimeSpan,
+                callback: &mut dyn FnMut($crate::sim::SimTime, &dyn $crate::sim::Sim),
+            ) {
+                if self.paused {
+                    log::debug!("Ignoring advance while paused");
+                    return;
+                }
+                if time_step <= $crate::SimTimeSpan::from_s(0.0) {
+                    let has_event = self.connector.time_manager.next_event_time().is_some();
+                    self.advance();
+                    if has_event {
+                        callback(self.time(), self);
+                    }
+                    return;
+                }
+
+                self.journal.record($crate::sim::JournalEntry::AdvanceByStepping { span_s: time_step.to_s() });
+                if !self.layer_manager.first_update() {
+                    self.layer_manager.update(&mut self.connector);
+                }
+
+                let end_time = self.time() + time_step;
+
+                // Stop at each event time within the span
+                while let Some(evt_time) = self.connector.time_manager.next_event_time() {
+                    if evt_time > end_time {
+                        break;
+                    }
+                    self.connector.time_manager.advance_to(evt_time);
+                    self.layer_manager.update(&mut self.connector);
+                    self.time_marks.fire(evt_time);
+                    callback(evt_time, self);
+                }
+
+                // Finish out the remainder of the span, if any
+                if self.time() < end_time {
+                    self.connector.time_manager.advance_to(end_time);
+                    self.layer_manager.update(&mut self.connector);
+                    self.time_marks.fire(end_time);
+                }
+            }
+
+            fn advance_until_event_type(
+                &mut self,
+                event_type: std::any::TypeId,
+                max_time: $crate::SimTimeSpan,
+            ) -> Option<$crate::sim::SimTime> {
+                if self.paused {
+                    log::debug!("Ignoring advance while paused");
+                    return None;
+                }
+
+                self.journal.record(match $crate::event::codec::registered_type_name(event_type) {
+                    Some(type_name) => $crate::sim::JournalEntry::AdvanceUntilEvent {
+                        type_name: type_name.to_string(),
+                        max_time_s: max_time.to_s(),
+                    },
+                    None => $crate::sim::JournalEntry::Unpersistable {
+                        reason: format!("advanced until an unregistered event type {:?}", event_type),
+                    },
+                });
+                if !self.layer_manager.first_update() {
+                    self.layer_manager.update(&mut self.connector);
+                }
+
+                let emitted = |connector: &$crate::sim::SimConnector| {
+                    connector.active_events.iter().any(|evt| evt.as_any().type_id() == event_type)
+                };
+                let end_time = self.time() + max_time;
+
+                // Stop at the first event time within the span which emits the event
+                while let Some(evt_time) = self.connector.time_manager.next_event_time() {
+                    if evt_time > end_time {
+                        break;
+                    }
+                    self.connector.time_manager.advance_to(evt_time);
+                    self.layer_manager.update(&mut self.connector);
+                    self.time_marks.fire(evt_time);
+                    if emitted(&self.connector) {
+                        return Some(evt_time);
+                    }
+                }
+
+                // Finish out the remainder of the span, if any
+                if self.time() < end_time {
+                    self.connector.time_manager.advance_to(end_time);
+                    self.layer_manager.update(&mut self.connector);
+                    self.time_marks.fire(end_time);
+                    if emitted(&self.connector) {
+                        return Some(end_time);
+                    }
+                }
+                None
             }
 
             fn active_components(&self) -> Vec<&'static str> {
@@ -137,8 +471,74 @@ macro_rules! impl_sim {
                 self.layer_manager.has_component(component_id)
             }
 
+            fn components_with_label(&self, label: &str) -> Vec<&'static str> {
+                self.layer_manager.components_with_label(label)
+            }
+
+            fn circulation_substance_owners(
+                &self,
+            ) -> std::collections::HashMap<$crate::substance::Substance, Vec<&'static str>> {
+                self.layer_manager.circulation_substance_owners()
+            }
+
+            fn total_substance(
+                &self,
+                substance: &$crate::substance::Substance,
+            ) -> $crate::units::base::Amount<f64> {
+                self.layer_manager.total_substance(substance)
+            }
+
+            fn state_report(&self) -> String {
+                self.layer_manager.state_report(&self.connector)
+            }
+
+            fn validate_event_graph(
+                &self,
+            ) -> Result<(), Vec<$crate::sim::layer::core::UnsatisfiedDependency>> {
+                self.layer_manager.validate_event_graph(&self.connector)
+            }
+
+            fn export_dependency_graph_dot(&self) -> String {
+                self.layer_manager.export_dependency_graph_dot()
+            }
+
+            fn drain_event_conflicts(&mut self) -> Vec<$crate::sim::layer::core::EventConflict> {
+                self.layer_manager.drain_event_conflicts()
+            }
+
             fn remove_component(&mut self, component_id: &str) -> anyhow::Result<&str> {
-                Ok(self.layer_manager.remove_component(&mut self.connector, component_id)?.id())
+                let id = self.layer_manager.remove_component(&mut self.connector, component_id)?.id();
+                self.journal.record($crate::sim::JournalEntry::RemoveComponent { id: id.to_string() });
+                Ok(id)
+            }
+
+            fn reset_component(&mut self, component_id: &str) -> anyhow::Result<()> {
+                self.layer_manager.reset_component(&mut self.connector, component_id)?;
+                self.journal.record($crate::sim::JournalEntry::ResetComponent { id: component_id.to_string() });
+                Ok(())
+            }
+
+            fn run_component_once(
+                &mut self,
+                component_id: &str,
+            ) -> anyhow::Result<Vec<($crate::sim::SimTime, &dyn $crate::event::Event)>> {
+                let schedule_ids = self.layer_manager.run_component_once(&mut self.connector, component_id)?;
+                self.journal.record($crate::sim::JournalEntry::RunComponentOnce { id: component_id.to_string() });
+                let mut scheduled: Vec<_> = schedule_ids
+                    .iter()
+                    .filter_map(|id| self.connector.time_manager.scheduled_event(id))
+                    .collect();
+                scheduled.sort_by_key(|(time, _)| *time);
+                Ok(scheduled)
+            }
+
+            fn layer_components(&self, layer_type: $crate::sim::layer::LayerType) -> Vec<&'static str> {
+                self.layer_manager.layer_components(layer_type)
+            }
+
+            fn remove_layer_components(&mut self, layer_type: $crate::sim::layer::LayerType) -> Vec<&'static str> {
+                self.journal.record($crate::sim::JournalEntry::RemoveLayerComponents { layer_type });
+                self.layer_manager.remove_layer_components(&mut self.connector, layer_type)
             }
 
             fn schedule_event(
@@ -146,6 +546,7 @@ macro_rules! impl_sim {
                 wait_time: $crate::SimTimeSpan,
                 event: Box<dyn $crate::event::Event>,
             ) -> $crate::IdType {
+                self.journal.record_schedule(wait_time, event.as_ref());
                 self.connector.time_manager.schedule_event(wait_time, event)
             }
 
@@ -153,7 +554,25 @@ macro_rules! impl_sim {
                 &mut self,
                 schedule_id: &$crate::IdType,
             ) -> anyhow::Result<()> {
-                self.connector.time_manager.unschedule_event(schedule_id)
+                self.connector.time_manager.unschedule_event(schedule_id)?;
+                self.journal.record($crate::sim::JournalEntry::UnscheduleEvent { schedule_id: *schedule_id });
+                Ok(())
+            }
+
+            fn force_layer_run(&mut self, layer_type: Option<$crate::sim::layer::LayerType>) {
+                self.journal.record($crate::sim::JournalEntry::ForceLayerRun { layer_type });
+                self.layer_manager.force_layer_run(&mut self.connector, layer_type);
+            }
+
+            fn initialize_baseline_vitals(&mut self) {
+                self.journal.record($crate::sim::JournalEntry::InitializeBaselineVitals);
+                self.connector.set_default_events(
+                    <$organism as $crate::sim::Organism>::baseline_vitals()
+                );
+            }
+
+            fn state(&self) -> &$crate::sim::SimState {
+                &self.connector.state
             }
 
             fn drain_active(
@@ -161,6 +580,72 @@ macro_rules! impl_sim {
             ) -> $crate::event::EventDrainIterator {
                 $crate::event::EventDrainIterator(self.connector.active_events.drain(..))
             }
+
+            fn drain_remaining(
+                &mut self
+            ) -> Vec<($crate::sim::SimTime, std::sync::Arc<dyn $crate::event::Event>)> {
+                self.journal.record($crate::sim::JournalEntry::DrainRemaining);
+                self.connector.time_manager.drain_scheduled()
+            }
+
+            fn journal(&self) -> &$crate::sim::SimJournal {
+                &self.journal
+            }
+
+            fn replay(&mut self, entry: &$crate::sim::JournalEntry) -> anyhow::Result<()> {
+                use $crate::sim::JournalEntry;
+                use $crate::SimTimeSpan;
+                match entry {
+                    JournalEntry::AddComponent { id } => {
+                        return Err(anyhow!("Component {} was added after the Sim was created", id));
+                    }
+                    JournalEntry::ConstantEvent { event } => {
+                        self.connector.set_constant_encoded_event(event)?;
+                        self.journal.record(entry.clone());
+                    }
+                    JournalEntry::SetMaxIterationsPerAdvance { max_iterations } => {
+                        self.set_max_iterations_per_advance(*max_iterations)
+                    }
+                    JournalEntry::SetEpoch { unix_s, nanos } => {
+                        self.set_epoch(std::time::UNIX_EPOCH + std::time::Duration::new(*unix_s, *nanos))
+                    }
+                    JournalEntry::ScheduleEvent { wait_s, event } => {
+                        self.schedule_event(SimTimeSpan::from_s(*wait_s), event.decode()?);
+                    }
+                    JournalEntry::UnscheduleEvent { schedule_id } => self.unschedule_event(schedule_id)?,
+                    JournalEntry::Advance { times } => (0..*times).for_each(|_| self.advance()),
+                    JournalEntry::AdvanceBy { span_s, times } => {
+                        (0..*times).for_each(|_| self.advance_by(SimTimeSpan::from_s(*span_s)))
+                    }
+                    JournalEntry::AdvanceByStepping { span_s } => {
+                        self.advance_by_stepping(SimTimeSpan::from_s(*span_s), &mut |_, _| {})
+                    }
+                    JournalEntry::AdvanceUntilEvent { type_name, max_time_s } => {
+                        let event_type = $crate::event::codec::registered_type_id(type_name)
+                            .ok_or_else(|| anyhow!("Event type {} isn't registered for serialization", type_name))?;
+                        self.advance_until_event_type(event_type, SimTimeSpan::from_s(*max_time_s));
+                    }
+                    JournalEntry::RemoveComponent { id } => {
+                        self.remove_component(id)?;
+                    }
+                    JournalEntry::ResetComponent { id } => self.reset_component(id)?,
+                    JournalEntry::RunComponentOnce { id } => {
+                        self.run_component_once(id)?;
+                    }
+                    JournalEntry::RemoveLayerComponents { layer_type } => {
+                        self.remove_layer_components(*layer_type);
+                    }
+                    JournalEntry::ForceLayerRun { layer_type } => self.force_layer_run(*layer_type),
+                    JournalEntry::InitializeBaselineVitals => self.initialize_baseline_vitals(),
+                    JournalEntry::DrainRemaining => {
+                        self.drain_remaining();
+                    }
+                    JournalEntry::Unpersistable { reason } => {
+                        return Err(anyhow!("Saved Sim can't be restored: {}", reason));
+                    }
+                }
+                Ok(())
+            }
         }
     };
 }
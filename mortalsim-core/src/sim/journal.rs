@@ -0,0 +1,227 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::event::codec::EncodedEvent;
+use crate::event::Event;
+use crate::{IdType, SimTimeSpan};
+
+use super::layer::LayerType;
+
+/// Input applied to a Sim from outside, such as an `Event` being scheduled
+/// or time advancing. Replaying the inputs of a Sim on another Sim created
+/// the same way reproduces its state, since simulations are deterministic.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum JournalEntry {
+    AddComponent { id: String },
+    ConstantEvent { event: EncodedEvent },
+    SetMaxIterationsPerAdvance { max_iterations: usize },
+    SetEpoch { unix_s: u64, nanos: u32 },
+    ScheduleEvent { wait_s: f64, event: EncodedEvent },
+    UnscheduleEvent { schedule_id: IdType },
+    Advance { times: u64 },
+    AdvanceBy { span_s: f64, times: u64 },
+    AdvanceByStepping { span_s: f64 },
+    AdvanceUntilEvent { type_name: String, max_time_s: f64 },
+    RemoveComponent { id: String },
+    ResetComponent { id: String },
+    RunComponentOnce { id: String },
+    RemoveLayerComponents { layer_type: LayerType },
+    ForceLayerRun { layer_type: Option<LayerType> },
+    InitializeBaselineVitals,
+    DrainRemaining,
+    /// Input which can't be saved, such as a global parameter of an
+    /// arbitrary type, with the reason why
+    Unpersistable { reason: String },
+}
+
+/// Record of the inputs applied to a Sim, in order, from which
+/// `Sim::load_from_file` restores a saved Sim. Consecutive advances by
+/// the same amount are combined into a single entry.
+#[derive(Debug, Clone, Default)]
+pub struct SimJournal {
+    entries: Vec<JournalEntry>,
+}
+
+impl SimJournal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Recorded entries, in the order they were applied
+    pub fn entries(&self) -> &[JournalEntry] {
+        &self.entries
+    }
+
+    /// Records an input applied to the Sim
+    ///
+    /// ### Arguments
+    /// * `entry` - input to record
+    pub fn record(&mut self, entry: JournalEntry) {
+        match (self.entries.last_mut(), &entry) {
+            (Some(JournalEntry::Advance { times }), JournalEntry::Advance { .. }) => *times += 1,
+            (
+                Some(JournalEntry::AdvanceBy { span_s, times }),
+                JournalEntry::AdvanceBy { span_s: next_span_s, .. },
+            ) if span_s == next_span_s => *times += 1,
+            _ => self.entries.push(entry),
+        }
+    }
+
+    /// Records an `Event` scheduled on the Sim
+    ///
+    /// ### Arguments
+    /// * `wait_time` - time to wait before emitting the `Event`
+    /// * `event`     - `Event` scheduled
+    pub fn record_schedule(&mut self, wait_time: SimTimeSpan, event: &dyn Event) {
+        self.record(match EncodedEvent::encode(event) {
+            Some(event) => JournalEntry::ScheduleEvent { wait_s: wait_time.to_s(), event },
+            None => Self::unregistered(event),
+        })
+    }
+
+    /// Records a constant `Event` set on the Sim
+    ///
+    /// ### Arguments
+    /// * `event` - `Event` set
+    pub fn record_constant(&mut self, event: &dyn Event) {
+        self.record(match EncodedEvent::encode(event) {
+            Some(event) => JournalEntry::ConstantEvent { event },
+            None => Self::unregistered(event),
+        })
+    }
+
+    /// Records the epoch set on the Sim
+    ///
+    /// ### Arguments
+    /// * `epoch` - calendar time of simulation time zero
+    pub fn record_epoch(&mut self, epoch: SystemTime) {
+        self.record(match epoch.duration_since(UNIX_EPOCH) {
+            Ok(since) => JournalEntry::SetEpoch { unix_s: since.as_secs(), nanos: since.subsec_nanos() },
+            Err(_) => JournalEntry::Unpersistable { reason: format!("epoch {:?} is before 1970", epoch) },
+        })
+    }
+
+    fn unregistered(event: &dyn Event) -> JournalEntry {
+        JournalEntry::Unpersistable {
+            reason: format!("{:?} isn't of a type registered for serialization", event),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+mod file {
+    use std::fs::File;
+    use std::io::{BufReader, BufWriter};
+    use std::path::Path;
+
+    use anyhow::Result;
+
+    use super::JournalEntry;
+    use crate::sim::Sim;
+
+    /// Version of the saved Sim format
+    const VERSION: u32 = 1;
+
+    /// Contents of a saved Sim file
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct SavedSim {
+        version: u32,
+        time_s: f64,
+        components: Vec<String>,
+        journal: Vec<JournalEntry>,
+        state: Vec<String>,
+    }
+
+    fn components(sim: &(impl Sim + ?Sized)) -> Vec<String> {
+        let mut components: Vec<String> = sim.active_components().iter().map(|id| id.to_string()).collect();
+        components.sort();
+        components
+    }
+
+    pub fn save<S: Sim + ?Sized>(sim: &S, path: &Path) -> Result<()> {
+        // A file missing any input could never be loaded
+        for entry in sim.journal().entries() {
+            if let JournalEntry::Unpersistable { reason } = entry {
+                return Err(anyhow!("Sim can't be saved: {}", reason));
+            }
+        }
+
+        let saved = SavedSim {
+            version: VERSION,
+            time_s: sim.time().to_s(),
+            components: components(sim),
+            journal: sim.journal().entries().to_vec(),
+            state: sim.state().snapshot(),
+        };
+        serde_json::to_writer(BufWriter::new(File::create(path)?), &saved)?;
+        Ok(())
+    }
+
+    pub fn load<S: Sim + ?Sized>(sim: &mut S, path: &Path) -> Result<()> {
+        let saved: SavedSim = serde_json::from_reader(BufReader::new(File::open(path)?))?;
+        if saved.version != VERSION {
+            return Err(anyhow!("Unsupported saved Sim version {}", saved.version));
+        }
+
+        // Inputs applied while creating the Sim must match
+        let applied = sim.journal().entries().len();
+        if !saved.journal.starts_with(sim.journal().entries()) {
+            return Err(anyhow!("Sim wasn't created the same way as the saved Sim"));
+        }
+        for entry in saved.journal[applied..].iter() {
+            sim.replay(entry)?;
+        }
+
+        if components(sim) != saved.components {
+            return Err(anyhow!(
+                "Loaded components {:?} differ from saved components {:?}",
+                components(sim),
+                saved.components
+            ));
+        }
+        if sim.time().to_s() != saved.time_s || sim.state().snapshot() != saved.state {
+            return Err(anyhow!("Loaded state at {:?} differs from the saved state", sim.time()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+pub use file::{load, save};
+
+mod tests {
+    use crate::event::test::TestEventA;
+    use crate::units::base::Distance;
+    use crate::SimTimeSpan;
+
+    use super::{JournalEntry, SimJournal};
+
+    #[test]
+    fn combines_equal_advances() {
+        let mut journal = SimJournal::new();
+        journal.record(JournalEntry::AdvanceBy { span_s: 1.0, times: 1 });
+        journal.record(JournalEntry::AdvanceBy { span_s: 1.0, times: 1 });
+        journal.record(JournalEntry::AdvanceBy { span_s: 2.0, times: 1 });
+        journal.record(JournalEntry::Advance { times: 1 });
+        journal.record(JournalEntry::Advance { times: 1 });
+        assert_eq!(
+            journal.entries(),
+            &[
+                JournalEntry::AdvanceBy { span_s: 1.0, times: 2 },
+                JournalEntry::AdvanceBy { span_s: 2.0, times: 1 },
+                JournalEntry::Advance { times: 2 },
+            ]
+        );
+    }
+
+    #[test]
+    fn records_events() {
+        let mut journal = SimJournal::new();
+        journal.record_schedule(SimTimeSpan::from_s(2.0), &TestEventA::new(Distance::from_m(1.0)));
+        if cfg!(feature = "serde") {
+            assert!(matches!(journal.entries(), [JournalEntry::ScheduleEvent { wait_s, .. }] if *wait_s == 2.0));
+        } else {
+            assert!(matches!(journal.entries(), [JournalEntry::Unpersistable { .. }]));
+        }
+    }
+}
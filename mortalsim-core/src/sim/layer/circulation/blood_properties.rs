@@ -0,0 +1,141 @@
+use crate::substance::SubstanceConcentration;
+
+/// Molar mass of the hemoglobin tetramer (g/mol)
+pub const HEMOGLOBIN_MOLAR_MASS: f64 = 64458.0;
+
+/// Number of O2 molecules bound by each fully saturated hemoglobin tetramer
+pub const HEMOGLOBIN_O2_SITES: f64 = 4.0;
+
+/// Whole blood properties which determine its oxygen carrying capacity.
+/// Defaults to those of a healthy adult human, with a hematocrit of 45%
+/// and 15 g/dL of hemoglobin.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BloodProperties {
+    /// Volume fraction of red blood cells, between 0 and 1
+    pub hematocrit: f64,
+    /// Concentration of hemoglobin tetramers in whole blood
+    pub hemoglobin: SubstanceConcentration,
+}
+
+impl BloodProperties {
+    /// Creates a new BloodProperties
+    ///
+    /// ### Arguments
+    /// * `hematocrit`          - Volume fraction of red blood cells, between 0 and 1
+    /// * `hemoglobin_g_per_dL` - Hemoglobin concentration of whole blood (g/dL)
+    #[allow(non_snake_case)]
+    pub fn new(hematocrit: f64, hemoglobin_g_per_dL: f64) -> Self {
+        Self {
+            hematocrit,
+            // g/dL -> g/L -> mol/L -> mM
+            hemoglobin: SubstanceConcentration::from_mM(hemoglobin_g_per_dL * 10.0 / HEMOGLOBIN_MOLAR_MASS * 1000.0),
+        }
+    }
+
+    /// Returns the hemoglobin concentration of whole blood (g/dL)
+    #[allow(non_snake_case)]
+    pub fn hemoglobin_g_per_dL(&self) -> f64 {
+        self.hemoglobin.to_mM() / 1000.0 * HEMOGLOBIN_MOLAR_MASS / 10.0
+    }
+
+    /// Concentration of O2 which can be bound by hemoglobin when fully saturated
+    pub fn o2_capacity(&self) -> SubstanceConcentration {
+        self.hemoglobin * HEMOGLOBIN_O2_SITES
+    }
+
+    /// Total O2 content of whole blood, both bound and dissolved
+    ///
+    /// ### Arguments
+    /// * `dissolved`  - Dissolved O2 concentration
+    /// * `saturation` - Fraction of hemoglobin binding sites occupied, between 0 and 1
+    ///
+    /// Returns the total O2 concentration
+    pub fn o2_content(&self, dissolved: SubstanceConcentration, saturation: f64) -> SubstanceConcentration {
+        self.o2_capacity() * saturation.clamp(0.0, 1.0) + dissolved
+    }
+}
+
+impl Default for BloodProperties {
+    fn default() -> Self {
+        Self::new(0.45, 15.0)
+    }
+}
+
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::sim::component::{ComponentRegistry, SimComponent};
+    use crate::sim::layer::circulation::{CirculationComponent, CirculationConnector, CirculationInitializer};
+    use crate::sim::organism::test::{TestBloodVessel, TestOrganism, TestSim};
+    use crate::sim::Sim;
+    use crate::substance::SubstanceConcentration;
+    use crate::SimTimeSpan;
+
+    use super::BloodProperties;
+
+    /// Computes the Aorta's O2 content at 97% saturation with 0.13 mM of
+    /// dissolved O2, optionally setting its hemoglobin concentration
+    struct O2ContentComponent {
+        circ_connector: CirculationConnector<TestOrganism>,
+        hemoglobin_g_per_dl: Option<f64>,
+        content: Arc<Mutex<Option<SubstanceConcentration>>>,
+    }
+
+    impl CirculationComponent<TestOrganism> for O2ContentComponent {
+        fn circulation_init(&mut self, initializer: &mut CirculationInitializer<TestOrganism>) {
+            initializer.attach_vessel(TestBloodVessel::Aorta);
+            if let Some(hemoglobin) = self.hemoglobin_g_per_dl {
+                initializer.set_blood_properties(TestBloodVessel::Aorta, BloodProperties::new(0.45, hemoglobin));
+            }
+        }
+
+        fn circulation_connector(&mut self) -> &mut CirculationConnector<TestOrganism> {
+            &mut self.circ_connector
+        }
+    }
+
+    impl SimComponent<TestOrganism> for O2ContentComponent {
+        fn id(&self) -> &'static str {
+            "O2ContentComponent"
+        }
+
+        fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+            registry.add_circulation_component(self)
+        }
+
+        fn run(&mut self) {
+            let properties = self.circ_connector.blood_store(&TestBloodVessel::Aorta).unwrap().blood_properties();
+            *self.content.lock().unwrap() = Some(properties.o2_content(SubstanceConcentration::from_mM(0.13), 0.97));
+        }
+    }
+
+    fn o2_content(hemoglobin_g_per_dl: Option<f64>) -> f64 {
+        let content = Arc::new(Mutex::new(None));
+        let mut sim = TestSim::new();
+        sim.add_component(O2ContentComponent {
+            circ_connector: CirculationConnector::new(),
+            hemoglobin_g_per_dl,
+            content: content.clone(),
+        }).unwrap();
+        sim.advance_by(SimTimeSpan::from_s(1.0));
+        let result = content.lock().unwrap().unwrap();
+        result.to_mM()
+    }
+
+    #[test]
+    fn o2_content_follows_hemoglobin() {
+        let properties = BloodProperties::new(0.45, 15.0);
+        assert!((properties.hemoglobin_g_per_dL() - 15.0).abs() < 1e-9);
+        // ~2.33 mM of tetramers, binding ~9.3 mM of O2
+        assert!((properties.o2_capacity().to_mM() - 9.308).abs() < 0.001, "{}", properties.o2_capacity());
+
+        // The organism's properties apply by default
+        let normal = o2_content(None);
+        assert!((normal - (0.97 * 9.308 + 0.13)).abs() < 0.001, "{}", normal);
+        assert_eq!(o2_content(Some(15.0)), normal);
+
+        // Anemia lowers the bound O2 in proportion to hemoglobin
+        let anemic = o2_content(Some(7.5));
+        assert!(((anemic - 0.13) / (normal - 0.13) - 0.5).abs() < 1e-9, "{} vs {}", anemic, normal);
+    }
+}
@@ -1,30 +1,115 @@
 use either::Either;
 
+use crate::event::Event;
+use crate::sim::layer::circulation::{BloodProperties, BloodVessel, VolumetricFlowRate};
 use crate::sim::organism::Organism;
-use crate::sim::SimTime;
+use crate::sim::{SimGlobals, SimTime};
 use crate::substance::substance_wrapper::substance_store_wrapper;
-use crate::substance::{Substance, SubstanceStore};
+use crate::substance::{ConcentrationRate, Substance, SubstanceConcentration, SubstanceStore};
+use crate::units::base::Amount;
 use crate::IdType;
+use std::any::Any;
 use std::borrow::{Borrow, BorrowMut};
-use std::cell::{RefCell, RefMut};
-use std::collections::{hash_map, HashMap};
+use std::cell::{Ref, RefCell, RefMut};
+use std::collections::{hash_map, HashMap, VecDeque};
 use std::sync::{Arc, Mutex, MutexGuard};
 
 pub struct BloodStore {
     store: SubstanceStore,
     change_id_map: HashMap<Substance, Vec<IdType>>,
+    properties: BloodProperties,
+    /// Number of samples retained for each Substance, or 0 if disabled
+    history_capacity: usize,
+    /// Recent concentration samples for each Substance, oldest first
+    history: HashMap<Substance, VecDeque<(SimTime, SubstanceConcentration)>>,
+    /// Whether area under the curve is accumulated on each advance
+    auc_enabled: bool,
+    /// Accumulated area under the curve for each Substance (mM*s)
+    auc: HashMap<Substance, f64>,
+    /// Fraction of each protein bound Substance which is bound
+    bound_fractions: HashMap<Substance, f64>,
+    /// Non-finite concentrations caught while advancing, oldest first
+    anomalies: Vec<ConcentrationAnomaly>,
+}
+
+/// Record of a non-finite Substance concentration caught on a `BloodStore`,
+/// e.g. from a change with a NaN amount. The concentration is restored to
+/// its previous value, or zero, and its scheduled changes are dropped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConcentrationAnomaly {
+    /// Simulation time the anomaly was caught
+    pub time: SimTime,
+    /// Substance with the non-finite concentration
+    pub substance: Substance,
+    /// Non-finite concentration which was reset
+    pub value: SubstanceConcentration,
 }
 
 impl BloodStore {
     pub fn new() -> BloodStore {
+        Self::build(SubstanceStore::new_tracking(), HashMap::new())
+    }
+
+    pub fn build(store: SubstanceStore, change_id_map: HashMap<Substance, Vec<IdType>>) -> BloodStore {
         BloodStore {
-            store: SubstanceStore::new_tracking(),
-            change_id_map: HashMap::new(),
+            store,
+            change_id_map,
+            properties: BloodProperties::default(),
+            history_capacity: 0,
+            history: HashMap::new(),
+            auc_enabled: false,
+            auc: HashMap::new(),
+            bound_fractions: HashMap::new(),
+            anomalies: Vec::new(),
         }
     }
 
-    pub fn build(store: SubstanceStore, change_id_map: HashMap<Substance, Vec<IdType>>) -> BloodStore {
-        BloodStore { store, change_id_map }
+    /// Whole blood properties of the vessel, e.g. to compute O2 content
+    pub fn blood_properties(&self) -> BloodProperties {
+        self.properties
+    }
+
+    pub(crate) fn set_blood_properties(&mut self, properties: BloodProperties) {
+        self.properties = properties;
+    }
+
+    /// Fraction of the Substance which is bound to plasma proteins, or 0
+    /// if it isn't protein bound
+    ///
+    /// ### Arguments
+    /// * `substance` - Substance to retrieve the bound fraction for
+    pub fn bound_fraction(&self, substance: &Substance) -> f64 {
+        self.bound_fractions.get(substance).copied().unwrap_or(0.0)
+    }
+
+    /// Fractions of each protein bound Substance which are bound
+    pub fn bound_fractions(&self) -> &HashMap<Substance, f64> {
+        &self.bound_fractions
+    }
+
+    /// Concentration of the Substance which isn't bound to plasma proteins,
+    /// and is therefore free to act and diffuse. `concentration_of` reports
+    /// the total concentration, including the bound fraction.
+    ///
+    /// ### Arguments
+    /// * `substance` - Substance to retrieve the free concentration of
+    pub fn free_concentration_of(&self, substance: &Substance) -> SubstanceConcentration {
+        self.store.concentration_of(substance) * (1.0 - self.bound_fraction(substance))
+    }
+
+    pub(crate) fn set_bound_fractions(&mut self, bound_fractions: &HashMap<Substance, f64>) {
+        self.bound_fractions.clone_from(bound_fractions);
+    }
+
+    /// Current concentration of each Substance in the store
+    pub(crate) fn composition(&self) -> &HashMap<Substance, SubstanceConcentration> {
+        self.store.get_composition()
+    }
+
+    /// Takes the Substances which have had changes scheduled directly on
+    /// the store since the last call
+    pub(crate) fn take_scheduled_substances(&mut self) -> impl Iterator<Item = Substance> + '_ {
+        self.store.take_scheduled_substances()
     }
 
     pub(crate) fn extract(self) -> (SubstanceStore, HashMap<Substance, Vec<IdType>>) {
@@ -32,7 +117,95 @@ impl BloodStore {
     }
 
     pub(crate) fn advance(&mut self, sim_time: SimTime) {
-        self.store.advance(sim_time)
+        let start_time = self.store.sim_time();
+        self.store.advance(sim_time);
+
+        if self.store.get_composition().values().any(|concentration| !concentration.molpm3.is_finite()) {
+            for (substance, value) in self.store.reset_non_finite() {
+                log::warn!("Non-finite {} concentration {} reset at {}", substance, value, sim_time);
+                self.change_id_map.remove(&substance);
+                self.anomalies.push(ConcentrationAnomaly { time: sim_time, substance, value });
+            }
+        }
+
+        if self.auc_enabled && sim_time > start_time {
+            // Trapezoidal rule over the interval, where only Substances
+            // which were changing differ from their starting concentrations
+            let dt = (sim_time - start_time).to_s();
+            for (substance, concentration) in self.store.get_composition() {
+                *self.auc.entry(*substance).or_default() += concentration.to_mM() * dt;
+            }
+            for (substance, start) in self.store.advance_start() {
+                let end = self.store.concentration_of(substance);
+                *self.auc.entry(*substance).or_default() += (start.to_mM() - end.to_mM()) / 2.0 * dt;
+            }
+        }
+
+        if self.history_capacity > 0 {
+            for (substance, concentration) in self.store.get_composition() {
+                let samples = self.history.entry(*substance).or_default();
+                samples.push_back((sim_time, *concentration));
+                if samples.len() > self.history_capacity {
+                    samples.pop_front();
+                }
+            }
+        }
+    }
+
+    /// Recent concentration samples of a Substance, recorded each time the
+    /// store advances, if history has been enabled for the vessel
+    ///
+    /// ### Arguments
+    /// * `substance` - Substance to retrieve history for
+    ///
+    /// Returns `(time, concentration)` samples, oldest first
+    pub fn history(&self, substance: &Substance) -> Vec<(SimTime, SubstanceConcentration)> {
+        self.history
+            .get(substance)
+            .map_or_else(Vec::new, |samples| samples.iter().copied().collect())
+    }
+
+    /// Area under the concentration curve of a Substance, accumulated
+    /// each time the store advances, if AUC has been enabled for the vessel
+    ///
+    /// ### Arguments
+    /// * `substance` - Substance to retrieve the AUC for
+    ///
+    /// Returns the AUC in mM*s, or 0 if none has accumulated
+    pub fn auc(&self, substance: &Substance) -> f64 {
+        self.auc.get(substance).copied().unwrap_or(0.0)
+    }
+
+    /// Non-finite concentrations caught on this store, oldest first
+    pub fn anomalies(&self) -> &[ConcentrationAnomaly] {
+        &self.anomalies
+    }
+
+    pub(crate) fn enable_auc(&mut self) {
+        self.auc_enabled = true;
+    }
+
+    /// Sets the number of samples retained for each Substance, dropping
+    /// the oldest samples if there are already more than that
+    pub(crate) fn set_history_capacity(&mut self, capacity: usize) {
+        self.history_capacity = capacity;
+        for samples in self.history.values_mut() {
+            while samples.len() > capacity {
+                samples.pop_front();
+            }
+        }
+    }
+
+    /// Registers the given maximum rates of change on the store
+    pub(crate) fn apply_max_rates(&mut self, max_rates: &HashMap<Substance, ConcentrationRate>) {
+        for (substance, max_rate) in max_rates.iter() {
+            // Rates are validated by the initializer
+            self.store.set_max_rate(*substance, *max_rate).unwrap();
+        }
+    }
+
+    pub(crate) fn clear_max_rate(&mut self, substance: &Substance) {
+        self.store.clear_max_rate(substance)
     }
 
     substance_store_wrapper!(store, change_id_map);
@@ -44,19 +217,49 @@ impl Default for BloodStore {
     }
 }
 
+/// One-shot notification for when a Substance concentration falls
+/// below a given level on a vessel
+pub(crate) struct BelowNotify<O: Organism> {
+    pub vessel: O::VesselType,
+    pub substance: Substance,
+    pub level: SubstanceConcentration,
+    /// Creates a new instance of the event to emit at the crossing
+    pub make_event: Box<dyn Fn() -> Box<dyn Event> + Send>,
+    /// Whether the notification will fire on the next crossing
+    pub armed: bool,
+    /// Schedule id and time of the event for the predicted crossing
+    pub scheduled: Option<(IdType, SimTime)>,
+}
+
 pub struct CirculationConnector<O: Organism> {
     /// Mapping of `BloodVessel`s to their corresponding `SubstanceStore`
     pub(crate) vessel_map: HashMap<O::VesselType, RefCell<BloodStore>>,
     /// Mapping of `BloodVessel`s to their corresponding `SubstanceStore`
     pub(crate) vessel_map_sync: HashMap<O::VesselType, Arc<Mutex<BloodStore>>>,
+    /// Cached order of the attached vessels by vessel name
+    vessel_order: RefCell<Vec<O::VesselType>>,
     /// indicate whether the Arcs for sync have already been cloned
     pub(crate) synced: bool,
     /// Copy of the current simulation time
     pub(crate) sim_time: SimTime,
+    /// Copy of the current cardiac output
+    pub(crate) cardiac_output: VolumetricFlowRate,
+    /// Global parameters of the Sim
+    pub(crate) globals: SimGlobals,
+    /// Copy of the mixing factors of vessels marked as reservoirs
+    pub(crate) reservoirs: HashMap<O::VesselType, f64>,
     /// Whether all changes should be unscheduled before each run
     /// NOTE: If this is set to false, the component is responsible for
     /// tracking and unscheduling preexisting changes, if necessary
     pub(crate) unschedule_all: bool,
+    /// Notifications requested during the last run, to be handed
+    /// off to the layer
+    pub(crate) below_notifies: Vec<BelowNotify<O>>,
+    /// Whether the component has yet to complete its first run
+    pub(crate) first_run: bool,
+    /// Amounts of each Substance held by the component outside of the
+    /// blood stores, to be handed off to the layer
+    pub(crate) held_amounts: HashMap<Substance, Amount<f64>>,
 }
 
 impl<O: Organism> CirculationConnector<O> {
@@ -64,9 +267,16 @@ impl<O: Organism> CirculationConnector<O> {
         CirculationConnector {
             vessel_map: HashMap::new(),
             vessel_map_sync: HashMap::new(),
+            vessel_order: RefCell::new(Vec::new()),
             synced: false,
             sim_time: SimTime::from_s(0.0),
+            cardiac_output: VolumetricFlowRate::from_L_per_min(O::cardiac_index() * O::body_surface_area().to_m2()),
+            globals: SimGlobals::new(),
+            reservoirs: HashMap::new(),
             unschedule_all: true,
+            below_notifies: Vec::new(),
+            first_run: true,
+            held_amounts: HashMap::new(),
         }
     }
 
@@ -82,16 +292,87 @@ impl<O: Organism> CirculationConnector<O> {
         None
     }
 
-    /// Retrieves an iterator of all existing blood stores which are connected
-    /// to this component.
-    pub fn with_blood_stores(&self, mut fcn: impl FnMut(O::VesselType, &mut BloodStore)) {
-        if self.vessel_map_sync.is_empty() {
-            for (v, s) in self.vessel_map.iter() {
-                fcn(*v, &mut *s.borrow_mut())
+    /// Calls the given function with each blood store which is connected
+    /// to this component, in order of vessel name.
+    pub fn with_blood_stores(&self, fcn: impl FnMut(O::VesselType, &mut BloodStore)) {
+        self.with_blood_stores_in(&self.attached_vessels(), fcn)
+    }
+
+    /// Calls the given function with each blood store which is connected
+    /// to this component, starting from the reference vessel and proceeding
+    /// in order of distance downstream of it. Vessels which aren't reachable
+    /// from the reference vessel are visited last, in order of vessel name.
+    ///
+    /// ### Arguments
+    /// * `from` - reference vessel to order by distance from
+    /// * `fcn`  - function to call with each vessel and its blood store
+    pub fn with_blood_stores_ordered(&self, from: O::VesselType, fcn: impl FnMut(O::VesselType, &mut BloodStore)) {
+        let mut order = vec![from];
+        order.extend(from.reachable_vessels().into_iter().map(|(vessel, _)| vessel));
+        let unreachable: Vec<O::VesselType> = self.attached_vessels().iter().copied().filter(|v| !order.contains(v)).collect();
+        order.extend(unreachable);
+        self.with_blood_stores_in(&order, fcn)
+    }
+
+    /// Vessels connected to this component whose blood stores have new
+    /// changes, i.e. those scheduled by any component before the stores
+    /// last advanced. This is the same view `SimpleBloodFlow` uses to
+    /// decide which changes to propagate. Only vessels with change
+    /// tracking (e.g. from `notify_any_change`) can report new changes.
+    ///
+    /// Returns the vessels in order of vessel name
+    pub fn vessels_with_active_changes(&self) -> Vec<O::VesselType> {
+        let mut vessels = Vec::new();
+        self.with_blood_stores(|vessel, store| {
+            if store.has_new_changes() {
+                vessels.push(vessel);
             }
+        });
+        vessels
+    }
+
+    /// Non-finite concentrations caught on the blood stores of vessels
+    /// connected to this component. Each is reset when caught, so the
+    /// stores remain usable.
+    ///
+    /// Returns the anomalies along with their vessels, in order of vessel name
+    pub fn anomalies(&self) -> Vec<(O::VesselType, ConcentrationAnomaly)> {
+        let mut anomalies = Vec::new();
+        self.with_blood_stores(|vessel, store| {
+            anomalies.extend(store.anomalies().iter().map(|anomaly| (vessel, *anomaly)));
+        });
+        anomalies
+    }
+
+    /// Vessels connected to this component, in order of vessel name. A
+    /// component is attached to the same vessels on each run, so the order
+    /// is cached and only rebuilt when the number of attached vessels changes.
+    fn attached_vessels(&self) -> Ref<'_, Vec<O::VesselType>> {
+        let count = if self.vessel_map_sync.is_empty() {
+            self.vessel_map.len()
         } else {
-            for (v, s) in self.vessel_map_sync.iter() {
-                fcn(*v, &mut *s.lock().unwrap())
+            self.vessel_map_sync.len()
+        };
+        if self.vessel_order.borrow().len() != count {
+            let mut vessels: Vec<O::VesselType> = if self.vessel_map_sync.is_empty() {
+                self.vessel_map.keys().copied().collect()
+            } else {
+                self.vessel_map_sync.keys().copied().collect()
+            };
+            vessels.sort_by_key(|v| Into::<&'static str>::into(*v));
+            *self.vessel_order.borrow_mut() = vessels;
+        }
+        self.vessel_order.borrow()
+    }
+
+    fn with_blood_stores_in(&self, order: &[O::VesselType], mut fcn: impl FnMut(O::VesselType, &mut BloodStore)) {
+        for vessel in order.iter().copied() {
+            if self.vessel_map_sync.is_empty() {
+                if let Some(store) = self.vessel_map.get(&vessel) {
+                    fcn(vessel, &mut store.borrow_mut())
+                }
+            } else if let Some(store) = self.vessel_map_sync.get(&vessel) {
+                fcn(vessel, &mut store.lock().unwrap())
             }
         }
     }
@@ -101,6 +382,97 @@ impl<O: Organism> CirculationConnector<O> {
         self.sim_time
     }
 
+    /// Retrieves the current cardiac output
+    pub fn cardiac_output(&self) -> VolumetricFlowRate {
+        self.cardiac_output
+    }
+
+    /// Rate of blood flow directly from one vessel into another, as the
+    /// current cardiac output times the fraction of it carried by the edge.
+    /// See `BloodVessel::flow_fractions` for how flow is divided.
+    ///
+    /// ### Arguments
+    /// * `from` - Upstream vessel
+    /// * `to`   - Vessel directly downstream of `from`
+    ///
+    /// Returns the flow rate, which is zero if `to` isn't directly downstream of `from`
+    pub fn flow_rate(&self, from: O::VesselType, to: O::VesselType) -> VolumetricFlowRate {
+        VolumetricFlowRate {
+            m3ps: self.cardiac_output.m3ps * from.edge_flow_fraction(to),
+        }
+    }
+
+    /// Fraction of normal mixing out of the given vessel, which is less
+    /// than 1 for vessels marked as reservoirs.
+    /// See `CirculationInitializer::set_reservoir`.
+    ///
+    /// ### Arguments
+    /// * `vessel` - Vessel to retrieve the mixing factor for
+    ///
+    /// Returns the mixing factor, which is 1 for ordinary vessels
+    pub fn mixing_factor(&self, vessel: &O::VesselType) -> f64 {
+        self.reservoirs.get(vessel).copied().unwrap_or(1.0)
+    }
+
+    /// Whether the component is running for the first time, e.g. to
+    /// seed baselines from the current state
+    pub fn is_first_run(&self) -> bool {
+        self.first_run
+    }
+
+    /// Retrieves the current value of a global parameter of the Sim,
+    /// such as ambient temperature or altitude
+    ///
+    /// ### Arguments
+    /// * `key` - Name of the global
+    ///
+    /// Returns a copy of the value, or `None` if the global isn't set or
+    /// holds a value of another type
+    pub fn get_global<T: Any + Clone>(&self, key: &str) -> Option<T> {
+        self.globals.get(key)
+    }
+
+    /// Requests a one-shot `Event` for the first time a Substance falls
+    /// below the given level on a vessel. The notification is armed once
+    /// the concentration is at or above the level, and the event is then
+    /// scheduled at the crossing time predicted from the pending
+    /// `SubstanceChange`s, being rescheduled as those changes are updated.
+    /// Once emitted, it is only re-armed after the concentration recovers.
+    ///
+    /// ### Arguments
+    /// * `vessel`    - Vessel to watch
+    /// * `substance` - Substance to watch
+    /// * `level`     - Concentration level which triggers the event
+    /// * `evt`       - Event to emit at each crossing
+    pub fn notify_once_below<E: Event + Clone>(
+        &mut self,
+        vessel: O::VesselType,
+        substance: Substance,
+        level: SubstanceConcentration,
+        evt: E,
+    ) {
+        self.below_notifies.push(BelowNotify {
+            vessel,
+            substance,
+            level,
+            make_event: Box::new(move || Box::new(evt.clone())),
+            armed: false,
+            scheduled: None,
+        });
+    }
+
+    /// Reports the amount of a Substance the component currently holds
+    /// outside of the blood stores, e.g. in a tissue compartment, so that
+    /// it's included in `Sim::total_substance`. Replaces any amount
+    /// previously reported for the Substance.
+    ///
+    /// ### Arguments
+    /// * `substance` - Substance held by the component
+    /// * `amount`    - Amount currently held
+    pub fn set_held_amount(&mut self, substance: Substance, amount: Amount<f64>) {
+        self.held_amounts.insert(substance, amount);
+    }
+
     /// Whether to unschedule all changes automatically before each run
     /// NOTE: If this is set to false, the component is responsible for
     /// tracking and unscheduling preexisting changes, if necessary
@@ -116,9 +488,11 @@ pub mod test {
     use std::collections::HashMap;
 
     use crate::sim::layer::circulation::component::connector::BloodStore;
+    use crate::sim::layer::circulation::BloodProperties;
     use crate::sim::organism::test::{TestBloodVessel, TestOrganism};
     use crate::sim::SimTime;
-    use crate::substance::{Substance, SubstanceChange, SubstanceStore};
+    use crate::math::BoundFn;
+    use crate::substance::{Substance, SubstanceChange, SubstanceConcentration, SubstanceStore};
     use crate::{mmol_per_L, SimTimeSpan};
     use simple_si_units::chemical::Concentration;
 
@@ -129,6 +503,13 @@ pub mod test {
         let store = BloodStore {
             store: SubstanceStore::new(),
             change_id_map: HashMap::new(),
+            properties: BloodProperties::default(),
+            history_capacity: 0,
+            history: HashMap::new(),
+            auc_enabled: false,
+            auc: HashMap::new(),
+            bound_fractions: HashMap::new(),
+            anomalies: Vec::new(),
         };
         assert_eq!(
             store.concentration_of(&Substance::GLC),
@@ -141,6 +522,13 @@ pub mod test {
         let mut store = BloodStore {
             store: SubstanceStore::new(),
             change_id_map: HashMap::new(),
+            properties: BloodProperties::default(),
+            history_capacity: 0,
+            history: HashMap::new(),
+            auc_enabled: false,
+            auc: HashMap::new(),
+            bound_fractions: HashMap::new(),
+            anomalies: Vec::new(),
         };
         store.schedule_change(Substance::GLC, mmol_per_L!(1.0), SimTimeSpan::from_s(1.0));
     }
@@ -150,6 +538,13 @@ pub mod test {
         let mut store = BloodStore {
             store: SubstanceStore::new(),
             change_id_map: HashMap::new(),
+            properties: BloodProperties::default(),
+            history_capacity: 0,
+            history: HashMap::new(),
+            auc_enabled: false,
+            auc: HashMap::new(),
+            bound_fractions: HashMap::new(),
+            anomalies: Vec::new(),
         };
         store.schedule_custom_change(
             Substance::GLC,
@@ -167,6 +562,13 @@ pub mod test {
         let mut store = BloodStore {
             store: SubstanceStore::new(),
             change_id_map: HashMap::new(),
+            properties: BloodProperties::default(),
+            history_capacity: 0,
+            history: HashMap::new(),
+            auc_enabled: false,
+            auc: HashMap::new(),
+            bound_fractions: HashMap::new(),
+            anomalies: Vec::new(),
         };
         let id = store.schedule_change(Substance::GLC, mmol_per_L!(1.0), SimTimeSpan::from_s(1.0));
         assert!(store.unschedule_change(&Substance::GLC, &id).is_some());
@@ -177,10 +579,213 @@ pub mod test {
         let mut store = BloodStore {
             store: SubstanceStore::new(),
             change_id_map: HashMap::new(),
+            properties: BloodProperties::default(),
+            history_capacity: 0,
+            history: HashMap::new(),
+            auc_enabled: false,
+            auc: HashMap::new(),
+            bound_fractions: HashMap::new(),
+            anomalies: Vec::new(),
         };
         assert!(store.unschedule_change(&Substance::GLC, &1).is_none());
     }
 
+    #[test]
+    fn test_blood_stores_ordered() {
+        let mut connector = CirculationConnector::<TestOrganism>::new();
+        for vessel in [
+            TestBloodVessel::InferiorVenaCava,
+            TestBloodVessel::LeftFemoralVein,
+            TestBloodVessel::Aorta,
+            TestBloodVessel::RightFemoralArtery,
+            TestBloodVessel::AbdominalAorta,
+            TestBloodVessel::RightFemoralVein,
+            TestBloodVessel::LeftFemoralArtery,
+        ] {
+            connector.vessel_map.insert(vessel, RefCell::new(BloodStore::new()));
+        }
+
+        let mut visited = Vec::new();
+        connector.with_blood_stores_ordered(TestBloodVessel::AbdominalAorta, |vessel, _| visited.push(vessel));
+
+        // Proximal vessels first, with the unreachable Aorta last
+        assert_eq!(visited, vec![
+            TestBloodVessel::AbdominalAorta,
+            TestBloodVessel::LeftFemoralArtery,
+            TestBloodVessel::RightFemoralArtery,
+            TestBloodVessel::LeftFemoralVein,
+            TestBloodVessel::RightFemoralVein,
+            TestBloodVessel::InferiorVenaCava,
+            TestBloodVessel::Aorta,
+        ]);
+
+        // Unordered iteration is by vessel name
+        visited.clear();
+        connector.with_blood_stores(|vessel, _| visited.push(vessel));
+        assert_eq!(visited, vec![
+            TestBloodVessel::AbdominalAorta,
+            TestBloodVessel::Aorta,
+            TestBloodVessel::InferiorVenaCava,
+            TestBloodVessel::LeftFemoralArtery,
+            TestBloodVessel::LeftFemoralVein,
+            TestBloodVessel::RightFemoralArtery,
+            TestBloodVessel::RightFemoralVein,
+        ]);
+
+        // The cached order picks up newly attached vessels
+        connector.vessel_map.insert(TestBloodVessel::VenaCava, RefCell::new(BloodStore::new()));
+        visited.clear();
+        connector.with_blood_stores(|vessel, _| visited.push(vessel));
+        assert_eq!(visited.len(), 8);
+        assert_eq!(visited.last(), Some(&TestBloodVessel::VenaCava));
+    }
+
+    #[test]
+    fn test_vessels_with_active_changes() {
+        let mut connector = CirculationConnector::<TestOrganism>::new();
+        for vessel in [TestBloodVessel::Aorta, TestBloodVessel::VenaCava, TestBloodVessel::AbdominalAorta] {
+            connector.vessel_map.insert(vessel, RefCell::new(BloodStore::new()));
+        }
+        assert!(connector.vessels_with_active_changes().is_empty());
+
+        for vessel in [TestBloodVessel::VenaCava, TestBloodVessel::AbdominalAorta] {
+            connector.blood_store(&vessel).unwrap().schedule_change(Substance::GLC, mmol_per_L!(1.0), SimTimeSpan::from_s(1.0));
+        }
+
+        // Changes are new once the stores advance, until the next advance
+        let advance = |connector: &CirculationConnector<TestOrganism>, s: f64| {
+            for store in connector.vessel_map.values() {
+                store.borrow_mut().advance(SimTime::from_s(s));
+            }
+        };
+        advance(&connector, 0.5);
+        assert_eq!(
+            connector.vessels_with_active_changes(),
+            vec![TestBloodVessel::AbdominalAorta, TestBloodVessel::VenaCava]
+        );
+
+        advance(&connector, 1.0);
+        assert!(connector.vessels_with_active_changes().is_empty());
+    }
+
+    #[test]
+    fn test_ramp_clamped() {
+        let mut store = BloodStore::new();
+        store.set_concentration_bounds(
+            Substance::O2,
+            SubstanceConcentration::from_uM(0.0),
+            SubstanceConcentration::from_uM(60.0),
+        ).unwrap();
+        store.schedule_ramp_clamped(
+            Substance::O2,
+            SubstanceConcentration::from_uM(100.0),
+            SimTimeSpan::from_s(10.0),
+            BoundFn::Linear,
+        );
+
+        let within = |val: SubstanceConcentration, expected: f64| {
+            assert!(
+                (val.to_uM() - expected).abs() < 0.001,
+                "O2 concentration {} is not {} uM",
+                val,
+                expected,
+            );
+        };
+
+        // Follows the ramp until hitting the bound
+        store.advance(SimTime::from_s(5.0));
+        within(store.concentration_of(&Substance::O2), 50.0);
+
+        // Saturates at the max partway through
+        store.advance(SimTime::from_s(6.5));
+        within(store.concentration_of(&Substance::O2), 60.0);
+        store.advance(SimTime::from_s(8.0));
+        within(store.concentration_of(&Substance::O2), 60.0);
+
+        // Relaxing the bound resumes the remainder of the ramp
+        // from the saturated value
+        store.set_concentration_bounds(
+            Substance::O2,
+            SubstanceConcentration::from_uM(0.0),
+            SubstanceConcentration::from_uM(200.0),
+        ).unwrap();
+        store.advance(SimTime::from_s(10.0));
+        within(store.concentration_of(&Substance::O2), 80.0);
+
+        // Tightening the bound clamps immediately
+        store.set_concentration_bounds(
+            Substance::O2,
+            SubstanceConcentration::from_uM(0.0),
+            SubstanceConcentration::from_uM(40.0),
+        ).unwrap();
+        within(store.concentration_of(&Substance::O2), 40.0);
+    }
+
+    #[test]
+    fn test_cancel_tagged() {
+        let mut store = BloodStore::new();
+        store.advance(SimTime::from_s(1.0));
+
+        store.schedule_change(Substance::GLC, mmol_per_L!(1.0), SimTimeSpan::from_s(1.0));
+        store.schedule_tagged_change(
+            Substance::GLC,
+            SubstanceChange::new(SimTime::from_s(1.0), mmol_per_L!(2.0), SimTimeSpan::from_s(1.0), BoundFn::Linear),
+            "infusion",
+        );
+
+        // Changes replicated from elsewhere
+        let source = SubstanceChange::new(SimTime::from_s(1.0), mmol_per_L!(1.0), SimTimeSpan::from_s(1.0), BoundFn::Linear);
+        store.schedule_dependent_change(Substance::O2, SimTime::from_s(1.5), 0.5, &source);
+        store.schedule_tagged_dependent_change(Substance::O2, SimTime::from_s(1.5), 1.0, &source, "infusion");
+
+        assert_eq!(store.cancel_tagged("infusion"), 2);
+        assert_eq!(store.cancel_tagged("infusion"), 0);
+        assert_eq!(store.get_substance_change_ids(Substance::GLC).count(), 1);
+
+        store.advance(SimTime::from_s(5.0));
+
+        // Only the untagged changes remain
+        assert!((store.concentration_of(&Substance::GLC).to_mM() - 1.0).abs() < 0.0001);
+        assert!((store.concentration_of(&Substance::O2).to_mM() - 0.5).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_overlapping_changes() {
+        // Overlapping changes superpose by default
+        let mut store = BloodStore::new();
+        store.schedule_custom_change(
+            Substance::GLC,
+            SubstanceChange::new(SimTime::from_s(0.0), mmol_per_L!(2.0), SimTimeSpan::from_s(2.0), BoundFn::Linear),
+        );
+        store.schedule_custom_change(
+            Substance::GLC,
+            SubstanceChange::new(SimTime::from_s(0.0), mmol_per_L!(1.0), SimTimeSpan::from_s(1.0), BoundFn::Linear),
+        );
+        store.advance(SimTime::from_s(1.0));
+        assert!((store.concentration_of(&Substance::GLC).to_mM() - 2.0).abs() < 0.0001);
+        store.advance(SimTime::from_s(3.0));
+        assert!((store.concentration_of(&Substance::GLC).to_mM() - 3.0).abs() < 0.0001);
+
+        // Halfway through, the remainder of the first change is replaced
+        let mut store = BloodStore::new();
+        store.schedule_custom_change(
+            Substance::GLC,
+            SubstanceChange::new(SimTime::from_s(0.0), mmol_per_L!(2.0), SimTimeSpan::from_s(2.0), BoundFn::Linear),
+        );
+        store.schedule_custom_change(
+            Substance::O2,
+            SubstanceChange::new(SimTime::from_s(0.0), mmol_per_L!(2.0), SimTimeSpan::from_s(2.0), BoundFn::Linear),
+        );
+        store.advance(SimTime::from_s(1.0));
+        let id = store.schedule_change_replace(Substance::GLC, mmol_per_L!(0.5), SimTimeSpan::from_s(1.0));
+        assert_eq!(store.get_substance_change_ids(Substance::GLC).collect::<Vec<_>>(), vec![&id]);
+        store.advance(SimTime::from_s(3.0));
+        assert!((store.concentration_of(&Substance::GLC).to_mM() - 1.5).abs() < 0.0001);
+
+        // Other Substances are unaffected
+        assert!((store.concentration_of(&Substance::O2).to_mM() - 2.0).abs() < 0.0001);
+    }
+
     #[test]
     fn test_get_multiple_stores() {
         let mut con = CirculationConnector::<TestOrganism>::new();
@@ -195,4 +800,55 @@ pub mod test {
 
         assert!(a.is_some() && aa.is_some() && vc.is_some() && laa.is_none());
     }
+
+    #[test]
+    fn test_anomalies() {
+        let mut connector = CirculationConnector::<TestOrganism>::new();
+        for vessel in [TestBloodVessel::Aorta, TestBloodVessel::VenaCava] {
+            connector.vessel_map.insert(vessel, RefCell::new(BloodStore::new()));
+        }
+
+        {
+            let mut store = connector.blood_store(&TestBloodVessel::Aorta).unwrap();
+            store.schedule_change(Substance::GLC, mmol_per_L!(1.0), SimTimeSpan::from_s(1.0));
+            store.schedule_change(Substance::O2, mmol_per_L!(1.0), SimTimeSpan::from_s(1.0));
+        }
+        for store in connector.vessel_map.values() {
+            store.borrow_mut().advance(SimTime::from_s(2.0));
+        }
+        assert!(connector.anomalies().is_empty());
+        let glc = connector.blood_store(&TestBloodVessel::Aorta).unwrap().concentration_of(&Substance::GLC);
+        let o2 = connector.blood_store(&TestBloodVessel::Aorta).unwrap().concentration_of(&Substance::O2);
+
+        // A NaN amount would otherwise poison the concentration
+        connector
+            .blood_store(&TestBloodVessel::Aorta)
+            .unwrap()
+            .schedule_change(Substance::GLC, mmol_per_L!(f64::NAN), SimTimeSpan::from_s(1.0));
+        for store in connector.vessel_map.values() {
+            store.borrow_mut().advance(SimTime::from_s(3.0));
+        }
+
+        let anomalies = connector.anomalies();
+        assert_eq!(anomalies.len(), 1, "{:?}", anomalies);
+        let (vessel, anomaly) = anomalies[0];
+        assert_eq!(vessel, TestBloodVessel::Aorta);
+        assert_eq!(anomaly.substance, Substance::GLC);
+        assert_eq!(anomaly.time, SimTime::from_s(3.0));
+        assert!(anomaly.value.molpm3.is_nan());
+
+        // The previous concentration is restored, without disturbing others
+        let store = connector.blood_store(&TestBloodVessel::Aorta).unwrap();
+        assert_eq!(store.concentration_of(&Substance::GLC), glc);
+        assert!((store.concentration_of(&Substance::O2) - o2).to_mM().abs() < 1e-6);
+        drop(store);
+
+        // and the change is dropped so it doesn't recur
+        for store in connector.vessel_map.values() {
+            store.borrow_mut().advance(SimTime::from_s(5.0));
+        }
+        assert_eq!(connector.anomalies().len(), 1);
+        let store = connector.blood_store(&TestBloodVessel::Aorta).unwrap();
+        assert!(store.concentration_of(&Substance::GLC).to_mM().is_finite());
+    }
 }
@@ -1,5 +1,6 @@
+use crate::sim::layer::circulation::BloodProperties;
 use crate::sim::organism::Organism;
-use crate::substance::{ConcentrationTracker, Substance, SubstanceConcentration};
+use crate::substance::{ConcentrationRate, ConcentrationTracker, Substance, SubstanceConcentration};
 use std::collections::{HashMap, HashSet};
 
 pub struct CirculationInitializer<O: Organism> {
@@ -9,10 +10,24 @@ pub struct CirculationInitializer<O: Organism> {
     pub(crate) substance_notifies: HashMap<O::VesselType, HashMap<Substance, ConcentrationTracker>>,
     /// Notifications requested for the associated component
     pub(crate) vessel_notifies: HashSet<O::VesselType>,
+    /// Substances read or written by the associated component
+    pub(crate) managed_substances: HashSet<Substance>,
     /// Notify any changes to any vessel
     pub(crate) notify_any: bool,
     /// Attached all vessels to the component.
     pub(crate) attach_all: bool,
+    /// Maximum rates of change requested for Substances
+    pub(crate) max_rates: HashMap<Substance, ConcentrationRate>,
+    /// Whole blood properties requested for vessels
+    pub(crate) blood_properties: HashMap<O::VesselType, BloodProperties>,
+    /// Number of composition samples to retain for vessels
+    pub(crate) history_capacities: HashMap<O::VesselType, usize>,
+    /// Vessels to accumulate area under the curve for
+    pub(crate) auc_vessels: HashSet<O::VesselType>,
+    /// Protein bound fractions requested for Substances
+    pub(crate) bound_fractions: HashMap<Substance, f64>,
+    /// Mixing factors of vessels marked as reservoirs
+    pub(crate) reservoirs: HashMap<O::VesselType, f64>,
 }
 
 impl<O: Organism> CirculationInitializer<O> {
@@ -21,8 +36,15 @@ impl<O: Organism> CirculationInitializer<O> {
             vessel_connections: HashSet::new(),
             substance_notifies: HashMap::new(),
             vessel_notifies: HashSet::new(),
+            managed_substances: HashSet::new(),
             notify_any: false,
             attach_all: false,
+            max_rates: HashMap::new(),
+            blood_properties: HashMap::new(),
+            history_capacities: HashMap::new(),
+            auc_vessels: HashSet::new(),
+            bound_fractions: HashMap::new(),
+            reservoirs: HashMap::new(),
         }
     }
 
@@ -47,6 +69,18 @@ impl<O: Organism> CirculationInitializer<O> {
             .or_insert(HashMap::new());
         substance_map.insert(substance, ConcentrationTracker::new(threshold));
         self.vessel_notifies.insert(vessel);
+        self.managed_substances.insert(substance);
+    }
+
+    /// Declares that the associated `CirculationComponent` schedules changes
+    /// to the given `Substance`. Used to report which components manage each
+    /// `Substance`, e.g. to detect components unintentionally writing the
+    /// same `Substance`.
+    ///
+    /// ### Arguments
+    /// * `substance` - `Substance` the component changes
+    pub fn manage_substance(&mut self, substance: Substance) {
+        self.managed_substances.insert(substance);
     }
 
     /// Registers the associated `CirculationComponent` to `run` whenever the
@@ -85,6 +119,104 @@ impl<O: Organism> CirculationInitializer<O> {
     pub fn attach_all_vessels(&mut self) {
         self.attach_all = true;
     }
+
+    /// Sets the whole blood properties, such as hematocrit and hemoglobin,
+    /// of the given vessel. Vessels without their own properties use those
+    /// of the `Organism`. Properties remain in effect if the component is
+    /// later removed, and where multiple components set properties for the
+    /// same vessel, the last component added applies.
+    ///
+    /// ### Arguments
+    /// * `vessel`     - `BloodVessel` to set properties for
+    /// * `properties` - Whole blood properties of the vessel
+    pub fn set_blood_properties(&mut self, vessel: O::VesselType, properties: BloodProperties) {
+        self.blood_properties.insert(vessel, properties);
+    }
+
+    /// Marks the given `Substance` as bound to plasma proteins on every
+    /// vessel. Only the free fraction is pharmacologically active and
+    /// diffuses, which is available from `BloodStore::free_concentration_of`,
+    /// while `BloodStore::concentration_of` continues to report the total.
+    /// Binding remains in effect if the component is later removed, and
+    /// where multiple components bind the same `Substance`, the last
+    /// component added applies.
+    ///
+    /// Panics if `bound_fraction` is not between 0 and 1
+    ///
+    /// ### Arguments
+    /// * `substance`      - `Substance` which is protein bound
+    /// * `bound_fraction` - Fraction of the total concentration which is bound
+    pub fn set_protein_binding(&mut self, substance: Substance, bound_fraction: f64) {
+        if !(0.0..=1.0).contains(&bound_fraction) {
+            panic!("Bound fraction for {} must be between 0 and 1!", substance);
+        }
+        self.bound_fractions.insert(substance, bound_fraction);
+    }
+
+    /// Marks the given vessel as a reservoir, e.g. a stagnant aneurysm sac,
+    /// which exchanges slowly with the rest of the circulation. Blood flow
+    /// components scale the mixing of the reservoir's contents back out to
+    /// other vessels by `mixing_factor`, while changes still flow into it.
+    /// Reservoirs remain in effect if the component is later removed, and
+    /// where multiple components mark the same vessel, the last component
+    /// added applies.
+    ///
+    /// Panics if `mixing_factor` is not between 0 and 1
+    ///
+    /// ### Arguments
+    /// * `vessel`        - `BloodVessel` which acts as a reservoir
+    /// * `mixing_factor` - Fraction of normal mixing out of the vessel, where 0 retains everything
+    pub fn set_reservoir(&mut self, vessel: O::VesselType, mixing_factor: f64) {
+        if !(0.0..=1.0).contains(&mixing_factor) {
+            panic!("Mixing factor for {:?} must be between 0 and 1!", vessel);
+        }
+        self.reservoirs.insert(vessel, mixing_factor);
+    }
+
+    /// Retains a bounded history of the concentration of each `Substance`
+    /// on the given vessel, sampled each time the simulation advances and
+    /// available from `BloodStore::history`. History remains enabled if the
+    /// component is later removed, and where multiple components enable
+    /// history on the same vessel, the largest capacity applies. Also
+    /// automatically attaches the vessel for use by the component.
+    ///
+    /// ### Arguments
+    /// * `vessel`   - `BloodVessel` to record history for
+    /// * `capacity` - Number of most recent samples to retain per `Substance`
+    pub fn enable_history(&mut self, vessel: O::VesselType, capacity: usize) {
+        self.vessel_connections.insert(vessel);
+        self.history_capacities.insert(vessel, capacity);
+    }
+
+    /// Accumulates the area under the concentration curve (AUC) of each
+    /// `Substance` on the given vessel, updated each time the simulation
+    /// advances and available from `BloodStore::auc`. AUC remains enabled
+    /// if the component is later removed. Also automatically attaches the
+    /// vessel for use by the component.
+    ///
+    /// ### Arguments
+    /// * `vessel` - `BloodVessel` to accumulate AUC for
+    pub fn enable_auc(&mut self, vessel: O::VesselType) {
+        self.vessel_connections.insert(vessel);
+        self.auc_vessels.insert(vessel);
+    }
+
+    /// Limits how fast the given `Substance` can change on every vessel.
+    /// Any change scheduled by any component which would exceed the rate
+    /// is stretched in duration by the `BloodStore`. Where multiple
+    /// components limit the same `Substance`, the lowest rate applies.
+    ///
+    /// Panics if `max_rate <= 0`
+    ///
+    /// ### Arguments
+    /// * `substance` - `Substance` to limit
+    /// * `max_rate`  - maximum rate of change, in either direction
+    pub fn set_max_rate(&mut self, substance: Substance, max_rate: ConcentrationRate) {
+        if max_rate.molpm3ps.is_nan() || max_rate.molpm3ps <= 0.0 {
+            panic!("Maximum rate for {} must be greater than 0!", substance);
+        }
+        self.max_rates.insert(substance, max_rate);
+    }
 }
 
 
@@ -1,6 +1,6 @@
 pub(crate) mod connector;
 pub(crate) mod initializer;
-pub use connector::BloodStore;
+pub use connector::{BloodStore, ConcentrationAnomaly};
 pub use connector::CirculationConnector;
 pub use initializer::CirculationInitializer;
 
@@ -34,7 +34,7 @@ pub mod test {
     use crate::sim::layer::circulation::component::connector::BloodStore;
     use crate::sim::organism::test::{TestBloodVessel, TestOrganism, TestSim};
     use crate::sim::SimTime;
-    use crate::substance::Substance;
+    use crate::substance::{Substance, SubstanceConcentration};
     use crate::{mmol_per_L, SimTimeSpan};
     use simple_si_units::chemical::Concentration;
 
@@ -88,6 +88,118 @@ pub mod test {
         }
     }
 
+    pub struct TestCircComponentB {
+        cc_sim_connector: CirculationConnector<TestOrganism>,
+    }
+
+    impl TestCircComponentB {
+        pub fn new() -> TestCircComponentB {
+            TestCircComponentB {
+                cc_sim_connector: CirculationConnector::new(),
+            }
+        }
+    }
+
+    impl CirculationComponent<TestOrganism> for TestCircComponentB {
+        fn circulation_init(
+            &mut self,
+            circulation_initializer: &mut CirculationInitializer<TestOrganism>,
+        ) {
+            circulation_initializer.attach_vessel(TestBloodVessel::VenaCava);
+            circulation_initializer.manage_substance(Substance::GLC);
+            circulation_initializer.manage_substance(Substance::O2);
+        }
+
+        fn circulation_connector(&mut self) -> &mut CirculationConnector<TestOrganism> {
+            &mut self.cc_sim_connector
+        }
+    }
+
+    impl SimComponent<TestOrganism> for TestCircComponentB {
+        fn id(&self) -> &'static str {
+            "TestCircComponentB"
+        }
+
+        fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+            registry.add_circulation_component(self)
+        }
+
+        fn run(&mut self) {
+            let mut store = self.cc_sim_connector
+                .blood_store(&TestBloodVessel::VenaCava)
+                .unwrap();
+            store.schedule_change(Substance::GLC, mmol_per_L!(-0.5), SimTimeSpan::from_s(1.0));
+            store.schedule_change(Substance::O2, mmol_per_L!(0.5), SimTimeSpan::from_s(1.0));
+        }
+    }
+
+    /// Schedules a bolus of a Substance into a vessel on its first run.
+    /// Any other vessels it attaches can be inspected through it.
+    pub struct TestBolusComponent {
+        vessel: TestBloodVessel,
+        substance: Substance,
+        amount: SubstanceConcentration,
+        duration: SimTimeSpan,
+        inspected: Vec<TestBloodVessel>,
+        cc_sim_connector: CirculationConnector<TestOrganism>,
+    }
+
+    impl TestBolusComponent {
+        /// ### Arguments
+        /// * `vessel`    - Vessel to inject the bolus into
+        /// * `substance` - Substance of the bolus
+        /// * `amount`    - Change in concentration of the Substance in the vessel
+        /// * `duration`  - Time over which the bolus is injected
+        pub fn new(vessel: TestBloodVessel, substance: Substance, amount: SubstanceConcentration, duration: SimTimeSpan) -> Self {
+            Self {
+                vessel,
+                substance,
+                amount,
+                duration,
+                inspected: Vec::new(),
+                cc_sim_connector: CirculationConnector::new(),
+            }
+        }
+
+        /// Attaches another vessel, so its store can be inspected
+        pub fn inspecting(mut self, vessel: TestBloodVessel) -> Self {
+            self.inspected.push(vessel);
+            self
+        }
+    }
+
+    impl CirculationComponent<TestOrganism> for TestBolusComponent {
+        fn circulation_init(&mut self, circulation_initializer: &mut CirculationInitializer<TestOrganism>) {
+            circulation_initializer.attach_vessel(self.vessel);
+            for vessel in self.inspected.iter() {
+                circulation_initializer.attach_vessel(*vessel);
+            }
+        }
+
+        fn circulation_connector(&mut self) -> &mut CirculationConnector<TestOrganism> {
+            &mut self.cc_sim_connector
+        }
+    }
+
+    impl SimComponent<TestOrganism> for TestBolusComponent {
+        fn id(&self) -> &'static str {
+            "TestBolusComponent"
+        }
+
+        fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+            registry.add_circulation_component(self)
+        }
+
+        fn run(&mut self) {
+            if self.cc_sim_connector.is_first_run() {
+                self.cc_sim_connector
+                    .blood_store(&self.vessel)
+                    .unwrap()
+                    .schedule_change(self.substance, self.amount, self.duration);
+            }
+        }
+    }
+
     #[test]
     fn test_component() {
         let mut component = TestCircComponentA::new();
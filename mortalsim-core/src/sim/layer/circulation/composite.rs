@@ -0,0 +1,283 @@
+use std::any::{Any, TypeId};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::hash::Hash;
+use std::sync::{Mutex, OnceLock};
+
+use strum::VariantArray;
+
+use crate::sim::layer::AnatomicalRegionIter;
+
+use super::{BloodVessel, BloodVesselType, VesselIter};
+
+/// Set of additional blood vessels which extend an existing vasculature.
+/// Each extension vessel declares its own links, which may connect to
+/// vessels of the base vasculature or to other extension vessels. Links
+/// are mirrored onto the connected vessels automatically.
+pub trait VesselExtension:
+    Hash + Clone + Copy + Eq + fmt::Debug + Send + Sync + VariantArray + Into<&'static str> + 'static
+{
+    type Base: BloodVessel;
+    fn vessel_type(&self) -> BloodVesselType;
+    fn upstream(&self) -> Vec<CompositeVessel<Self::Base, Self>>;
+    fn downstream(&self) -> Vec<CompositeVessel<Self::Base, Self>>;
+    fn regions(&self) -> Vec<<Self::Base as BloodVessel>::AnatomyType>;
+}
+
+/// Blood vessel of a base vasculature augmented with the vessels
+/// of a `VesselExtension`, e.g. to add an arteriovenous fistula
+/// to an existing organism without redefining its vasculature.
+#[derive(Debug, Hash, Clone, Copy, PartialEq, Eq)]
+pub enum CompositeVessel<B: BloodVessel, E: VesselExtension<Base = B>> {
+    Base(B),
+    Ext(E),
+}
+
+impl<B: BloodVessel, E: VesselExtension<Base = B>> From<CompositeVessel<B, E>> for &'static str {
+    fn from(vessel: CompositeVessel<B, E>) -> Self {
+        match vessel {
+            CompositeVessel::Base(b) => b.into(),
+            CompositeVessel::Ext(e) => e.into(),
+        }
+    }
+}
+
+/// Cached structure of a composite vasculature
+struct CompositeGraph<V, A> {
+    start_vessels: HashSet<V>,
+    arteries: HashSet<V>,
+    veins: HashSet<V>,
+    pre_capillaries: HashSet<V>,
+    post_capillaries: HashSet<V>,
+    upstream: HashMap<V, HashSet<V>>,
+    downstream: HashMap<V, HashSet<V>>,
+    regions: HashMap<V, HashSet<A>>,
+    max_arterial_depth: u32,
+    max_venous_depth: u32,
+    empty: HashSet<V>,
+    empty_regions: HashSet<A>,
+}
+
+// Generic statics aren't supported, so graphs for each composite type
+// are built once and leaked into a map keyed by the composite's TypeId
+static GRAPHS: OnceLock<Mutex<HashMap<TypeId, &'static (dyn Any + Send + Sync)>>> = OnceLock::new();
+
+impl<B, E> CompositeVessel<B, E>
+where
+    B: BloodVessel + Sync + 'static,
+    B::AnatomyType: Hash + Eq + Send + Sync + 'static,
+    E: VesselExtension<Base = B>,
+{
+    fn all_vessels() -> impl Iterator<Item = Self> {
+        B::arteries()
+            .chain(B::veins())
+            .map(CompositeVessel::Base)
+            .chain(E::VARIANTS.iter().map(|e| CompositeVessel::Ext(*e)))
+    }
+
+    fn graph() -> &'static CompositeGraph<Self, B::AnatomyType> {
+        let mut graphs = GRAPHS.get_or_init(|| Mutex::new(HashMap::new())).lock().unwrap();
+        let graph = *graphs
+            .entry(TypeId::of::<Self>())
+            .or_insert_with(|| Box::leak(Box::new(Self::build_graph())));
+        graph.downcast_ref().unwrap()
+    }
+
+    fn build_graph() -> CompositeGraph<Self, B::AnatomyType> {
+        let mut upstream: HashMap<Self, HashSet<Self>> = HashMap::new();
+        let mut downstream: HashMap<Self, HashSet<Self>> = HashMap::new();
+        let mut regions = HashMap::new();
+
+        for vessel in Self::all_vessels() {
+            match vessel {
+                CompositeVessel::Base(b) => {
+                    upstream.entry(vessel).or_default().extend(b.upstream().map(CompositeVessel::Base));
+                    downstream.entry(vessel).or_default().extend(b.downstream().map(CompositeVessel::Base));
+                    regions.insert(vessel, b.regions().collect());
+                }
+                CompositeVessel::Ext(e) => {
+                    // Mirror each declared link onto the linked vessel
+                    for up in e.upstream() {
+                        upstream.entry(vessel).or_default().insert(up);
+                        downstream.entry(up).or_default().insert(vessel);
+                    }
+                    for down in e.downstream() {
+                        downstream.entry(vessel).or_default().insert(down);
+                        upstream.entry(down).or_default().insert(vessel);
+                    }
+                    regions.insert(vessel, e.regions().into_iter().collect());
+                }
+            }
+        }
+
+        let arteries: HashSet<Self> = Self::all_vessels()
+            .filter(|v| v.vessel_type() == BloodVesselType::Artery)
+            .collect();
+        let veins: HashSet<Self> = Self::all_vessels()
+            .filter(|v| v.vessel_type() == BloodVesselType::Vein)
+            .collect();
+
+        // Capillary beds are wherever an artery links directly to a vein
+        let pre_capillaries = arteries
+            .iter()
+            .filter(|a| downstream.get(a).is_some_and(|d| d.iter().any(|v| veins.contains(v))))
+            .copied()
+            .collect();
+        let post_capillaries = veins
+            .iter()
+            .filter(|v| upstream.get(v).is_some_and(|u| u.iter().any(|a| arteries.contains(a))))
+            .copied()
+            .collect();
+
+        let start_vessels: HashSet<Self> = arteries
+            .iter()
+            .filter(|a| upstream.get(a).map_or(true, |u| u.is_empty()))
+            .copied()
+            .collect();
+        let venous_roots: Vec<Self> = veins
+            .iter()
+            .filter(|v| downstream.get(v).map_or(true, |d| d.iter().all(|x| !veins.contains(x))))
+            .copied()
+            .collect();
+
+        let max_arterial_depth = start_vessels
+            .iter()
+            .map(|v| Self::depth(*v, &downstream, &arteries, &mut Vec::new()))
+            .max()
+            .unwrap_or(0);
+        let max_venous_depth = venous_roots
+            .iter()
+            .map(|v| Self::depth(*v, &upstream, &veins, &mut Vec::new()))
+            .max()
+            .unwrap_or(0);
+
+        CompositeGraph {
+            start_vessels,
+            arteries,
+            veins,
+            pre_capillaries,
+            post_capillaries,
+            upstream,
+            downstream,
+            regions,
+            max_arterial_depth,
+            max_venous_depth,
+            empty: HashSet::new(),
+            empty_regions: HashSet::new(),
+        }
+    }
+
+    // Longest chain of vessels of the same type starting from `vessel`
+    fn depth(
+        vessel: Self,
+        links: &HashMap<Self, HashSet<Self>>,
+        same_type: &HashSet<Self>,
+        visited: &mut Vec<Self>,
+    ) -> u32 {
+        if visited.contains(&vessel) {
+            return 0;
+        }
+        visited.push(vessel);
+        let max_child = links
+            .get(&vessel)
+            .into_iter()
+            .flatten()
+            .filter(|v| same_type.contains(v))
+            .map(|v| Self::depth(*v, links, same_type, visited))
+            .max()
+            .unwrap_or(0);
+        visited.pop();
+        max_child + 1
+    }
+}
+
+impl<B, E> BloodVessel for CompositeVessel<B, E>
+where
+    B: BloodVessel + Sync + 'static,
+    B::AnatomyType: Hash + Eq + Send + Sync + 'static,
+    E: VesselExtension<Base = B>,
+{
+    type AnatomyType = B::AnatomyType;
+
+    fn max_arterial_depth() -> u32 {
+        Self::graph().max_arterial_depth
+    }
+    fn max_venous_depth() -> u32 {
+        Self::graph().max_venous_depth
+    }
+    fn max_cycle() -> u32 {
+        Self::max_arterial_depth() + Self::max_venous_depth()
+    }
+    fn start_vessels<'a>() -> VesselIter<'a, Self> {
+        VesselIter(Self::graph().start_vessels.iter())
+    }
+    fn arteries<'a>() -> VesselIter<'a, Self> {
+        VesselIter(Self::graph().arteries.iter())
+    }
+    fn veins<'a>() -> VesselIter<'a, Self> {
+        VesselIter(Self::graph().veins.iter())
+    }
+    fn pre_capillaries<'a>() -> VesselIter<'a, Self> {
+        VesselIter(Self::graph().pre_capillaries.iter())
+    }
+    fn post_capillaries<'a>() -> VesselIter<'a, Self> {
+        VesselIter(Self::graph().post_capillaries.iter())
+    }
+    fn vessel_type(&self) -> BloodVesselType {
+        match self {
+            CompositeVessel::Base(b) => b.vessel_type(),
+            CompositeVessel::Ext(e) => e.vessel_type(),
+        }
+    }
+    fn upstream<'a>(&self) -> VesselIter<'a, Self> {
+        let graph = Self::graph();
+        VesselIter(graph.upstream.get(self).unwrap_or(&graph.empty).iter())
+    }
+    fn downstream<'a>(&self) -> VesselIter<'a, Self> {
+        let graph = Self::graph();
+        VesselIter(graph.downstream.get(self).unwrap_or(&graph.empty).iter())
+    }
+    fn regions<'a>(&self) -> AnatomicalRegionIter<Self::AnatomyType> {
+        let graph = Self::graph();
+        AnatomicalRegionIter(graph.regions.get(self).unwrap_or(&graph.empty_regions).iter())
+    }
+    fn volume(&self) -> Option<crate::units::geometry::Volume<f64>> {
+        match self {
+            CompositeVessel::Base(b) => b.volume(),
+            CompositeVessel::Ext(_) => None,
+        }
+    }
+}
+
+mod tests {
+    use crate::sim::layer::circulation::BloodVessel;
+    use crate::sim::organism::test::{FistulaTestVessel, FistulaVessel, TestAnatomicalRegion, TestBloodVessel};
+
+    use super::CompositeVessel;
+
+    #[test]
+    fn composite_structure() {
+        let fistula = CompositeVessel::Ext(FistulaVessel::LeftArteriovenousFistula);
+        let artery = FistulaTestVessel::Base(TestBloodVessel::LeftAxillaryArtery);
+        let vein = FistulaTestVessel::Base(TestBloodVessel::LeftAxillaryVein);
+
+        // Base vasculature is carried over
+        assert_eq!(FistulaTestVessel::arteries().len(), TestBloodVessel::arteries().len() + 1);
+        assert_eq!(FistulaTestVessel::veins().len(), TestBloodVessel::veins().len());
+        assert_eq!(FistulaTestVessel::start_vessels().collect::<Vec<_>>(), vec![FistulaTestVessel::Base(TestBloodVessel::Aorta)]);
+        assert_eq!(FistulaTestVessel::max_arterial_depth(), TestBloodVessel::max_arterial_depth());
+        assert_eq!(FistulaTestVessel::max_venous_depth(), TestBloodVessel::max_venous_depth());
+
+        // The fistula is linked in both directions
+        assert!(artery.downstream().any(|v| v == fistula));
+        assert!(artery.downstream().any(|v| v == vein));
+        assert!(vein.upstream().any(|v| v == fistula));
+        assert_eq!(fistula.upstream().collect::<Vec<_>>(), vec![artery]);
+        assert_eq!(fistula.downstream().collect::<Vec<_>>(), vec![vein]);
+        assert!(FistulaTestVessel::pre_capillaries().any(|v| v == fistula));
+
+        assert_eq!(fistula.regions().collect::<Vec<_>>(), vec![TestAnatomicalRegion::LeftArm]);
+        assert_eq!(<&'static str>::from(fistula), "LeftArteriovenousFistula");
+        assert_eq!(<&'static str>::from(artery), "LeftAxillaryArtery");
+    }
+}
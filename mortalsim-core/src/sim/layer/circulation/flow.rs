@@ -0,0 +1,36 @@
+use std::fmt;
+
+/// Volumetric flow rate of blood, in m^3 per second
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct VolumetricFlowRate {
+    pub m3ps: f64,
+}
+
+#[allow(non_snake_case)]
+impl VolumetricFlowRate {
+    /// Creates a new VolumetricFlowRate in L per minute
+    pub fn from_L_per_min(rate: f64) -> Self {
+        Self { m3ps: rate / 60.0e3 }
+    }
+
+    /// Creates a new VolumetricFlowRate in mL per second
+    pub fn from_mL_per_s(rate: f64) -> Self {
+        Self { m3ps: rate / 1.0e6 }
+    }
+
+    /// Returns the rate in L per minute
+    pub fn to_L_per_min(&self) -> f64 {
+        self.m3ps * 60.0e3
+    }
+
+    /// Returns the rate in mL per second
+    pub fn to_mL_per_s(&self) -> f64 {
+        self.m3ps * 1.0e6
+    }
+}
+
+impl fmt::Display for VolumetricFlowRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} L/min", self.to_L_per_min())
+    }
+}
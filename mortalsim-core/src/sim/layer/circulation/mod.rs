@@ -1,9 +1,15 @@
+pub(crate) mod blood_properties;
 pub(crate) mod circulation_layer;
 pub(crate) mod component;
+pub(crate) mod composite;
+pub(crate) mod flow;
 pub(crate) mod vessel;
 
+pub use blood_properties::{BloodProperties, HEMOGLOBIN_MOLAR_MASS, HEMOGLOBIN_O2_SITES};
 pub use circulation_layer::CirculationLayer;
 pub use component::{
-    BloodStore, CirculationComponent, CirculationConnector, CirculationInitializer,
+    BloodStore, CirculationComponent, CirculationConnector, CirculationInitializer, ConcentrationAnomaly,
 };
+pub use composite::{CompositeVessel, VesselExtension};
+pub use flow::VolumetricFlowRate;
 pub use vessel::{BloodVessel, BloodVesselType, VesselIter};
@@ -1,9 +1,10 @@
-use std::collections::hash_set;
+use std::collections::{hash_set, HashMap, HashSet, VecDeque};
 use std::fmt;
 use std::hash::Hash;
 use std::str::FromStr;
 
 use crate::sim::layer::AnatomicalRegionIter;
+use crate::units::geometry::Volume;
 
 pub trait BloodVessel:
     Hash + Clone + Copy + Eq + fmt::Debug + Send + Into<&'static str>
@@ -21,6 +22,157 @@ pub trait BloodVessel:
     fn upstream<'a>(&self) -> VesselIter<'a, Self>;
     fn downstream<'a>(&self) -> VesselIter<'a, Self>;
     fn regions<'a>(&self) -> AnatomicalRegionIter<Self::AnatomyType>;
+    /// Nominal volume of blood held by the vessel, if known
+    fn volume(&self) -> Option<Volume<f64>> {
+        None
+    }
+    /// Simple cycles in the downstream vessel graph, for debugging
+    /// organism topology. Each cycle is listed once, starting from its
+    /// first vessel in name order.
+    fn find_cycles() -> Vec<Vec<Self>> {
+        find_cycles(false)
+    }
+    /// Same as `find_cycles`, but also follows the pulmonary loopback from
+    /// terminal vessels to the start vessels, as blood flow layers do
+    fn find_cycles_with_loopback() -> Vec<Vec<Self>> {
+        find_cycles(true)
+    }
+    /// Fraction of the total blood flow leaving the start vessels which
+    /// passes through each vessel. Flow splits between downstream vessels
+    /// in proportion to their nominal volumes, or evenly if any are
+    /// unknown, and merges where vessels join. Vessels on cycles in the
+    /// downstream graph aren't assigned any flow.
+    fn flow_fractions() -> HashMap<Self, f64> {
+        flow_fractions()
+    }
+    /// Share of the flow through this vessel which passes directly into
+    /// the given vessel, or 0 if it isn't directly downstream
+    ///
+    /// ### Arguments
+    /// * `downstream` - Vessel the flow passes into
+    fn flow_split(&self, downstream: Self) -> f64 {
+        split_fraction(*self, downstream)
+    }
+    /// Fraction of the total blood flow passing directly from this vessel
+    /// into the given vessel, or 0 if it isn't directly downstream
+    ///
+    /// ### Arguments
+    /// * `downstream` - Vessel the flow passes into
+    fn edge_flow_fraction(&self, downstream: Self) -> f64 {
+        Self::flow_fractions().get(self).copied().unwrap_or(0.0) * self.flow_split(downstream)
+    }
+    /// Vessels reachable downstream of this one, along with the number of
+    /// links along the shortest path to each. Vessels are listed in order
+    /// of increasing distance, and this vessel is not included.
+    fn reachable_vessels(&self) -> Vec<(Self, usize)> {
+        let mut visited = HashSet::from([*self]);
+        let mut reachable = Vec::new();
+        let mut queue = VecDeque::from([(*self, 0)]);
+
+        while let Some((vessel, distance)) = queue.pop_front() {
+            // Order siblings by name so results are deterministic
+            let mut next: Vec<Self> = vessel.downstream().collect();
+            next.sort_by_key(|v| Into::<&'static str>::into(*v));
+
+            for next in next {
+                if visited.insert(next) {
+                    reachable.push((next, distance + 1));
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+        reachable
+    }
+}
+
+/// Downstream vessels of `vessel`, optionally looping terminal vessels
+/// back around to the start vessels
+fn next_vessels<V: BloodVessel>(vessel: V, loopback: bool) -> Vec<V> {
+    if loopback && vessel.downstream().len() == 0 {
+        V::start_vessels().collect()
+    } else {
+        vessel.downstream().collect()
+    }
+}
+
+/// Share of the flow through `from` which passes into `to`
+fn split_fraction<V: BloodVessel>(from: V, to: V) -> f64 {
+    let downstream: Vec<V> = from.downstream().collect();
+    if !downstream.contains(&to) {
+        return 0.0;
+    }
+
+    let volumes: Option<Vec<f64>> = downstream.iter().map(|v| v.volume().map(|vol| vol.m3)).collect();
+    match volumes {
+        Some(volumes) if volumes.iter().all(|v| *v > 0.0) => {
+            to.volume().unwrap().m3 / volumes.iter().sum::<f64>()
+        }
+        _ => 1.0 / downstream.len() as f64,
+    }
+}
+
+fn flow_fractions<V: BloodVessel>() -> HashMap<V, f64> {
+    let vessels: Vec<V> = V::arteries().chain(V::veins()).collect();
+    let mut in_degree: HashMap<V, usize> = vessels.iter().map(|v| (*v, 0)).collect();
+    for vessel in vessels.iter() {
+        for next in vessel.downstream() {
+            *in_degree.entry(next).or_default() += 1;
+        }
+    }
+
+    let start_count = V::start_vessels().len() as f64;
+    let mut inflow: HashMap<V, f64> = V::start_vessels().map(|v| (v, 1.0 / start_count)).collect();
+
+    // Visit vessels in topological order so each one's inflow is
+    // complete before it is split downstream
+    let mut fractions = HashMap::new();
+    let mut queue: VecDeque<V> = in_degree.iter().filter(|(_, d)| **d == 0).map(|(v, _)| *v).collect();
+    while let Some(vessel) = queue.pop_front() {
+        let flow = inflow.get(&vessel).copied().unwrap_or(0.0);
+        fractions.insert(vessel, flow);
+
+        for next in vessel.downstream() {
+            *inflow.entry(next).or_default() += flow * vessel.flow_split(next);
+            let degree = in_degree.get_mut(&next).unwrap();
+            *degree -= 1;
+            if *degree == 0 {
+                queue.push_back(next);
+            }
+        }
+    }
+    fractions
+}
+
+fn find_cycles<V: BloodVessel>(loopback: bool) -> Vec<Vec<V>> {
+    // Order vessels by name so results are deterministic
+    let mut vessels: Vec<V> = V::arteries().chain(V::veins()).collect();
+    vessels.sort_by_key(|v| Into::<&'static str>::into(*v));
+    let index = |v: &V| vessels.iter().position(|x| x == v);
+
+    let mut cycles = Vec::new();
+    for (start_idx, start) in vessels.iter().enumerate() {
+        // Only walk vessels ordered after the start, so each cycle is
+        // found exactly once, from its lowest vessel
+        let mut path = vec![*start];
+        let mut stack = vec![next_vessels(*start, loopback)];
+        while let Some(candidates) = stack.last_mut() {
+            match candidates.pop() {
+                Some(next) if next == *start => cycles.push(path.clone()),
+                Some(next) => {
+                    if index(&next).is_some_and(|i| i > start_idx) && !path.contains(&next) {
+                        path.push(next);
+                        stack.push(next_vessels(next, loopback));
+                    }
+                }
+                None => {
+                    stack.pop();
+                    path.pop();
+                }
+            }
+        }
+    }
+    cycles.sort_by_key(|c| c.iter().map(|v| Into::<&'static str>::into(*v)).collect::<Vec<_>>());
+    cycles
 }
 
 /// Type of a blood vessel
@@ -87,3 +239,36 @@ impl BloodVessel for DummyVessel {
         panic!()
     }
 }
+
+mod tests {
+    use crate::sim::organism::test::TestBloodVessel;
+
+    use super::BloodVessel;
+
+    #[test]
+    fn find_cycles() {
+        // The test circulation is a tree from the Aorta to the vena cavae
+        assert!(TestBloodVessel::find_cycles().is_empty());
+
+        // Each terminal path forms a cycle through the pulmonary loopback
+        let cycles = TestBloodVessel::find_cycles_with_loopback();
+        assert_eq!(cycles.len(), 6);
+        assert!(cycles.contains(&vec![
+            TestBloodVessel::Aorta,
+            TestBloodVessel::LeftAxillaryArtery,
+            TestBloodVessel::LeftAxillaryVein,
+            TestBloodVessel::VenaCava,
+        ]));
+        assert!(cycles.contains(&vec![
+            TestBloodVessel::AbdominalAorta,
+            TestBloodVessel::LeftFemoralArtery,
+            TestBloodVessel::LeftFemoralVein,
+            TestBloodVessel::InferiorVenaCava,
+            TestBloodVessel::Aorta,
+        ]));
+        assert_eq!(
+            cycles.iter().map(|c| c.len() as u32).max(),
+            Some(TestBloodVessel::max_cycle())
+        );
+    }
+}
@@ -1,14 +1,15 @@
 use crate::event::Event;
 use crate::hub::event_transformer::TransformerItem;
 use crate::hub::EventTransformer;
-use crate::sim::{Organism, SimState, SimTime};
+use crate::sim::{Organism, SimGlobals, SimState, SimTime};
 use crate::id_gen::IdType;
 use crate::{IdGenerator, SimTimeSpan};
 use anyhow::Result;
-use std::any::TypeId;
+use std::any::{Any, TypeId};
 use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::sync::Arc;
+use std::time::SystemTime;
 
 /// Provides methods for `Core` modules to interact with the simulation
 pub struct CoreConnector<O: Organism> {
@@ -35,8 +36,20 @@ pub struct CoreConnector<O: Organism> {
     pub(crate) pending_untransforms: Vec<IdType>,
     /// Copy of the current simulation time
     pub(crate) sim_time: SimTime,
+    /// Copy of the current calendar time, if the simulation has an epoch
+    pub(crate) wall_clock: Option<SystemTime>,
     /// Whether to indicate to the parent Sim that all previously scheduled events should be unscheduled
     pub(crate) unschedule_all: bool,
+    /// Whether the component has yet to complete its first run
+    pub(crate) first_run: bool,
+    /// Timers set during the current run, with their tokens
+    pub(crate) pending_timers: Vec<(SimTimeSpan, u64)>,
+    /// Expiration time, token and layer schedule id of each active timer
+    pub(crate) timers: Vec<(SimTime, u64, IdType)>,
+    /// Tokens of the timers which expired as of the current run
+    pub(crate) expired_timers: Vec<u64>,
+    /// Global parameters of the Sim
+    pub(crate) globals: SimGlobals,
 }
 
 impl<O: Organism> CoreConnector<O> {
@@ -56,7 +69,13 @@ impl<O: Organism> CoreConnector<O> {
             pending_transforms: Vec::new(),
             pending_untransforms: Vec::new(),
             sim_time: SimTime::from_s(0.0),
+            wall_clock: None,
             unschedule_all: true,
+            first_run: true,
+            pending_timers: Vec::new(),
+            timers: Vec::new(),
+            expired_timers: Vec::new(),
+            globals: SimGlobals::new(),
         }
     }
 
@@ -71,6 +90,18 @@ impl<O: Organism> CoreConnector<O> {
         schedule_id
     }
 
+    /// Schedules an already boxed `Event` for future emission after a
+    /// specified delay, e.g. one produced by a user supplied factory
+    ///
+    /// ### Arguments
+    /// * `wait_time` - Amount of time to wait before execution
+    /// * `evt` - `Event` to emit after `wait_time` has elapsed
+    pub fn schedule_boxed_event(&mut self, wait_time: SimTimeSpan, evt: Box<dyn Event>) -> IdType {
+        let schedule_id = self.id_gen.get_id();
+        self.pending_schedules.push((wait_time, (schedule_id, evt)));
+        schedule_id
+    }
+
     /// Whether to unschedule all previously scheduled `Event` objects (default is true)
     /// Set to `false` in order to manually specify which `Event` objects to unschedule
     /// using `unschedule_event`
@@ -95,26 +126,106 @@ impl<O: Organism> CoreConnector<O> {
         Err(anyhow!("Invalid schedule_id provided"))
     }
 
+    /// Sets a timer private to the component, which triggers a run once
+    /// it expires. Unlike scheduled `Event`s, timers aren't emitted on the
+    /// Sim and aren't unscheduled by `unschedule_all`.
+    ///
+    /// ### Arguments
+    /// * `span`  - Amount of time until the timer expires
+    /// * `token` - Value identifying the timer in `expired_timers`
+    pub fn set_timer(&mut self, span: SimTimeSpan, token: u64) {
+        self.pending_timers.push((span, token));
+    }
+
+    /// Retrieves the tokens of the timers which expired since the last
+    /// run, in order of expiration
+    pub fn expired_timers(&self) -> impl Iterator<Item = u64> + '_ {
+        self.expired_timers.iter().copied()
+    }
+
     /// Retrieves the current simulation time
     pub fn sim_time(&self) -> SimTime {
         self.sim_time
     }
 
+    /// Retrieves the calendar time corresponding to the current simulation
+    /// time, or `None` if the simulation has no epoch set
+    pub fn wall_clock(&self) -> Option<SystemTime> {
+        self.wall_clock
+    }
+
+    /// Whether the component is running for the first time, e.g. to
+    /// seed baselines from the current state
+    pub fn is_first_run(&self) -> bool {
+        self.first_run
+    }
+
+    /// Retrieves the current value of a global parameter of the Sim,
+    /// such as ambient temperature or altitude
+    ///
+    /// ### Arguments
+    /// * `key` - Name of the global
+    ///
+    /// Returns a copy of the value, or `None` if the global isn't set or
+    /// holds a value of another type
+    pub fn get_global<T: Any + Clone>(&self, key: &str) -> Option<T> {
+        self.globals.get(key)
+    }
+
     /// Retrieves a reference to the current `Event` object from state
     /// or from active events
     pub fn get<E: Event>(&self) -> Option<&E> {
-        if let Some(evt) = self.sim_state.get_state::<E>() {
-            return Some(evt);
-        }
-        // Search in reverse order so the most recent event
-        // takes precedence
+        // Search active events in reverse order so the most recent
+        // event takes precedence over both older ones and the state
         for evt in self.active_events.iter().rev() {
             if evt.is::<E>() {
                 return Some(evt.downcast_ref::<E>().unwrap());
             }
         }
-        
-        None
+        self.sim_state.get_state::<E>()
+    }
+
+    /// Retrieves a reference to the `Event` emitted before the one `get`
+    /// returns, i.e. the previous emission of the given type. Only
+    /// non-transient events remain on the state, so transient events
+    /// only have a previous value when emitted more than once at a time.
+    pub fn get_previous<E: Event>(&self) -> Option<&E> {
+        let mut active = self.active_events.iter().rev().filter(|evt| evt.is::<E>());
+        if active.next().is_none() {
+            return self.sim_state.get_previous_state::<E>();
+        }
+        // State isn't updated with active events until after the run
+        match active.next() {
+            Some(evt) => evt.downcast_ref::<E>(),
+            None => self.sim_state.get_state::<E>(),
+        }
+    }
+
+    /// Fractional change between the previous and current values of an
+    /// `Event`, e.g. 0.2 when a heart rate rises from 70 to 84 bpm
+    ///
+    /// ### Arguments
+    /// * `extract` - Function retrieving the value from the `Event`
+    ///
+    /// Returns the relative change, or `None` if there is no previous
+    /// value or the previous value is zero
+    pub fn relative_change<E: Event>(&self, extract: impl Fn(&E) -> f64) -> Option<f64> {
+        let previous = extract(self.get_previous::<E>()?);
+        let current = extract(self.get::<E>()?);
+        if previous == 0.0 {
+            return None;
+        }
+        Some((current - previous) / previous)
+    }
+
+    /// Copy of the current state with any active events applied, matching
+    /// what `get` would return for each `Event` type
+    pub(crate) fn current_state(&self) -> SimState {
+        let mut state = self.sim_state.clone();
+        for evt in self.active_events.iter() {
+            state.put_state(evt.clone());
+        }
+        state
     }
 
     /// Retrieves any active events of the given type
@@ -190,7 +301,9 @@ pub mod test {
     use crate::event::test::TestEventA;
     use crate::event::test::TestEventB;
     use crate::sim::organism::test::{TestOrganism, TestSim};
+    use crate::event::HeartRate;
     use crate::sim::SimState;
+    use crate::units::mechanical::Frequency;
     use crate::units::base::Amount;
     use crate::units::base::Distance;
     use crate::units::base::Time;
@@ -271,6 +384,30 @@ pub mod test {
         assert!(connector.get::<TestEventB>().is_none());
     }
 
+    #[test]
+    pub fn test_relative_change() {
+        let hr = |bpm: f64| HeartRate(Frequency::from_Hz(bpm / 60.0));
+        let extract = |hr: &HeartRate| hr.as_ref().Hz;
+
+        let mut connector = CoreConnector::<TestOrganism>::new();
+        assert!(connector.relative_change(extract).is_none());
+        connector.sim_state.set_state(hr(70.0));
+        assert!(connector.relative_change(extract).is_none());
+
+        // Newly emitted values are compared with the state
+        connector.active_events.push(Arc::new(hr(84.0)));
+        assert!((connector.relative_change(extract).unwrap() - 0.2).abs() < 1e-9);
+        assert!((connector.get_previous::<HeartRate>().unwrap().as_ref().Hz - 70.0 / 60.0).abs() < 1e-9);
+
+        // Once on the state, the replaced value is kept as the previous one
+        connector.active_events.clear();
+        connector.sim_state.set_state(hr(84.0));
+        assert!((connector.relative_change(extract).unwrap() - 0.2).abs() < 1e-9);
+
+        connector.active_events.push(Arc::new(hr(63.0)));
+        assert!((connector.relative_change(extract).unwrap() + 0.25).abs() < 1e-9);
+    }
+
     #[test]
     pub fn test_trigger() {
         let connector = connector();
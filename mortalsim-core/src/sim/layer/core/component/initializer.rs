@@ -3,8 +3,8 @@ use crate::hub::event_transformer::{EventTransformer, TransformerItem};
 use crate::sim::Organism;
 use crate::IdGenerator;
 use crate::IdType;
-use std::any::TypeId;
-use std::collections::HashSet;
+use std::any::{type_name, TypeId};
+use std::collections::{HashMap, HashSet};
 use std::marker::PhantomData;
 
 /// Provides `Core` component initialization methods
@@ -14,6 +14,10 @@ pub struct CoreInitializer<O: Organism> {
     pub(crate) id_gen: IdGenerator,
     /// Notifications pending from the last run of the component
     pub(crate) pending_notifies: Vec<TypeId>,
+    /// Events which the component declares it produces
+    pub(crate) pending_produces: Vec<TypeId>,
+    /// Names of the `Event` types referenced by the component
+    pub(crate) event_names: HashMap<TypeId, &'static str>,
     /// Transforms pending initial addition
     pub(crate) pending_transforms: Vec<(IdType, Box<dyn EventTransformer>)>,
     /// Default event state from the component
@@ -26,6 +30,8 @@ impl<O: Organism> CoreInitializer<O> {
             pd: PhantomData,
             id_gen: IdGenerator::new(),
             pending_notifies: Vec::new(),
+            pending_produces: Vec::new(),
+            event_names: HashMap::new(),
             pending_transforms: Vec::new(),
             initial_outputs: Vec::new(),
         }
@@ -37,9 +43,18 @@ impl<O: Organism> CoreInitializer<O> {
     /// ### Arguments
     /// * `default` - Default `Event` value when one isn't provided by another module
     pub fn notify<E: Event>(&mut self) {
+        self.event_names.insert(TypeId::of::<E>(), type_name::<E>());
         self.pending_notifies.push(TypeId::of::<E>())
     }
 
+    /// Declares that the associated `CoreComponent` emits the provided
+    /// `Event`, so that components which are notified on it can be
+    /// validated against the rest of the `Sim`.
+    pub fn produces<E: Event>(&mut self) {
+        self.event_names.insert(TypeId::of::<E>(), type_name::<E>());
+        self.pending_produces.push(TypeId::of::<E>())
+    }
+
     fn register_transform<E: Event>(&mut self, transformer: TransformerItem<'static, E>) -> IdType {
         let local_id = self.id_gen.get_id();
 
@@ -83,6 +98,7 @@ impl<O: Organism> CoreInitializer<O> {
     /// ### Arguments
     /// * `event` - `Event` instance to set on initial state
     pub fn set_output<E: Event>(&mut self, initial_value: E) {
+        self.produces::<E>();
         self.initial_outputs.push(Box::new(initial_value))
     }
 }
@@ -132,6 +148,14 @@ pub mod test {
             .transform_prioritized(1, |evt: &mut TestEventA| evt.len += Distance::from_m(2.0));
     }
 
+    #[test]
+    fn test_produces() {
+        let mut initializer = CoreInitializer::<TestOrganism>::new();
+        initializer.produces::<TestEventB>();
+        initializer.set_output(basic_event());
+        assert_eq!(initializer.pending_produces.len(), 2);
+    }
+
     #[test]
     fn test_output() {
         let mut initializer = CoreInitializer::<TestOrganism>::new();
@@ -4,13 +4,27 @@ use crate::sim::organism::Organism;
 use crate::sim::SimConnector;
 use crate::id_gen::IdType;
 use std::any::TypeId;
-use std::collections::{HashMap, HashSet};
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
 use std::marker::PhantomData;
 use std::mem::swap;
 
 use super::component::{CoreComponent, CoreInitializer};
-use super::CoreConnector;
+use super::{CoreConnector, EventConflict, EventConflictPolicy, UnsatisfiedDependency};
+use crate::event::Event;
+use crate::sim::SimTime;
+
+/// Maximum number of `EventConflict`s retained between drains
+const MAX_RECORDED_CONFLICTS: usize = 1000;
+
+/// Internal Event used to trigger a component when one of its timers expires
+#[derive(Debug, Clone)]
+struct TimerTrigger {
+    component_id: &'static str,
+}
+
+impl Event for TimerTrigger {}
 
 #[derive(Debug)]
 pub struct CoreLayer<O: Organism> {
@@ -20,6 +34,16 @@ pub struct CoreLayer<O: Organism> {
     module_notifications: HashMap<TypeId, Vec<&'static str>>,
     /// Map of pending updates for each module
     notify_map: HashMap<&'static str, HashSet<TypeId>>,
+    /// Events each component is notified on, with their type names
+    component_notifies: HashMap<&'static str, Vec<(TypeId, &'static str)>>,
+    /// Events each component declares it produces
+    component_produces: HashMap<&'static str, HashSet<TypeId>>,
+    /// Component and value of each non-transient event scheduled
+    /// for the current time or later, to detect conflicts
+    scheduled_values: BTreeMap<(SimTime, TypeId), (&'static str, String)>,
+    conflict_policy: EventConflictPolicy,
+    /// Conflicts detected since the last drain
+    conflicts: Vec<EventConflict>,
 }
 
 impl<O: Organism> CoreLayer<O> {
@@ -30,9 +54,142 @@ impl<O: Organism> CoreLayer<O> {
             pd: PhantomData,
             module_notifications: HashMap::new(),
             notify_map: HashMap::new(),
+            component_notifies: HashMap::new(),
+            component_produces: HashMap::new(),
+            scheduled_values: BTreeMap::new(),
+            conflict_policy: EventConflictPolicy::default(),
+            conflicts: Vec::new(),
+        }
+    }
+
+    /// Sets how conflicting events scheduled by different components
+    /// are reported
+    ///
+    /// ### Arguments
+    /// * `policy` - policy to apply to any subsequent conflicts
+    pub fn set_conflict_policy(&mut self, policy: EventConflictPolicy) {
+        self.conflict_policy = policy;
+    }
+
+    /// Drains the conflicts detected since the last drain. Only the most
+    /// recent conflicts are retained.
+    pub fn drain_conflicts(&mut self) -> Vec<EventConflict> {
+        std::mem::take(&mut self.conflicts)
+    }
+
+    /// Records the value of a non-transient event scheduled by a component,
+    /// reporting a conflict if a different component has already scheduled
+    /// a different value of the same event type for the same time
+    ///
+    /// ### Arguments
+    /// * `comp_id` - component scheduling the event
+    /// * `time`    - time the event is scheduled for
+    /// * `evt`     - the scheduled event
+    fn check_conflict(&mut self, comp_id: &'static str, time: SimTime, evt: &dyn Event) {
+        let event_type = evt.as_any().type_id();
+        let value = format!("{:?}", evt);
+
+        match self.scheduled_values.entry((time, event_type)) {
+            Entry::Vacant(entry) => {
+                entry.insert((comp_id, value));
+            }
+            Entry::Occupied(mut entry) => {
+                let (other_id, other_value) = entry.get();
+                if *other_id != comp_id && *other_value != value {
+                    let conflict = EventConflict {
+                        time,
+                        event_type,
+                        components: [other_id, comp_id],
+                        values: [other_value.clone(), value.clone()],
+                    };
+                    match self.conflict_policy {
+                        EventConflictPolicy::Error => log::error!("Conflicting events: {}", conflict),
+                        _ => log::warn!("Conflicting events: {}", conflict),
+                    }
+                    if self.conflicts.len() >= MAX_RECORDED_CONFLICTS {
+                        self.conflicts.remove(0);
+                    }
+                    self.conflicts.push(conflict);
+                }
+                entry.insert((comp_id, value));
+            }
         }
     }
 
+    /// Cross-references the events each component is notified on against
+    /// the events produced by components, the events already scheduled or
+    /// set on the `Sim`, and any additional events produced elsewhere.
+    ///
+    /// ### Arguments
+    /// * `connector` - `SimConnector` holding the state and scheduled events
+    /// * `external`  - Additional `Event` types with a known producer
+    ///
+    /// Returns the list of unsatisfied dependencies, ordered by component id
+    pub fn unsatisfied_dependencies(
+        &self,
+        connector: &SimConnector,
+        external: &HashSet<TypeId>,
+    ) -> Vec<UnsatisfiedDependency> {
+        let produced: HashSet<TypeId> = self
+            .component_produces
+            .values()
+            .flatten()
+            .chain(external.iter())
+            .copied()
+            .collect();
+
+        let mut missing: Vec<UnsatisfiedDependency> = self
+            .component_notifies
+            .iter()
+            .flat_map(|(comp_id, notifies)| notifies.iter().map(move |n| (*comp_id, *n)))
+            .filter(|(_, (type_id, _))| {
+                !(produced.contains(type_id)
+                    || connector.state.get_dyn_state(type_id).is_some()
+                    || connector.time_manager.has_scheduled(type_id))
+            })
+            .map(|(component_id, (event_type, event_name))| UnsatisfiedDependency {
+                component_id,
+                event_type,
+                event_name,
+            })
+            .collect();
+
+        missing.sort_by(|a, b| (a.component_id, a.event_name).cmp(&(b.component_id, b.event_name)));
+        missing
+    }
+
+    /// Renders the components on this layer as a DOT graph, with an edge
+    /// from each component producing an `Event` to each component notified
+    /// on it, labeled with the `Event` type name. Only `Event`s declared
+    /// via `CoreInitializer::produces` result in edges.
+    pub fn dependency_graph_dot(&self) -> String {
+        let mut components: Vec<&'static str> = self.component_produces.keys().copied().collect();
+        components.sort();
+
+        let mut edges: Vec<(&'static str, &'static str, &'static str)> = self
+            .component_notifies
+            .iter()
+            .flat_map(|(consumer, notifies)| notifies.iter().map(move |(type_id, name)| (*consumer, type_id, *name)))
+            .flat_map(|(consumer, type_id, name)| {
+                self.component_produces
+                    .iter()
+                    .filter(move |(_, produces)| produces.contains(type_id))
+                    .map(move |(producer, _)| (*producer, consumer, name))
+            })
+            .collect();
+        edges.sort();
+
+        let mut dot = String::from("digraph {\n");
+        for component in components {
+            dot.push_str(&format!("    \"{}\";\n", component));
+        }
+        for (producer, consumer, event_name) in edges {
+            dot.push_str(&format!("    \"{}\" -> \"{}\" [label=\"{}\"];\n", producer, consumer, event_name));
+        }
+        dot.push('}');
+        dot
+    }
+
     fn prep_connector(&mut self, connector: &mut SimConnector, component: &mut impl CoreComponent<O>) {
         component.core_connector().trigger_events = self
             .notify_map
@@ -44,20 +201,35 @@ impl<O: Organism> CoreLayer<O> {
 
         let comp_connector = component.core_connector();
         comp_connector.sim_time = connector.sim_time();
+        comp_connector.wall_clock = connector.wall_clock();
+        comp_connector.globals = connector.globals.clone();
+
+        // Move any timers which are due to the expired list
+        let sim_time = connector.sim_time();
+        let (mut expired, active): (Vec<_>, Vec<_>) = comp_connector
+            .timers
+            .drain(..)
+            .partition(|(time, _, _)| *time <= sim_time);
+        comp_connector.timers = active;
+        expired.sort_by_key(|(time, _, _)| *time);
+        comp_connector.expired_timers = expired.into_iter().map(|(_, token, _)| token).collect();
     }
 
     fn process_connector(&mut self, connector: &mut SimConnector, component: &mut impl CoreComponent<O>) {
         let comp_id = component.id();
         let comp_connector = component.core_connector();
+        comp_connector.first_run = false;
 
         // Unschedule any requested events
         if comp_connector.unschedule_all {
             for (_, schedule_id) in comp_connector.scheduled_id_map.drain() {
                 log::trace!("Unscheduling change {} for component {}", schedule_id, comp_id);
+                // Ignore if it's an Err, since events which have
+                // already been emitted can't be unscheduled
                 connector
                     .time_manager
                     .unschedule_event(&schedule_id)
-                    .unwrap();
+                    .ok();
             }
         } else {
             for schedule_id in comp_connector.pending_unschedules.drain(..) {
@@ -78,6 +250,9 @@ impl<O: Organism> CoreLayer<O> {
 
         // Schedule any new events
         for (wait_time, (local_id, evt)) in comp_connector.pending_schedules.drain(..) {
+            if !evt.transient() && self.conflict_policy != EventConflictPolicy::Ignore {
+                self.check_conflict(comp_id, connector.sim_time() + wait_time, evt.as_ref());
+            }
             let schedule_id = connector.time_manager.schedule_event(wait_time, evt);
             log::trace!("Scheduling event {} for component {}", schedule_id, comp_id);
             comp_connector
@@ -91,6 +266,15 @@ impl<O: Organism> CoreLayer<O> {
             log::trace!("Scheduling transform {} for component {}", transform_id, comp_id);
             comp_connector.transform_id_map.insert(local_id, transform_id);
         }
+
+        // Schedule triggers for any new timers
+        for (span, token) in comp_connector.pending_timers.drain(..) {
+            let schedule_id = connector
+                .time_manager
+                .schedule_internal_event(span, Box::new(TimerTrigger { component_id: comp_id }));
+            log::trace!("Setting timer {} for component {}", token, comp_id);
+            comp_connector.timers.push((connector.sim_time() + span, token, schedule_id));
+        }
     }
 }
 
@@ -99,6 +283,10 @@ impl<O: Organism> SimLayer for CoreLayer<O> {
         // Drain events from the last run, if any
         connector.active_events.drain(..);
 
+        // Events scheduled for past times can no longer conflict
+        let sim_time = connector.sim_time();
+        self.scheduled_values.retain(|(time, _), _| *time >= sim_time);
+
         connector
             .time_manager
             .next_events()
@@ -117,7 +305,13 @@ impl<O: Organism> SimLayer for CoreLayer<O> {
 
                 // Internal layer trigger events don't end up on the state
                 // or in the active_events list
-                if !evt.is::<InternalLayerTrigger>() {
+                if let Some(trigger) = evt.downcast_ref::<InternalLayerTrigger>() {
+                    connector.forced_layers.extend(trigger.forced_layers.iter().copied());
+                }
+                else if let Some(timer) = evt.downcast_ref::<TimerTrigger>() {
+                    self.notify_map.entry(timer.component_id).or_default();
+                }
+                else {
                     connector.active_events.push(evt.into());
                 }
             })
@@ -172,8 +366,17 @@ impl<O: Organism, T: CoreComponent<O>> SimComponentProcessor<O, T> for CoreLayer
                 .entry(type_id)
                 .or_default()
                 .push(component.id());
+            self.component_notifies
+                .entry(comp_id)
+                .or_default()
+                .push((type_id, initializer.event_names.get(&type_id).copied().unwrap_or("<unknown>")));
         }
 
+        self.component_produces
+            .entry(comp_id)
+            .or_default()
+            .extend(initializer.pending_produces);
+
         for event in initializer.initial_outputs {
             log::debug!("Setting initial state with event {:?} from component {}", event, comp_id);
             connector.state.put_state(event.into());
@@ -204,6 +407,12 @@ impl<O: Organism, T: CoreComponent<O>> SimComponentProcessor<O, T> for CoreLayer
     fn remove_component(&mut self, connector: &mut SimConnector, component: &mut T) {
         let comp_id = component.id();
 
+        self.component_notifies.remove(comp_id);
+        self.component_produces.remove(comp_id);
+        for notify_list in self.module_notifications.values_mut() {
+            notify_list.retain(|id| *id != comp_id);
+        }
+
         // unschedule all the component's pending events and transforms
         for schedule_id in component.core_connector().scheduled_id_map.values() {
             log::debug!("Unscheduling event {} from component {}", schedule_id, comp_id);
@@ -213,6 +422,10 @@ impl<O: Organism, T: CoreComponent<O>> SimComponentProcessor<O, T> for CoreLayer
             log::debug!("Unscheduling transform {} from component {}", transformer_id, comp_id);
             connector.time_manager.unset_transform(transformer_id).ok();
         }
+        for (_, _, schedule_id) in component.core_connector().timers.drain(..) {
+            connector.time_manager.unschedule_event(&schedule_id).ok();
+        }
+        self.notify_map.remove(comp_id);
     }
 
 }
@@ -256,8 +469,10 @@ impl<O: Organism, T: CoreComponent<O>> SimComponentProcessorSync<O, T> for CoreL
 
 
 pub mod test {
+    use std::any::TypeId;
+    use std::collections::HashSet;
     use std::panic::catch_unwind;
-    use std::sync::Mutex;
+    use std::sync::{Arc, Mutex};
     use std::thread::{scope, spawn};
 
     use simple_si_units::base::Amount;
@@ -271,6 +486,10 @@ pub mod test {
     use crate::sim::layer::core::component::connector::test::basic_event_a;
     use crate::sim::organism::test::TestOrganism;
     use crate::sim::{SimConnector, SimTime};
+    use crate::sim::organism::test::TestSim;
+    use crate::sim::component::registry::ComponentRegistry;
+    use crate::sim::layer::core::{CoreComponent, CoreConnector, CoreInitializer};
+    use crate::sim::Sim;
     use crate::{secs, SimTimeSpan};
 
     #[test]
@@ -315,6 +534,37 @@ pub mod test {
         }
     }
 
+    #[test]
+    fn unsatisfied_dependencies() {
+        let mut layer = CoreLayer::<TestOrganism>::new();
+        let mut component_a = TestComponentA::new();
+        let mut component_b = TestComponentB::new();
+        let mut connector = SimConnector::new();
+
+        layer.setup_component(&mut connector, &mut component_a);
+        layer.setup_component(&mut connector, &mut component_b);
+
+        // Nothing produces either event yet
+        let missing = layer.unsatisfied_dependencies(&connector, &HashSet::new());
+        assert_eq!(
+            missing.iter().map(|d| (d.component_id, d.event_type)).collect::<Vec<_>>(),
+            vec![
+                ("TestComponentA", TypeId::of::<TestEventA>()),
+                ("TestComponentA", TypeId::of::<TestEventB>()),
+                ("TestComponentB", TypeId::of::<TestEventA>()),
+            ]
+        );
+
+        // Externally scheduled events count as produced
+        connector.time_manager.schedule_event(SimTimeSpan::from_s(1.0), Box::new(TestEventA::new(Distance::from_m(1.0))));
+        let missing = layer.unsatisfied_dependencies(&connector, &HashSet::new());
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].event_type, TypeId::of::<TestEventB>());
+
+        let external = HashSet::from([TypeId::of::<TestEventB>()]);
+        assert!(layer.unsatisfied_dependencies(&connector, &external).is_empty());
+    }
+
     #[test]
     fn layer_process_sync() {
         let layer = Mutex::new(CoreLayer::<TestOrganism>::new());
@@ -362,4 +612,58 @@ pub mod test {
             }
         }
     }
+
+    type Expirations = Arc<Mutex<Vec<(SimTime, Vec<u64>)>>>;
+
+    /// Sets timers on its first run, recording the time and tokens of
+    /// each run with expired timers
+    struct TimerComponent {
+        connector: CoreConnector<TestOrganism>,
+        expirations: Expirations,
+    }
+
+    impl CoreComponent<TestOrganism> for TimerComponent {
+        fn core_init(&mut self, _initializer: &mut CoreInitializer<TestOrganism>) {}
+        fn core_connector(&mut self) -> &mut CoreConnector<TestOrganism> {
+            &mut self.connector
+        }
+    }
+
+    impl SimComponent<TestOrganism> for TimerComponent {
+        fn id(&self) -> &'static str {
+            "TimerComponent"
+        }
+        fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+            registry.add_core_component(self);
+        }
+        fn run(&mut self) {
+            if self.connector.is_first_run() {
+                self.connector.set_timer(SimTimeSpan::from_s(3.0), 7);
+                self.connector.set_timer(SimTimeSpan::from_s(1.5), 2);
+                self.connector.set_timer(SimTimeSpan::from_s(3.0), 8);
+            }
+            let expired: Vec<u64> = self.connector.expired_timers().collect();
+            if !expired.is_empty() {
+                self.expirations.lock().unwrap().push((self.connector.sim_time(), expired));
+            }
+        }
+    }
+
+    #[test]
+    fn component_timers() {
+        let mut sim = TestSim::new();
+        let expirations = Arc::new(Mutex::new(Vec::new()));
+        sim.add_component(TimerComponent {
+            connector: CoreConnector::new(),
+            expirations: expirations.clone(),
+        }).unwrap();
+
+        for _ in 0..8 {
+            sim.advance_by(SimTimeSpan::from_s(0.5));
+            // Timers are never emitted on the Sim
+            assert_eq!(sim.drain_active().count(), 0);
+        }
+
+        assert_eq!(*expirations.lock().unwrap(), vec![(secs!(1.5), vec![2]), (secs!(3.0), vec![7, 8])]);
+    }
 }
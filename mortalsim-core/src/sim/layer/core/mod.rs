@@ -3,3 +3,65 @@ pub(crate) mod core_layer;
 
 pub use component::{CoreComponent, CoreConnector, CoreInitializer};
 pub use core_layer::CoreLayer;
+
+use std::any::TypeId;
+use std::fmt;
+
+use crate::sim::SimTime;
+
+/// An `Event` which a component is notified on, but which
+/// no component in the `Sim` produces and which hasn't been
+/// scheduled or set on the `Sim` state externally.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsatisfiedDependency {
+    /// Component which depends on the `Event`
+    pub component_id: &'static str,
+    /// `TypeId` of the missing `Event`
+    pub event_type: TypeId,
+    /// Type name of the missing `Event`
+    pub event_name: &'static str,
+}
+
+impl fmt::Display for UnsatisfiedDependency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is notified on {}, which has no producer", self.component_id, self.event_name)
+    }
+}
+
+/// How the Core layer responds when multiple components schedule
+/// conflicting values for the same `Event` type at the same time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EventConflictPolicy {
+    /// Conflicts are neither logged nor recorded
+    Ignore,
+    /// Conflicts are recorded and logged as warnings
+    #[default]
+    Warn,
+    /// Conflicts are recorded and logged as errors
+    Error,
+}
+
+/// Two components scheduling different values of the same non-transient
+/// `Event` type for the same time, in which case the last one emitted
+/// silently replaces the other on the state
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventConflict {
+    /// Time at which both `Event`s are scheduled
+    pub time: SimTime,
+    /// `TypeId` of the `Event`
+    pub event_type: TypeId,
+    /// Components which scheduled the `Event`s, in order of scheduling
+    pub components: [&'static str; 2],
+    /// Debug representations of the conflicting `Event`s
+    pub values: [String; 2],
+}
+
+impl fmt::Display for EventConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} scheduled {} and {} scheduled {} at time {:?}",
+            self.components[0], self.values[0], self.components[1], self.values[1], self.time
+        )
+    }
+}
@@ -1,10 +1,11 @@
+use std::any::Any;
 use std::collections::HashMap;
 use std::marker::PhantomData;
 
 use crate::sim::layer::digestion::consumable::Consumable;
 use crate::sim::layer::digestion::consumed::Consumed;
 use crate::sim::layer::digestion::DigestionDirection;
-use crate::sim::{Organism, SimTime};
+use crate::sim::{Organism, SimGlobals, SimTime};
 use crate::substance::substance_wrapper::substance_store_wrapper;
 use crate::substance::Substance;
 use crate::units::geometry::Volume;
@@ -15,12 +16,16 @@ pub struct DigestionConnector<O: Organism> {
     pd: PhantomData<O>,
     /// Copy of the current simulation time
     pub(crate) sim_time: SimTime,
+    /// Global parameters of the Sim
+    pub(crate) globals: SimGlobals,
     /// Consumable which is accessible by the current module
     pub(crate) consumed_list: Vec<Consumed>,
     /// Whether all changes should be unscheduled before each run
     /// NOTE: If this is set to false, the component is responsible for
     /// tracking and unscheduling preexisting changes, if necessary
     pub(crate) unschedule_all: bool,
+    /// Whether the component has yet to complete its first run
+    pub(crate) first_run: bool,
 }
 
 impl<O: Organism> DigestionConnector<O> {
@@ -29,8 +34,10 @@ impl<O: Organism> DigestionConnector<O> {
         Self {
             pd: PhantomData,
             sim_time: SimTime::from_s(0.0),
+            globals: SimGlobals::new(),
             consumed_list: Vec::new(),
             unschedule_all: true,
+            first_run: true,
         }
     }
 
@@ -39,6 +46,24 @@ impl<O: Organism> DigestionConnector<O> {
         self.sim_time
     }
 
+    /// Whether the component is running for the first time, e.g. to
+    /// seed baselines from the current state
+    pub fn is_first_run(&self) -> bool {
+        self.first_run
+    }
+
+    /// Retrieves the current value of a global parameter of the Sim,
+    /// such as ambient temperature or altitude
+    ///
+    /// ### Arguments
+    /// * `key` - Name of the global
+    ///
+    /// Returns a copy of the value, or `None` if the global isn't set or
+    /// holds a value of another type
+    pub fn get_global<T: Any + Clone>(&self, key: &str) -> Option<T> {
+        self.globals.get(key)
+    }
+
     /// Whether to unschedule all changes automatically before each run
     /// NOTE: If this is set to false, the component is responsible for
     /// tracking and unscheduling preexisting changes, if necessary
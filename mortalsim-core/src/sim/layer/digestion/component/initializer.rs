@@ -1,13 +1,33 @@
 use std::marker::PhantomData;
 
 use crate::sim::Organism;
+use crate::SimTimeSpan;
 
 pub struct DigestionInitializer<O: Organism> {
     pd: PhantomData<O>,
+    /// Maximum step for applying changes to consumables held by the component
+    pub(crate) reaction_substep: Option<SimTimeSpan>,
 }
 
 impl<O: Organism> DigestionInitializer<O> {
     pub fn new() -> Self {
-        Self { pd: PhantomData }
+        Self {
+            pd: PhantomData,
+            reaction_substep: None,
+        }
+    }
+
+    /// Subdivides each advance of the consumables held by this component
+    /// into steps no longer than `substep`. Improves the accuracy of fast
+    /// substance changes which would otherwise be clamped at zero or at
+    /// their bounds only at the end of a long advance.
+    ///
+    /// ### Arguments
+    /// * `substep` - maximum time step when applying changes
+    pub fn set_reaction_substep(&mut self, substep: SimTimeSpan) {
+        if substep.to_s() <= 0.0 {
+            panic!("Digestion reaction substep must be greater than 0!");
+        }
+        self.reaction_substep = Some(substep);
     }
 }
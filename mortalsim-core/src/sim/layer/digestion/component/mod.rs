@@ -100,11 +100,11 @@ pub mod test {
         assert_eq!(fiber.exit_direction, DigestionDirection::FORWARD);
 
         let orig_conc = food.concentration_of(&Substance::GLC);
-        food.advance(SimTime::from_min(1.0));
+        food.advance(SimTime::from_min(1.0), None);
 
         assert!(food.concentration_of(&Substance::GLC) < orig_conc);
 
-        food.advance(SimTime::from_min(10.0));
+        food.advance(SimTime::from_min(10.0), None);
         assert!(food.concentration_of(&Substance::GLC) < mmol_per_L!(0.1));
     }
 }
@@ -21,6 +21,8 @@ pub struct VolumeChange {
     function: BoundFn,
     start: SimTime,
     end: SimTime,
+    /// Portion of the change applied so far
+    applied: Volume<f64>,
 }
 
 /// A homogeneous chemical solution to be consumed by a `Sim`'s
@@ -74,6 +76,8 @@ pub struct Consumable {
     volume_changes: HashMap<IdType, VolumeChange>,
     /// composite changes (volume & substance)
     composite_changes: HashMap<IdType, (IdType, IdType)>,
+    /// Opaque tag for tracking provenance, if set
+    tag: Option<u64>,
     /// Store of substances in the `Consumable`
     pub(crate) store: SubstanceStore,
 }
@@ -112,6 +116,7 @@ impl Consumable {
             id_gen: IdGenerator::new(),
             volume_changes: HashMap::new(),
             composite_changes: HashMap::new(),
+            tag: None,
             store: store,
         };
 
@@ -135,20 +140,23 @@ impl Consumable {
     /// Internal execution of volume changes on each advance
     fn execute_volume_changes(&mut self, sim_time: SimTime) {
         let mut remove_list = Vec::new();
-        for (cid, change) in self.volume_changes.iter() {
+        for (cid, change) in self.volume_changes.iter_mut() {
             if change.start < sim_time && change.end > sim_time {
-                let result = change.function.call(
+                let result = Volume::from_m3(change.function.call(
                     (sim_time - change.start).to_s(),
                     (change.end - change.start).to_s(),
                     change.amount.m3,
-                );
+                ));
+                // Only apply the portion of the change since the last advance,
+                // so the result doesn't depend on how often this is called.
                 // Make sure the volume change is valid, and log a warning if it's not
-                let new_vol = self.volume() + Volume::from_m3(result);
+                let new_vol = self.volume + result - change.applied;
                 if new_vol <= Volume::from_L(0.0) {
                     log::warn!("Scheduled volume change attempted to set invalid volume: {}", new_vol);
                     continue;
                 }
                 self.volume = new_vol;
+                change.applied = result;
             }
             if change.end < sim_time {
                 remove_list.push(*cid);
@@ -261,6 +269,7 @@ impl Consumable {
                 function: bound_fn,
                 start: start,
                 end: end,
+                applied: Volume::from_m3(0.0),
             });
             change_id
     }
@@ -330,6 +339,22 @@ impl Consumable {
         &self.store
     }
 
+    /// Sets an opaque tag on the `Consumable`, such as an id for the meal
+    /// or experiment it came from. The tag is carried through digestion,
+    /// including onto any refluxed portion, and can be retrieved from
+    /// the `Consumable` on its `EliminateEvent`.
+    ///
+    /// ### Arguments
+    /// * `tag` - Tag to set, replacing any previous tag
+    pub fn set_tag(&mut self, tag: u64) {
+        self.tag = Some(tag);
+    }
+
+    /// Tag previously set on the `Consumable`, if any
+    pub fn tag(&self) -> Option<u64> {
+        self.tag
+    }
+
 }
 
 
@@ -12,6 +12,7 @@ use crate::substance::Substance;
 use crate::IdGenerator;
 use crate::math::BoundFn;
 use crate::IdType;
+use crate::SimTimeSpan;
 
 use super::consumable::VolumeChange;
 
@@ -30,6 +31,9 @@ pub struct Consumed {
     pub(crate) exit_time: SimTime,
     /// Where the consumable should go after this component is done with it (Default is FORWARD)
     pub(crate) exit_direction: DigestionDirection,
+    /// Fraction of the consumable which refluxes to the previous
+    /// component when it exits forward (Default is 0)
+    pub(crate) reflux_fraction: f64,
     /// Local map of previous changes to this consumable
     pub(crate) change_map: HashMap<Substance, Vec<IdType>>,
     /// Local list of active volume changes to this consumable
@@ -48,6 +52,7 @@ impl Consumed {
             entry_direction: DigestionDirection::FORWARD,
             exit_time: SimTime::from_s(60.0),
             exit_direction: DigestionDirection::FORWARD,
+            reflux_fraction: 0.0,
             change_map: HashMap::new(),
             vol_changes: Vec::new(),
         }
@@ -130,6 +135,25 @@ impl Consumed {
         self.entry_time - self.sim_time
     }
 
+    /// Simulation time when the `Consumed` will exit the current component
+    pub fn exit_time(&self) -> SimTime {
+        self.exit_time
+    }
+
+    /// Time remaining until the `Consumed` exits the current component
+    ///
+    /// ### Arguments
+    /// * `now` - current simulation time
+    ///
+    /// Returns the remaining time, or `None` if the exit time has passed
+    pub fn time_until_exit(&self, now: SimTime) -> Option<SimTimeSpan> {
+        if now > self.exit_time {
+            None
+        } else {
+            Some(now.span_to(&self.exit_time))
+        }
+    }
+
     /// Sets the exit time and direction of the `Consumed`
     ///
     /// ### Arguments
@@ -151,6 +175,58 @@ impl Consumed {
         }
     }
 
+    /// Fraction of the `Consumed` which will reflux to the previous
+    /// component when it exits forward
+    pub fn reflux_fraction(&self) -> f64 {
+        self.reflux_fraction
+    }
+
+    /// Sets the fraction of the `Consumed` which refluxes to the previous
+    /// component (e.g. duodenogastric reflux) when it exits forward, while
+    /// the rest continues on. Solutes are split in proportion to volume.
+    /// A fraction of 1 is equivalent to exiting `DigestionDirection::BACK`.
+    /// Has no effect in the first component, or for any other exit direction.
+    ///
+    /// ### Arguments
+    /// * `fraction` - fraction of the volume to move back, between 0 and 1
+    ///
+    /// Returns an Err if the fraction is out of range
+    pub fn set_reflux_fraction(&mut self, fraction: f64) -> anyhow::Result<()> {
+        if !(0.0..=1.0).contains(&fraction) {
+            Err(anyhow!("Consumed reflux fraction must be between 0 and 1, got {}", fraction))
+        } else {
+            self.reflux_fraction = fraction;
+            Ok(())
+        }
+    }
+
+    /// Splits off the refluxing portion of the `Consumed`, if any, leaving
+    /// the remainder in place. A full reflux turns the exit around instead.
+    pub(crate) fn split_reflux(&mut self) -> Option<Consumed> {
+        let fraction = self.reflux_fraction;
+        self.reflux_fraction = 0.0;
+
+        if fraction <= 0.0 || self.exit_direction != DigestionDirection::FORWARD {
+            return None;
+        }
+        if fraction >= 1.0 {
+            self.exit_direction = DigestionDirection::BACK;
+            return None;
+        }
+
+        self.clear_all_changes();
+        let volume = self.consumable.volume();
+        let mut refluxed = self.consumable.clone();
+
+        // Both volumes are positive, so neither can fail
+        refluxed.set_volume(volume * fraction).unwrap();
+        self.consumable.set_volume(volume * (1.0 - fraction)).unwrap();
+
+        let mut consumed = Consumed::new(refluxed);
+        consumed.sim_time = self.sim_time;
+        Some(consumed)
+    }
+
     pub(crate) fn clear_all_changes(&mut self) {
         for (substance, change_ids) in self.change_map.drain() {
             for change_id in change_ids {
@@ -171,8 +247,20 @@ impl Consumed {
     }
 
     /// Advance simulation time to the given value.
-    pub(crate) fn advance(&mut self, sim_time: SimTime) {
+    ///
+    /// ### Arguments
+    /// * `sim_time` - the new simulation time
+    /// * `substep`  - maximum step to take when applying changes, if any
+    pub(crate) fn advance(&mut self, sim_time: SimTime, substep: Option<SimTimeSpan>) {
+        if let Some(step) = substep {
+            let mut step_time = self.sim_time + step;
+            while step_time < sim_time {
+                self.consumable.advance(step_time);
+                step_time += step;
+            }
+        }
         self.consumable.advance(sim_time);
+        self.sim_time = sim_time;
     }
 }
 
@@ -182,7 +270,7 @@ pub mod test {
     use crate::units::base::{Amount, Mass};
     use crate::units::geometry::Volume;
 
-    use crate::secs;
+    use crate::{secs, SimTimeSpan};
     use crate::{sim::Consumable, substance::Substance};
 
     use super::Consumed;
@@ -202,12 +290,12 @@ pub mod test {
         consumed.schedule_volume_change(Volume::from_mL(-50.0), secs!(30.0), secs!(50.0));
         consumed.set_exit(secs!(40.0), DigestionDirection::FORWARD).unwrap();
 
-        consumed.advance(secs!(20.0));
+        consumed.advance(secs!(20.0), None);
 
         // The first volume change should have started
         assert!(consumed.volume() < Volume::from_mL(250.0));
 
-        consumed.advance(secs!(45.0));
+        consumed.advance(secs!(45.0), None);
         assert_eq!(consumed.exit_time, secs!(40.0));
 
         let (mut food, exit_dir) = consumed.exit();
@@ -229,4 +317,25 @@ pub mod test {
 
         assert!(consumed.set_exit(secs!(-1.0), DigestionDirection::FORWARD).is_err());
     }
+
+    #[test]
+    fn consumed_time_until_exit() {
+        let mut consumed = Consumed::new(Consumable::new(Volume::from_mL(250.0)));
+        consumed.set_exit(secs!(40.0), DigestionDirection::FORWARD).unwrap();
+        assert_eq!(consumed.exit_time(), secs!(40.0));
+
+        let mut remaining = consumed.time_until_exit(consumed.sim_time).unwrap();
+        assert_eq!(remaining, SimTimeSpan::from_s(40.0));
+
+        for t in [10.0, 25.0, 40.0] {
+            consumed.advance(secs!(t), None);
+            let next = consumed.time_until_exit(consumed.sim_time).unwrap();
+            assert!(next < remaining);
+            remaining = next;
+        }
+        assert_eq!(remaining, SimTimeSpan::from_s(0.0));
+
+        consumed.advance(secs!(45.0), None);
+        assert!(consumed.time_until_exit(consumed.sim_time).is_none());
+    }
 }
\ No newline at end of file
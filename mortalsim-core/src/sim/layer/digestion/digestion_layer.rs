@@ -10,7 +10,9 @@ use super::component::{DigestionComponent, DigestionInitializer};
 use super::consumable::Consumable;
 use super::consumed::Consumed;
 use super::{ConsumeEvent, DigestionDirection, EliminateEvent};
-use crate::units::base::Time;
+use crate::substance::Substance;
+use crate::units::base::{Amount, Time};
+use crate::units::geometry::Volume;
 
 type ConsumableId = IdType;
 
@@ -27,6 +29,8 @@ pub struct DigestionLayer<O: Organism> {
     trigger_map: HashSet<usize>,
     /// Map to track stores in between components
     consumed_map: Vec<Vec<Consumed>>,
+    /// Reaction substep for each component position, if set
+    substeps: Vec<Option<SimTimeSpan>>,
     /// Consumables staged for elimination
     elimination_list: Vec<(Consumable, DigestionDirection)>,
     /// Internal trigger id to unschedule if needed
@@ -46,20 +50,56 @@ impl<O: Organism> DigestionLayer<O> {
             component_map: HashMap::new(),
             trigger_map: HashSet::new(),
             consumed_map: Vec::new(),
+            substeps: Vec::new(),
             elimination_list: Vec::new(),
             internal_trigger_id: None,
         }
     }
 
     /// Consume a new SubstanceStore
-    fn consume(&mut self, consumable: Consumable) {
+    fn consume(&mut self, consumable: Consumable, sim_time: SimTime) {
         log::debug!("Adding new consumable to the digestion layer: {:?}", consumable);
-        let consumed = Consumed::new(consumable);
+        let mut consumed = Consumed::new(consumable);
+        consumed.sim_time = sim_time;
         if let Some(list) = self.consumed_map.get_mut(0) {
             list.push(consumed);
         }
     }
 
+    /// Total amount of a `Substance` across all consumables
+    /// currently within the digestive tract
+    ///
+    /// ### Arguments
+    /// * `substance` - Substance to total
+    ///
+    /// Returns the total amount of the substance in the digestive tract
+    pub fn total_substance(&self, substance: &Substance) -> Amount<f64> {
+        self.consumed_map
+            .iter()
+            .flatten()
+            .fold(Amount::from_mol(0.0), |total, consumed| total + consumed.consumable.amount_of(substance))
+    }
+
+    /// Consumables currently within the digestive tract
+    ///
+    /// Returns the id of the component holding each consumable, along
+    /// with its volume and the time it exits the component, in order of
+    /// position along the tract
+    pub fn in_transit(&self) -> Vec<(&'static str, Volume<f64>, SimTime)> {
+        let mut components: Vec<(&'static str, usize)> = self.component_map.iter().map(|(id, pos)| (*id, *pos)).collect();
+        components.sort_by_key(|(_, pos)| *pos);
+        components
+            .into_iter()
+            .flat_map(|(id, pos)| {
+                self.consumed_map
+                    .get(pos)
+                    .into_iter()
+                    .flatten()
+                    .map(move |consumed| (id, consumed.consumable.volume(), consumed.exit_time))
+            })
+            .collect()
+    }
+
     // Internal method for retrieving the position of a component
     // in the digestive tract
     fn component_position<T: SimComponent<O>>(&self, component: &T) -> usize {
@@ -80,15 +120,16 @@ impl<O: Organism> SimLayer for DigestionLayer<O> {
 
         for evt in connector.active_events.iter() {
             if let Some(consume_evt) = evt.downcast_ref::<ConsumeEvent>() {
-                self.consume(consume_evt.0.clone());
+                self.consume(consume_evt.0.clone(), connector.sim_time());
             }
         }
         // Keep track of vector indices of items which need to move
         let mut moving_indices: Vec<Vec<usize>> = vec![vec![]; self.consumed_map.len()];
         for (pos, consumed_list) in self.consumed_map.iter_mut().enumerate() {
+            let substep = self.substeps.get(pos).copied().flatten();
             for (idx, consumed) in consumed_list.iter_mut().enumerate() {
                 // advance time for the consumable
-                consumed.advance(connector.sim_time());
+                consumed.advance(connector.sim_time(), substep);
                 // if time has exceeded the exit time, stage it for movement
                 if consumed.exit_time <= connector.sim_time() {
                     moving_indices
@@ -108,22 +149,37 @@ impl<O: Organism> SimLayer for DigestionLayer<O> {
         };
 
         for (pos, indices) in moving_indices.into_iter().enumerate() {
-            for idx in indices {
+            // Remove in reverse order so earlier removals don't shift later indices
+            for idx in indices.into_iter().rev() {
                 let mut removed = self
                     .consumed_map
                     .get_mut(pos)
                     .expect("moving_indices referenced invalid position")
                     .remove(idx);
 
-                // Check cases for elimination, either forward or backward
+                // Part of the content may reflux to the previous component
+                // while the rest continues on
+                if pos > 0 {
+                    if let Some(mut refluxed) = removed.split_reflux() {
+                        log::debug!("Refluxing consumable to index {}: {:?}", pos - 1, refluxed.consumable);
+                        refluxed.entry_time = removed.exit_time;
+                        refluxed.exit_time = refluxed.entry_time + self.default_digestion_duration;
+                        self.consumed_map
+                            .get_mut(pos - 1)
+                            .expect("invalid index")
+                            .push(refluxed);
+                        self.trigger_map.insert(pos - 1);
+                    }
+                }
+
+                // Check cases for elimination, either forward, backward or exhausted
                 if (pos == 0 && removed.exit_direction == DigestionDirection::BACK)
                     || (pos >= last && removed.exit_direction == DigestionDirection::FORWARD)
+                    || removed.exit_direction == DigestionDirection::EXHAUSTED
                 {
                     log::debug!("Eliminating consumable {:?}: {:?}", removed.exit_direction, removed.consumable);
-                    let evt = Box::new(EliminateEvent::new(
-                        removed.consumable,
-                        removed.exit_direction,
-                    ));
+                    let (consumable, direction) = removed.exit();
+                    let evt = Box::new(EliminateEvent::new(consumable, direction));
                     connector.time_manager.schedule_event(Self::ELIMINATION_DELAY, evt);
                     continue;
                 }
@@ -136,33 +192,24 @@ impl<O: Organism> SimLayer for DigestionLayer<O> {
 
                 // set defaults, which the component may override
                 removed.exit_time = removed.entry_time + self.default_digestion_duration;
+                removed.reflux_fraction = 0.0;
                 
                 let target_idx = match removed.exit_direction {
-                    DigestionDirection::FORWARD => {
-                        Some(pos + 1)
-                    }
                     DigestionDirection::BACK => {
                         // Always default to FORWARD, even if it was previously BACK
                         removed.exit_direction = DigestionDirection::FORWARD;
-                        Some(pos - 1)
-                    }
-                    DigestionDirection::EXHAUSTED => {
-                        // Drop the removed consumable completely
-                        None
+                        pos - 1
                     }
+                    // EXHAUSTED consumables are eliminated above
+                    _ => pos + 1,
                 };
 
-                if let Some(idx) = target_idx {
-                    log::debug!("Moving consumable FORWARD to index {}: {:?}", idx, removed.consumable);
-                    self.consumed_map
-                        .get_mut(idx)
-                        .expect("invalid index")
-                        .push(removed);
-                    self.trigger_map.insert(idx);
-                }
-                else {
-                    log::debug!("Exhausting Consumable from pos {}: {:?}", pos, removed.consumable);
-                }
+                log::debug!("Moving consumable to index {}: {:?}", target_idx, removed.consumable);
+                self.consumed_map
+                    .get_mut(target_idx)
+                    .expect("invalid index")
+                    .push(removed);
+                self.trigger_map.insert(target_idx);
             }
         }
     }
@@ -180,7 +227,7 @@ impl<O: Organism> SimLayer for DigestionLayer<O> {
             }
             let id = connector
                 .time_manager
-                .schedule_event(delay, Box::new(InternalLayerTrigger));
+                .schedule_event(delay, Box::new(InternalLayerTrigger::default()));
             log::debug!("Setting internal digestion trigger with {} delay. ID: {}", delay, id);
             self.internal_trigger_id = Some(id);
         }
@@ -210,6 +257,7 @@ impl<O: Organism, T: DigestionComponent<O>> SimComponentProcessor<O, T> for Dige
 
         if self.consumed_map.len() < self.component_map.len() {
             self.consumed_map.push(Vec::new());
+            self.substeps.push(initializer.reaction_substep);
         }
     }
 
@@ -218,8 +266,9 @@ impl<O: Organism, T: DigestionComponent<O>> SimComponentProcessor<O, T> for Dige
         self.trigger_map.contains(&component_pos)
     }
 
-    fn prepare_component(&mut self, _connector: &mut SimConnector, component: &mut T) {
+    fn prepare_component(&mut self, connector: &mut SimConnector, component: &mut T) {
         let component_pos = self.component_position(component);
+        component.digestion_connector().globals = connector.globals.clone();
 
         // move consumed items from the layer map into the component connector
         let consumed_list = self.consumed_map.get_mut(component_pos).unwrap();
@@ -239,6 +288,7 @@ impl<O: Organism, T: DigestionComponent<O>> SimComponentProcessor<O, T> for Dige
         let component_pos = self.component_position(component);
 
         // move consumed items from the component connector back into the layer map
+        component.digestion_connector().first_run = false;
         let consumed_list = &mut component.digestion_connector().consumed_list;
         self.consumed_map
             .get_mut(component_pos)
@@ -253,6 +303,19 @@ impl<O: Organism, T: DigestionComponent<O>> SimComponentProcessor<O, T> for Dige
         let component_idx = self.component_map.remove(component.id())
             .expect(format!("component index is missing for '{:?}'!", component.id()).as_str());
         self.consumed_map.remove(component_idx);
+        self.substeps.remove(component_idx);
+
+        // Shift later components up a position in the digestive tract
+        for idx in self.component_map.values_mut() {
+            if *idx > component_idx {
+                *idx -= 1;
+            }
+        }
+        self.trigger_map = self.trigger_map
+            .drain()
+            .filter(|idx| *idx != component_idx)
+            .map(|idx| if idx > component_idx { idx - 1 } else { idx })
+            .collect();
     }
 
 }
@@ -289,6 +352,12 @@ mod tests {
     use crate::{sim::{component::{SimComponent, SimComponentProcessor, SimComponentProcessorSync}, layer::{digestion::{component::test::TestDigestionComponent, consumable::test::{test_ammonia, test_fiber, test_food}, ConsumeEvent, DigestionComponent, DigestionDirection, EliminateEvent}, InternalLayerTrigger, SimLayer}, organism::test::TestOrganism, Organism, SimConnector, SimTime}, substance::{Substance, SubstanceConcentration}, util::secs, SimTimeSpan};
 
     use super::DigestionLayer;
+    use crate::sim::layer::digestion::consumed::Consumed;
+    use crate::math::BoundFn;
+    use crate::sim::component::ComponentRegistry;
+    use crate::sim::layer::digestion::{DigestionConnector, DigestionInitializer};
+    use crate::substance::SubstanceChange;
+    use crate::units::base::Amount;
 
 
     #[test]
@@ -296,6 +365,23 @@ mod tests {
         DigestionLayer::<TestOrganism>::new();
     }
 
+    #[test]
+    fn total_substance() {
+        let mut layer = DigestionLayer::<TestOrganism>::new();
+        let mut connector = SimConnector::new();
+        layer.setup_component(&mut connector, &mut TestDigestionComponent::new());
+        assert_eq!(layer.total_substance(&Substance::GLC), Amount::from_mol(0.0));
+
+        let food_a = test_food(200.0);
+        let food_b = test_food(100.0);
+        let expected = food_a.amount_of(&Substance::GLC) + food_b.amount_of(&Substance::GLC);
+        layer.consume(food_a, SimTime::from_s(0.0));
+        layer.consume(food_b, SimTime::from_s(0.0));
+
+        let total = layer.total_substance(&Substance::GLC);
+        assert!((total - expected).to_mol().abs() < 1e-12, "Unexpected total {}", total);
+    }
+
     fn run_layer<O: Organism>(layer: &mut DigestionLayer<O>, connector: &mut SimConnector, components: &mut Vec<TestDigestionComponent<O>>) {
         for component in components.iter_mut() {
             layer.prepare_component(connector, component);
@@ -426,13 +512,16 @@ mod tests {
         connector.time_manager.advance_by(SimTimeSpan::from_s(1.0));
 
         // Should be eliminating the fiber
-        let (_, mut evts) = connector.time_manager.next_events().next().unwrap();
+        let (_, evts) = connector.time_manager.next_events().next().unwrap();
 
         assert!(evts.get(0).is_some());
-        assert!(evts.get(0).unwrap().is::<EliminateEvent>());
+        assert!(evts.iter().all(|e| e.is::<EliminateEvent>()));
 
-        let elim = evts.pop().unwrap().downcast::<EliminateEvent>().unwrap();
-        assert_eq!(elim.direction, DigestionDirection::FORWARD);
+        // Food is also eliminated at the same time as EXHAUSTED
+        let elim = evts.into_iter()
+            .map(|e| e.downcast::<EliminateEvent>().unwrap())
+            .find(|e| e.direction == DigestionDirection::FORWARD)
+            .unwrap();
         assert!(elim.excrement.concentration_of(&Substance::Cellulose) > SubstanceConcentration::from_mM(0.0));
 
         // Food should have dissappeared
@@ -441,6 +530,346 @@ mod tests {
 
     }
 
+    #[test]
+    fn layer_eliminate() {
+        let mut layer = DigestionLayer::<TestOrganism>::new();
+        let mut components = vec![
+            TestDigestionComponent::new(),
+            TestDigestionComponent::new(),
+        ];
+        let mut connector = SimConnector::new();
+        for component in components.iter_mut() {
+            layer.setup_component(&mut connector, component);
+        }
+
+        let food = test_food(200.0);
+        let starting_glc = food.concentration_of(&Substance::GLC);
+
+        connector.active_events.push(Arc::new(ConsumeEvent(food)));
+        connector.active_events.push(Arc::new(ConsumeEvent(test_ammonia(50.0))));
+        connector.active_events.push(Arc::new(ConsumeEvent(test_fiber(150.0))));
+
+        let mut eliminated = Vec::new();
+
+        for _ in 0..20 {
+            layer.pre_exec(&mut connector);
+            connector.active_events.drain(..);
+            run_layer(&mut layer, &mut connector, &mut components);
+            layer.post_exec(&mut connector);
+
+            connector.time_manager.advance_by(SimTimeSpan::from_s(30.0));
+            for (_, evts) in connector.time_manager.next_events() {
+                for evt in evts {
+                    if let Ok(elim) = evt.downcast::<EliminateEvent>() {
+                        eliminated.push(elim);
+                    }
+                }
+            }
+        }
+
+        assert_eq!(eliminated.len(), 3);
+
+        let find = |direction: DigestionDirection| {
+            let matches: Vec<_> = eliminated.iter().filter(|e| e.direction() == direction).collect();
+            assert_eq!(matches.len(), 1, "Expected a single {:?} elimination", direction);
+            matches[0].excrement().clone()
+        };
+
+        // Ammonia gets sent back from the first component
+        assert!(find(DigestionDirection::BACK).concentration_of(&Substance::NH3) > SubstanceConcentration::from_mM(0.0));
+        // Fiber passes forward through both components
+        assert!(find(DigestionDirection::FORWARD).concentration_of(&Substance::Cellulose) > SubstanceConcentration::from_mM(0.0));
+        // Food gets exhausted by the first component
+        assert!(find(DigestionDirection::EXHAUSTED).concentration_of(&Substance::GLC) < starting_glc);
+
+        assert!(layer.consumed_map.iter().all(|l| l.is_empty()));
+    }
+
+    #[test]
+    fn layer_eliminate_tag() {
+        let mut layer = DigestionLayer::<TestOrganism>::new();
+        let mut components = vec![
+            TestDigestionComponent::new(),
+            TestDigestionComponent::new(),
+        ];
+        let mut connector = SimConnector::new();
+        for component in components.iter_mut() {
+            layer.setup_component(&mut connector, component);
+        }
+
+        let mut ammonia = test_ammonia(50.0);
+        ammonia.set_tag(7);
+        let mut fiber = test_fiber(150.0);
+        fiber.set_tag(42);
+
+        connector.active_events.push(Arc::new(ConsumeEvent(ammonia)));
+        connector.active_events.push(Arc::new(ConsumeEvent(fiber)));
+        connector.active_events.push(Arc::new(ConsumeEvent(test_food(200.0))));
+
+        let mut eliminated = Vec::new();
+
+        for _ in 0..20 {
+            layer.pre_exec(&mut connector);
+            connector.active_events.drain(..);
+            run_layer(&mut layer, &mut connector, &mut components);
+            layer.post_exec(&mut connector);
+
+            connector.time_manager.advance_by(SimTimeSpan::from_s(30.0));
+            for (_, evts) in connector.time_manager.next_events() {
+                for evt in evts {
+                    if let Ok(elim) = evt.downcast::<EliminateEvent>() {
+                        eliminated.push(elim);
+                    }
+                }
+            }
+        }
+
+        assert_eq!(eliminated.len(), 3);
+
+        let tag_of = |direction: DigestionDirection| {
+            eliminated.iter().find(|e| e.direction() == direction).unwrap().excrement().tag()
+        };
+
+        // Tags survive transit through each component
+        assert_eq!(tag_of(DigestionDirection::BACK), Some(7));
+        assert_eq!(tag_of(DigestionDirection::FORWARD), Some(42));
+        assert_eq!(tag_of(DigestionDirection::EXHAUSTED), None);
+    }
+
+    /// Rapidly consumes glucose while slowly producing it, starting from
+    /// none, so the result depends on how often the zero floor is applied
+    struct FastReactionComponent {
+        connector: DigestionConnector<TestOrganism>,
+        substep: Option<SimTimeSpan>,
+    }
+
+    impl DigestionComponent<TestOrganism> for FastReactionComponent {
+        fn digestion_init(&mut self, initializer: &mut DigestionInitializer<TestOrganism>) {
+            if let Some(substep) = self.substep {
+                initializer.set_reaction_substep(substep);
+            }
+        }
+
+        fn digestion_connector(&mut self) -> &mut DigestionConnector<TestOrganism> {
+            &mut self.connector
+        }
+    }
+
+    impl SimComponent<TestOrganism> for FastReactionComponent {
+        fn id(&self) -> &'static str {
+            "FastReactionComponent"
+        }
+
+        fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+            registry.add_digestion_component(self)
+        }
+
+        fn run(&mut self) {
+            for cons in self.connector.consumed() {
+                let start = cons.sim_time();
+                cons.schedule_custom_change(
+                    Substance::GLC,
+                    SubstanceChange::new(start, SubstanceConcentration::from_mM(-10.0), SimTimeSpan::from_s(1.0), BoundFn::Linear),
+                );
+                cons.schedule_custom_change(
+                    Substance::GLC,
+                    SubstanceChange::new(start, SubstanceConcentration::from_mM(10.0), SimTimeSpan::from_s(10.0), BoundFn::Linear),
+                );
+            }
+        }
+    }
+
+    /// Glucose concentration (mM) after the reaction completes
+    fn glucose_after_reaction(substep: Option<SimTimeSpan>) -> f64 {
+        let mut layer = DigestionLayer::<TestOrganism>::new();
+        let mut connector = SimConnector::new();
+        let mut component = FastReactionComponent {
+            connector: DigestionConnector::new(),
+            substep,
+        };
+        layer.setup_component(&mut connector, &mut component);
+
+        connector.active_events.push(Arc::new(ConsumeEvent(test_fiber(150.0))));
+        layer.pre_exec(&mut connector);
+        connector.active_events.drain(..);
+
+        layer.prepare_component(&mut connector, &mut component);
+        component.run();
+        layer.process_component(&mut connector, &mut component);
+        layer.post_exec(&mut connector);
+
+        connector.time_manager.advance_by(SimTimeSpan::from_s(10.5));
+        layer.pre_exec(&mut connector);
+        layer.consumed_map[0][0].concentration_of(&Substance::GLC).to_mM()
+    }
+
+    /// Holds each consumable for 10s, refluxing part of it on exit
+    struct RefluxComponent {
+        id: &'static str,
+        connector: DigestionConnector<TestOrganism>,
+        reflux: f64,
+    }
+
+    impl DigestionComponent<TestOrganism> for RefluxComponent {
+        fn digestion_connector(&mut self) -> &mut DigestionConnector<TestOrganism> {
+            &mut self.connector
+        }
+    }
+
+    impl SimComponent<TestOrganism> for RefluxComponent {
+        fn id(&self) -> &'static str {
+            self.id
+        }
+
+        fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+            registry.add_digestion_component(self)
+        }
+
+        fn run(&mut self) {
+            for cons in self.connector.consumed() {
+                cons.set_exit(cons.entry_time + secs!(10.0), DigestionDirection::FORWARD).unwrap();
+                cons.set_reflux_fraction(self.reflux).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn layer_reflux() {
+        let mut layer = DigestionLayer::<TestOrganism>::new();
+        let mut connector = SimConnector::new();
+        let mut components: Vec<RefluxComponent> = [("Stomach", 0.0), ("Duodenum", 0.25)]
+            .into_iter()
+            .map(|(id, reflux)| RefluxComponent { id, connector: DigestionConnector::new(), reflux })
+            .collect();
+        for component in components.iter_mut() {
+            layer.setup_component(&mut connector, component);
+        }
+
+        let fiber = test_fiber(200.0);
+        let cellulose = fiber.concentration_of(&Substance::Cellulose);
+        let total = fiber.amount_of(&Substance::Cellulose);
+        connector.active_events.push(Arc::new(ConsumeEvent(fiber)));
+
+        let mut eliminated = Vec::new();
+        for _ in 0..4 {
+            layer.pre_exec(&mut connector);
+            connector.active_events.drain(..);
+            for component in components.iter_mut() {
+                layer.prepare_component(&mut connector, component);
+                component.run();
+                layer.process_component(&mut connector, component);
+            }
+            layer.post_exec(&mut connector);
+
+            connector.time_manager.advance_by(SimTimeSpan::from_s(10.0));
+            for (_, evts) in connector.time_manager.next_events() {
+                eliminated.extend(evts.into_iter().filter_map(|evt| evt.downcast::<EliminateEvent>().ok()));
+            }
+            if !eliminated.is_empty() {
+                break;
+            }
+        }
+
+        // A quarter of the content moved back to the stomach, while
+        // the rest continued forward with the same composition
+        assert_eq!(eliminated.len(), 1);
+        let passed = eliminated[0].excrement();
+        assert!((passed.volume().to_mL() - 150.0).abs() < 1e-9, "{}", passed.volume());
+        assert_eq!(passed.concentration_of(&Substance::Cellulose), cellulose);
+
+        assert_eq!(layer.consumed_map[0].len(), 1);
+        assert!(layer.consumed_map[1].is_empty());
+        let refluxed = &layer.consumed_map[0][0];
+        assert!((refluxed.volume().to_mL() - 50.0).abs() < 1e-9, "{}", refluxed.volume());
+        assert_eq!(refluxed.concentration_of(&Substance::Cellulose), cellulose);
+
+        let remaining = passed.amount_of(&Substance::Cellulose) + refluxed.amount_of(&Substance::Cellulose);
+        assert!((remaining.to_mol() - total.to_mol()).abs() < 1e-9);
+
+        let mut consumed = Consumed::new(test_fiber(100.0));
+        assert!(consumed.set_reflux_fraction(1.5).is_err());
+        assert!(consumed.set_reflux_fraction(-0.1).is_err());
+    }
+
+    #[test]
+    fn layer_tracer() {
+        let mut layer = DigestionLayer::<TestOrganism>::new();
+        let mut connector = SimConnector::new();
+        let mut refluxing: Vec<RefluxComponent> = [("Stomach", 0.0), ("Duodenum", 0.25)]
+            .into_iter()
+            .map(|(id, reflux)| RefluxComponent { id, connector: DigestionConnector::new(), reflux })
+            .collect();
+        let mut absorbing = TestDigestionComponent::new();
+        for component in refluxing.iter_mut() {
+            layer.setup_component(&mut connector, component);
+        }
+        layer.setup_component(&mut connector, &mut absorbing);
+
+        let tracer = SubstanceConcentration::from_mM(1.0);
+        let mut food = test_food(200.0);
+        food.set_concentration(Substance::Tracer(1), tracer).unwrap();
+        let ingested = food.amount_of(&Substance::Tracer(1));
+        let glucose = food.amount_of(&Substance::GLC);
+        connector.active_events.push(Arc::new(ConsumeEvent(food)));
+
+        let mut eliminated = Vec::new();
+        for _ in 0..60 {
+            layer.pre_exec(&mut connector);
+            connector.active_events.drain(..);
+            for component in refluxing.iter_mut() {
+                layer.prepare_component(&mut connector, component);
+                component.run();
+                layer.process_component(&mut connector, component);
+            }
+            layer.prepare_component(&mut connector, &mut absorbing);
+            absorbing.run();
+            layer.process_component(&mut connector, &mut absorbing);
+            layer.post_exec(&mut connector);
+
+            connector.time_manager.advance_by(SimTimeSpan::from_s(10.0));
+            for (_, evts) in connector.time_manager.next_events() {
+                eliminated.extend(evts.into_iter().filter_map(|evt| evt.downcast::<EliminateEvent>().ok()));
+            }
+        }
+
+        // Glucose is absorbed along the way, while the tracer passes
+        // through unchanged, whether refluxed, in transit or eliminated
+        assert!(!eliminated.is_empty());
+        let eliminated_glucose = eliminated
+            .iter()
+            .fold(Amount::from_mol(0.0), |total, elim| total + elim.excrement().amount_of(&Substance::GLC));
+        assert!(eliminated_glucose + layer.total_substance(&Substance::GLC) < glucose * 0.9);
+
+        let in_transit = layer.consumed_map.iter().flatten().map(|cons| &cons.consumable);
+        for consumable in eliminated.iter().map(|elim| elim.excrement()).chain(in_transit) {
+            assert_eq!(consumable.concentration_of(&Substance::Tracer(1)), tracer);
+        }
+        let eliminated_tracer = eliminated
+            .iter()
+            .fold(Amount::from_mol(0.0), |total, elim| total + elim.excrement().amount_of(&Substance::Tracer(1)));
+        let remaining = eliminated_tracer + layer.total_substance(&Substance::Tracer(1));
+        assert!((remaining - ingested).to_mol().abs() < 1e-12, "{} != {}", remaining, ingested);
+    }
+
+    #[test]
+    fn reaction_substep() {
+        // Glucose stays at zero until consumption ends after 1s,
+        // then accumulates to 9 mM by the end of production
+        let expected = 9.0;
+        let errors: Vec<f64> = [None, Some(0.3), Some(0.03)]
+            .into_iter()
+            .map(|step| glucose_after_reaction(step.map(SimTimeSpan::from_s)))
+            .map(|glc| (glc - expected).abs())
+            .collect();
+
+        // A single coarse step either cancels the changes out or skips
+        // the zero floor entirely, depending on which change applies first
+        assert!(errors[0] >= 1.0 - 1e-9, "{:?}", errors);
+        // Finer substeps converge on the expected result
+        assert!(errors.windows(2).all(|w| w[1] < w[0]), "{:?}", errors);
+        assert!(errors[2] < 0.05, "{:?}", errors);
+    }
+
     #[test]
     fn layer_process_sync() {
         let layer = Mutex::new(DigestionLayer::<TestOrganism>::new());
@@ -551,13 +980,16 @@ mod tests {
         connector.lock().unwrap().time_manager.advance_by(SimTimeSpan::from_s(1.0));
 
         // Should be eliminating the fiber
-        let (_, mut evts) = connector.lock().unwrap().time_manager.next_events().next().unwrap();
+        let (_, evts) = connector.lock().unwrap().time_manager.next_events().next().unwrap();
 
         assert!(evts.get(0).is_some());
-        assert!(evts.get(0).unwrap().is::<EliminateEvent>());
+        assert!(evts.iter().all(|e| e.is::<EliminateEvent>()));
 
-        let elim = evts.pop().unwrap().downcast::<EliminateEvent>().unwrap();
-        assert_eq!(elim.direction, DigestionDirection::FORWARD);
+        // Food is also eliminated at the same time as EXHAUSTED
+        let elim = evts.into_iter()
+            .map(|e| e.downcast::<EliminateEvent>().unwrap())
+            .find(|e| e.direction == DigestionDirection::FORWARD)
+            .unwrap();
         assert!(elim.excrement.concentration_of(&Substance::Cellulose) > SubstanceConcentration::from_mM(0.0));
 
         // Food should have dissappeared
@@ -23,6 +23,10 @@ pub struct ConsumeEvent(pub Consumable);
 
 impl Event for ConsumeEvent {}
 
+/// Emitted by the digestion layer whenever a `Consumable` leaves the
+/// digestive tract, whether it exits `FORWARD` past the last component,
+/// `BACK` past the first component, or is `EXHAUSTED` by a component.
+/// Components can subscribe to it through the core layer.
 #[derive(Debug, Clone)]
 pub struct EliminateEvent {
     excrement: Consumable,
@@ -36,6 +40,16 @@ impl EliminateEvent {
             direction,
         }
     }
+
+    /// The `Consumable` which was eliminated, as it was when it exited
+    pub fn excrement(&self) -> &Consumable {
+        &self.excrement
+    }
+
+    /// Direction in which the `Consumable` exited the digestive tract
+    pub fn direction(&self) -> DigestionDirection {
+        self.direction
+    }
 }
 
 impl Event for EliminateEvent {}
@@ -1,6 +1,6 @@
 use std::any::TypeId;
 use std::borrow::BorrowMut;
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::sync::Mutex;
 use std::thread::{scope, Scope};
 
@@ -8,14 +8,23 @@ use strum::VariantArray;
 use rand::distributions::{Alphanumeric, DistString};
 
 use crate::sim::component::registry::{ComponentRegistry, ComponentWrapper};
-use crate::sim::component::{ComponentFactory, SimComponent, SimComponentProcessor, SimComponentProcessorSync};
-use crate::sim::layer::SimLayer;
+use crate::sim::component::{ComponentFactory, Phase, SimComponent, SimComponentProcessor, SimComponentProcessorSync};
+use crate::sim::layer::circulation::CirculationConnector;
+use crate::sim::layer::core::{EventConflict, EventConflictPolicy, UnsatisfiedDependency};
+use crate::sim::layer::digestion::EliminateEvent;
+use crate::sim::layer::{InternalLayerTrigger, SimLayer};
 use crate::sim::{Organism, SimConnector};
+use crate::substance::{ConcentrationDisplay, Substance};
+use crate::units::base::Amount;
+use crate::{IdType, SimTimeSpan};
 
 use super::layer_processor::{LayerProcessor, LayerProcessorSync};
 use super::{LayerType, SimLayerSync};
 use super::LayerType::*;
 
+/// Default cap on the number of update passes within a single advance
+pub const DEFAULT_MAX_ITERATIONS_PER_ADVANCE: usize = 100;
+
 pub struct LayerManager<O: Organism> {
     id: String,
     registry: ComponentRegistry<O>,
@@ -23,6 +32,12 @@ pub struct LayerManager<O: Organism> {
     layers_sync: Vec<Mutex<LayerProcessorSync<O>>>,
     missing_layers: Vec<&'static LayerType>,
     first_update: bool,
+    /// Maximum number of update passes to execute for a single advance
+    max_iterations_per_advance: usize,
+    /// Free-form labels attached to each component
+    labels: HashMap<&'static str, HashSet<String>>,
+    /// Mutual exclusion group of each grouped component
+    exclusion_groups: HashMap<&'static str, String>,
 }
 
 impl<O: Organism> LayerManager<O> {
@@ -36,6 +51,9 @@ impl<O: Organism> LayerManager<O> {
             id: Alphanumeric.sample_string(&mut rand::thread_rng(), 16),
             registry: ComponentRegistry::new(),
             first_update: false,
+            max_iterations_per_advance: DEFAULT_MAX_ITERATIONS_PER_ADVANCE,
+            labels: HashMap::new(),
+            exclusion_groups: HashMap::new(),
             layers,
             layers_sync,
             missing_layers: missing_layers,
@@ -115,6 +133,65 @@ impl<O: Organism> LayerManager<O> {
         self.first_update
     }
 
+    /// Sets the maximum number of update passes executed for a single
+    /// advance. Events scheduled by components for the current time
+    /// trigger another pass, so feedback between components can cascade
+    /// within the same advance. Once the cap is reached, any remaining
+    /// events are deferred to the next update and a warning is emitted.
+    ///
+    /// This applies to every chain of `Event`s scheduled without a delay,
+    /// not only to feedback loops: components responding to such an
+    /// `Event` run within the same advance, where a single pass would
+    /// leave them for the next advance at the same time. A cap of 1
+    /// restores a single pass per advance. Components which only schedule
+    /// `Event`s with a delay behave the same either way.
+    ///
+    /// ### Arguments
+    /// * `max_iterations` - maximum number of passes (at least 1)
+    pub fn set_max_iterations_per_advance(&mut self, max_iterations: usize) {
+        self.max_iterations_per_advance = max_iterations.max(1);
+    }
+
+    /// Maximum number of update passes executed for a single advance
+    pub fn max_iterations_per_advance(&self) -> usize {
+        self.max_iterations_per_advance
+    }
+
+    /// Sets how the Core layer reports components scheduling conflicting
+    /// values of the same `Event` type for the same time
+    ///
+    /// ### Arguments
+    /// * `policy` - conflict policy to apply
+    pub fn set_event_conflict_policy(&mut self, policy: EventConflictPolicy) {
+        for layer in self.layers.iter_mut() {
+            if let LayerProcessor::Core(core_layer) = layer {
+                core_layer.set_conflict_policy(policy);
+            }
+        }
+        for layer in self.layers_sync.iter() {
+            if let LayerProcessorSync::Core(core_layer) = &mut *layer.lock().unwrap() {
+                core_layer.set_conflict_policy(policy);
+            }
+        }
+    }
+
+    /// Drains the `Event` conflicts detected by the Core layer since the
+    /// last drain
+    pub fn drain_event_conflicts(&mut self) -> Vec<EventConflict> {
+        let mut conflicts = Vec::new();
+        for layer in self.layers.iter_mut() {
+            if let LayerProcessor::Core(core_layer) = layer {
+                conflicts.extend(core_layer.drain_conflicts());
+            }
+        }
+        for layer in self.layers_sync.iter() {
+            if let LayerProcessorSync::Core(core_layer) = &mut *layer.lock().unwrap() {
+                conflicts.extend(core_layer.drain_conflicts());
+            }
+        }
+        conflicts
+    }
+
     /// Whether this LayerManager is threaded or not
     pub fn is_threaded(&self) -> bool {
         self.layers.is_empty()
@@ -198,6 +275,48 @@ impl<O: Organism> LayerManager<O> {
         Ok(wrapper)
     }
 
+    /// Registers and initializes a new component with this LayerManager,
+    /// tagging it with the given labels
+    ///
+    /// ### Arguments
+    /// * `connector` - connector for the owning Sim
+    /// * `component` - component to add
+    /// * `labels`    - labels to tag the component with
+    pub fn add_component_with_labels(
+        &mut self,
+        connector: &mut SimConnector,
+        component: impl SimComponent<O>,
+        labels: &[&str],
+    ) -> anyhow::Result<()> {
+        let component_id = self.add_component(connector, component)?.id();
+        self.labels
+            .entry(component_id)
+            .or_default()
+            .extend(labels.iter().map(|l| l.to_string()));
+        Ok(())
+    }
+
+    /// Registers and initializes a new component with this LayerManager in
+    /// a mutual exclusion group. When threaded, components in the same group
+    /// never run concurrently, such as components which modify the same
+    /// vessel stores. Sequential LayerManagers already run one component at
+    /// a time, so groups have no effect on them.
+    ///
+    /// ### Arguments
+    /// * `connector` - connector for the owning Sim
+    /// * `group_id`  - id of the exclusion group
+    /// * `component` - component to add
+    pub fn add_component_in_exclusion_group(
+        &mut self,
+        connector: &mut SimConnector,
+        group_id: &str,
+        component: impl SimComponent<O>,
+    ) -> anyhow::Result<()> {
+        let component_id = self.add_component(connector, component)?.id();
+        self.exclusion_groups.insert(component_id, group_id.to_string());
+        Ok(())
+    }
+
     /// Registers and initializes a new component with this LayerManager from
     /// the given ComponentFactory
     pub fn add_component_from_factory<'a>(
@@ -216,12 +335,77 @@ impl<O: Organism> LayerManager<O> {
         match self.registry.remove_component(component_id) {
             Ok(mut wrapper) => {
                 Self::process_removal(&mut self.layers, &mut self.layers_sync, connector, &mut wrapper);
+                self.labels.remove(wrapper.id());
+                self.exclusion_groups.remove(wrapper.id());
                 Ok(wrapper)
             },
             Err(msg) => Err(msg),
         }
     }
 
+    /// Resets a component to its initial configuration and re-runs its
+    /// layer initialization. If the simulation has already started, the
+    /// component is then run once, as it would be on its initial run.
+    ///
+    /// ### Arguments
+    /// * `connector`    - connector for the owning Sim
+    /// * `component_id` - id of the component to reset
+    ///
+    /// Returns an Err Result if the component isn't registered
+    pub fn reset_component(&mut self, connector: &mut SimConnector, component_id: &str) -> anyhow::Result<()> {
+        let wrapper = self
+            .registry
+            .all_components_mut()
+            .find(|c| c.id() == component_id)
+            .ok_or_else(|| anyhow!("Component '{}' is not registered", component_id))?;
+
+        log::debug!("LayerManager {} resetting component {}", self.id, component_id);
+        wrapper.reset();
+        Self::process_removal(&mut self.layers, &mut self.layers_sync, connector, wrapper);
+        Self::setup_component(&mut self.layers, &mut self.layers_sync, connector, wrapper);
+
+        if self.first_update {
+            self.run_component_once(connector, component_id)?;
+        }
+        Ok(())
+    }
+
+    /// Unregisters and removes every component which participates in the
+    /// given layer, allowing them to be replaced by a different model
+    ///
+    /// ### Arguments
+    /// * `connector`  - connector for the owning Sim
+    /// * `layer_type` - Layer whose components should be removed
+    ///
+    /// Returns the ids of the removed components
+    pub fn remove_layer_components(&mut self, connector: &mut SimConnector, layer_type: LayerType) -> Vec<&'static str> {
+        let component_ids = self.layer_components(layer_type);
+        for component_id in component_ids.iter() {
+            log::debug!("Removing component {} with layer {:?}", component_id, layer_type);
+            self.remove_component(connector, component_id).unwrap();
+        }
+        component_ids
+    }
+
+    /// Retrieves the ids of all registered components which participate
+    /// in the given layer
+    pub fn layer_components(&self, layer_type: LayerType) -> Vec<&'static str> {
+        self.registry
+            .all_components()
+            .filter(|c| c.has_layer(&layer_type))
+            .map(|c| c.id())
+            .collect()
+    }
+
+    /// Retrieves the ids of all registered components tagged with the given label
+    pub fn components_with_label(&self, label: &str) -> Vec<&'static str> {
+        self.registry
+            .all_components()
+            .map(|c| c.id())
+            .filter(|id| self.labels.get(id).is_some_and(|labels| labels.contains(label)))
+            .collect()
+    }
+
     /// Retrieves an iterator of all registered components
     pub fn components(&self) -> impl Iterator<Item = &'static str> + '_ {
         self.registry.all_components().map(|c| c.id())
@@ -232,7 +416,162 @@ impl<O: Organism> LayerManager<O> {
         self.registry.has_component(component_id)
     }
 
-    fn update_sequential(&mut self, connector: &mut SimConnector) {
+    /// Retrieves the circulation components which manage each `Substance`.
+    /// Empty if this LayerManager has no Circulation layer.
+    pub fn circulation_substance_owners(&self) -> HashMap<Substance, Vec<&'static str>> {
+        for layer in self.layers.iter() {
+            if let LayerProcessor::Circulation(circulation_layer) = layer {
+                return circulation_layer.substance_owners();
+            }
+        }
+        for layer in self.layers_sync.iter() {
+            if let LayerProcessorSync::Circulation(circulation_layer) = &*layer.lock().unwrap() {
+                return circulation_layer.substance_owners();
+            }
+        }
+        HashMap::new()
+    }
+
+    /// Total amount of a `Substance` across all layers which hold
+    /// substance stores with a known volume
+    pub fn total_substance(&self, substance: &Substance) -> Amount<f64> {
+        let mut total = Amount::from_mol(0.0);
+        for layer in self.layers.iter() {
+            match layer {
+                LayerProcessor::Circulation(circulation_layer) => total += circulation_layer.total_substance(substance),
+                LayerProcessor::Digestion(digestion_layer) => total += digestion_layer.total_substance(substance),
+                _ => {}
+            }
+        }
+        for layer in self.layers_sync.iter() {
+            match &*layer.lock().unwrap() {
+                LayerProcessorSync::Circulation(circulation_layer) => total += circulation_layer.total_substance(substance),
+                LayerProcessorSync::Digestion(digestion_layer) => total += digestion_layer.total_substance(substance),
+                _ => {}
+            }
+        }
+        total
+    }
+
+    /// Human readable summary of the simulation, with the current time,
+    /// each `Event` on the state, the non-zero Substance concentrations
+    /// of each vessel and the consumables within the digestive tract
+    ///
+    /// ### Arguments
+    /// * `connector` - `SimConnector` holding the time and state
+    pub fn state_report(&self, connector: &SimConnector) -> String {
+        let mut compositions = Vec::new();
+        let mut in_transit = Vec::new();
+        for layer in self.layers.iter() {
+            match layer {
+                LayerProcessor::Circulation(circulation_layer) => compositions = circulation_layer.composition_summary(),
+                LayerProcessor::Digestion(digestion_layer) => in_transit = digestion_layer.in_transit(),
+                _ => {}
+            }
+        }
+        for layer in self.layers_sync.iter() {
+            match &*layer.lock().unwrap() {
+                LayerProcessorSync::Circulation(circulation_layer) => compositions = circulation_layer.composition_summary(),
+                LayerProcessorSync::Digestion(digestion_layer) => in_transit = digestion_layer.in_transit(),
+                _ => {}
+            }
+        }
+
+        let mut report = format!("Sim state at {:.3} s\n", connector.sim_time().to_s());
+
+        report.push_str("\n== State ==\n");
+        let snapshot = connector.state.snapshot();
+        if snapshot.is_empty() {
+            report.push_str("  (none)\n");
+        }
+        for evt in snapshot {
+            report.push_str(&format!("  {}\n", evt));
+        }
+
+        report.push_str("\n== Blood composition ==\n");
+        let compositions: Vec<_> = compositions.into_iter().filter(|(_, composition)| !composition.is_empty()).collect();
+        if compositions.is_empty() {
+            report.push_str("  (none)\n");
+        }
+        for (vessel, composition) in compositions {
+            let concentrations: Vec<String> = composition
+                .iter()
+                .map(|(substance, concentration)| format!("{:?} {}", substance, concentration.to_human_string()))
+                .collect();
+            report.push_str(&format!("  {:?}: {}\n", vessel, concentrations.join(", ")));
+        }
+
+        report.push_str("\n== Digestion ==\n");
+        if in_transit.is_empty() {
+            report.push_str("  (none)\n");
+        }
+        for (component_id, volume, exit_time) in in_transit {
+            report.push_str(&format!(
+                "  {}: {:.1} mL, exits at {:.3} s\n",
+                component_id,
+                volume.to_mL(),
+                exit_time.to_s(),
+            ));
+        }
+
+        report
+    }
+
+    /// Checks that every `Event` a component is notified on has a producer,
+    /// whether that be another component, a layer, or an `Event` scheduled
+    /// or set on the `Sim` externally.
+    ///
+    /// ### Arguments
+    /// * `connector` - `SimConnector` holding the state and scheduled events
+    ///
+    /// Returns the list of unsatisfied dependencies, if any
+    pub fn validate_event_graph(&self, connector: &SimConnector) -> Result<(), Vec<UnsatisfiedDependency>> {
+        let mut layer_types: HashSet<LayerType> = self.layers.iter().map(|l| l.layer_type()).collect();
+        layer_types.extend(self.layers_sync.iter().map(|l| l.lock().unwrap().layer_type()));
+
+        // Events emitted by layers rather than components
+        let mut external = HashSet::new();
+        if layer_types.contains(&Digestion) {
+            external.insert(TypeId::of::<EliminateEvent>());
+        }
+
+        let mut missing = Vec::new();
+        for layer in self.layers.iter() {
+            if let LayerProcessor::Core(core_layer) = layer {
+                missing = core_layer.unsatisfied_dependencies(connector, &external);
+            }
+        }
+        for layer in self.layers_sync.iter() {
+            if let LayerProcessorSync::Core(core_layer) = &*layer.lock().unwrap() {
+                missing = core_layer.unsatisfied_dependencies(connector, &external);
+            }
+        }
+
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(missing)
+        }
+    }
+
+    /// Renders the Core components as a DOT graph, with an edge from each
+    /// producer of an `Event` to each component notified on it. Empty if
+    /// this LayerManager has no Core layer.
+    pub fn export_dependency_graph_dot(&self) -> String {
+        for layer in self.layers.iter() {
+            if let LayerProcessor::Core(core_layer) = layer {
+                return core_layer.dependency_graph_dot();
+            }
+        }
+        for layer in self.layers_sync.iter() {
+            if let LayerProcessorSync::Core(core_layer) = &*layer.lock().unwrap() {
+                return core_layer.dependency_graph_dot();
+            }
+        }
+        String::from("digraph {\n}")
+    }
+
+    fn update_sequential(&mut self, connector: &mut SimConnector, run_all: bool, phase: Phase, last_phase: bool) {
         log::trace!("Running sequential update");
         for layer in self.layers.iter_mut() {
             log::trace!("Running pre_exec for layer {:?}", layer.layer_type());
@@ -240,16 +579,28 @@ impl<O: Organism> LayerManager<O> {
         }
 
         let mut update_list;
+        // Forced layers apply to components of later phases as well
+        let forced_layers = if last_phase {
+            std::mem::take(&mut connector.forced_layers)
+        } else {
+            connector.forced_layers.clone()
+        };
 
-        if !self.first_update {
+        if run_all {
             // If we haven't executed the first update,
             // let ALL components run
-            log::trace!("Staging all components for initial run");
-            update_list = self.registry.all_components_mut().collect();
+            log::trace!("Staging all components of phase {:?} for initial run", phase);
+            update_list = self.registry.all_components_mut().filter(|c| c.phase() == phase).collect();
         }
         else {
             update_list = Vec::new();
-            for component in self.registry.all_components_mut() {
+            for component in self.registry.all_components_mut().filter(|c| c.phase() == phase) {
+                if forced_layers.iter().any(|lt| component.has_layer(lt)) {
+                    log::trace!("Component {} staged for a forced run", component.id());
+                    update_list.push(component);
+                    continue;
+                }
+
                 log::trace!("Checking component {}", component.id());
                 let mut check_list = self
                     .layers
@@ -296,7 +647,7 @@ impl<O: Organism> LayerManager<O> {
         }
     }
 
-    fn update_threaded(&mut self, connector: &mut SimConnector) {
+    fn update_threaded(&mut self, connector: &mut SimConnector, run_all: bool, phase: Phase, last_phase: bool) {
         log::trace!("Running threaded update");
         for layer in self.layers_sync.iter_mut() {
             let mut locked_layer = layer.lock().unwrap();
@@ -305,17 +656,29 @@ impl<O: Organism> LayerManager<O> {
         }
 
         let mut update_list;
+        // Forced layers apply to components of later phases as well
+        let forced_layers = if last_phase {
+            std::mem::take(&mut connector.forced_layers)
+        } else {
+            connector.forced_layers.clone()
+        };
 
-        if !self.first_update {
+        if run_all {
             // If we haven't executed the first update,
             // let ALL components run
-            log::trace!("Staging all components for initial run");
-            update_list = self.registry.all_components_mut().collect();
+            log::trace!("Staging all components of phase {:?} for initial run", phase);
+            update_list = self.registry.all_components_mut().filter(|c| c.phase() == phase).collect();
         }
         else {
             update_list = Vec::new();
 
-            for component in self.registry.all_components_mut() {
+            for component in self.registry.all_components_mut().filter(|c| c.phase() == phase) {
+                if forced_layers.iter().any(|lt| component.has_layer(lt)) {
+                    log::trace!("Component {} staged for a forced run", component.id());
+                    update_list.push(component);
+                    continue;
+                }
+
                 log::trace!("Checking component {}", component.id());
                 let mut check_list = self
                     .layers_sync
@@ -331,34 +694,58 @@ impl<O: Organism> LayerManager<O> {
             }
         }
 
+        // Components in the same exclusion group run one after another on
+        // a single thread, while all others get a thread of their own
+        let mut run_groups: Vec<Vec<&mut Box<dyn ComponentWrapper<O>>>> = Vec::new();
+        let mut group_idx: HashMap<&str, usize> = HashMap::new();
+        for component in update_list {
+            match self.exclusion_groups.get(component.id()) {
+                Some(group_id) => match group_idx.get(group_id.as_str()) {
+                    Some(idx) => run_groups[*idx].push(component),
+                    None => {
+                        group_idx.insert(group_id, run_groups.len());
+                        run_groups.push(vec![component]);
+                    }
+                },
+                None => run_groups.push(vec![component]),
+            }
+        }
+
         let layers = &self.layers_sync;
         let mconnector = Mutex::new(connector);
 
-        scope(|s| {
-            for component in update_list {
-                s.spawn(|| {
-                    // Prepare the component with each of the associated layers
-                    // have to collect here to avoid conflicting borrows of component
-                    let mut layer_list: Vec<&Mutex<LayerProcessorSync<O>>> = layers
-                        .iter()
-                        .filter(|l| component.has_layer(&l.lock().unwrap().layer_type()))
-                        .collect();
-
-                    for layer in layer_list.iter_mut() {
-                        let mut locked_layer = layer.lock().unwrap();
-                        log::trace!("Preparing component {} with layer {:?}", component.id(), locked_layer.layer_type());
-                        locked_layer.prepare_component_sync(mconnector.lock().unwrap().borrow_mut(), component);
-                    }
+        let run_component = |component: &mut Box<dyn ComponentWrapper<O>>| {
+            // Prepare the component with each of the associated layers
+            // have to collect here to avoid conflicting borrows of component
+            let mut layer_list: Vec<&Mutex<LayerProcessorSync<O>>> = layers
+                .iter()
+                .filter(|l| component.has_layer(&l.lock().unwrap().layer_type()))
+                .collect();
 
-                    // Execute component logic
-                    log::trace!("Executing component {}", component.id());
-                    component.run();
+            for layer in layer_list.iter_mut() {
+                let mut locked_layer = layer.lock().unwrap();
+                log::trace!("Preparing component {} with layer {:?}", component.id(), locked_layer.layer_type());
+                locked_layer.prepare_component_sync(mconnector.lock().unwrap().borrow_mut(), component);
+            }
 
-                    // Execute post run processing
-                    for layer in layer_list.iter_mut() {
-                        let mut locked_layer = layer.lock().unwrap();
-                        log::trace!("Processing component {} with layer {:?}", component.id(), locked_layer.layer_type());
-                        locked_layer.process_component_sync(mconnector.lock().unwrap().borrow_mut(), component);
+            // Execute component logic
+            log::trace!("Executing component {}", component.id());
+            component.run();
+
+            // Execute post run processing
+            for layer in layer_list.iter_mut() {
+                let mut locked_layer = layer.lock().unwrap();
+                log::trace!("Processing component {} with layer {:?}", component.id(), locked_layer.layer_type());
+                locked_layer.process_component_sync(mconnector.lock().unwrap().borrow_mut(), component);
+            }
+        };
+        let run_component = &run_component;
+
+        scope(|s| {
+            for group in run_groups {
+                s.spawn(move || {
+                    for component in group {
+                        run_component(component);
                     }
                 });
             }
@@ -372,13 +759,929 @@ impl<O: Organism> LayerManager<O> {
         }
     }
 
-    /// Executes an update across all layers and registered components
-    pub fn update(&mut self, connector: &mut SimConnector) {
-        if self.is_threaded() {
-            self.update_threaded(connector)
+    /// Provides read-only access to a circulation component's connector,
+    /// with the current stores of its attached vessels, without running
+    /// the component
+    ///
+    /// ### Arguments
+    /// * `component_id` - id of the component to inspect
+    /// * `inspect`      - function given the component's connector
+    ///
+    /// Returns an Err Result if the component isn't registered or isn't
+    /// a circulation component
+    pub fn inspect_circulation(
+        &mut self,
+        component_id: &str,
+        inspect: impl FnOnce(&CirculationConnector<O>),
+    ) -> anyhow::Result<()> {
+        let component = self
+            .registry
+            .all_components_mut()
+            .find(|c| c.id() == component_id)
+            .ok_or_else(|| anyhow!("Component '{}' is not registered", component_id))?;
+        if !component.is_circulation_component() {
+            return Err(anyhow!("Component '{}' is not a circulation component", component_id));
+        }
+
+        // Threaded stores are shared with the connector already
+        if self.layers.is_empty() {
+            inspect(component.circulation_connector());
+            return Ok(());
+        }
+
+        for layer in self.layers.iter_mut() {
+            if let LayerProcessor::Circulation(circulation_layer) = layer {
+                circulation_layer.inspect_component(component, inspect);
+                return Ok(());
+            }
+        }
+        Err(anyhow!("No Circulation layer is present"))
+    }
+
+    /// Runs a single component once with the current state, without
+    /// advancing time or running any other components
+    ///
+    /// ### Arguments
+    /// * `connector`    - connector for the owning Sim
+    /// * `component_id` - id of the component to run
+    ///
+    /// Returns the schedule IDs of the events the component scheduled,
+    /// or an Err Result if the component isn't registered
+    pub fn run_component_once(&mut self, connector: &mut SimConnector, component_id: &str) -> anyhow::Result<Vec<IdType>> {
+        let component = self
+            .registry
+            .all_components_mut()
+            .find(|c| c.id() == component_id)
+            .ok_or_else(|| anyhow!("Component '{}' is not registered", component_id))?;
+        let prior_ids = connector.time_manager.scheduled_ids();
+
+        log::debug!("LayerManager {} running component {} once", self.id, component_id);
+        if self.layers_sync.is_empty() {
+            let mut layer_list: Vec<&mut LayerProcessor<O>> = self
+                .layers
+                .iter_mut()
+                .filter(|l| component.has_layer(&l.layer_type()))
+                .collect();
+
+            for layer in layer_list.iter_mut() {
+                layer.prepare_component(connector, component);
+            }
+            component.run();
+            for layer in layer_list.iter_mut() {
+                layer.process_component(connector, component);
+            }
         }
         else {
-            self.update_sequential(connector)
+            let layer_list: Vec<&Mutex<LayerProcessorSync<O>>> = self
+                .layers_sync
+                .iter()
+                .filter(|l| component.has_layer(&l.lock().unwrap().layer_type()))
+                .collect();
+
+            for layer in layer_list.iter() {
+                layer.lock().unwrap().prepare_component_sync(connector, component);
+            }
+            component.run();
+            for layer in layer_list.iter() {
+                layer.lock().unwrap().process_component_sync(connector, component);
+            }
+        }
+
+        let mut scheduled: Vec<IdType> = connector
+            .time_manager
+            .scheduled_ids()
+            .difference(&prior_ids)
+            .copied()
+            .collect();
+        scheduled.sort();
+        Ok(scheduled)
+    }
+
+    /// Schedules an internal trigger for the current simulation time which
+    /// runs every component on the given layer, or on all layers if `None`,
+    /// during the next update regardless of whether they were triggered.
+    ///
+    /// ### Arguments
+    /// * `connector`  - connector for the owning Sim
+    /// * `layer_type` - Layer to force, or `None` for all layers
+    ///
+    /// Returns the schedule ID of the trigger
+    pub fn force_layer_run(&self, connector: &mut SimConnector, layer_type: Option<LayerType>) -> IdType {
+        let forced_layers = match layer_type {
+            Some(lt) => vec![lt],
+            None => LayerType::VARIANTS.to_vec(),
+        };
+        log::debug!("LayerManager {} forcing a run of layers {:?}", self.id, forced_layers);
+        connector
+            .time_manager
+            .schedule_event(SimTimeSpan::from_s(0.0), Box::new(InternalLayerTrigger { forced_layers }))
+    }
+
+    /// Executes an update across all layers and registered components,
+    /// running components in order of their `Phase`. Additional passes are executed for components triggered by events
+    /// scheduled for the current time, up to the configured maximum.
+    pub fn update(&mut self, connector: &mut SimConnector) {
+        // Active events from earlier passes, which would otherwise
+        // be drained by the next pass
+        let mut prior_events = Vec::new();
+        let mut iterations = 0;
+
+        loop {
+            // Only the first pass is subject to running all components,
+            // subsequent passes only run newly triggered components
+            let run_all = iterations == 0 && !self.first_update;
+
+            // Each phase runs as its own update, so that `Event`s emitted
+            // by earlier phases are on the state for later ones
+            let mut phases: BTreeSet<Phase> = self.registry.all_components_mut().map(|c| c.phase()).collect();
+            if phases.is_empty() {
+                phases.insert(Phase::default());
+            }
+            let phase_count = phases.len();
+            for (idx, phase) in phases.into_iter().enumerate() {
+                if idx > 0 {
+                    prior_events.append(&mut connector.active_events);
+                }
+                let last_phase = idx + 1 == phase_count;
+                if self.is_threaded() {
+                    self.update_threaded(connector, run_all, phase, last_phase)
+                }
+                else {
+                    self.update_sequential(connector, run_all, phase, last_phase)
+                }
+            }
+            iterations += 1;
+            self.first_update = true;
+
+            if !connector.time_manager.has_ready_events() {
+                break;
+            }
+
+            if iterations >= self.max_iterations_per_advance {
+                log::warn!(
+                    "LayerManager {} reached the maximum of {} iterations at time {}, deferring remaining events",
+                    self.id,
+                    self.max_iterations_per_advance,
+                    connector.sim_time(),
+                );
+                break;
+            }
+
+            prior_events.append(&mut connector.active_events);
+        }
+
+        if !prior_events.is_empty() {
+            prior_events.append(&mut connector.active_events);
+            connector.active_events = prior_events;
+        }
+    }
+}
+
+mod tests {
+    use std::any::TypeId;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use crate::event::test::{TestEventA, TestEventB};
+    use crate::event::HeartRate;
+    use crate::sim::component::registry::ComponentRegistry;
+    use crate::sim::component::{Phase, SimComponent};
+    use crate::sim::layer::circulation::component::test::TestCircComponentA;
+    use crate::sim::layer::core::{CoreComponent, CoreConnector, CoreInitializer, EventConflictPolicy};
+    use crate::sim::layer::digestion::component::test::TestDigestionComponent;
+    use crate::sim::layer::LayerType;
+    use crate::sim::organism::test::{TestBloodVessel, TestOrganism, TestSim};
+    use crate::sim::{Sim, SimConnector, SimTime};
+    use crate::substance::Substance;
+    use crate::units::base::{Amount, Distance};
+    use crate::units::mechanical::Frequency;
+    use crate::SimTimeSpan;
+
+    use super::LayerManager;
+
+    /// Responds to each `TestEventB` with a `TestEventA` at the same time
+    struct PingComponent {
+        connector: CoreConnector<TestOrganism>,
+    }
+
+    impl CoreComponent<TestOrganism> for PingComponent {
+        fn core_init(&mut self, initializer: &mut CoreInitializer<TestOrganism>) {
+            initializer.notify::<TestEventB>();
+        }
+        fn core_connector(&mut self) -> &mut CoreConnector<TestOrganism> {
+            &mut self.connector
+        }
+    }
+
+    impl SimComponent<TestOrganism> for PingComponent {
+        fn id(&self) -> &'static str {
+            "PingComponent"
+        }
+        fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+            registry.add_core_component(self);
+        }
+        fn run(&mut self) {
+            if self.connector.trigger_events().any(|t| *t == TypeId::of::<TestEventB>()) {
+                self.connector.schedule_event(SimTimeSpan::from_s(0.0), TestEventA::new(Distance::from_m(1.0)));
+            }
+        }
+    }
+
+    /// Responds to each `TestEventA` with a `TestEventB` at the same time
+    struct PongComponent {
+        connector: CoreConnector<TestOrganism>,
+    }
+
+    impl CoreComponent<TestOrganism> for PongComponent {
+        fn core_init(&mut self, initializer: &mut CoreInitializer<TestOrganism>) {
+            initializer.notify::<TestEventA>();
+        }
+        fn core_connector(&mut self) -> &mut CoreConnector<TestOrganism> {
+            &mut self.connector
+        }
+    }
+
+    impl SimComponent<TestOrganism> for PongComponent {
+        fn id(&self) -> &'static str {
+            "PongComponent"
+        }
+        fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+            registry.add_core_component(self);
+        }
+        fn run(&mut self) {
+            if self.connector.trigger_events().any(|t| *t == TypeId::of::<TestEventA>()) {
+                self.connector.schedule_event(SimTimeSpan::from_s(0.0), TestEventB::new(Amount::from_mol(1.0)));
+            }
         }
     }
+
+    /// `PingComponent` which declares the `Event` it produces
+    struct DeclaredPingComponent(PingComponent);
+
+    impl CoreComponent<TestOrganism> for DeclaredPingComponent {
+        fn core_init(&mut self, initializer: &mut CoreInitializer<TestOrganism>) {
+            self.0.core_init(initializer);
+            initializer.produces::<TestEventA>();
+        }
+        fn core_connector(&mut self) -> &mut CoreConnector<TestOrganism> {
+            self.0.core_connector()
+        }
+    }
+
+    impl SimComponent<TestOrganism> for DeclaredPingComponent {
+        fn id(&self) -> &'static str {
+            "DeclaredPingComponent"
+        }
+        fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+            registry.add_core_component(self);
+        }
+        fn run(&mut self) {
+            self.0.run()
+        }
+    }
+
+    /// `PongComponent` which declares the `Event` it produces
+    struct DeclaredPongComponent(PongComponent);
+
+    impl CoreComponent<TestOrganism> for DeclaredPongComponent {
+        fn core_init(&mut self, initializer: &mut CoreInitializer<TestOrganism>) {
+            self.0.core_init(initializer);
+            initializer.produces::<TestEventB>();
+        }
+        fn core_connector(&mut self) -> &mut CoreConnector<TestOrganism> {
+            self.0.core_connector()
+        }
+    }
+
+    impl SimComponent<TestOrganism> for DeclaredPongComponent {
+        fn id(&self) -> &'static str {
+            "DeclaredPongComponent"
+        }
+        fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+            registry.add_core_component(self);
+        }
+        fn run(&mut self) {
+            self.0.run()
+        }
+    }
+
+    /// Counts the number of times it has run
+    struct CountingComponent {
+        connector: CoreConnector<TestOrganism>,
+        runs: Arc<Mutex<usize>>,
+    }
+
+    impl CoreComponent<TestOrganism> for CountingComponent {
+        fn core_init(&mut self, _initializer: &mut CoreInitializer<TestOrganism>) {}
+        fn core_connector(&mut self) -> &mut CoreConnector<TestOrganism> {
+            &mut self.connector
+        }
+    }
+
+    impl SimComponent<TestOrganism> for CountingComponent {
+        fn id(&self) -> &'static str {
+            "CountingComponent"
+        }
+        fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+            registry.add_core_component(self);
+        }
+        fn run(&mut self) {
+            *self.runs.lock().unwrap() += 1;
+        }
+    }
+
+    /// Tracks how many components are running at once, holding its run
+    /// long enough for concurrent runs to overlap
+    struct OverlapComponent {
+        id: &'static str,
+        connector: CoreConnector<TestOrganism>,
+        running: Arc<AtomicUsize>,
+        max_running: Arc<AtomicUsize>,
+    }
+
+    impl CoreComponent<TestOrganism> for OverlapComponent {
+        fn core_init(&mut self, _initializer: &mut CoreInitializer<TestOrganism>) {}
+        fn core_connector(&mut self) -> &mut CoreConnector<TestOrganism> {
+            &mut self.connector
+        }
+    }
+
+    impl SimComponent<TestOrganism> for OverlapComponent {
+        fn id(&self) -> &'static str {
+            self.id
+        }
+        fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+            registry.add_core_component(self);
+        }
+        fn run(&mut self) {
+            let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_running.fetch_max(running, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(50));
+            self.running.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Maximum number of the given components running at once over a
+    /// forced update of a threaded LayerManager
+    fn max_overlap(groups: &[Option<&str>]) -> usize {
+        const IDS: [&str; 3] = ["OverlapA", "OverlapB", "OverlapC"];
+        let mut manager = LayerManager::new_threaded();
+        let mut connector = SimConnector::new();
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+
+        for (id, group) in IDS.iter().zip(groups) {
+            let component = OverlapComponent {
+                id,
+                connector: CoreConnector::new(),
+                running: running.clone(),
+                max_running: max_running.clone(),
+            };
+            match group {
+                Some(group_id) => manager.add_component_in_exclusion_group(&mut connector, group_id, component).unwrap(),
+                None => { manager.add_component(&mut connector, component).unwrap(); }
+            }
+        }
+
+        // Initial run of all components
+        manager.update(&mut connector);
+        max_running.load(Ordering::SeqCst)
+    }
+
+    #[test]
+    fn exclusion_groups() {
+        // Ungrouped components run concurrently
+        assert!(max_overlap(&[None, None]) > 1);
+
+        // while grouped components are serialized
+        assert_eq!(max_overlap(&[Some("aorta"), Some("aorta")]), 1);
+
+        // but may still run alongside components outside of the group
+        assert_eq!(max_overlap(&[Some("aorta"), Some("aorta"), Some("legs")]), 2);
+    }
+
+    /// Emits a `HeartRate` at each run
+    struct HeartRateComponent {
+        id: &'static str,
+        hz: f64,
+        connector: CoreConnector<TestOrganism>,
+    }
+
+    impl CoreComponent<TestOrganism> for HeartRateComponent {
+        fn core_init(&mut self, _initializer: &mut CoreInitializer<TestOrganism>) {}
+        fn core_connector(&mut self) -> &mut CoreConnector<TestOrganism> {
+            &mut self.connector
+        }
+    }
+
+    impl SimComponent<TestOrganism> for HeartRateComponent {
+        fn id(&self) -> &'static str {
+            self.id
+        }
+        fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+            registry.add_core_component(self);
+        }
+        fn run(&mut self) {
+            self.connector.schedule_event(SimTimeSpan::from_s(1.0), HeartRate(Frequency::from_Hz(self.hz)));
+        }
+    }
+
+    fn heart_rate_component(id: &'static str, hz: f64) -> HeartRateComponent {
+        HeartRateComponent { id, hz, connector: CoreConnector::new() }
+    }
+
+    fn forced_run(mut manager: LayerManager<TestOrganism>) {
+        let mut connector = SimConnector::new();
+        let runs = Arc::new(Mutex::new(0));
+        manager.add_component(&mut connector, CountingComponent {
+            connector: CoreConnector::new(),
+            runs: runs.clone(),
+        }).unwrap();
+
+        // Everything runs on the first update, but not after
+        manager.update(&mut connector);
+        connector.time_manager.advance_by(SimTimeSpan::from_s(1.0));
+        manager.update(&mut connector);
+        assert_eq!(*runs.lock().unwrap(), 1);
+
+        // Forcing a layer the component isn't on has no effect
+        manager.force_layer_run(&mut connector, Some(LayerType::Circulation));
+        connector.time_manager.advance_by(SimTimeSpan::from_s(1.0));
+        manager.update(&mut connector);
+        assert_eq!(*runs.lock().unwrap(), 1);
+
+        manager.force_layer_run(&mut connector, Some(LayerType::Core));
+        connector.time_manager.advance_by(SimTimeSpan::from_s(1.0));
+        manager.update(&mut connector);
+        assert_eq!(*runs.lock().unwrap(), 2);
+
+        // The trigger isn't exposed as an active event
+        assert!(connector.active_events.is_empty());
+
+        manager.force_layer_run(&mut connector, None);
+        connector.time_manager.advance_by(SimTimeSpan::from_s(1.0));
+        manager.update(&mut connector);
+        assert_eq!(*runs.lock().unwrap(), 3);
+
+        // Only a single run is forced
+        connector.time_manager.advance_by(SimTimeSpan::from_s(1.0));
+        manager.update(&mut connector);
+        assert_eq!(*runs.lock().unwrap(), 3);
+    }
+
+    #[test]
+    fn remove_layer_components() {
+        let mut manager = LayerManager::new();
+        let mut connector = SimConnector::new();
+        let runs = Arc::new(Mutex::new(0));
+        manager.add_component(&mut connector, CountingComponent {
+            connector: CoreConnector::new(),
+            runs: runs.clone(),
+        }).unwrap();
+        for _ in 0..3 {
+            manager.add_component(&mut connector, TestDigestionComponent::new()).unwrap();
+        }
+        manager.update(&mut connector);
+        assert_eq!(manager.layer_components(LayerType::Digestion).len(), 3);
+
+        let removed = manager.remove_layer_components(&mut connector, LayerType::Digestion);
+        assert_eq!(removed.len(), 3);
+        assert!(removed.iter().all(|id| !manager.has_component(id)));
+        assert!(manager.layer_components(LayerType::Digestion).is_empty());
+        assert_eq!(manager.components().collect::<Vec<_>>(), vec!["CountingComponent"]);
+
+        // Remaining components continue to run
+        manager.force_layer_run(&mut connector, None);
+        connector.time_manager.advance_by(SimTimeSpan::from_s(1.0));
+        manager.update(&mut connector);
+        assert_eq!(*runs.lock().unwrap(), 2);
+    }
+
+    #[test]
+    fn component_labels() {
+        let mut manager = LayerManager::new();
+        let mut connector = SimConnector::new();
+        manager.add_component_with_labels(&mut connector, PingComponent {
+            connector: CoreConnector::new(),
+        }, &["cardiac", "experimental"]).unwrap();
+        manager.add_component_with_labels(&mut connector, PongComponent {
+            connector: CoreConnector::new(),
+        }, &["cardiac"]).unwrap();
+        manager.add_component(&mut connector, CountingComponent {
+            connector: CoreConnector::new(),
+            runs: Arc::new(Mutex::new(0)),
+        }).unwrap();
+
+        let mut cardiac = manager.components_with_label("cardiac");
+        cardiac.sort();
+        assert_eq!(cardiac, vec!["PingComponent", "PongComponent"]);
+        assert_eq!(manager.components_with_label("experimental"), vec!["PingComponent"]);
+        assert!(manager.components_with_label("renal").is_empty());
+
+        // Labels are dropped along with the component
+        manager.remove_component(&mut connector, "PingComponent").unwrap();
+        assert_eq!(manager.components_with_label("cardiac"), vec!["PongComponent"]);
+        assert!(manager.components_with_label("experimental").is_empty());
+    }
+
+    /// Schedules a `TestEventA` 1 s out with its length set to the number of runs
+    struct ReportingComponent {
+        connector: CoreConnector<TestOrganism>,
+        runs: usize,
+    }
+
+    impl CoreComponent<TestOrganism> for ReportingComponent {
+        fn core_init(&mut self, _initializer: &mut CoreInitializer<TestOrganism>) {}
+        fn core_connector(&mut self) -> &mut CoreConnector<TestOrganism> {
+            &mut self.connector
+        }
+    }
+
+    impl SimComponent<TestOrganism> for ReportingComponent {
+        fn id(&self) -> &'static str {
+            "ReportingComponent"
+        }
+        fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+            registry.add_core_component(self);
+        }
+        fn run(&mut self) {
+            self.runs += 1;
+            self.connector.schedule_event(SimTimeSpan::from_s(1.0), TestEventA::new(Distance::from_m(self.runs as f64)));
+        }
+    }
+
+    /// Schedules a `TestEventA` each second for 3 s from its first run,
+    /// with lengths 1, 2 and 3 m
+    struct ScheduleComponent {
+        connector: CoreConnector<TestOrganism>,
+        started: bool,
+    }
+
+    impl CoreComponent<TestOrganism> for ScheduleComponent {
+        fn core_init(&mut self, _initializer: &mut CoreInitializer<TestOrganism>) {}
+        fn core_connector(&mut self) -> &mut CoreConnector<TestOrganism> {
+            &mut self.connector
+        }
+    }
+
+    impl SimComponent<TestOrganism> for ScheduleComponent {
+        fn id(&self) -> &'static str {
+            "ScheduleComponent"
+        }
+        fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+            registry.add_core_component(self);
+        }
+        fn run(&mut self) {
+            if !self.started {
+                self.started = true;
+                self.connector.unschedule_all(false);
+                for i in 1..=3 {
+                    self.connector.schedule_event(SimTimeSpan::from_s(i as f64), TestEventA::new(Distance::from_m(i as f64)));
+                }
+            }
+        }
+        fn reset(&mut self) {
+            self.started = false;
+        }
+    }
+
+    #[test]
+    fn reset_component() {
+        let mut sim = TestSim::new();
+        sim.add_component(ScheduleComponent {
+            connector: CoreConnector::new(),
+            started: false,
+        }).unwrap();
+
+        let mut emitted = Vec::new();
+        for i in 0..10 {
+            if i == 3 {
+                sim.reset_component("ScheduleComponent").unwrap();
+            }
+            sim.advance_by(SimTimeSpan::from_s(0.5));
+            let time = sim.time().to_s();
+            emitted.extend(sim.drain_active().filter_map(|e| e.downcast_ref::<TestEventA>().map(|a| (time, a.len.m))));
+        }
+
+        // The remaining schedule is dropped and restarts from the reset at 1.5 s
+        assert_eq!(emitted, vec![(1.0, 1.0), (2.5, 1.0), (3.5, 2.0), (4.5, 3.0)]);
+        assert!(sim.reset_component("MissingComponent").is_err());
+    }
+
+    #[test]
+    fn run_component_once() {
+        let mut sim = TestSim::new();
+        let runs = Arc::new(Mutex::new(0));
+        sim.add_component(CountingComponent {
+            connector: CoreConnector::new(),
+            runs: runs.clone(),
+        }).unwrap();
+        sim.add_component(ReportingComponent {
+            connector: CoreConnector::new(),
+            runs: 0,
+        }).unwrap();
+
+        let scheduled = sim.run_component_once("ReportingComponent").unwrap();
+        assert_eq!(scheduled.len(), 1);
+        assert_eq!(scheduled[0].0, SimTime::from_s(1.0));
+        assert_eq!(scheduled[0].1.downcast_ref::<TestEventA>().unwrap().len, Distance::from_m(1.0));
+
+        // Neither time nor any other component moved
+        assert_eq!(sim.time(), SimTime::from_s(0.0));
+        assert_eq!(*runs.lock().unwrap(), 0);
+        assert!(sim.run_component_once("MissingComponent").is_err());
+
+        // The scheduled event is replaced as usual by the next run
+        let scheduled = sim.run_component_once("ReportingComponent").unwrap();
+        assert_eq!(scheduled[0].1.downcast_ref::<TestEventA>().unwrap().len, Distance::from_m(2.0));
+        sim.advance_by(SimTimeSpan::from_s(0.5));
+        sim.advance_by(SimTimeSpan::from_s(1.0));
+        let lens: Vec<f64> = sim.drain_active().filter_map(|e| e.downcast_ref::<TestEventA>().map(|a| a.len.m)).collect();
+        assert_eq!(lens, vec![3.0]);
+        assert_eq!(*runs.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn force_layer_run() {
+        forced_run(LayerManager::new());
+    }
+
+    #[test]
+    fn force_layer_run_threaded() {
+        forced_run(LayerManager::new_threaded());
+    }
+
+    fn oscillation(mut manager: LayerManager<TestOrganism>) {
+        let mut connector = SimConnector::new();
+        manager.set_max_iterations_per_advance(5);
+        assert_eq!(manager.max_iterations_per_advance(), 5);
+
+        let mut ping = PingComponent { connector: CoreConnector::new() };
+        let mut pong = PongComponent { connector: CoreConnector::new() };
+        ping.connector.unschedule_all(false);
+        pong.connector.unschedule_all(false);
+        manager.add_component(&mut connector, ping).unwrap();
+        manager.add_component(&mut connector, pong).unwrap();
+
+        connector.time_manager.schedule_event(SimTimeSpan::from_s(1.0), Box::new(TestEventA::new(Distance::from_m(1.0))));
+        connector.time_manager.advance_by(SimTimeSpan::from_s(1.0));
+        manager.update(&mut connector);
+
+        // The cap engaged, leaving the next step of the loop pending
+        // and keeping the events from every pass
+        assert_eq!(connector.active_events.len(), 5);
+        assert!(connector.time_manager.has_ready_events());
+
+        // The deferred events are picked up by the following update
+        manager.update(&mut connector);
+        assert_eq!(connector.active_events.len(), 5);
+        assert!(connector.time_manager.has_ready_events());
+    }
+
+    /// Responds to each `TestEventA` with a `TestEventB` half a second later
+    struct DelayedPongComponent {
+        connector: CoreConnector<TestOrganism>,
+    }
+
+    impl CoreComponent<TestOrganism> for DelayedPongComponent {
+        fn core_init(&mut self, initializer: &mut CoreInitializer<TestOrganism>) {
+            initializer.notify::<TestEventA>();
+        }
+        fn core_connector(&mut self) -> &mut CoreConnector<TestOrganism> {
+            &mut self.connector
+        }
+    }
+
+    impl SimComponent<TestOrganism> for DelayedPongComponent {
+        fn id(&self) -> &'static str {
+            "DelayedPongComponent"
+        }
+        fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+            registry.add_core_component(self);
+        }
+        fn run(&mut self) {
+            if self.connector.trigger_events().any(|t| *t == TypeId::of::<TestEventA>()) {
+                self.connector.unschedule_all(false);
+                self.connector.schedule_event(SimTimeSpan::from_s(0.5), TestEventB::new(Amount::from_mol(1.0)));
+            }
+        }
+    }
+
+    /// Time and emitted `Event`s of each advance of a Sim in which events
+    /// are only ever scheduled with a delay
+    fn delayed_responses(max_iterations: Option<usize>) -> Vec<(SimTime, Vec<String>)> {
+        let mut sim = TestSim::new();
+        if let Some(max_iterations) = max_iterations {
+            sim.set_max_iterations_per_advance(max_iterations);
+        }
+        sim.add_component(ScheduleComponent { connector: CoreConnector::new(), started: false }).unwrap();
+        sim.add_component(DelayedPongComponent { connector: CoreConnector::new() }).unwrap();
+
+        let mut history = Vec::new();
+        for _ in 0..10 {
+            sim.advance();
+            history.push((sim.time(), sim.drain_active().map(|evt| format!("{:?}", evt)).collect()));
+        }
+        history
+    }
+
+    #[test]
+    fn single_pass_without_same_time_events() {
+        // Without any events scheduled for the current time, each advance
+        // makes a single pass, as when passes are capped at one
+        let history = delayed_responses(None);
+        assert_eq!(history, delayed_responses(Some(1)));
+        assert_eq!(history.iter().map(|(_, events)| events.len()).sum::<usize>(), 6);
+    }
+
+    #[test]
+    fn iteration_cap() {
+        oscillation(LayerManager::new());
+    }
+
+    #[test]
+    fn iteration_cap_threaded() {
+        oscillation(LayerManager::new_threaded());
+    }
+
+    fn event_conflicts(mut manager: LayerManager<TestOrganism>) {
+        let mut connector = SimConnector::new();
+        manager.add_component(&mut connector, heart_rate_component("FirstHeartRate", 1.0)).unwrap();
+        manager.add_component(&mut connector, heart_rate_component("SecondHeartRate", 1.5)).unwrap();
+        manager.add_component(&mut connector, heart_rate_component("SameHeartRate", 1.5)).unwrap();
+        manager.update(&mut connector);
+
+        // Only the emitters with different values conflict
+        let conflicts = manager.drain_event_conflicts();
+        assert_eq!(conflicts.len(), 1, "{:?}", conflicts);
+        let conflict = &conflicts[0];
+        assert_eq!(conflict.time, SimTime::from_s(1.0));
+        assert_eq!(conflict.event_type, TypeId::of::<HeartRate>());
+        let mut components = conflict.components;
+        components.sort();
+        assert_eq!(components, ["FirstHeartRate", "SecondHeartRate"]);
+        assert!(conflict.values.iter().any(|v| v.contains("1.5")));
+        assert!(manager.drain_event_conflicts().is_empty());
+
+        // Conflicts are no longer recorded once ignored
+        manager.set_event_conflict_policy(EventConflictPolicy::Ignore);
+        manager.force_layer_run(&mut connector, None);
+        connector.time_manager.advance_by(SimTimeSpan::from_s(1.0));
+        manager.update(&mut connector);
+        assert!(manager.drain_event_conflicts().is_empty());
+    }
+
+    #[test]
+    fn event_conflict() {
+        event_conflicts(LayerManager::new());
+    }
+
+    #[test]
+    fn event_conflict_threaded() {
+        event_conflicts(LayerManager::new_threaded());
+    }
+
+    fn inspection(mut sim: TestSim) {
+        sim.add_component(TestCircComponentA::new()).unwrap();
+        sim.add_component(CountingComponent {
+            connector: CoreConnector::new(),
+            runs: Arc::new(Mutex::new(0)),
+        }).unwrap();
+        sim.advance_by(SimTimeSpan::from_s(2.0));
+
+        let vena_cava_glc = |sim: &mut TestSim| {
+            let mut glc = None;
+            sim.inspect_circulation("TestCircComponentA", |connector| {
+                let store = connector.blood_store(&TestBloodVessel::VenaCava).unwrap();
+                glc = Some(store.concentration_of(&Substance::GLC).to_mM());
+            }).unwrap();
+            glc.unwrap()
+        };
+        assert!((vena_cava_glc(&mut sim) - 1.0).abs() < 1e-6);
+
+        // Stores are returned to the layer after each inspection
+        assert!((vena_cava_glc(&mut sim) - 1.0).abs() < 1e-6);
+
+        assert!(sim.inspect_circulation("CountingComponent", |_| {}).is_err());
+        assert!(sim.inspect_circulation("MissingComponent", |_| {}).is_err());
+    }
+
+    #[test]
+    fn inspect_circulation() {
+        inspection(TestSim::new());
+    }
+
+    #[test]
+    fn inspect_circulation_threaded() {
+        inspection(TestSim::new_threaded());
+    }
+
+    #[test]
+    fn dependency_graph_dot() {
+        let mut sim = TestSim::new();
+        sim.add_component(DeclaredPingComponent(PingComponent { connector: CoreConnector::new() })).unwrap();
+        sim.add_component(DeclaredPongComponent(PongComponent { connector: CoreConnector::new() })).unwrap();
+        sim.add_component(CountingComponent {
+            connector: CoreConnector::new(),
+            runs: Arc::new(Mutex::new(0)),
+        }).unwrap();
+
+        let dot = sim.export_dependency_graph_dot();
+        assert!(dot.starts_with("digraph {"));
+        assert!(dot.contains("\"CountingComponent\";"));
+
+        // Each component feeds the other, forming a cycle
+        let event_a = std::any::type_name::<TestEventA>();
+        let event_b = std::any::type_name::<TestEventB>();
+        assert!(dot.contains(&format!("\"DeclaredPingComponent\" -> \"DeclaredPongComponent\" [label=\"{}\"];", event_a)));
+        assert!(dot.contains(&format!("\"DeclaredPongComponent\" -> \"DeclaredPingComponent\" [label=\"{}\"];", event_b)));
+        assert_eq!(dot.matches("->").count(), 2);
+    }
+
+    /// Reports a heart rate of 1.5 Hz on each run
+    struct SensorComponent {
+        connector: CoreConnector<TestOrganism>,
+    }
+
+    impl CoreComponent<TestOrganism> for SensorComponent {
+        fn core_init(&mut self, initializer: &mut CoreInitializer<TestOrganism>) {
+            initializer.produces::<HeartRate>();
+        }
+        fn core_connector(&mut self) -> &mut CoreConnector<TestOrganism> {
+            &mut self.connector
+        }
+    }
+
+    impl SimComponent<TestOrganism> for SensorComponent {
+        fn id(&self) -> &'static str {
+            "SensorComponent"
+        }
+        fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+            registry.add_core_component(self);
+        }
+        fn run(&mut self) {
+            self.connector.schedule_event(SimTimeSpan::from_s(0.0), HeartRate(Frequency::from_Hz(1.5)));
+        }
+        fn phase(&self) -> Phase {
+            Phase::Sense
+        }
+    }
+
+    /// Records the heart rate on the state each time it runs
+    struct ActuatorComponent {
+        connector: CoreConnector<TestOrganism>,
+        observed: Arc<Mutex<Vec<Option<f64>>>>,
+    }
+
+    impl CoreComponent<TestOrganism> for ActuatorComponent {
+        fn core_init(&mut self, _initializer: &mut CoreInitializer<TestOrganism>) {}
+        fn core_connector(&mut self) -> &mut CoreConnector<TestOrganism> {
+            &mut self.connector
+        }
+    }
+
+    impl SimComponent<TestOrganism> for ActuatorComponent {
+        fn id(&self) -> &'static str {
+            "ActuatorComponent"
+        }
+        fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+            registry.add_core_component(self);
+        }
+        fn run(&mut self) {
+            let heart_rate = self.connector.get::<HeartRate>().map(|hr| hr.0.Hz);
+            self.observed.lock().unwrap().push(heart_rate);
+        }
+        fn phase(&self) -> Phase {
+            Phase::Actuate
+        }
+    }
+
+    fn phases(mut manager: LayerManager<TestOrganism>) {
+        let mut connector = SimConnector::new();
+        let observed = Arc::new(Mutex::new(Vec::new()));
+
+        // Registered ahead of the sensor, but runs after it
+        manager.add_component(&mut connector, ActuatorComponent {
+            connector: CoreConnector::new(),
+            observed: observed.clone(),
+        }).unwrap();
+        manager.add_component(&mut connector, SensorComponent {
+            connector: CoreConnector::new(),
+        }).unwrap();
+
+        manager.update(&mut connector);
+        assert_eq!(*observed.lock().unwrap(), vec![Some(1.5)]);
+        assert!(connector.active_events.iter().any(|e| e.is::<HeartRate>()));
+    }
+
+    #[test]
+    fn phases_sequential() {
+        phases(LayerManager::new());
+    }
+
+    #[test]
+    fn phases_threaded() {
+        phases(LayerManager::new_threaded());
+    }
 }
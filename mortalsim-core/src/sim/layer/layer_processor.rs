@@ -10,7 +10,7 @@ use super::{LayerType, SimLayer, SimLayerSync};
 
 pub enum LayerProcessor<O: Organism> {
     Core(CoreLayer<O>),
-    Circulation(CirculationLayer<O>),
+    Circulation(Box<CirculationLayer<O>>),
     Digestion(DigestionLayer<O>),
     Nervous(NervousLayer<O>),
 }
@@ -19,7 +19,7 @@ impl<O: Organism + 'static> LayerProcessor<O> {
     pub fn new(layer_type: LayerType) -> Self {
         match layer_type {
             LayerType::Core => Self::Core(CoreLayer::new()),
-            LayerType::Circulation => Self::Circulation(CirculationLayer::new()),
+            LayerType::Circulation => Self::Circulation(Box::new(CirculationLayer::new())),
             LayerType::Digestion => Self::Digestion(DigestionLayer::new()),
             LayerType::Nervous => Self::Nervous(NervousLayer::new()),
         }
@@ -103,7 +103,7 @@ impl<O: Organism, T: ComponentWrapper<O>> SimComponentProcessor<O, T> for LayerP
 
 pub enum LayerProcessorSync<O: Organism> {
     Core(CoreLayer<O>),
-    Circulation(CirculationLayer<O>),
+    Circulation(Box<CirculationLayer<O>>),
     Digestion(DigestionLayer<O>),
     Nervous(NervousLayer<O>),
 }
@@ -112,7 +112,7 @@ impl<O: Organism + 'static> LayerProcessorSync<O> {
     pub fn new(layer_type: LayerType) -> Self {
         match layer_type {
             LayerType::Core => Self::Core(CoreLayer::new()),
-            LayerType::Circulation => Self::Circulation(CirculationLayer::new()),
+            LayerType::Circulation => Self::Circulation(Box::new(CirculationLayer::new())),
             LayerType::Digestion => Self::Digestion(DigestionLayer::new()),
             LayerType::Nervous => Self::Nervous(NervousLayer::new()),
         }
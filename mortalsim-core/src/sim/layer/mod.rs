@@ -19,6 +19,7 @@ pub use layer_manager::LayerManager;
 use super::SimConnector;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Copy, EnumCount, VariantArray)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LayerType {
     Core,
     Circulation,
@@ -42,9 +43,12 @@ pub trait SimLayerSync: Send {
     fn post_exec_sync(&mut self, connector: &mut SimConnector);
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 /// Internal Event used to force layer processing
-pub(crate) struct InternalLayerTrigger;
+pub(crate) struct InternalLayerTrigger {
+    /// Layers on which all components are run when the trigger fires
+    pub(crate) forced_layers: Vec<LayerType>,
+}
 
 impl Event for InternalLayerTrigger {}
 
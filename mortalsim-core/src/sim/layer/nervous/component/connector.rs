@@ -5,15 +5,20 @@ use std::sync::Arc;
 use downcast_rs::Downcast;
 
 use crate::event::Event;
-use crate::sim::layer::nervous::NerveSignal;
+use crate::sim::layer::nervous::{Nerve, NerveSignal};
 use crate::sim::layer::nervous::transform::{TransformFn, NerveSignalTransformer};
 use crate::sim::organism::Organism;
-use crate::sim::SimTime;
-use crate::{IdGenerator, IdType};
+use crate::sim::{SimGlobals, SimTime};
+use crate::{IdGenerator, IdType, SimTimeSpan};
+
+/// Relative amplitude below which retained signals are dropped
+const DECAY_CUTOFF: f64 = 0.001;
 
 pub struct NervousConnector<O: Organism> {
     /// Copy of the current simulation time
     pub(crate) sim_time: SimTime,
+    /// Global parameters of the Sim
+    pub(crate) globals: SimGlobals,
     /// Incoming signals
     pub(crate) incoming: HashMap<TypeId, Vec<NerveSignal<O>>>,
     /// Outgoing signals
@@ -29,14 +34,21 @@ pub struct NervousConnector<O: Organism> {
     pub(crate) removing_transforms: HashMap<O::NerveType, HashMap<TypeId, IdType>>,
     /// List of signal ids to unschedule
     pub(crate) pending_unschedules: Vec<(SimTime, IdType)>,
+    /// Decay time constants for signal types which are retained after arrival
+    pub(crate) signal_decays: HashMap<TypeId, SimTimeSpan>,
+    /// Previously arrived signals which are still decaying
+    pub(crate) retained: HashMap<TypeId, Vec<NerveSignal<O>>>,
     /// Empty Event list for ergonomic message use
     empty: Vec<NerveSignal<O>>,
+    /// Whether the component has yet to complete its first run
+    pub(crate) first_run: bool,
 }
 
 impl<O: Organism> NervousConnector<O> {
     pub fn new() -> Self {
         Self {
             sim_time: SimTime::from_s(0.0),
+            globals: SimGlobals::new(),
             incoming: HashMap::new(),
             outgoing: Vec::new(),
             scheduled_signals: HashMap::new(),
@@ -44,7 +56,10 @@ impl<O: Organism> NervousConnector<O> {
             registered_transforms: HashMap::new(),
             removing_transforms: HashMap::new(),
             pending_unschedules: Vec::new(),
+            signal_decays: HashMap::new(),
+            retained: HashMap::new(),
             empty: Vec::new(),
+            first_run: true,
         }
     }
 
@@ -53,6 +68,24 @@ impl<O: Organism> NervousConnector<O> {
         self.sim_time
     }
 
+    /// Whether the component is running for the first time, e.g. to
+    /// seed baselines from the current state
+    pub fn is_first_run(&self) -> bool {
+        self.first_run
+    }
+
+    /// Retrieves the current value of a global parameter of the Sim,
+    /// such as ambient temperature or altitude
+    ///
+    /// ### Arguments
+    /// * `key` - Name of the global
+    ///
+    /// Returns a copy of the value, or `None` if the global isn't set or
+    /// holds a value of another type
+    pub fn get_global<T: Any + Clone>(&self, key: &str) -> Option<T> {
+        self.globals.get(key)
+    }
+
     fn extract_message<T: Event>(s: &NerveSignal<O>) -> (O::NerveType, &'_ T) {
         (s.terminating_nerve(), s.message::<T>())
     }
@@ -64,6 +97,58 @@ impl<O: Organism> NervousConnector<O> {
         }
     }
     
+    /// Retrieves all signals of the given type which are still decaying,
+    /// including those which arrived prior to the current run. The decay
+    /// time constant must be registered with `NervousInitializer::decay_signal`,
+    /// otherwise only newly arrived signals are included, without decay.
+    ///
+    /// Returns an iterator of (terminating nerve, message, amplitude) where the
+    /// amplitude is the fraction of the signal's effect remaining, starting at
+    /// 1.0 when the signal arrives
+    pub fn get_messages_decayed<T: Event>(&self) -> impl Iterator<Item = (O::NerveType, &'_ T, f64)> {
+        let tau = self.signal_decays.get(&TypeId::of::<T>()).copied();
+        let sim_time = self.sim_time;
+        let retained = self.retained.get(&TypeId::of::<T>()).unwrap_or(&self.empty);
+        let incoming = self.incoming.get(&TypeId::of::<T>()).unwrap_or(&self.empty);
+
+        retained.iter().chain(incoming.iter()).map(move |s| {
+            let (nerve, message) = Self::extract_message(s);
+            (nerve, message, Self::decay_factor(s, sim_time, tau))
+        })
+    }
+
+    fn decay_factor(signal: &NerveSignal<O>, sim_time: SimTime, tau: Option<SimTimeSpan>) -> f64 {
+        match tau {
+            Some(tau) => {
+                let elapsed = signal.send_time().span_to(&sim_time);
+                f64::exp(-elapsed.to_s() / tau.to_s())
+            }
+            None => 1.0,
+        }
+    }
+
+    /// Moves newly arrived signals of decaying types into the retained list
+    pub(crate) fn retain_decaying(&mut self) {
+        for (type_id, signals) in self.incoming.iter() {
+            if self.signal_decays.contains_key(type_id) {
+                self.retained
+                    .entry(*type_id)
+                    .or_default()
+                    .extend(signals.iter().cloned());
+            }
+        }
+    }
+
+    /// Drops retained signals which have decayed below the cutoff
+    pub(crate) fn prune_decayed(&mut self) {
+        let sim_time = self.sim_time;
+        for (type_id, signals) in self.retained.iter_mut() {
+            let tau = self.signal_decays.get(type_id).copied();
+            signals.retain(|s| Self::decay_factor(s, sim_time, tau) >= DECAY_CUTOFF);
+        }
+        self.retained.retain(|_, signals| !signals.is_empty());
+    }
+
     pub fn send_message<T: Event>(
         &mut self,
         message: T,
@@ -84,6 +169,37 @@ impl<O: Organism> NervousConnector<O> {
         Ok(signal_id)
     }
 
+    /// Broadcasts a message from a source nerve to every nerve reachable
+    /// downstream of it. Each nerve receives its own signal after a delay
+    /// proportional to its distance from the source, so transforms on
+    /// intermediate nerves are not applied.
+    ///
+    /// ### Arguments
+    /// * `message`    - message to deliver to each nerve
+    /// * `source`     - nerve the broadcast originates from
+    /// * `conduction` - conduction delay across each link
+    ///
+    /// Returns the signal ids, or an Err Result if the conduction delay is not positive
+    pub fn broadcast_downstream<T: Event + Clone>(
+        &mut self,
+        message: T,
+        source: O::NerveType,
+        conduction: SimTimeSpan,
+    ) -> anyhow::Result<Vec<IdType>> {
+        if conduction <= SimTimeSpan::from_s(0.0) {
+            return Err(anyhow!("Invalid conduction delay {}: must be positive", conduction));
+        }
+
+        source
+            .reachable_nerves()
+            .into_iter()
+            .map(|(nerve, distance)| {
+                let send_time = self.sim_time + conduction * distance as f64;
+                self.send_message(message.clone(), vec![nerve], send_time)
+            })
+            .collect()
+    }
+
     pub fn transform_message<T: Event>(
         &mut self,
         nerve: O::NerveType,
@@ -137,6 +253,7 @@ pub mod test {
     use crate::sim::layer::nervous::NerveSignal;
     use crate::sim::organism::test::{TestAnatomicalRegion, TestNerve, TestOrganism};
     use crate::sim::SimTime;
+    use crate::SimTimeSpan;
 
     use super::NervousConnector;
 
@@ -180,6 +297,39 @@ pub mod test {
         ).is_err());
     }
 
+    #[test]
+    fn broadcast_downstream() {
+        let mut connector = NervousConnector::<TestOrganism>::new();
+        connector.sim_time = SimTime::from_s(1.0);
+        let ids = connector.broadcast_downstream(
+            MovementEvent {amount: 1},
+            TestNerve::SpinalCord,
+            SimTimeSpan::from_s(0.01),
+        ).unwrap();
+        assert_eq!(ids.len(), 8);
+
+        let arrivals: HashMap<TestNerve, SimTime> = connector
+            .outgoing
+            .iter()
+            .map(|s| (s.terminating_nerve(), s.send_time()))
+            .collect();
+
+        // One link from the spinal cord for the cervical and lumbar
+        // nerves, and two for the peripheral nerves beyond them
+        for nerve in [TestNerve::RightC, TestNerve::RightL, TestNerve::LeftC, TestNerve::LeftL] {
+            assert_eq!(arrivals.get(&nerve), Some(&SimTime::from_s(1.01)), "{}", nerve);
+        }
+        for nerve in [TestNerve::RightAxillary, TestNerve::RightFemoral, TestNerve::LeftAxillary, TestNerve::LeftFemoral] {
+            assert_eq!(arrivals.get(&nerve), Some(&SimTime::from_s(1.02)), "{}", nerve);
+        }
+        assert!(!arrivals.contains_key(&TestNerve::SpinalCord));
+        assert!(!arrivals.contains_key(&TestNerve::Brain));
+
+        // Nothing lies downstream of a peripheral nerve
+        assert!(connector.broadcast_downstream(MovementEvent {amount: 1}, TestNerve::LeftFemoral, SimTimeSpan::from_s(0.01)).unwrap().is_empty());
+        assert!(connector.broadcast_downstream(MovementEvent {amount: 1}, TestNerve::Brain, SimTimeSpan::from_s(0.0)).is_err());
+    }
+
     #[test]
     fn transform_message() {
         let mut connector = NervousConnector::<TestOrganism>::new();
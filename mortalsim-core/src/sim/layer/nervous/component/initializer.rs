@@ -1,6 +1,7 @@
 use crate::event::Event;
 use crate::sim::layer::nervous::transform::{NerveSignalTransformer, TransformFn};
 use crate::sim::organism::Organism;
+use crate::SimTimeSpan;
 use std::any::TypeId;
 use std::collections::{HashMap, HashSet};
 
@@ -11,6 +12,8 @@ pub struct NervousInitializer<O: Organism> {
     /// Transformations to add
     pub(crate) adding_transforms:
         HashMap<O::NerveType, HashMap<TypeId, Box<dyn NerveSignalTransformer>>>,
+    /// Decay time constants for signal types which should be retained after arrival
+    pub(crate) signal_decays: HashMap<TypeId, SimTimeSpan>,
 }
 
 impl<O: Organism> NervousInitializer<O> {
@@ -18,6 +21,7 @@ impl<O: Organism> NervousInitializer<O> {
         NervousInitializer {
             signal_notifies: HashMap::new(),
             adding_transforms: HashMap::new(),
+            signal_decays: HashMap::new(),
         }
     }
 
@@ -40,6 +44,17 @@ impl<O: Organism> NervousInitializer<O> {
             .insert(TypeId::of::<T>(), Box::new(TransformFn(Box::new(handler))));
     }
 
+    /// Retains arrived signals of the given type so that their effect decays
+    /// exponentially with the given time constant, rather than disappearing
+    /// after the run in which they arrived. Decayed signals are retrieved
+    /// with `NervousConnector::get_messages_decayed`.
+    ///
+    /// ### Arguments
+    /// * `tau` - decay time constant
+    pub fn decay_signal<T: Event>(&mut self, tau: SimTimeSpan) {
+        self.signal_decays.insert(TypeId::of::<T>(), tau);
+    }
+
 }
 
 
@@ -25,9 +25,10 @@ pub trait NervousComponent<O: Organism>: SimComponent<O> {
 
 
 pub mod test {
-    use crate::{event::Event, sim::{component::SimComponent, organism::test::{TestAnatomicalRegion, TestNerve, TestOrganism}, Organism, SimTime}};
+    use crate::{event::Event, sim::{component::SimComponent, organism::test::{TestAnatomicalRegion, TestNerve, TestOrganism}, Organism, SimTime}, SimTimeSpan};
 
     use super::{NervousComponent, NervousConnector};
+    use crate::sim::layer::nervous::Nerve;
 
     #[derive(Debug)]
     pub struct PainEvent {
@@ -37,7 +38,7 @@ pub mod test {
 
     impl Event for PainEvent {}
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub struct MovementEvent {
         pub amount: u8,
     }
@@ -141,14 +142,14 @@ pub mod test {
             for (_, pain_signal) in self.nervous_connector.get_messages::<PainEvent>() {
                 let mut reflex_amount = 0;
                 if pain_signal.level < 5 {
-                    println!("It's just a scratch. Ignore.");
+                    log::debug!("It's just a scratch. Ignore.");
                 }
                 else if pain_signal.level >= 5 && pain_signal.level < 8 {
-                    println!("Ow");
+                    log::debug!("Ow");
                     reflex_amount = 100;
                 }
                 else {
-                    println!("AAAAAAAGGHGHHHAGHAHGHGHGHGH!!!");
+                    log::debug!("AAAAAAAGGHGHHHAGHAHGHGHGHGH!!!");
                     reflex_amount = 200;
                 }
 
@@ -197,13 +198,13 @@ pub mod test {
 
         fn print_movement_amt(amt: u8, appendage: &'static str) {
             if amt > 128 {
-                println!("I'm flailing my {} around!", appendage);
+                log::debug!("I'm flailing my {} around!", appendage);
             }
             else if amt > 0 {
-                println!("My {} moves casually.", appendage);
+                log::debug!("My {} moves casually.", appendage);
             }
             else {
-                println!("My {} is not moving.", appendage);
+                log::debug!("My {} is not moving.", appendage);
             }
         }
     }
@@ -300,13 +301,13 @@ pub mod test {
 
         fn print_movement_amt(amt: u8, appendage: &'static str) {
             if amt > 128 {
-                println!("I'm flailing my {} around!", appendage);
+                log::debug!("I'm flailing my {} around!", appendage);
             }
             else if amt > 0 {
-                println!("My {} moves casually.", appendage);
+                log::debug!("My {} moves casually.", appendage);
             }
             else {
-                println!("My {} is not moving.", appendage);
+                log::debug!("My {} is not moving.", appendage);
             }
         }
     }
@@ -342,4 +343,112 @@ pub mod test {
         }
     }
 
+    /// Sends itself a motor command on its first run and records the
+    /// decayed amplitude of the command on each subsequent run
+    pub struct TestDecayComponent {
+        nervous_connector: NervousConnector<TestOrganism>,
+        first_run: bool,
+        pub amplitudes: Vec<f64>,
+    }
+
+    impl TestDecayComponent {
+        pub fn new() -> Self {
+            TestDecayComponent {
+                nervous_connector: NervousConnector::new(),
+                first_run: false,
+                amplitudes: Vec::new(),
+            }
+        }
+    }
+
+    impl NervousComponent<TestOrganism> for TestDecayComponent {
+        fn nervous_init(&mut self, nervous_initializer: &mut super::NervousInitializer<TestOrganism>) {
+            nervous_initializer.notify_of::<MovementEvent>(TestNerve::LeftAxillary);
+            nervous_initializer.decay_signal::<MovementEvent>(SimTimeSpan::from_s(1.0));
+        }
+
+        fn nervous_connector(&mut self) -> &mut NervousConnector<TestOrganism> {
+            &mut self.nervous_connector
+        }
+    }
+
+    impl SimComponent<TestOrganism> for TestDecayComponent {
+        fn id(&self) -> &'static str {
+            "TestDecayComponent"
+        }
+
+        fn attach(self, registry: &mut crate::sim::component::ComponentRegistry<TestOrganism>) {
+            registry.add_nervous_component(self)
+        }
+
+        fn run(&mut self) {
+            for (_, _, amplitude) in self.nervous_connector.get_messages_decayed::<MovementEvent>() {
+                self.amplitudes.push(amplitude);
+            }
+            if !self.first_run {
+                self.nervous_connector.send_message(
+                    MovementEvent { amount: 100 },
+                    TestPainReflexComponent::left_arm_path(),
+                    SimTime::from_s(1.0),
+                ).unwrap();
+                self.first_run = true;
+            }
+        }
+    }
+
+    /// Broadcasts a motor command from the spinal cord on its first run
+    /// and records each nerve the command arrives at, with the time
+    pub struct TestBroadcastComponent {
+        nervous_connector: NervousConnector<TestOrganism>,
+        first_run: bool,
+        pub arrivals: Vec<(TestNerve, SimTime)>,
+    }
+
+    impl TestBroadcastComponent {
+        pub fn new() -> Self {
+            TestBroadcastComponent {
+                nervous_connector: NervousConnector::new(),
+                first_run: false,
+                arrivals: Vec::new(),
+            }
+        }
+    }
+
+    impl NervousComponent<TestOrganism> for TestBroadcastComponent {
+        fn nervous_init(&mut self, nervous_initializer: &mut super::NervousInitializer<TestOrganism>) {
+            for (nerve, _) in TestNerve::SpinalCord.reachable_nerves() {
+                nervous_initializer.notify_of::<MovementEvent>(nerve);
+            }
+        }
+
+        fn nervous_connector(&mut self) -> &mut NervousConnector<TestOrganism> {
+            &mut self.nervous_connector
+        }
+    }
+
+    impl SimComponent<TestOrganism> for TestBroadcastComponent {
+        fn id(&self) -> &'static str {
+            "TestBroadcastComponent"
+        }
+
+        fn attach(self, registry: &mut crate::sim::component::ComponentRegistry<TestOrganism>) {
+            registry.add_nervous_component(self)
+        }
+
+        fn run(&mut self) {
+            let sim_time = self.nervous_connector.sim_time();
+            for (nerve, _) in self.nervous_connector.get_messages::<MovementEvent>() {
+                self.arrivals.push((nerve, sim_time));
+            }
+            if !self.first_run {
+                self.nervous_connector.broadcast_downstream(
+                    MovementEvent { amount: 50 },
+                    TestNerve::SpinalCord,
+                    SimTimeSpan::from_s(0.01),
+                ).unwrap();
+                self.first_run = true;
+            }
+        }
+    }
+
 }
@@ -1,4 +1,5 @@
 use std::any::{Any, TypeId};
+use std::collections::{HashSet, VecDeque};
 use std::fmt;
 use std::hash::Hash;
 use std::str::FromStr;
@@ -20,6 +21,26 @@ pub trait Nerve:
     fn uplink<'a>(&self) -> NerveIter<'a, Self>;
     fn downlink<'a>(&self) -> NerveIter<'a, Self>;
     fn regions<'a>(&self) -> AnatomicalRegionIter<Self::AnatomyType>;
+
+    /// Nerves reachable downstream of this one, following downlinks,
+    /// along with the number of links along the shortest path to each.
+    /// Nerves are listed in order of increasing distance, and this nerve
+    /// is not included.
+    fn reachable_nerves(&self) -> Vec<(Self, usize)> {
+        let mut visited = HashSet::from([*self]);
+        let mut reachable = Vec::new();
+        let mut queue = VecDeque::from([(*self, 0)]);
+
+        while let Some((nerve, distance)) = queue.pop_front() {
+            for next in nerve.downlink() {
+                if visited.insert(next) {
+                    reachable.push((next, distance + 1));
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+        reachable
+    }
 }
 
 pub struct NerveIter<'a, N: Nerve>(pub core::slice::Iter<'a, N>);
@@ -108,6 +108,10 @@ impl<O: Organism> NervousLayer<O> {
 
     fn prepare_connector(&mut self, connector: &mut SimConnector, component: &mut (impl NervousComponent<O> + ?Sized)) -> HashSet<u32> {
         component.nervous_connector().sim_time = connector.sim_time();
+        component.nervous_connector().globals = connector.globals.clone();
+
+        // Drop any retained signals which have fully decayed
+        component.nervous_connector().prune_decayed();
 
         self
             .notify_map
@@ -117,6 +121,7 @@ impl<O: Organism> NervousLayer<O> {
 
     fn process_connector(&mut self, _connector: &mut SimConnector, component: &mut (impl NervousComponent<O> + ?Sized)) {
         let n_connector = component.nervous_connector();
+        n_connector.first_run = false;
 
         // Remove any signals staged for removal
         self.remove_signals(n_connector.pending_unschedules.drain(..));
@@ -206,7 +211,7 @@ impl<O: Organism> SimLayer for NervousLayer<O> {
             }
             let id = connector
                 .time_manager
-                .schedule_event(delay, Box::new(InternalLayerTrigger));
+                .schedule_event(delay, Box::new(InternalLayerTrigger::default()));
             self.internal_trigger_id = Some(id);
         }
     }
@@ -250,6 +255,8 @@ impl<O: Organism, T: NervousComponent<O> + ?Sized> SimComponentProcessor<O, T> f
             &mut component.nervous_connector().registered_transforms,
             initializer.adding_transforms.drain()
         );
+
+        component.nervous_connector().signal_decays = initializer.signal_decays;
     }
 
     fn check_component(&mut self, component: &T) -> bool {
@@ -289,6 +296,9 @@ impl<O: Organism, T: NervousComponent<O> + ?Sized> SimComponentProcessor<O, T> f
     fn process_component(&mut self, connector: &mut SimConnector, component: &mut T) {
         self.process_connector(connector, component);
 
+        // Hold on to any signals which should continue to decay
+        component.nervous_connector().retain_decaying();
+
         // Move the signals back into our signal list in case other components need them
         for (_, mut signals) in component.nervous_connector().incoming.drain() {
             self.delivery_signals.append(&mut signals);
@@ -336,6 +346,9 @@ impl<O: Organism, T: NervousComponent<O>> SimComponentProcessorSync<O, T> for Ne
     fn process_component_sync(&mut self, connector: &mut SimConnector, component: &mut T) {
         self.process_connector(connector, component);
 
+        // Hold on to any signals which should continue to decay
+        component.nervous_connector().retain_decaying();
+
         // Drop the incoming signal references
         component.nervous_connector().incoming.clear();
     }
@@ -347,20 +360,39 @@ impl<O: Organism, T: NervousComponent<O>> SimComponentProcessorSync<O, T> for Ne
 
 
 pub mod test {
-    use std::os::windows::process;
     use std::sync::Mutex;
     use std::thread::scope;
 
     use crate::event::test::TestEventA;
     use crate::sim::component::{SimComponent, SimComponentProcessor};
-    use crate::sim::layer::nervous::component::test::{MovementEvent, PainEvent, TestMovementComponent, TestPainReflexComponent, TestPainkillerComponent};
+    use crate::sim::layer::nervous::component::test::{MovementEvent, PainEvent, TestBroadcastComponent, TestDecayComponent, TestMovementComponent, TestPainReflexComponent, TestPainkillerComponent};
     use crate::sim::layer::nervous::{NervousComponent, NervousLayer};
     use crate::sim::layer::{SimLayer, SimLayerSync};
-    use crate::sim::organism::test::TestOrganism;
+    use crate::sim::organism::test::{TestNerve, TestOrganism};
     use crate::sim::{Organism, SimConnector, SimTime};
     use crate::SimTimeSpan;
 
 
+    /// Logger which captures messages from the test components, so their
+    /// output can be checked without writing to stdout
+    struct CapturingLogger(Mutex<Vec<String>>);
+
+    impl log::Log for CapturingLogger {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            metadata.target().starts_with("mortalsim_core::sim::layer::nervous")
+        }
+
+        fn log(&self, record: &log::Record) {
+            if self.enabled(record.metadata()) {
+                self.0.lock().unwrap().push(record.args().to_string());
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: CapturingLogger = CapturingLogger(Mutex::new(Vec::new()));
+
     fn process_components<O: Organism>(layer: &mut NervousLayer<O>, connector: &mut SimConnector, components: &mut Vec<Box<dyn NervousComponent<O>>>) {
         layer.pre_exec(connector);
 
@@ -409,7 +441,7 @@ pub mod test {
         assert!(layer.pending_signals.len() == 4);
         assert!(layer.pending_signals.values().all(|x| x.get(0).is_some_and(|s| s.message_is::<PainEvent>())));
 
-        println!("{:?}", layer.pending_signals.keys());
+        log::debug!("{:?}", layer.pending_signals.keys());
 
         connector.time_manager.advance_by(SimTimeSpan::from_s(1.0));
         process_components(&mut layer, &mut connector, &mut components);
@@ -439,7 +471,7 @@ pub mod test {
         assert!(layer.pending_signals.values().any(|x| x.get(0).is_some_and(|s| s.message_is::<MovementEvent>())));
 
         // Lets add the painkiller component
-        println!("Gettin' some painkillers!");
+        log::debug!("Gettin' some painkillers!");
         let mut painkiller_component = TestPainkillerComponent::new();
         layer.setup_component(&mut connector, &mut painkiller_component);
         components.push(Box::new(painkiller_component));
@@ -453,6 +485,38 @@ pub mod test {
 
     }
 
+    #[test]
+    fn component_logging() {
+        // Messages can only be captured if no other logger was installed
+        // first, e.g. by another test in the same process
+        if log::set_logger(&LOGGER).is_err() {
+            assert!(std::ptr::addr_eq(log::logger(), &LOGGER), "Another logger is already installed");
+        }
+        log::set_max_level(log::LevelFilter::Debug);
+
+        let mut layer = NervousLayer::<TestOrganism>::new();
+        let mut connector = SimConnector::new();
+
+        let mut components: Vec<Box<dyn NervousComponent<TestOrganism>>> = vec![
+            Box::new(TestPainReflexComponent::new()),
+            Box::new(TestMovementComponent::new()),
+        ];
+
+        for component in components.iter_mut() {
+            layer.setup_component(&mut connector, component.as_mut());
+        }
+
+        for _ in 0..3 {
+            process_components(&mut layer, &mut connector, &mut components);
+            connector.time_manager.advance_by(SimTimeSpan::from_s(1.0));
+        }
+
+        // Reflexes are reported through the logger rather than stdout
+        let messages = LOGGER.0.lock().unwrap();
+        assert!(messages.iter().any(|m| m == "Ow"), "{:?}", messages);
+        assert!(messages.iter().any(|m| m == "My left arm moves casually."), "{:?}", messages);
+    }
+
     #[test]
     fn layer_process_sync() {
         let layer = Mutex::new(NervousLayer::<TestOrganism>::new());
@@ -473,7 +537,7 @@ pub mod test {
         assert!(layer.lock().unwrap().pending_signals.len() == 4);
         assert!(layer.lock().unwrap().pending_signals.values().all(|x| x.get(0).is_some_and(|s| s.message_is::<PainEvent>())));
 
-        println!("{:?}", layer.lock().unwrap().pending_signals.keys());
+        log::debug!("{:?}", layer.lock().unwrap().pending_signals.keys());
 
         connector.lock().unwrap().time_manager.advance_by(SimTimeSpan::from_s(1.0));
         process_components_sync(&layer, &connector, &mut components);
@@ -503,7 +567,7 @@ pub mod test {
         assert!(layer.lock().unwrap().pending_signals.values().any(|x| x.get(0).is_some_and(|s| s.message_is::<MovementEvent>())));
 
         // Lets add the painkiller component
-        println!("Gettin' some painkillers!");
+        log::debug!("Gettin' some painkillers!");
         let mut painkiller_component = TestPainkillerComponent::new();
         layer.lock().unwrap().setup_component(&mut *connector.lock().unwrap(), &mut painkiller_component);
         components.push(Box::new(painkiller_component));
@@ -516,4 +580,84 @@ pub mod test {
         assert!(layer.lock().unwrap().pending_signals.len() == 0);
 
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn layer_decay() {
+        let mut layer = NervousLayer::<TestOrganism>::new();
+        let mut connector = SimConnector::new();
+        let mut component = TestDecayComponent::new();
+        layer.setup_component(&mut connector, &mut component);
+
+        let process = |layer: &mut NervousLayer<TestOrganism>, connector: &mut SimConnector, component: &mut TestDecayComponent| {
+            layer.pre_exec(connector);
+            layer.prepare_component(connector, component);
+            component.run();
+            layer.process_component(connector, component);
+            layer.post_exec(connector);
+        };
+
+        // Command is sent on the first run and arrives at 1s
+        process(&mut layer, &mut connector, &mut component);
+        assert!(component.amplitudes.is_empty());
+
+        for _ in 0..4 {
+            connector.time_manager.advance_by(SimTimeSpan::from_s(0.5));
+            process(&mut layer, &mut connector, &mut component);
+        }
+
+        // Full amplitude on arrival, decaying with a 1s time constant after
+        let expected = [1.0, f64::exp(-0.5), f64::exp(-1.0)];
+        assert_eq!(component.amplitudes.len(), expected.len());
+        for (amplitude, expected) in component.amplitudes.iter().zip(expected) {
+            assert!((amplitude - expected).abs() < 1e-9, "amplitude {} != {}", amplitude, expected);
+        }
+        assert!(component.amplitudes.windows(2).all(|w| w[1] < w[0]));
+
+        // Signal is dropped once it has fully decayed
+        connector.time_manager.advance_by(SimTimeSpan::from_s(10.0));
+        process(&mut layer, &mut connector, &mut component);
+        assert_eq!(component.amplitudes.len(), expected.len());
+    }
+
+    #[test]
+    fn layer_broadcast() {
+        let mut layer = NervousLayer::<TestOrganism>::new();
+        let mut connector = SimConnector::new();
+        let mut component = TestBroadcastComponent::new();
+        layer.setup_component(&mut connector, &mut component);
+
+        let process = |layer: &mut NervousLayer<TestOrganism>, connector: &mut SimConnector, component: &mut TestBroadcastComponent| {
+            layer.pre_exec(connector);
+            layer.prepare_component(connector, component);
+            component.run();
+            layer.process_component(connector, component);
+            layer.post_exec(connector);
+        };
+
+        // Broadcast is sent on the first run, nothing has arrived yet
+        process(&mut layer, &mut connector, &mut component);
+        assert!(component.arrivals.is_empty());
+
+        for _ in 0..3 {
+            connector.time_manager.advance_by(SimTimeSpan::from_s(0.01));
+            process(&mut layer, &mut connector, &mut component);
+        }
+
+        // Nerves one link from the spinal cord receive the command first,
+        // and the peripheral nerves beyond them one link later
+        let mut arrivals = component.arrivals.clone();
+        arrivals.sort_by_key(|(nerve, _)| nerve.to_string());
+        let mut expected: Vec<(TestNerve, SimTime)> = [TestNerve::RightC, TestNerve::RightL, TestNerve::LeftC, TestNerve::LeftL]
+            .into_iter()
+            .map(|n| (n, SimTime::from_s(0.01)))
+            .chain(
+                [TestNerve::RightAxillary, TestNerve::RightFemoral, TestNerve::LeftAxillary, TestNerve::LeftFemoral]
+                    .into_iter()
+                    .map(|n| (n, SimTime::from_s(0.02))),
+            )
+            .collect();
+        expected.sort_by_key(|(nerve, _)| nerve.to_string());
+        assert_eq!(arrivals, expected);
+        assert!(layer.pending_signals.is_empty());
+    }
+}
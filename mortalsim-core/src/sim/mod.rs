@@ -1,28 +1,39 @@
 pub mod organism;
 pub mod component;
+pub mod globals;
+pub mod journal;
 pub mod layer;
 pub mod sim;
 pub mod sim_state;
 pub mod time_manager;
 mod impl_sim;
 
+use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::SystemTime;
 
-pub use sim::Sim;
+pub use sim::{assert_no_orphan_events, Sim};
 pub use sim_state::SimState;
+pub use globals::SimGlobals;
+pub use journal::{JournalEntry, SimJournal};
 pub use time_manager::TimeManager;
-pub use layer::Consumable;
+pub use layer::{Consumable, LayerType};
 
-pub use organism::{Organism, AnatomicalRegion};
+pub use organism::{Organism, AnatomicalRegion, OrganismInfo};
 pub use impl_sim::impl_sim;
 
 pub use crate::{SimTime, SimTimeSpan};
+use crate::event::codec::EncodedEvent;
 use crate::event::Event;
 
 pub struct SimConnector {
     pub state: SimState,
     pub time_manager: TimeManager,
     pub active_events: Vec<Arc<dyn Event>>,
+    /// Layers on which all components should run on the next update
+    pub(crate) forced_layers: HashSet<LayerType>,
+    /// Global parameters shared with every component
+    pub globals: SimGlobals,
 }
 
 impl SimConnector {
@@ -31,10 +42,55 @@ impl SimConnector {
             state: SimState::new(),
             time_manager: TimeManager::new(),
             active_events: Vec::new(),
+            forced_layers: HashSet::new(),
+            globals: SimGlobals::new(),
         }
     }
 
     pub fn sim_time(&self) -> SimTime {
         self.time_manager.get_time()
     }
+
+    pub fn wall_clock(&self) -> Option<SystemTime> {
+        self.time_manager.wall_clock()
+    }
+
+    /// Sets an `Event` on the state which remains until replaced by another
+    /// `Event` of the same type, without any component producing it. The
+    /// `Event` is also emitted on the next advance so that any components
+    /// notified on it will run.
+    ///
+    /// ### Arguments
+    /// * `event` - `Event` to set
+    pub fn set_constant_event<E: Event + Clone>(&mut self, event: E) {
+        self.state.set_state(event.clone());
+        self.time_manager.schedule_event(SimTimeSpan::from_s(0.0), Box::new(event));
+    }
+
+    /// Sets a serialized constant `Event`, as with `set_constant_event`
+    ///
+    /// ### Arguments
+    /// * `event` - serialized `Event` to set
+    ///
+    /// Returns an Err Result if the `Event` can't be deserialized
+    pub fn set_constant_encoded_event(&mut self, event: &EncodedEvent) -> anyhow::Result<()> {
+        self.state.put_state(Arc::from(event.decode()?));
+        self.time_manager.schedule_event(SimTimeSpan::from_s(0.0), event.decode()?);
+        Ok(())
+    }
+
+    /// Sets default `Event`s on the state, for any types which aren't
+    /// already present. Unlike `set_constant_event`, the `Event`s are not
+    /// emitted, so only components reading the state will see them.
+    ///
+    /// ### Arguments
+    /// * `events` - default `Event`s to set
+    pub fn set_default_events(&mut self, events: impl IntoIterator<Item = Box<dyn Event>>) {
+        for event in events {
+            let event: Arc<dyn Event> = Arc::from(event);
+            if self.state.get_dyn_state(&event.as_any().type_id()).is_none() {
+                self.state.put_state(event);
+            }
+        }
+    }
 }
@@ -1,14 +1,187 @@
+use std::collections::{HashSet, VecDeque};
 use std::fmt::Debug;
 
-use super::layer::circulation::BloodVessel;
+use super::layer::circulation::{BloodProperties, BloodVessel};
 use super::layer::nervous::Nerve;
+use crate::components::CO2_SOLUBILITY;
+use crate::event::{
+    AorticBloodPressure, CardiacOutput, CoreBodyTemp, Event, HeartRate, PulmonaryBloodPressure, RespiratoryRate,
+};
+use crate::substance::{Substance, SubstanceConcentration};
+use crate::units::base::{Mass, Temperature};
+use crate::units::geometry::{Area, Volume};
+use crate::units::mechanical::{Frequency, Pressure};
 
-pub trait AnatomicalRegion: Debug + Copy + PartialEq + Eq + Send + Sync {}
+pub trait AnatomicalRegion: Debug + Copy + PartialEq + Eq + Send + Sync {
+    /// Region which this region is a part of, if any
+    fn parent(&self) -> Option<Self> {
+        None
+    }
+
+    /// Regions which are directly a part of this region
+    fn children(&self) -> Vec<Self> {
+        Vec::new()
+    }
+}
 
 pub trait Organism: Debug + Send + Clone + Copy + 'static {
     type VesselType: BloodVessel;
-    type NerveType: Nerve;
+    type NerveType: Nerve<AnatomyType = Self::AnatomyType>;
     type AnatomyType: AnatomicalRegion;
+
+    /// Retrieves the region which the given region is a part of in the
+    /// anatomical hierarchy, e.g. the arm for a hand. Returns `None` for
+    /// the root of the hierarchy.
+    ///
+    /// ### Arguments
+    /// * `region` - Region to retrieve the parent of
+    fn region_parent(region: Self::AnatomyType) -> Option<Self::AnatomyType> {
+        region.parent()
+    }
+
+    /// Retrieves the regions which are directly a part of the given region
+    /// in the anatomical hierarchy
+    ///
+    /// ### Arguments
+    /// * `region` - Region to retrieve the children of
+    fn region_children(region: Self::AnatomyType) -> Vec<Self::AnatomyType> {
+        region.children()
+    }
+
+    /// Retrieves the nerves which innervate the given region, or any of
+    /// the regions which are a part of it, e.g. to wire pain or motor
+    /// signals for the region. Nerves are found by following uplinks
+    /// from each terminal nerve, and are listed in that order.
+    ///
+    /// ### Arguments
+    /// * `region` - Region to retrieve the nerves for
+    fn nerves_for_region(region: Self::AnatomyType) -> Vec<Self::NerveType> {
+        let mut regions = vec![region];
+        let mut pending = Self::region_children(region);
+        while let Some(child) = pending.pop() {
+            pending.extend(Self::region_children(child));
+            regions.push(child);
+        }
+
+        let mut visited = HashSet::new();
+        let mut queue: VecDeque<Self::NerveType> = Self::NerveType::terminal_nerves().collect();
+        let mut nerves = Vec::new();
+        while let Some(nerve) = queue.pop_front() {
+            if !visited.insert(nerve) {
+                continue;
+            }
+            if nerve.regions().any(|r| regions.contains(&r)) {
+                nerves.push(nerve);
+            }
+            queue.extend(nerve.uplink());
+        }
+        nerves
+    }
+
+    /// Total volume of blood in the organism. Defaults to that of a
+    /// 70 kg adult human.
+    fn total_blood_volume() -> Volume<f64> {
+        Volume::from_L(5.0)
+    }
+
+    /// Body mass of the organism. Defaults to that of an adult human.
+    fn body_mass() -> Mass<f64> {
+        Mass::from_kg(70.0)
+    }
+
+    /// Body surface area of the organism. Defaults to that of a 70 kg
+    /// adult human.
+    fn body_surface_area() -> Area<f64> {
+        Area::from_m2(1.8)
+    }
+
+    /// Resting cardiac output per unit of body surface area, in
+    /// L/min/m^2. Defaults to that of an adult human.
+    fn cardiac_index() -> f64 {
+        3.0
+    }
+
+    /// Whole blood properties, such as hematocrit and hemoglobin, for
+    /// any vessels which don't have their own set. Defaults to those of
+    /// a healthy adult human.
+    fn blood_properties() -> BloodProperties {
+        BloodProperties::default()
+    }
+
+    /// Vital sign `Event`s set on the state by
+    /// `Sim::initialize_baseline_vitals`. Defaults to the resting vitals
+    /// of a healthy adult human.
+    fn baseline_vitals() -> Vec<Box<dyn Event>> {
+        vec![
+            Box::new(HeartRate(Frequency::from_Hz(70.0 / 60.0))),
+            Box::new(RespiratoryRate(Frequency::from_Hz(12.0 / 60.0))),
+            Box::new(AorticBloodPressure {
+                systolic: Pressure::from_mmHg(120.0),
+                diastolic: Pressure::from_mmHg(80.0),
+            }),
+            Box::new(PulmonaryBloodPressure {
+                systolic: Pressure::from_mmHg(25.0),
+                diastolic: Pressure::from_mmHg(10.0),
+            }),
+            Box::new(CoreBodyTemp(Temperature::from_celsius(37.0))),
+            Box::new(CardiacOutput(Self::cardiac_index() * Self::body_surface_area().m2)),
+        ]
+    }
+
+    /// Normal range of a Substance's blood concentration, which alarms
+    /// and reports can use to flag abnormal values. Defaults to adult
+    /// human plasma ranges for common labs, with CO2 as dissolved CO2.
+    ///
+    /// ### Arguments
+    /// * `substance` - Substance to retrieve the range for
+    ///
+    /// Returns the lower and upper limits, or `None` if the Substance
+    /// doesn't have a reference range
+    fn reference_range(substance: Substance) -> Option<(SubstanceConcentration, SubstanceConcentration)> {
+        let (low, high) = match substance {
+            // Fasting glucose
+            Substance::GLC => (3.9, 5.6),
+            Substance::Na => (135.0, 145.0),
+            Substance::K => (3.5, 5.0),
+            Substance::Cl => (98.0, 106.0),
+            Substance::Ca => (2.1, 2.6),
+            Substance::LAC => (0.5, 2.2),
+            // PaCO2 of 35 to 45 mmHg
+            Substance::CO2 => (35.0 * CO2_SOLUBILITY, 45.0 * CO2_SOLUBILITY),
+            // pH of 7.45 to 7.35
+            Substance::H => (10_f64.powf(-7.45) * 1000.0, 10_f64.powf(-7.35) * 1000.0),
+            _ => return None,
+        };
+        Some((SubstanceConcentration::from_mM(low), SubstanceConcentration::from_mM(high)))
+    }
+}
+
+/// Organism-level constants which components can use to configure
+/// themselves at construction, rather than assuming a particular organism
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrganismInfo {
+    /// Number of arteries in the vasculature
+    pub artery_count: usize,
+    /// Number of veins in the vasculature
+    pub vein_count: usize,
+    /// Total number of blood vessels in the vasculature
+    pub vessel_count: usize,
+    /// Number of terminal nerves in the nervous system
+    pub terminal_nerve_count: usize,
+}
+
+impl OrganismInfo {
+    /// Collects the constants for the given `Organism`
+    pub fn of<O: Organism>() -> Self {
+        let artery_count = O::VesselType::arteries().len();
+        let vein_count = O::VesselType::veins().len();
+        Self {
+            artery_count,
+            vein_count,
+            vessel_count: artery_count + vein_count,
+            terminal_nerve_count: O::NerveType::terminal_nerves().len(),
+        }
+    }
 }
 
 pub mod test;
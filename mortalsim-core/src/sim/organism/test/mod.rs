@@ -1,25 +1,38 @@
 mod test_anatomy;
 mod test_circulation;
+mod test_fistula;
 mod test_nervous;
 
 pub use test_circulation::TestBloodVessel;
 pub use test_anatomy::TestAnatomicalRegion;
 pub use test_nervous::TestNerve;
+pub use test_fistula::{FistulaOrganism, FistulaTestVessel, FistulaVessel};
 
 use std::any::Any;
 use std::cell::Cell;
 use std::collections::HashSet;
 use std::path::Component;
-use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, SystemTime};
 
-use crate::sim::layer::circulation::component::test::TestCircComponentA;
+use crate::sim::layer::circulation::component::test::{TestBolusComponent, TestCircComponentA};
 use crate::sim::layer::digestion::component::test::TestDigestionComponent;
 use crate::sim::layer::nervous::component::test::{TestMovementComponent, TestPainReflexComponent};
 use crate::units::base::Distance;
+use crate::units::geometry::Volume;
+use crate::sim::layer::digestion::{DigestionDirection, EliminateEvent};
+use crate::sim::Consumable;
 
 use crate::event::test::TestEventA;
+use crate::event::Event;
+use crate::substance::{Substance, SubstanceConcentration};
 use crate::sim::layer::core::component::test::{TestComponentA, TestComponentB};
-use crate::sim::{Sim, SimTime};
+use crate::sim::component::{ComponentRegistry, SimComponent};
+use crate::sim::layer::circulation::{CirculationComponent, CirculationConnector, CirculationInitializer};
+use crate::sim::layer::digestion::{DigestionComponent, DigestionConnector};
+use crate::sim::layer::nervous::{NervousComponent, NervousConnector, NervousInitializer};
+use crate::sim::layer::core::{CoreComponent, CoreConnector, CoreInitializer};
+use crate::sim::{assert_no_orphan_events, Sim, SimTime};
 use crate::{secs, SimTimeSpan};
 
 use crate::sim::impl_sim;
@@ -43,6 +56,519 @@ fn test_organism() {
     // not parallel
     test_default();
     test_layers_init_run();
+    test_wall_clock();
+    test_advance_by_stepping();
+    test_first_run();
+    test_pause();
+    test_time_marks();
+    test_auto_step();
+    test_nerves_for_region();
+    test_drain_remaining();
+    test_drain_remaining_keeps_timers();
+    test_advance_until_event();
+    test_globals();
+    test_advance_to_wall_clock();
+    test_reference_range();
+}
+
+/// Whether glucose is outside the organism's reference range
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct GlucoseAlarm(bool);
+
+impl Event for GlucoseAlarm {
+    fn transient(&self) -> bool {
+        false
+    }
+}
+
+/// Sample alarm which checks a vessel's glucose each second, flagging
+/// values outside the reference range of the organism rather than
+/// hardcoding normal values
+struct GlucoseAlarmComponent<O: Organism> {
+    vessel: O::VesselType,
+    core_connector: CoreConnector<O>,
+    circ_connector: CirculationConnector<O>,
+}
+
+impl<O: Organism> CoreComponent<O> for GlucoseAlarmComponent<O> {
+    fn core_init(&mut self, _initializer: &mut CoreInitializer<O>) {}
+
+    fn core_connector(&mut self) -> &mut CoreConnector<O> {
+        &mut self.core_connector
+    }
+}
+
+impl<O: Organism> CirculationComponent<O> for GlucoseAlarmComponent<O> {
+    fn circulation_init(&mut self, initializer: &mut CirculationInitializer<O>) {
+        initializer.attach_vessel(self.vessel);
+    }
+
+    fn circulation_connector(&mut self) -> &mut CirculationConnector<O> {
+        &mut self.circ_connector
+    }
+}
+
+impl<O: Organism> SimComponent<O> for GlucoseAlarmComponent<O> {
+    fn id(&self) -> &'static str {
+        "GlucoseAlarmComponent"
+    }
+
+    fn attach(self, registry: &mut ComponentRegistry<O>) {
+        registry.add_core_circulation_component(self)
+    }
+
+    fn run(&mut self) {
+        self.core_connector.set_timer(SimTimeSpan::from_s(1.0), 0);
+        let Some((low, high)) = O::reference_range(Substance::GLC) else {
+            return;
+        };
+        let glc = self.circ_connector.blood_store(&self.vessel).unwrap().concentration_of(&Substance::GLC);
+        self.core_connector.schedule_event(SimTimeSpan::from_s(0.0), GlucoseAlarm(glc < low || glc > high));
+    }
+}
+
+fn test_reference_range() {
+    // The test organism uses the human defaults
+    let (low, high) = TestOrganism::reference_range(Substance::GLC).unwrap();
+    assert!(low.to_mM() > 3.0 && high.to_mM() < 7.0);
+
+    for (glucose, flagged) in [(5.0, false), (12.0, true)] {
+        let mut sim = TestSim::new();
+        sim.add_component(TestBolusComponent::new(
+            TestBloodVessel::Aorta,
+            Substance::GLC,
+            SubstanceConcentration::from_mM(glucose),
+            SimTimeSpan::from_s(1.0),
+        )).unwrap();
+        sim.add_component(GlucoseAlarmComponent {
+            vessel: TestBloodVessel::Aorta,
+            core_connector: CoreConnector::new(),
+            circ_connector: CirculationConnector::new(),
+        }).unwrap();
+
+        for _ in 0..5 {
+            sim.advance_by(SimTimeSpan::from_s(1.0));
+        }
+        assert_eq!(sim.state().get_state::<GlucoseAlarm>(), Some(&GlucoseAlarm(flagged)), "{} mM", glucose);
+    }
+}
+
+fn test_drain_remaining() {
+    let mut sim = TestSim::new();
+    sim.schedule_event(SimTimeSpan::from_s(1.0), Box::new(TestEventA::new(Distance::from_m(1.0))));
+    sim.advance_by(SimTimeSpan::from_s(2.0));
+
+    // Everything scheduled has fired
+    assert_no_orphan_events(&mut sim);
+
+    // A far future event is never reached by the run
+    sim.schedule_event(SimTimeSpan::from_s(1000.0), Box::new(TestEventA::new(Distance::from_m(2.0))));
+    sim.advance_by(SimTimeSpan::from_s(2.0));
+    let orphaned = sim.drain_remaining();
+    assert_eq!(orphaned.len(), 1);
+    assert_eq!(orphaned[0].0, secs!(1002.0));
+    assert!(orphaned[0].1.is::<TestEventA>());
+
+    // Draining removes the events, so they're no longer emitted
+    assert!(sim.drain_remaining().is_empty());
+    sim.advance_by(SimTimeSpan::from_s(2000.0));
+    assert!(!sim.drain_active().any(|e| e.is::<TestEventA>()));
+
+    sim.schedule_event(SimTimeSpan::from_s(1000.0), Box::new(TestEventA::new(Distance::from_m(3.0))));
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| assert_no_orphan_events(&mut sim)));
+    assert!(result.is_err());
+}
+
+fn test_drain_remaining_keeps_timers() {
+    let mut sim = TestSim::new();
+    sim.add_component(InspiredO2Component { connector: CoreConnector::new() }).unwrap();
+    sim.advance_by(SimTimeSpan::from_s(1.5));
+
+    // The component's next timer isn't an orphan
+    assert_no_orphan_events(&mut sim);
+
+    // and still fires, so the component keeps running
+    sim.drain_active().for_each(drop);
+    sim.advance_by(SimTimeSpan::from_s(1.0));
+    assert!(sim.drain_active().any(|e| e.is::<InspiredO2>()));
+    sim.advance_by(SimTimeSpan::from_s(1.0));
+    assert!(sim.drain_active().any(|e| e.is::<InspiredO2>()));
+}
+
+fn test_advance_until_event() {
+    let mut sim = TestSim::new();
+    sim.schedule_event(SimTimeSpan::from_s(5.0), Box::new(TestEventA::new(Distance::from_m(1.0))));
+    sim.schedule_event(
+        SimTimeSpan::from_s(30.0),
+        Box::new(EliminateEvent::new(Consumable::new(Volume::from_mL(10.0)), DigestionDirection::FORWARD)),
+    );
+
+    // Not reached within the limit
+    assert_eq!(sim.advance_until_event::<EliminateEvent>(SimTimeSpan::from_s(10.0)), None);
+    assert_eq!(sim.time(), secs!(10.0));
+
+    // Other events along the way don't stop the advance
+    assert_eq!(sim.advance_until_event::<EliminateEvent>(SimTimeSpan::from_s(60.0)), Some(secs!(30.0)));
+    assert_eq!(sim.time(), secs!(30.0));
+    assert!(sim.drain_active().any(|e| e.is::<EliminateEvent>()));
+
+    // Nothing else is scheduled, so the full span elapses
+    assert_eq!(sim.advance_until_event::<EliminateEvent>(SimTimeSpan::from_s(60.0)), None);
+    assert_eq!(sim.time(), secs!(90.0));
+}
+
+/// Inspired partial pressure of O2 (mmHg)
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct InspiredO2(f64);
+
+impl Event for InspiredO2 {
+    fn transient(&self) -> bool {
+        false
+    }
+}
+
+/// Reports the inspired PO2 each second, from the barometric pressure
+/// at the altitude given by the "altitude" global (m), or sea level
+struct InspiredO2Component {
+    connector: CoreConnector<TestOrganism>,
+}
+
+impl CoreComponent<TestOrganism> for InspiredO2Component {
+    fn core_init(&mut self, initializer: &mut CoreInitializer<TestOrganism>) {
+        initializer.produces::<InspiredO2>();
+    }
+
+    fn core_connector(&mut self) -> &mut CoreConnector<TestOrganism> {
+        &mut self.connector
+    }
+}
+
+impl SimComponent<TestOrganism> for InspiredO2Component {
+    fn id(&self) -> &'static str {
+        "InspiredO2Component"
+    }
+
+    fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+        registry.add_core_component(self)
+    }
+
+    fn run(&mut self) {
+        self.connector.set_timer(SimTimeSpan::from_s(1.0), 0);
+        let altitude = self.connector.get_global::<f64>("altitude").unwrap_or(0.0);
+        let barometric = 760.0 * (-altitude / 8400.0).exp();
+        self.connector.schedule_event(SimTimeSpan::from_s(0.0), InspiredO2((barometric - 47.0) * 0.21));
+    }
+}
+
+fn test_globals() {
+    let mut sim = TestSim::new();
+    sim.add_component(InspiredO2Component { connector: CoreConnector::new() }).unwrap();
+    let inspired_o2 = |sim: &TestSim| sim.state().get_state::<InspiredO2>().unwrap().0;
+
+    sim.advance_by(SimTimeSpan::from_s(1.0));
+    assert!((inspired_o2(&sim) - 149.7).abs() < 0.1, "{}", inspired_o2(&sim));
+
+    // Components pick up the global on their next run
+    sim.set_global("altitude", 3000.0_f64);
+    assert_eq!(sim.get_global::<f64>("altitude"), Some(3000.0));
+    sim.advance_by(SimTimeSpan::from_s(1.0));
+    assert!((inspired_o2(&sim) - 101.8).abs() < 0.1, "{}", inspired_o2(&sim));
+
+    // Globals are typed
+    assert_eq!(sim.get_global::<u32>("altitude"), None);
+    assert_eq!(sim.get_global::<f64>("temperature"), None);
+
+    // and readable through the connector of every layer
+    let altitudes = Arc::new(Mutex::new(Vec::new()));
+    sim.add_component(GlobalsReaderComponent {
+        altitudes: altitudes.clone(),
+        circ_connector: CirculationConnector::new(),
+        digestion_connector: DigestionConnector::new(),
+        nervous_connector: NervousConnector::new(),
+    }).unwrap();
+    sim.set_global("altitude", 1500.0_f64);
+    sim.force_layer_run(None);
+    sim.advance_by(SimTimeSpan::from_s(1.0));
+    assert_eq!(altitudes.lock().unwrap().last(), Some(&[Some(1500.0); 3]));
+}
+
+/// Records the "altitude" global as read through each of its layer connectors
+struct GlobalsReaderComponent {
+    altitudes: Arc<Mutex<Vec<[Option<f64>; 3]>>>,
+    circ_connector: CirculationConnector<TestOrganism>,
+    digestion_connector: DigestionConnector<TestOrganism>,
+    nervous_connector: NervousConnector<TestOrganism>,
+}
+
+impl CirculationComponent<TestOrganism> for GlobalsReaderComponent {
+    fn circulation_init(&mut self, _initializer: &mut CirculationInitializer<TestOrganism>) {}
+
+    fn circulation_connector(&mut self) -> &mut CirculationConnector<TestOrganism> {
+        &mut self.circ_connector
+    }
+}
+
+impl DigestionComponent<TestOrganism> for GlobalsReaderComponent {
+    fn digestion_connector(&mut self) -> &mut DigestionConnector<TestOrganism> {
+        &mut self.digestion_connector
+    }
+}
+
+impl NervousComponent<TestOrganism> for GlobalsReaderComponent {
+    fn nervous_init(&mut self, _initializer: &mut NervousInitializer<TestOrganism>) {}
+
+    fn nervous_connector(&mut self) -> &mut NervousConnector<TestOrganism> {
+        &mut self.nervous_connector
+    }
+}
+
+impl SimComponent<TestOrganism> for GlobalsReaderComponent {
+    fn id(&self) -> &'static str {
+        "GlobalsReaderComponent"
+    }
+
+    fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+        registry.add_circulation_digestion_nervous_component(self)
+    }
+
+    fn run(&mut self) {
+        self.altitudes.lock().unwrap().push([
+            self.circ_connector.get_global::<f64>("altitude"),
+            self.digestion_connector.get_global::<f64>("altitude"),
+            self.nervous_connector.get_global::<f64>("altitude"),
+        ]);
+    }
+}
+
+fn test_advance_to_wall_clock() {
+    let mut sim = TestSim::new();
+    let wall = Arc::new(Mutex::new(Duration::from_secs(100)));
+    let clock = wall.clone();
+    sim.set_real_time_clock(Box::new(move || *clock.lock().unwrap()));
+    let wait = |secs: f64| *wall.lock().unwrap() += Duration::from_secs_f64(secs);
+
+    // The first call only starts tracking
+    assert_eq!(sim.advance_to_wall_clock(1.0).unwrap(), SimTimeSpan::from_s(0.0));
+    assert_eq!(sim.time(), secs!(0.0));
+
+    // Real time
+    wait(2.5);
+    assert_eq!(sim.advance_to_wall_clock(1.0).unwrap(), SimTimeSpan::from_s(2.5));
+    assert_eq!(sim.time(), secs!(2.5));
+
+    // Faster than real time, including a scheduled event along the way
+    sim.schedule_event(SimTimeSpan::from_s(3.0), Box::new(TestEventA::new(Distance::from_m(1.0))));
+    wait(0.5);
+    sim.advance_to_wall_clock(10.0).unwrap();
+    assert!((sim.time().to_s() - 7.5).abs() < 1e-9, "{}", sim.time());
+    assert!(sim.drain_remaining().is_empty());
+
+    // Wall time passing while paused is skipped
+    sim.pause();
+    wait(60.0);
+    assert_eq!(sim.advance_to_wall_clock(1.0).unwrap(), SimTimeSpan::from_s(0.0));
+    sim.resume();
+    wait(0.25);
+    sim.advance_to_wall_clock(4.0).unwrap();
+    assert!((sim.time().to_s() - 8.5).abs() < 1e-9, "{}", sim.time());
+
+    assert!(sim.advance_to_wall_clock(-1.0).is_err());
+    assert!(sim.advance_to_wall_clock(f64::NAN).is_err());
+}
+
+fn test_nerves_for_region() {
+    assert_eq!(TestOrganism::nerves_for_region(TestAnatomicalRegion::RightArm), vec![TestNerve::RightAxillary]);
+    assert_eq!(TestOrganism::nerves_for_region(TestAnatomicalRegion::LeftLeg), vec![TestNerve::LeftFemoral]);
+
+    // The spinal cord and its branches innervate the torso
+    let torso: HashSet<TestNerve> = TestOrganism::nerves_for_region(TestAnatomicalRegion::Torso).into_iter().collect();
+    assert!(torso.contains(&TestNerve::RightC) && torso.contains(&TestNerve::SpinalCord), "{:?}", torso);
+    assert!(!torso.contains(&TestNerve::RightAxillary));
+}
+
+fn test_pause() {
+    let mut sim = TestSim::new();
+    sim.schedule_event(SimTimeSpan::from_s(1.0), Box::new(TestEventA::new(Distance::from_m(1.0))));
+    sim.advance_by(SimTimeSpan::from_s(0.5));
+
+    sim.pause();
+    assert!(sim.is_paused());
+    sim.advance_by(SimTimeSpan::from_s(1.0));
+    sim.advance();
+    sim.advance_by_stepping(SimTimeSpan::from_s(1.0), &mut |_, _| panic!("Advanced while paused"));
+    assert!(sim.try_advance_by(SimTimeSpan::from_s(1.0)).is_err());
+    assert_eq!(sim.time(), secs!(0.5));
+    assert_eq!(sim.drain_active().count(), 0);
+
+    // Components can still be changed while paused
+    sim.add_component(TestComponentA::new()).unwrap();
+    assert!(sim.has_component("TestComponentA"));
+
+    // The queued event is emitted once time resumes
+    sim.resume();
+    assert!(!sim.is_paused());
+    sim.try_advance_by(SimTimeSpan::from_s(1.0)).unwrap();
+    assert_eq!(sim.time(), secs!(1.5));
+    assert!(sim.drain_active().any(|e| e.is::<TestEventA>()));
+}
+
+fn test_time_marks() {
+    let mut sim = TestSim::new();
+    let marks = Arc::new(Mutex::new(Vec::new()));
+    let marks_ref = marks.clone();
+    sim.on_time_mark(SimTimeSpan::from_s(1.0), Box::new(move |time| marks_ref.lock().unwrap().push(time))).unwrap();
+    assert!(sim.on_time_mark(SimTimeSpan::from_s(0.0), Box::new(|_| {})).is_err());
+
+    // Crossing several marks at once fires each of them, and landing
+    // exactly on a mark fires it only once
+    for step in [0.3, 0.4, 2.5, 0.8, 1.0, 0.05] {
+        sim.advance_by(SimTimeSpan::from_s(step));
+    }
+    assert_eq!(*marks.lock().unwrap(), vec![secs!(1.0), secs!(2.0), secs!(3.0), secs!(4.0), secs!(5.0)]);
+}
+
+fn test_auto_step() {
+    let mut sim = TestSim::new();
+    let steps = Arc::new(Mutex::new(0));
+    let steps_ref = steps.clone();
+    let metric = move |state: &crate::sim::SimState| {
+        *steps_ref.lock().unwrap() += 1;
+        state.get_state::<TestEventA>().map_or(0.0, |evt| evt.len.to_m())
+    };
+    assert!(sim.set_auto_step(SimTimeSpan::from_s(0.0), SimTimeSpan::from_s(2.0), Box::new(|_| 0.0)).is_err());
+    assert!(sim.set_auto_step(SimTimeSpan::from_s(1.0), SimTimeSpan::from_s(0.5), Box::new(|_| 0.0)).is_err());
+    sim.set_auto_step(SimTimeSpan::from_s(0.1), SimTimeSpan::from_s(2.0), Box::new(metric)).unwrap();
+
+    // Quiescent until 10.5 s, then rising by 10% every 0.1 s for 10 s
+    sim.schedule_event(SimTimeSpan::from_s(0.0), Box::new(TestEventA::new(Distance::from_m(1.0))));
+    for i in 0..100 {
+        let len = Distance::from_m(1.1_f64.powi(i + 1));
+        sim.schedule_event(SimTimeSpan::from_s(10.5 + 0.1 * i as f64), Box::new(TestEventA::new(len)));
+    }
+
+    sim.advance_by(SimTimeSpan::from_s(10.0));
+    assert_eq!(sim.auto_step(), Some(SimTimeSpan::from_s(2.0)));
+    assert_eq!(*steps.lock().unwrap(), 5);
+
+    // Steps shrink to the minimum during the rapid change
+    sim.advance_by(SimTimeSpan::from_s(5.0));
+    assert_eq!(sim.auto_step(), Some(SimTimeSpan::from_s(0.1)));
+    *steps.lock().unwrap() = 0;
+    sim.advance_by(SimTimeSpan::from_s(1.0));
+    assert!(*steps.lock().unwrap() >= 10);
+
+    // And grow back once it's quiescent again
+    sim.advance_by(SimTimeSpan::from_s(20.0));
+    assert_eq!(sim.auto_step(), Some(SimTimeSpan::from_s(2.0)));
+    *steps.lock().unwrap() = 0;
+    sim.advance_by(SimTimeSpan::from_s(2.0));
+    assert_eq!(*steps.lock().unwrap(), 1);
+    assert!(sim.time() > secs!(37.9) && sim.time() < secs!(38.1));
+
+    sim.clear_auto_step();
+    assert_eq!(sim.auto_step(), None);
+}
+
+/// Records whether each of its connectors reports a first run
+struct FirstRunComponent {
+    core_connector: CoreConnector<TestOrganism>,
+    circ_connector: CirculationConnector<TestOrganism>,
+    runs: Arc<Mutex<Vec<(bool, bool)>>>,
+}
+
+impl CoreComponent<TestOrganism> for FirstRunComponent {
+    fn core_init(&mut self, _initializer: &mut CoreInitializer<TestOrganism>) {}
+
+    fn core_connector(&mut self) -> &mut CoreConnector<TestOrganism> {
+        &mut self.core_connector
+    }
+}
+
+impl CirculationComponent<TestOrganism> for FirstRunComponent {
+    fn circulation_init(&mut self, initializer: &mut CirculationInitializer<TestOrganism>) {
+        initializer.attach_vessel(TestBloodVessel::Aorta);
+    }
+
+    fn circulation_connector(&mut self) -> &mut CirculationConnector<TestOrganism> {
+        &mut self.circ_connector
+    }
+}
+
+impl SimComponent<TestOrganism> for FirstRunComponent {
+    fn id(&self) -> &'static str {
+        "FirstRunComponent"
+    }
+
+    fn attach(self, registry: &mut ComponentRegistry<TestOrganism>) {
+        registry.add_core_circulation_component(self)
+    }
+
+    fn run(&mut self) {
+        self.runs
+            .lock()
+            .unwrap()
+            .push((self.core_connector.is_first_run(), self.circ_connector.is_first_run()));
+    }
+}
+
+fn test_first_run() {
+    for mut sim in [TestSim::new(), TestSim::new_threaded()] {
+        let runs = Arc::new(Mutex::new(Vec::new()));
+        sim.add_component(FirstRunComponent {
+            core_connector: CoreConnector::new(),
+            circ_connector: CirculationConnector::new(),
+            runs: runs.clone(),
+        }).unwrap();
+
+        for _ in 0..4 {
+            sim.advance_by(SimTimeSpan::from_s(1.0));
+            sim.force_layer_run(None);
+        }
+        sim.advance_by(SimTimeSpan::from_s(1.0));
+
+        let runs = runs.lock().unwrap();
+        assert!(runs.len() >= 4, "{:?}", runs);
+        assert_eq!(runs[0], (true, true));
+        assert!(runs[1..].iter().all(|r| *r == (false, false)), "{:?}", runs);
+    }
+}
+
+fn test_advance_by_stepping() {
+    let mut sim = TestSim::new();
+
+    for t in [1.0, 2.5, 2.5, 4.0, 15.0] {
+        sim.schedule_event(SimTimeSpan::from_s(t), Box::new(TestEventA::new(Distance::from_m(1.0))));
+    }
+
+    let mut times = Vec::new();
+    sim.advance_by_stepping(SimTimeSpan::from_s(10.0), &mut |evt_time, state| {
+        assert_eq!(state.time(), evt_time);
+        times.push(evt_time);
+    });
+
+    // One call per distinct event time within the span
+    assert_eq!(times, vec![secs!(1.0), secs!(2.5), secs!(4.0)]);
+    assert_eq!(sim.time(), secs!(10.0));
+
+    // The remaining event is picked up by the next span
+    let mut count = 0;
+    sim.advance_by_stepping(SimTimeSpan::from_s(10.0), &mut |_, _| count += 1);
+    assert_eq!(count, 1);
+    assert_eq!(sim.time(), secs!(20.0));
+}
+
+fn test_wall_clock() {
+    let mut sim = TestSim::new();
+
+    // No epoch by default
+    assert!(sim.wall_clock().is_none());
+
+    let epoch = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    sim.set_epoch(epoch);
+    assert_eq!(sim.wall_clock(), Some(epoch));
+
+    sim.advance_by(SimTimeSpan::from_s(3600.0));
+    assert_eq!(sim.wall_clock(), Some(epoch + Duration::from_secs(3600)));
 }
 
 fn test_default() {
@@ -59,6 +585,7 @@ fn test_default() {
     assert!(sim.has_component("TestComponentA"));
     assert!(sim.has_component("TestComponentB"));
     assert!(!sim.has_component("not there"));
+    assert!(sim.circulation_substance_owners().is_empty());
     assert!(sim.remove_component("test").is_err());
     sim.schedule_event(SimTimeSpan::from_s(0.0), Box::new(TestEventA::new(Distance::from_m(1.0))));
     assert!(sim.unschedule_event(&1234).is_err());
@@ -7,6 +7,7 @@ use std::collections::HashSet;
 use std::sync::OnceLock;
 use crate::sim::layer::circulation::{BloodVesselType, BloodVessel, VesselIter};
 use crate::sim::layer::AnatomicalRegionIter;
+use crate::units::geometry::Volume;
 use super::TestAnatomicalRegion;
 
 #[derive(Debug, Display, Hash, Clone, Copy, PartialEq, Eq, EnumString, IntoStaticStr)]
@@ -510,4 +511,24 @@ impl BloodVessel for TestBloodVessel {
             }).iter())
         }
     }
+    fn volume(&self) -> Option<Volume<f64>> {
+        match self {
+            TestBloodVessel::Aorta => Some(Volume::from_mL(100.0)),
+            TestBloodVessel::RightCarotidArtery => Some(Volume::from_mL(10.0)),
+            TestBloodVessel::LeftCarotidArtery => Some(Volume::from_mL(10.0)),
+            TestBloodVessel::RightAxillaryArtery => Some(Volume::from_mL(15.0)),
+            TestBloodVessel::LeftAxillaryArtery => Some(Volume::from_mL(15.0)),
+            TestBloodVessel::AbdominalAorta => Some(Volume::from_mL(50.0)),
+            TestBloodVessel::RightFemoralArtery => Some(Volume::from_mL(20.0)),
+            TestBloodVessel::LeftFemoralArtery => Some(Volume::from_mL(20.0)),
+            TestBloodVessel::VenaCava => Some(Volume::from_mL(150.0)),
+            TestBloodVessel::RightAxillaryVein => Some(Volume::from_mL(30.0)),
+            TestBloodVessel::LeftAxillaryVein => Some(Volume::from_mL(30.0)),
+            TestBloodVessel::RightJugularVein => Some(Volume::from_mL(20.0)),
+            TestBloodVessel::LeftJugularVein => Some(Volume::from_mL(20.0)),
+            TestBloodVessel::InferiorVenaCava => Some(Volume::from_mL(150.0)),
+            TestBloodVessel::RightFemoralVein => Some(Volume::from_mL(40.0)),
+            TestBloodVessel::LeftFemoralVein => Some(Volume::from_mL(40.0)),
+        }
+    }
 }
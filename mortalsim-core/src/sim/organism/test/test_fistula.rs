@@ -0,0 +1,39 @@
+use strum::VariantArray;
+
+use crate::sim::layer::circulation::{BloodVesselType, CompositeVessel, VesselExtension};
+use crate::sim::organism::Organism;
+
+use super::{TestAnatomicalRegion, TestBloodVessel, TestNerve};
+
+/// Arteriovenous fistula added on top of the test vasculature
+#[derive(Debug, Hash, Clone, Copy, PartialEq, Eq, IntoStaticStr, VariantArray)]
+pub enum FistulaVessel {
+    LeftArteriovenousFistula,
+}
+
+pub type FistulaTestVessel = CompositeVessel<TestBloodVessel, FistulaVessel>;
+
+impl VesselExtension for FistulaVessel {
+    type Base = TestBloodVessel;
+    fn vessel_type(&self) -> BloodVesselType {
+        BloodVesselType::Artery
+    }
+    fn upstream(&self) -> Vec<FistulaTestVessel> {
+        vec![CompositeVessel::Base(TestBloodVessel::LeftAxillaryArtery)]
+    }
+    fn downstream(&self) -> Vec<FistulaTestVessel> {
+        vec![CompositeVessel::Base(TestBloodVessel::LeftAxillaryVein)]
+    }
+    fn regions(&self) -> Vec<TestAnatomicalRegion> {
+        vec![TestAnatomicalRegion::LeftArm]
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FistulaOrganism;
+
+impl Organism for FistulaOrganism {
+    type VesselType = FistulaTestVessel;
+    type NerveType = TestNerve;
+    type AnatomyType = TestAnatomicalRegion;
+}
@@ -1,17 +1,58 @@
-use std::collections::HashSet;
+use std::any::{Any, TypeId};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 use std::vec::Drain;
 
 use crate::event::{Event, EventDrainIterator};
+use crate::substance::Substance;
+use crate::units::base::Amount;
 use crate::{IdType, SimTimeSpan};
 
 use super::component::registry::ComponentRegistry;
-use super::{Organism, SimTime};
+use super::layer::core::{EventConflict, UnsatisfiedDependency};
+use super::journal::{JournalEntry, SimJournal};
+use super::layer::LayerType;
+use super::{Organism, SimState, SimTime};
 
 pub trait Sim {
     /// Returns the current simulation time
     fn time(&self) -> SimTime;
 
+    /// Anchors simulation time zero to a calendar time, so that
+    /// simulation time can be mapped onto real timestamps
+    ///
+    /// ### Arguments
+    /// * `epoch` - calendar time corresponding to a simulation time of zero
+    fn set_epoch(&mut self, epoch: SystemTime);
+
+    /// Returns the calendar time corresponding to the current simulation
+    /// time, or `None` if no epoch has been set
+    fn wall_clock(&self) -> Option<SystemTime>;
+
+    /// Sets a global parameter, such as ambient temperature or altitude,
+    /// which components can read at any time through the `get_global`
+    /// method of any of their connectors. Setting a global doesn't trigger
+    /// any components to run.
+    ///
+    /// ### Arguments
+    /// * `key`   - Name of the global
+    /// * `value` - Value to set, of any type
+    fn set_global<T: Any + Send + Sync>(&mut self, key: &str, value: T)
+    where
+        Self: Sized;
+
+    /// Retrieves the current value of a global parameter
+    ///
+    /// ### Arguments
+    /// * `key` - Name of the global
+    ///
+    /// Returns a copy of the value, or `None` if the global isn't set
+    /// or holds a value of another type
+    fn get_global<T: Any + Clone>(&self, key: &str) -> Option<T>
+    where
+        Self: Sized;
+
     /// Determines if the given component name corresponds to an active component
     /// on this Sim
     fn has_component(&self, component_id: &str) -> bool;
@@ -19,6 +60,49 @@ pub trait Sim {
     /// Retrieves a list of components which are active on this Sim
     fn active_components(&self) -> Vec<&str>;
 
+    /// Retrieves the components on this Sim which were tagged with the
+    /// given label when added
+    ///
+    /// ### Arguments
+    /// * `label` - Label to look up
+    fn components_with_label(&self, label: &str) -> Vec<&'static str>;
+
+    /// Retrieves the circulation components which manage each `Substance`,
+    /// which is useful to detect unintended interactions between components
+    fn circulation_substance_owners(&self) -> HashMap<Substance, Vec<&'static str>>;
+
+    /// Total amount of a `Substance` across the Sim, including all blood
+    /// stores and consumables in the digestive tract. Blood vessels without
+    /// a known volume are excluded.
+    ///
+    /// ### Arguments
+    /// * `substance` - Substance to total
+    fn total_substance(&self, substance: &Substance) -> Amount<f64>;
+
+    /// Human readable summary of the simulation for quick inspection during
+    /// development, with the current time, each `Event` on the state, such
+    /// as vitals, the non-zero Substance concentrations of each vessel and
+    /// the consumables within the digestive tract
+    fn state_report(&self) -> String;
+
+    /// Checks that every `Event` a component is notified on is produced by
+    /// some component or layer, or has been scheduled or set externally.
+    ///
+    /// Returns the list of unsatisfied dependencies, if any
+    fn validate_event_graph(&self) -> Result<(), Vec<UnsatisfiedDependency>>;
+
+    /// Renders the Core components as a DOT graph, with an edge from each
+    /// component producing an `Event` to each component notified on it,
+    /// which is useful to document or debug feedback loops between
+    /// components
+    fn export_dependency_graph_dot(&self) -> String;
+
+    /// Drains the conflicts detected since the last drain, where different
+    /// components scheduled different values of the same non-transient
+    /// `Event` type for the same time. Conflicts are also logged, per the
+    /// policy set on the Sim.
+    fn drain_event_conflicts(&mut self) -> Vec<EventConflict>;
+
     /// Removes a component from this Sim. Panics if any of the component names
     /// are invalid.
     ///
@@ -26,9 +110,96 @@ pub trait Sim {
     /// * `component_ids` - List of components to remove
     fn remove_component(&mut self, component_id: &str) -> anyhow::Result<&str>;
 
+    /// Resets a component to its initial configuration, e.g. to restart
+    /// an infusion schedule without removing and re-adding it. Its layer
+    /// initialization is re-run and, if the simulation has started, it is
+    /// run once with the current state.
+    ///
+    /// ### Arguments
+    /// * `component_id` - id of the component to reset
+    ///
+    /// Returns an Err Result if the component isn't registered
+    fn reset_component(&mut self, component_id: &str) -> anyhow::Result<()>;
+
+    /// Runs a single component once with the current state, without advancing
+    /// time or running any other components, to test it in isolation within
+    /// a full Sim. Scheduled events are emitted by later advances as usual.
+    ///
+    /// ### Arguments
+    /// * `component_id` - id of the component to run
+    ///
+    /// Returns the events the component scheduled with their emission
+    /// times, or an Err Result if the component isn't registered
+    fn run_component_once(&mut self, component_id: &str) -> anyhow::Result<Vec<(SimTime, &dyn Event)>>;
+
+    /// Retrieves the components on this Sim which participate in the given layer
+    ///
+    /// ### Arguments
+    /// * `layer_type` - Layer to list components for
+    fn layer_components(&self, layer_type: LayerType) -> Vec<&'static str>;
+
+    /// Removes every component which participates in the given layer,
+    /// e.g. to replace all digestion components with a different model.
+    /// Components are removed from all of their layers.
+    ///
+    /// ### Arguments
+    /// * `layer_type` - Layer whose components should be removed
+    ///
+    /// Returns the ids of the removed components
+    fn remove_layer_components(&mut self, layer_type: LayerType) -> Vec<&'static str>;
+
+    /// Pauses the simulation clock. While paused, advances have no effect
+    /// and scheduled `Event`s remain queued, but the state can still be
+    /// queried and components added or removed.
+    fn pause(&mut self);
+
+    /// Resumes the simulation clock after `pause`
+    fn resume(&mut self);
+
+    /// Whether the simulation clock is paused
+    fn is_paused(&self) -> bool;
+
+    /// Registers a host side callback invoked each time the simulation
+    /// clock crosses a multiple of the interval, once per mark regardless
+    /// of how far each advance moves the clock
+    ///
+    /// ### Arguments
+    /// * `interval` - time between marks
+    /// * `callback` - function called with the time of each crossed mark
+    ///
+    /// Returns an Err Result if the interval isn't positive
+    fn on_time_mark(&mut self, interval: SimTimeSpan, callback: Box<dyn FnMut(SimTime) + Send>) -> anyhow::Result<()>;
+
+    /// Subdivides each `advance_by` into adaptive internal steps. The step
+    /// halves while the activity metric changes by more than 5% per step
+    /// and doubles while it changes by less than 1%, e.g. to resolve a
+    /// bolus injection finely while stepping coarsely through quiet periods.
+    ///
+    /// ### Arguments
+    /// * `min_span`        - smallest internal step
+    /// * `max_span`        - largest internal step, which is also the initial step
+    /// * `activity_metric` - function retrieving the tracked value from the state
+    ///
+    /// Returns an Err Result if the minimum step isn't positive or exceeds the maximum
+    fn set_auto_step(
+        &mut self,
+        min_span: SimTimeSpan,
+        max_span: SimTimeSpan,
+        activity_metric: Box<dyn FnMut(&SimState) -> f64 + Send>,
+    ) -> anyhow::Result<()>;
+
+    /// Disables adaptive internal steps set by `set_auto_step`
+    fn clear_auto_step(&mut self);
+
+    /// Returns the current adaptive internal step, if enabled
+    fn auto_step(&self) -> Option<SimTimeSpan>;
+
     /// Advances simulation time to the next `Event` or listener in the queue, if any.
+    /// `Event`s which components schedule without a delay are handled within
+    /// the same advance, up to the cap set by `set_max_iterations_per_advance`.
     ///
-    /// If there are no Events or listeners in the queue, time will remain unchanged
+    /// If there are no Events or listeners in the queue, or the Sim is
+    /// paused, time will remain unchanged
     fn advance(&mut self);
 
     /// Advances simulation time by the provided time step
@@ -40,6 +211,100 @@ pub trait Sim {
     /// * `time_step` - Amount of time to advance by
     fn advance_by(&mut self, time_step: SimTimeSpan);
 
+    /// Advances simulation time by the provided time step, as with
+    /// `advance_by`, but fails rather than doing nothing while paused
+    ///
+    /// ### Arguments
+    /// * `time_step` - Amount of time to advance by
+    ///
+    /// Returns an Err Result if the Sim is paused
+    fn try_advance_by(&mut self, time_step: SimTimeSpan) -> anyhow::Result<()> {
+        if self.is_paused() {
+            return Err(anyhow!("Cannot advance while the Sim is paused"));
+        }
+        self.advance_by(time_step);
+        Ok(())
+    }
+
+    /// Advances simulation time by the provided time step, stopping at each
+    /// scheduled `Event` along the way and invoking the given callback with
+    /// the event time and the state of the Sim once that time is processed.
+    ///
+    /// If a negative value is provided, time will immediately jump to
+    /// the next scheduled Event, if any.
+    ///
+    /// ### Arguments
+    /// * `time_step` - Amount of time to advance by
+    /// * `callback`  - Function called at each event time within the span
+    fn advance_by_stepping(&mut self, time_step: SimTimeSpan, callback: &mut dyn FnMut(SimTime, &dyn Sim));
+
+    /// Advances simulation time event by event until an `Event` with the
+    /// given type id is emitted, either as scheduled or by a component, or
+    /// until `max_time` elapses. See `advance_until_event` for a typed
+    /// variant.
+    ///
+    /// ### Arguments
+    /// * `event_type` - `TypeId` of the `Event` to stop on
+    /// * `max_time`   - Maximum amount of time to advance by
+    ///
+    /// Returns the time the `Event` was emitted, or `None` if it wasn't
+    /// emitted within `max_time` or the Sim is paused
+    fn advance_until_event_type(&mut self, event_type: TypeId, max_time: SimTimeSpan) -> Option<SimTime>;
+
+    /// Advances simulation time event by event until an `Event` of type `E`
+    /// is emitted, either as scheduled or by a component, or until
+    /// `max_time` elapses
+    ///
+    /// ### Arguments
+    /// * `max_time` - Maximum amount of time to advance by
+    ///
+    /// Returns the time the `Event` was emitted, or `None` if it wasn't
+    /// emitted within `max_time` or the Sim is paused
+    fn advance_until_event<E: Event>(&mut self, max_time: SimTimeSpan) -> Option<SimTime>
+    where
+        Self: Sized,
+    {
+        self.advance_until_event_type(TypeId::of::<E>(), max_time)
+    }
+
+    /// Replaces the wall clock used by `advance_to_wall_clock`, e.g. to
+    /// mock the passage of wall time. Elapsed time is tracked from the
+    /// next call to `advance_to_wall_clock`.
+    ///
+    /// ### Arguments
+    /// * `clock` - function returning the wall time elapsed since a fixed origin
+    fn set_real_time_clock(&mut self, clock: Box<dyn FnMut() -> Duration + Send>);
+
+    /// Advances simulation time to keep pace with wall time, for driving
+    /// the Sim from an external real time loop such as a game or monitor.
+    /// Simulation time advances by the wall time elapsed since the previous
+    /// call, scaled by `target_factor`, in steps of at most a second. The
+    /// first call only starts tracking wall time. Wall time elapsed while
+    /// the Sim is paused isn't simulated.
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use mortalsim_core::sim::organism::test::TestSim;
+    /// use mortalsim_core::sim::Sim;
+    ///
+    /// let mut sim = TestSim::new();
+    ///
+    /// // Frame loop of the host application, running the
+    /// // simulation at ten times real time
+    /// for _frame in 0..3 {
+    ///     sim.advance_to_wall_clock(10.0).unwrap();
+    ///     // ... render the current state of the sim ...
+    ///     std::thread::sleep(Duration::from_millis(20));
+    /// }
+    /// ```
+    ///
+    /// ### Arguments
+    /// * `target_factor` - Simulation time per unit of wall time, e.g. 2 to run twice as fast
+    ///
+    /// Returns the amount of simulation time advanced, or an Err if
+    /// `target_factor` is negative or not finite
+    fn advance_to_wall_clock(&mut self, target_factor: f64) -> anyhow::Result<SimTimeSpan>;
+
     /// Schedules an `Event` for future emission on this simulation
     ///
     /// ### Arguments
@@ -57,6 +322,108 @@ pub trait Sim {
     /// Returns an Err Result if the provided ID is invalid
     fn unschedule_event(&mut self, schedule_id: &IdType) -> anyhow::Result<()>;
 
+    /// Forces every component on the given layer, or on all layers if `None`,
+    /// to run on the next advance, even if nothing they depend on changed.
+    /// This is useful when a component's behavior is altered by something
+    /// other than an `Event`, such as a parameter set directly on the
+    /// component or a custom layer, and it needs to re-evaluate.
+    ///
+    /// ### Arguments
+    /// * `layer_type` - Layer whose components should run, or `None` for all
+    fn force_layer_run(&mut self, layer_type: Option<LayerType>);
+
+    /// Sets the organism's baseline vitals, such as heart rate and blood
+    /// pressure, on the state. Any vitals already on the state, such as
+    /// those set by `set_constant_event`, are left as they are. Intended
+    /// to be called before the first advance, so that components have
+    /// physiological inputs from the start.
+    fn initialize_baseline_vitals(&mut self);
+
+    /// Returns the current state of the Sim, with the latest `Event`
+    /// of each type
+    fn state(&self) -> &SimState;
+
     /// Drains the last active `Event`s from the Sim
     fn drain_active(&mut self) -> EventDrainIterator;
+
+    /// Removes all `Event`s still scheduled beyond the last advance,
+    /// without emitting them. Intended for the end of a run, to detect
+    /// components which schedule events that never get a chance to fire.
+    /// Components expecting those events won't receive them if the Sim
+    /// is advanced further. Component timers remain set, so periodic
+    /// components keep running.
+    ///
+    /// Returns the emission time and `Event` of each remaining `Event`,
+    /// in order of emission time
+    fn drain_remaining(&mut self) -> Vec<(SimTime, Arc<dyn Event>)>;
+
+    /// Returns the journal of inputs applied to this Sim since it was created
+    fn journal(&self) -> &SimJournal;
+
+    /// Applies an input recorded in the journal of another Sim, as when
+    /// loading a saved Sim
+    ///
+    /// ### Arguments
+    /// * `entry` - input to apply
+    ///
+    /// Returns an Err Result if the input can't be applied, such as a
+    /// component added after the Sim was created
+    fn replay(&mut self, entry: &JournalEntry) -> anyhow::Result<()>;
+
+    /// Saves the Sim to a file, from which it can be restored with
+    /// `load_from_file`. The inputs applied to the Sim are saved rather
+    /// than the internal state of its components, so scheduled `Event`s
+    /// must be of types registered with `register_event`, and global
+    /// parameters can't be saved at all. The journal of inputs is kept
+    /// for the lifetime of the Sim, so the file grows with the number of
+    /// inputs applied, though not with repeated equal advances.
+    ///
+    /// ### Arguments
+    /// * `path` - file to write
+    ///
+    /// Returns an Err Result naming the input if any input applied to the
+    /// Sim can't be saved, in which case no file is written, or if the
+    /// file can't be written
+    #[cfg(feature = "serde")]
+    fn save_to_file(&self, path: &std::path::Path) -> anyhow::Result<()> {
+        super::journal::save(self, path)
+    }
+
+    /// Restores a Sim saved with `save_to_file` onto this Sim, which must
+    /// be created the same way as the saved Sim, with the same components
+    /// added in the same order. This isn't a checkpoint of the runtime
+    /// state: every input applied to the saved Sim is replayed from time
+    /// zero, re-simulating the whole run, and the resulting state is then
+    /// compared against the saved one. Loading therefore takes as long as
+    /// the original run, after which this Sim continues exactly as the
+    /// saved one would have.
+    ///
+    /// ### Arguments
+    /// * `path` - file to read
+    ///
+    /// Returns an Err Result if the file can't be read, this Sim wasn't
+    /// created the same way, or the restored state differs from the saved
+    /// state
+    #[cfg(feature = "serde")]
+    fn load_from_file(&mut self, path: &std::path::Path) -> anyhow::Result<()> {
+        super::journal::load(self, path)
+    }
+}
+
+/// Asserts that a Sim ended cleanly, with no `Event`s left scheduled
+/// which never fired. Any remaining events are drained from the Sim.
+///
+/// Panics with the list of remaining events, if any
+///
+/// ### Arguments
+/// * `sim` - Sim to check at the end of a run
+pub fn assert_no_orphan_events(sim: &mut dyn Sim) {
+    let remaining = sim.drain_remaining();
+    assert!(
+        remaining.is_empty(),
+        "{} orphaned event(s) remain after {:?}: {:?}",
+        remaining.len(),
+        sim.time(),
+        remaining,
+    );
 }
@@ -9,6 +9,8 @@ pub struct SimState {
     state: HashMap<TypeId, Arc<dyn Event>>,
     /// Keep track of any Events which have been tainted
     tainted_states: HashSet<TypeId>,
+    /// Events which were most recently replaced by each type
+    previous: HashMap<TypeId, Arc<dyn Event>>,
 }
 
 impl SimState {
@@ -17,6 +19,7 @@ impl SimState {
         SimState {
             state: HashMap::new(),
             tainted_states: HashSet::new(),
+            previous: HashMap::new(),
         }
     }
 
@@ -39,6 +42,16 @@ impl SimState {
             .downcast_arc::<T>().ok()
     }
 
+    /// Retrieves a typed reference to the `Event` which was replaced by
+    /// the current `Event` of its type
+    ///
+    /// returns a `&E` or `None` if the `Event` hasn't been replaced
+    pub fn get_previous_state<T: Event>(&self) -> Option<&T> {
+        self.previous
+            .get(&TypeId::of::<T>())?
+            .downcast_ref::<T>()
+    }
+
     /// Retrieves a dyn `Event` in this state
     ///
     /// returns a cloned `Arc<E>` or `None` if no `Event` of this type has been set
@@ -46,6 +59,14 @@ impl SimState {
         Some(self.state.get(&type_id)?)
     }
 
+    /// Debug representation of every `Event` in this state, sorted so
+    /// that states can be compared regardless of insertion order
+    pub fn snapshot(&self) -> Vec<String> {
+        let mut snapshot: Vec<String> = self.state.values().map(|evt| format!("{:?}", evt)).collect();
+        snapshot.sort();
+        snapshot
+    }
+
     /// Checks whether an `Event` exists in this state for a given `Event` type
     ///
     /// returns `true` if it exists or `false` otherwise
@@ -62,7 +83,7 @@ impl SimState {
     /// returns previously stored `Event` or `None`
     pub(super) fn put_state(&mut self, event: Arc<dyn Event>) -> Option<Arc<dyn Event>> {
         self.tainted_states.insert(event.type_id());
-        self.state.insert(event.type_id(), event)
+        self.replace(event.type_id(), event)
     }
 
     /// Sets an `Event` object on the current state. The previous `Event`
@@ -75,7 +96,7 @@ impl SimState {
     pub(super) fn set_state<T: Event>(&mut self, event: T) -> Option<Arc<dyn Event>> {
         let type_id = TypeId::of::<T>();
         self.tainted_states.insert(type_id);
-        self.state.insert(type_id, Arc::new(event))
+        self.replace(type_id, Arc::new(event))
     }
 
     /// Sets an `Event` object on the current state, without tainting.
@@ -88,7 +109,19 @@ impl SimState {
     /// returns previously stored `Event` or `None`
     pub(super) fn set_state_quiet<T: Event>(&mut self, event: T) -> Option<Arc<dyn Event>> {
         let type_id = TypeId::of::<T>();
-        self.state.insert(type_id, Arc::new(event))
+        self.replace(type_id, Arc::new(event))
+    }
+
+    /// Replaces the `Event` of the given type, keeping the replaced
+    /// `Event` as the previous one unless it's the same object
+    ///
+    /// returns previously stored `Event` or `None`
+    fn replace(&mut self, type_id: TypeId, event: Arc<dyn Event>) -> Option<Arc<dyn Event>> {
+        let replaced = self.state.insert(type_id, event.clone())?;
+        if !Arc::ptr_eq(&replaced, &event) {
+            self.previous.insert(type_id, replaced.clone());
+        }
+        Some(replaced)
     }
 
     /// Retrieves the `Set` of tainted `Event` `TypeId`s on this `State`
@@ -109,6 +142,11 @@ impl SimState {
     pub fn merge_tainted(&mut self, other: &Self) {
         for type_key in other.tainted_states.iter() {
             self.put_state(other.get_dyn_state(type_key).unwrap().clone());
+            // Keep the other state's history, which may have seen
+            // emissions this one hasn't
+            if let Some(previous) = other.previous.get(type_key) {
+                self.previous.insert(*type_key, previous.clone());
+            }
         }
     }
 
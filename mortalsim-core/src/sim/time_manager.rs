@@ -5,6 +5,7 @@
 //! immediately to the next `Event`
 
 use crate::event::Event;
+use crate::sim::SimState;
 use crate::hub::event_transformer::{EventTransformer, TransformerItem};
 use crate::units::base::Time;
 use crate::id_gen::{IdGenerator, IdType, InvalidIdError};
@@ -12,8 +13,11 @@ use crate::{SimTime, SimTimeSpan};
 use anyhow::{Error, Result};
 use std::any::TypeId;
 use std::collections::hash_map::HashMap;
+use std::collections::HashSet;
 use std::collections::BTreeMap;
 use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 
 pub struct TimeManager {
     /// Current simulation time
@@ -28,6 +32,10 @@ pub struct TimeManager {
     id_gen: IdGenerator,
     /// Used to lookup listeners and Event objects for unscheduling
     id_time_map: HashMap<IdType, SimTime>,
+    /// Schedule IDs of internal events, such as component timers
+    internal_ids: HashSet<IdType>,
+    /// Calendar time corresponding to a simulation time of zero, if set
+    epoch: Option<SystemTime>,
 }
 
 impl<'b> fmt::Debug for TimeManager {
@@ -50,6 +58,8 @@ impl TimeManager {
             transformer_type_map: HashMap::new(),
             id_gen: IdGenerator::new(),
             id_time_map: HashMap::new(),
+            internal_ids: HashSet::new(),
+            epoch: None,
         }
     }
 
@@ -58,6 +68,31 @@ impl TimeManager {
         self.sim_time
     }
 
+    /// Anchors simulation time zero to a calendar time
+    ///
+    /// ### Arguments
+    /// * `epoch` - calendar time corresponding to a simulation time of zero
+    pub fn set_epoch(&mut self, epoch: SystemTime) {
+        self.epoch = Some(epoch);
+    }
+
+    /// Returns the calendar time corresponding to a simulation time of zero, if set
+    pub fn epoch(&self) -> Option<SystemTime> {
+        self.epoch
+    }
+
+    /// Returns the calendar time corresponding to the current simulation time,
+    /// or `None` if no epoch has been set
+    pub fn wall_clock(&self) -> Option<SystemTime> {
+        let epoch = self.epoch?;
+        let secs = self.sim_time.to_s();
+        if secs >= 0.0 {
+            epoch.checked_add(Duration::from_secs_f64(secs))
+        } else {
+            epoch.checked_sub(Duration::from_secs_f64(-secs))
+        }
+    }
+
     /// Advances simulation time to the next `Event` or listener in the queue, if any.
     ///
     /// If there are no Events or listeners in the queue, time will remain unchanged
@@ -70,6 +105,66 @@ impl TimeManager {
         }
     }
 
+    /// Whether any scheduled `Event`s are ready for emission at the
+    /// current simulation time
+    pub fn has_ready_events(&self) -> bool {
+        self.event_queue
+            .iter()
+            .take_while(|(time, _)| **time <= self.sim_time)
+            .any(|(_, evts)| !evts.is_empty())
+    }
+
+    /// Returns the time of the earliest scheduled `Event` which occurs
+    /// after the current simulation time, if any
+    pub fn next_event_time(&self) -> Option<SimTime> {
+        self.event_queue
+            .iter()
+            .find(|(time, evts)| **time > self.sim_time && !evts.is_empty())
+            .map(|(time, _)| *time)
+    }
+
+    /// Iterates over all scheduled `Event`s in order of emission time,
+    /// without removing them from the queue
+    pub fn scheduled_events(&self) -> impl Iterator<Item = (SimTime, &dyn Event)> {
+        self.event_queue
+            .iter()
+            .flat_map(|(time, evts)| evts.iter().map(move |(_, evt)| (*time, evt.as_ref())))
+    }
+
+    /// Retrieves a scheduled `Event` along with its emission time
+    ///
+    /// ### Arguments
+    /// * `schedule_id` - Schedule ID returned by `schedule_event`
+    ///
+    /// Returns the emission time and `Event`, if it's still scheduled
+    pub fn scheduled_event(&self, schedule_id: &IdType) -> Option<(SimTime, &dyn Event)> {
+        let time = self.id_time_map.get(schedule_id)?;
+        self.event_queue
+            .get(time)?
+            .iter()
+            .find(|(id, _)| id == schedule_id)
+            .map(|(_, evt)| (*time, evt.as_ref()))
+    }
+
+    /// Schedule IDs of all currently scheduled `Event`s
+    pub(crate) fn scheduled_ids(&self) -> HashSet<IdType> {
+        self.event_queue
+            .values()
+            .flat_map(|evts| evts.iter().map(|(id, _)| *id))
+            .collect()
+    }
+
+    /// Whether any `Event` of the given type is currently scheduled
+    ///
+    /// ### Arguments
+    /// * `type_id` - `TypeId` of the `Event` to look for
+    pub fn has_scheduled(&self, type_id: &TypeId) -> bool {
+        self.event_queue
+            .values()
+            .flatten()
+            .any(|(_, evt)| evt.as_any().type_id() == *type_id)
+    }
+
     /// Advances simulation time by the provided time step
     ///
     /// If a negative value is provided, time will immediately jump to
@@ -88,6 +183,17 @@ impl TimeManager {
         self.sim_time = self.sim_time + time_step;
     }
 
+    /// Advances simulation time to the provided time. If the provided time
+    /// is not after the current simulation time, time remains unchanged.
+    ///
+    /// ### Arguments
+    /// * `time` - Simulation time to advance to
+    pub fn advance_to(&mut self, time: SimTime) {
+        if time > self.sim_time {
+            self.sim_time = time;
+        }
+    }
+
     /// Schedules an `Event` for future emission
     ///
     /// ### Arguments
@@ -121,6 +227,20 @@ impl TimeManager {
         id
     }
 
+    /// Schedules an `Event` used internally by the simulation, such as a
+    /// component timer, which isn't removed by `drain_scheduled`
+    ///
+    /// ### Arguments
+    /// * `wait_time` - amount of simulation time to wait before emitting the Event
+    /// * `event` - Event instance to emit
+    ///
+    /// Returns the schedule ID
+    pub fn schedule_internal_event(&mut self, wait_time: SimTimeSpan, event: Box<dyn Event>) -> IdType {
+        let id = self.schedule_event(wait_time, event);
+        self.internal_ids.insert(id);
+        id
+    }
+
     /// Unschedules a previously scheduled `Event`
     ///
     /// ### Arguments
@@ -132,6 +252,7 @@ impl TimeManager {
             Some(time) => match self.event_queue.get_mut(time) {
                 Some(evt_list) => {
                     evt_list.retain(|item| item.0 != *schedule_id);
+                    self.internal_ids.remove(schedule_id);
                     Ok(())
                 }
                 None => {
@@ -166,6 +287,9 @@ impl TimeManager {
 
         for evt_time in times_to_remove {
             let evt_list = self.event_queue.remove(&evt_time).unwrap();
+            for (id, _) in evt_list.iter() {
+                self.internal_ids.remove(id);
+            }
 
             // Drop the registration token when returning the result vector
             let mut result: Vec<Box<dyn Event>> =
@@ -187,6 +311,64 @@ impl TimeManager {
         results.into_iter()
     }
 
+    /// Removes the next scheduled `Event` from the queue, advancing
+    /// simulation time to its emission time if that is later than the
+    /// current time. No components are run, so this is intended for
+    /// stepping through the queue one `Event` at a time when debugging.
+    /// Events scheduled for the same time are returned in the same order
+    /// as `next_events`.
+    ///
+    /// Returns the emission time and `Event`, or `None` if the queue is empty
+    pub fn step_once(&mut self) -> Option<(SimTime, Arc<dyn Event>)> {
+        loop {
+            let mut entry = self.event_queue.first_entry()?;
+            let evt_time = *entry.key();
+
+            // Unscheduled events can leave empty lists behind
+            let Some((id, mut evt)) = entry.get_mut().pop() else {
+                entry.remove();
+                continue;
+            };
+            if entry.get().is_empty() {
+                entry.remove();
+            }
+            self.id_time_map.remove(&id);
+            self.internal_ids.remove(&id);
+
+            for transformers in self.event_transformers.get_mut(&evt.type_id()).iter_mut() {
+                for transformer in transformers.iter_mut() {
+                    transformer.transform(evt.as_mut());
+                }
+            }
+
+            self.advance_to(evt_time);
+            return Some((evt_time, Arc::from(evt)));
+        }
+    }
+
+    /// Removes every scheduled `Event` from the queue without emitting
+    /// them or advancing simulation time, e.g. to report events which
+    /// never had a chance to fire at the end of a run. Internal events,
+    /// such as component timers, remain scheduled. Events scheduled for
+    /// the same time are returned in the same order as `next_events`.
+    ///
+    /// Returns the emission time and `Event` of each removed `Event`, in
+    /// order of emission time
+    pub fn drain_scheduled(&mut self) -> Vec<(SimTime, Arc<dyn Event>)> {
+        let mut drained = Vec::new();
+        for (time, evts) in self.event_queue.iter_mut() {
+            let (internal, external): (Vec<_>, Vec<_>) =
+                std::mem::take(evts).into_iter().partition(|(id, _)| self.internal_ids.contains(id));
+            *evts = internal;
+            for (id, evt) in external.into_iter().rev() {
+                self.id_time_map.remove(&id);
+                drained.push((*time, Arc::from(evt)));
+            }
+        }
+        self.event_queue.retain(|_, evts| !evts.is_empty());
+        drained
+    }
+
     /// Registers a transformer for a specific Event.
     ///
     /// ### Arguments
@@ -274,6 +456,187 @@ impl TimeManager {
     }
 }
 
+type TimeMarkFn = Box<dyn FnMut(SimTime) + Send>;
+
+/// Host side callbacks invoked each time the simulation clock crosses a
+/// multiple of their interval, e.g. to synchronize external systems.
+/// Unlike recurring `Event`s, these don't affect the simulation itself.
+pub struct TimeMarks {
+    /// Interval, index of the next mark and callback of each registration
+    marks: Vec<(SimTimeSpan, u64, TimeMarkFn)>,
+}
+
+impl TimeMarks {
+    /// Creates a new TimeMarks without any registrations
+    pub fn new() -> Self {
+        Self { marks: Vec::new() }
+    }
+
+    /// Registers a callback for each future multiple of the interval
+    ///
+    /// ### Arguments
+    /// * `now`      - current simulation time
+    /// * `interval` - time between marks
+    /// * `callback` - function called with the time of each mark
+    ///
+    /// Returns an Err if the interval isn't positive
+    pub fn add(&mut self, now: SimTime, interval: SimTimeSpan, callback: TimeMarkFn) -> Result<()> {
+        if interval.to_s().is_nan() || interval.to_s() <= 0.0 {
+            return Err(anyhow!("Time mark interval must be positive, got {}", interval));
+        }
+        let next = (now.to_s() / interval.to_s()).floor() as u64 + 1;
+        self.marks.push((interval, next, callback));
+        Ok(())
+    }
+
+    /// Invokes the callbacks once for each mark crossed since the last call,
+    /// in order of time for each registration
+    ///
+    /// ### Arguments
+    /// * `sim_time` - current simulation time
+    pub fn fire(&mut self, sim_time: SimTime) {
+        for (interval, next, callback) in self.marks.iter_mut() {
+            loop {
+                // Computed from the index so marks don't drift
+                let mark = SimTime::from_s(*next as f64 * interval.to_s());
+                if mark > sim_time {
+                    break;
+                }
+                callback(mark);
+                *next += 1;
+            }
+        }
+    }
+}
+
+impl Default for TimeMarks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+type ClockFn = Box<dyn FnMut() -> Duration + Send>;
+
+/// Tracks the wall time elapsed between advances of a Sim driven by an
+/// external real time loop, such as a game or monitor
+pub struct RealTimeSync {
+    /// Wall time elapsed since an arbitrary fixed origin
+    clock: ClockFn,
+    /// Clock reading at the previous call, if any
+    last: Option<Duration>,
+}
+
+impl RealTimeSync {
+    /// Largest step of simulation time (s) taken at once to catch up
+    /// with wall time
+    pub const MAX_STEP: f64 = 1.0;
+
+    /// Creates a new RealTimeSync using the system's monotonic clock
+    pub fn new() -> Self {
+        let start = Instant::now();
+        Self::with_clock(Box::new(move || start.elapsed()))
+    }
+
+    /// Creates a new RealTimeSync using the given clock, e.g. to mock
+    /// the passage of wall time
+    ///
+    /// ### Arguments
+    /// * `clock` - function returning the wall time elapsed since a fixed origin
+    pub fn with_clock(clock: ClockFn) -> Self {
+        Self { clock, last: None }
+    }
+
+    /// Wall time elapsed since the previous call, which is zero for the
+    /// first call or if the clock went backwards
+    pub fn elapsed(&mut self) -> Duration {
+        let now = (self.clock)();
+        let elapsed = self.last.map_or(Duration::ZERO, |last| now.saturating_sub(last));
+        self.last = Some(now);
+        elapsed
+    }
+}
+
+impl Default for RealTimeSync {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+type ActivityFn = Box<dyn FnMut(&SimState) -> f64 + Send>;
+
+/// Relative change of the activity metric per step above which the step shrinks
+const AUTO_STEP_MAX_CHANGE: f64 = 0.05;
+/// Relative change of the activity metric per step below which the step grows
+const AUTO_STEP_MIN_CHANGE: f64 = 0.01;
+
+/// Adaptive internal step for advances, which halves while a tracked
+/// value of the state changes rapidly and doubles while it's quiescent,
+/// within the configured bounds
+pub struct AutoStep {
+    min_span: SimTimeSpan,
+    max_span: SimTimeSpan,
+    /// Value tracked on the state after each step
+    metric: ActivityFn,
+    step: SimTimeSpan,
+    last_value: Option<f64>,
+}
+
+impl AutoStep {
+    /// Creates a new AutoStep, starting from the maximum step
+    ///
+    /// ### Arguments
+    /// * `min_span` - smallest allowed step
+    /// * `max_span` - largest allowed step
+    /// * `metric`   - function retrieving the tracked value from the state
+    ///
+    /// Returns an Err if the minimum step isn't positive or exceeds the maximum
+    pub fn new(min_span: SimTimeSpan, max_span: SimTimeSpan, metric: ActivityFn) -> Result<Self> {
+        if min_span.to_s().is_nan() || min_span.to_s() <= 0.0 {
+            return Err(anyhow!("Minimum auto step must be positive, got {}", min_span));
+        }
+        if max_span < min_span {
+            return Err(anyhow!("Maximum auto step {} is less than the minimum {}", max_span, min_span));
+        }
+        Ok(Self {
+            min_span,
+            max_span,
+            metric,
+            step: max_span,
+            last_value: None,
+        })
+    }
+
+    /// Current step
+    pub fn step(&self) -> SimTimeSpan {
+        self.step
+    }
+
+    /// Adjusts the step based on the relative change of the tracked value
+    /// since the previous step
+    ///
+    /// ### Arguments
+    /// * `state` - state of the simulation after the step
+    pub fn observe(&mut self, state: &SimState) {
+        let value = (self.metric)(state);
+        let Some(last) = self.last_value.replace(value) else {
+            return;
+        };
+        if !value.is_finite() || !last.is_finite() {
+            return;
+        }
+
+        let change = (value - last).abs() / last.abs().max(f64::EPSILON);
+        let step = if change > AUTO_STEP_MAX_CHANGE {
+            self.step / 2.0
+        } else if change < AUTO_STEP_MIN_CHANGE {
+            self.step * 2.0
+        } else {
+            self.step
+        };
+        self.step = step.clamp(self.min_span, self.max_span);
+    }
+}
+
 
 mod tests {
     use super::Time;
@@ -288,6 +651,7 @@ mod tests {
     use crate::secs;
     use crate::SimTimeSpan;
     use std::any::TypeId;
+    use std::time::{Duration, SystemTime};
 
     #[test]
     fn advance_test() {
@@ -319,6 +683,47 @@ mod tests {
         assert_eq!(time_manager.get_time(), SimTime::from_s(5.0));
     }
 
+    #[test]
+    fn scheduled_events_test() {
+        let mut time_manager = TimeManager::new();
+        time_manager.schedule_event(SimTimeSpan::from_s(6.0), Box::new(TestEventB::new(Amount::from_mol(1.0))));
+        time_manager.schedule_event(SimTimeSpan::from_s(2.0), Box::new(TestEventA::new(Distance::from_m(1.0))));
+        time_manager.schedule_event(SimTimeSpan::from_s(2.0), Box::new(TestEventA::new(Distance::from_m(2.0))));
+
+        let scheduled: Vec<(SimTime, String)> = time_manager
+            .scheduled_events()
+            .map(|(time, evt)| (time, format!("{:?}", evt)))
+            .collect();
+        assert_eq!(scheduled.len(), 3);
+        assert_eq!(scheduled.iter().map(|(t, _)| *t).collect::<Vec<_>>(), vec![secs!(2.0), secs!(2.0), secs!(6.0)]);
+        assert!(scheduled[0].1.contains("TestEventA"));
+        assert!(scheduled[2].1.contains("TestEventB"));
+
+        // Enumerating doesn't consume or reorder the queue
+        assert_eq!(time_manager.scheduled_events().count(), 3);
+        time_manager.advance_by(SimTimeSpan::from_s(2.0));
+        let (time, evts) = time_manager.next_events().next().unwrap();
+        assert_eq!(time, secs!(2.0));
+        assert_eq!(evts.len(), 2);
+        assert_eq!(time_manager.scheduled_events().map(|(t, _)| t).collect::<Vec<_>>(), vec![secs!(6.0)]);
+    }
+
+    #[test]
+    fn wall_clock_test() {
+        let mut time_manager = TimeManager::new();
+
+        // No epoch by default
+        assert!(time_manager.wall_clock().is_none());
+
+        let epoch = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        time_manager.set_epoch(epoch);
+        assert_eq!(time_manager.wall_clock(), Some(epoch));
+
+        // Advancing an hour should advance the wall clock by an hour
+        time_manager.advance_by(SimTimeSpan::from_s(3600.0));
+        assert_eq!(time_manager.wall_clock(), Some(epoch + Duration::from_secs(3600)));
+    }
+
     #[test]
     fn emit_events_test() {
         let a_evt = TestEventA::new(Distance::from_m(3.5));
@@ -371,6 +776,38 @@ mod tests {
         assert_eq!(time_manager.get_time(), SimTime::from_s(6.0));
     }
 
+    #[test]
+    fn step_once_test() {
+        let mut time_manager = TimeManager::new();
+        time_manager.advance_by(SimTimeSpan::from_s(1.0));
+
+        time_manager.schedule_event(SimTimeSpan::from_s(3.0), Box::new(TestEventB::new(Amount::from_mol(1.0))));
+        time_manager.schedule_event(SimTimeSpan::from_s(1.0), Box::new(TestEventA::new(Distance::from_m(1.0))));
+        let id = time_manager.schedule_event(SimTimeSpan::from_s(2.0), Box::new(TestEventA::new(Distance::from_m(2.0))));
+        time_manager.schedule_event(SimTimeSpan::from_s(3.0), Box::new(TestEventA::new(Distance::from_m(3.0))));
+        time_manager.unschedule_event(&id).unwrap();
+        time_manager.transform(|evt: &mut TestEventB| evt.amt = Amount::from_mol(5.0));
+
+        let (time, evt) = time_manager.step_once().unwrap();
+        assert_eq!(time, SimTime::from_s(2.0));
+        assert_eq!(time_manager.get_time(), SimTime::from_s(2.0));
+        assert_eq!(evt.downcast_ref::<TestEventA>().unwrap().len, Distance::from_m(1.0));
+
+        // The unscheduled event at 3s is skipped, and events at the same
+        // time come out in the same order as `next_events`
+        let (time, evt) = time_manager.step_once().unwrap();
+        assert_eq!(time, SimTime::from_s(4.0));
+        assert_eq!(evt.downcast_ref::<TestEventA>().unwrap().len, Distance::from_m(3.0));
+
+        let (time, evt) = time_manager.step_once().unwrap();
+        assert_eq!(time, SimTime::from_s(4.0));
+        assert_eq!(evt.downcast_ref::<TestEventB>().unwrap().amt, Amount::from_mol(5.0));
+
+        assert!(time_manager.step_once().is_none());
+        assert_eq!(time_manager.get_time(), SimTime::from_s(4.0));
+        assert!(time_manager.next_events().next().is_none());
+    }
+
     #[test]
     fn transformer_test() {
         let mut listener = TransformerItem::new(|evt: &mut TestEventA| {
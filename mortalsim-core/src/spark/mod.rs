@@ -162,21 +162,21 @@ pub struct ModuleSoleA {
 
 impl ModuleA for ModuleSoleA {
     fn init_a(&mut self, _initializer: &mut InitializerA) {
-        println!("Initializing ModuleSoleA");
+        log::debug!("Initializing ModuleSoleA");
     }
     fn connector_a(&mut self) -> &mut ConnectorA {
-        println!("Accessing ModuleSoleA->ConnectorA");
+        log::debug!("Accessing ModuleSoleA->ConnectorA");
         &mut self.connector_a
     }
 }
 
 impl Module for ModuleSoleA {
     fn attach(self, keeper: &mut ModuleKeeper) {
-        println!("attaching ModuleSoleA");
+        log::debug!("attaching ModuleSoleA");
         keeper.add_a_module(self);
     }
     fn run(&mut self) {
-        println!("Running ModuleSoleA");
+        log::debug!("Running ModuleSoleA");
     }
 }
 
@@ -186,10 +186,10 @@ pub struct ModuleSoleB {
 
 impl ModuleB for ModuleSoleB {
     fn init_b(&mut self, _initializer: &mut InitializerB) {
-        println!("Initializing ModuleSoleB");
+        log::debug!("Initializing ModuleSoleB");
     }
     fn connector_b(&mut self) -> &mut ConnectorB {
-        println!("Accessing ModuleSoleB->ConnectorB");
+        log::debug!("Accessing ModuleSoleB->ConnectorB");
         &mut self.connector_b
     }
 }
@@ -199,7 +199,7 @@ impl Module for ModuleSoleB {
         keeper.add_b_module(self);
     }
     fn run(&mut self) {
-        println!("Running ModuleSoleB");
+        log::debug!("Running ModuleSoleB");
     }
 }
 
@@ -210,20 +210,20 @@ pub struct ModuleAB {
 
 impl ModuleA for ModuleAB {
     fn init_a(&mut self, _initializer: &mut InitializerA) {
-        println!("Initializing ModuleA of ModuleAB");
+        log::debug!("Initializing ModuleA of ModuleAB");
     }
     fn connector_a(&mut self) -> &mut ConnectorA {
-        println!("Accessing ModuleAB->ConnectorA");
+        log::debug!("Accessing ModuleAB->ConnectorA");
         &mut self.connector_a
     }
 }
 
 impl ModuleB for ModuleAB {
     fn init_b(&mut self, _initializer: &mut InitializerB) {
-        println!("Initializing ModuleB of ModuleAB");
+        log::debug!("Initializing ModuleB of ModuleAB");
     }
     fn connector_b(&mut self) -> &mut ConnectorB {
-        println!("Accessing ModuleAB->ConnectorB");
+        log::debug!("Accessing ModuleAB->ConnectorB");
         &mut self.connector_b
     }
 }
@@ -233,7 +233,7 @@ impl Module for ModuleAB {
         keeper.add_ab_module(self);
     }
     fn run(&mut self) {
-        println!("Running ModuleAB");
+        log::debug!("Running ModuleAB");
     }
 }
 
@@ -274,7 +274,7 @@ impl MainObject {
         module.attach(&mut self.keeper);
     }
     fn execute(&mut self) {
-        println!("Executing main...");
+        log::debug!("Executing main...");
         for module in self.keeper.modules.iter_mut() {
             if module.has_a() {
                 self.component_a.prepare_a(module.as_mut());
@@ -14,6 +14,13 @@ pub trait SubstanceChangeItem {
     /// * `start_time` - simulation time when the change began
     fn next_amount(&mut self, sim_time: SimTime) -> SubstanceConcentration;
 
+    /// Retrieves the delta of the concentration change from the previous call to
+    /// `next_amount` up to the given time, without applying it
+    ///
+    /// ### Arguments
+    /// * `sim_time` - simulation time to evaluate the change at
+    fn pending_amount(&self, sim_time: SimTime) -> SubstanceConcentration;
+
     /// Simulation time when the change begins
     fn start_time(&self) -> SimTime;
 
@@ -77,6 +84,11 @@ impl SubstanceChangeFn {
 
 /// Representation of a substance change
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "super::SubstanceChangeRecord", try_from = "super::SubstanceChangeRecord")
+)]
 pub struct SubstanceChange {
     cancel_time: Arc<RwLock<SimTime>>,
     prev_val: SubstanceConcentration,
@@ -107,6 +119,56 @@ impl SubstanceChange {
         }
     }
 
+    /// Total concentration change to take place
+    pub fn amount(&self) -> SubstanceConcentration {
+        self.change_fn.amount
+    }
+
+    /// Simulation time when the change starts
+    pub fn start_time(&self) -> SimTime {
+        self.change_fn.start_time
+    }
+
+    /// Amount of time over which the change takes place
+    pub fn duration(&self) -> SimTimeSpan {
+        self.change_fn.duration
+    }
+
+    /// Creates a copy of this change with its amount scaled by the given
+    /// factor. Cancelling this change also cancels the copy's dependents.
+    ///
+    /// ### Arguments
+    /// * `factor` - factor to scale the amount by
+    pub fn scaled(&self, factor: f64) -> Self {
+        let mut change_fn = (*self.change_fn).clone();
+        change_fn.amount *= factor;
+        Self {
+            cancel_time: self.cancel_time.clone(),
+            prev_val: SubstanceConcentration::from_mM(0.0),
+            change_fn: Arc::new(change_fn),
+        }
+    }
+
+    /// Creates a copy of this change which starts later by the given span.
+    /// Cancelling this change also cancels the copy's dependents.
+    ///
+    /// ### Arguments
+    /// * `delay` - amount of time to delay the start of the change by
+    pub fn delayed(&self, delay: SimTimeSpan) -> Self {
+        let mut change_fn = (*self.change_fn).clone();
+        change_fn.start_time += delay;
+        Self {
+            cancel_time: self.cancel_time.clone(),
+            prev_val: SubstanceConcentration::from_mM(0.0),
+            change_fn: Arc::new(change_fn),
+        }
+    }
+
+    /// Shape of the change over its duration
+    pub fn bound_fn(&self) -> BoundFn {
+        self.change_fn.bound_fn
+    }
+
     /// Cancels any changes dependent on this change
     /// ### Arguments
     /// * `cur_time` - current simulation time to evaluate the change at
@@ -123,6 +185,10 @@ impl SubstanceChangeItem for SubstanceChange {
         result
     }
 
+    fn pending_amount(&self, sim_time: SimTime) -> SubstanceConcentration {
+        self.change_fn.next_amount(sim_time) - self.prev_val
+    }
+
     fn start_time(&self) -> SimTime {
         self.change_fn.start_time
     }
@@ -140,6 +206,7 @@ pub struct DependentSubstanceChange {
     prev_val: SubstanceConcentration,
     change_fn: Arc<SubstanceChangeFn>,
     factor: f64,
+    tag: Option<&'static str>,
 }
 
 impl DependentSubstanceChange {
@@ -166,9 +233,21 @@ impl DependentSubstanceChange {
             prev_val: SubstanceConcentration::from_mM(0.0),
             change_fn: change.change_fn.clone(),
             factor: factor,
+            tag: None,
         }
     }
 
+    /// Attaches a tag to this change for selective cancellation
+    pub(crate) fn with_tag(mut self, tag: &'static str) -> Self {
+        self.tag = Some(tag);
+        self
+    }
+
+    /// Tag attached to this change, if any
+    pub(crate) fn tag(&self) -> Option<&'static str> {
+        self.tag
+    }
+
     pub fn is_cancelled(&self, sim_time: SimTime) -> bool {
         let cancel_time = *self.cancel_time.read().unwrap();
         if cancel_time > SimTime::from_s(0.0) {
@@ -191,6 +270,13 @@ impl SubstanceChangeItem for DependentSubstanceChange {
         result
     }
 
+    fn pending_amount(&self, sim_time: SimTime) -> SubstanceConcentration {
+        if self.is_cancelled(sim_time) {
+            return SubstanceConcentration::from_M(0.0);
+        }
+        self.change_fn.next_amount(sim_time - self.time_diff) * self.factor - self.prev_val
+    }
+
     fn start_time(&self) -> SimTime {
         self.change_fn.start_time + self.time_diff
     }
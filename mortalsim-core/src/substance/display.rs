@@ -0,0 +1,67 @@
+use super::SubstanceConcentration;
+
+/// Units available for display, largest first, with their size in mM
+const DISPLAY_UNITS: [(&str, f64); 4] = [
+    ("M", 1.0e3),
+    ("mM", 1.0),
+    ("µM", 1.0e-3),
+    ("nM", 1.0e-6),
+];
+
+/// Number of significant figures to display
+const SIG_FIGS: i32 = 3;
+
+/// Human readable formatting of concentrations for logging
+pub trait ConcentrationDisplay {
+    /// Formats the concentration with the largest unit which keeps the
+    /// value at or above 1, to three significant figures, e.g. "1.23 mM"
+    /// or "450 µM". Values below 1 nM are still shown in nM.
+    fn to_human_string(&self) -> String;
+}
+
+impl ConcentrationDisplay for SubstanceConcentration {
+    fn to_human_string(&self) -> String {
+        let mm = self.molpm3;
+        if mm == 0.0 || !mm.is_finite() {
+            return format!("{} mM", mm);
+        }
+
+        // Round first so values like 999.9 µM are promoted to "1.00 mM"
+        let magnitude = mm.abs().log10().floor() as i32;
+        let scale = 10f64.powi(SIG_FIGS - 1 - magnitude);
+        let rounded = (mm * scale).round() / scale;
+
+        let (unit, size) = DISPLAY_UNITS
+            .iter()
+            .find(|(_, size)| rounded.abs() >= *size)
+            .unwrap_or(&DISPLAY_UNITS[DISPLAY_UNITS.len() - 1]);
+
+        let value = rounded / size;
+        let decimals = (SIG_FIGS - 1 - value.abs().log10().floor() as i32).max(0) as usize;
+        format!("{:.*} {}", decimals, value, unit)
+    }
+}
+
+mod tests {
+    use crate::substance::SubstanceConcentration;
+
+    use super::ConcentrationDisplay;
+
+    #[test]
+    fn human_string() {
+        assert_eq!(SubstanceConcentration::from_M(2.5).to_human_string(), "2.50 M");
+        assert_eq!(SubstanceConcentration::from_mM(1.2345).to_human_string(), "1.23 mM");
+        assert_eq!(SubstanceConcentration::from_mM(12.0).to_human_string(), "12.0 mM");
+        assert_eq!(SubstanceConcentration::from_uM(450.0).to_human_string(), "450 µM");
+        assert_eq!(SubstanceConcentration::from_uM(4.567).to_human_string(), "4.57 µM");
+        assert_eq!(SubstanceConcentration::from_nM(78.91).to_human_string(), "78.9 nM");
+        assert_eq!(SubstanceConcentration::from_nM(0.05).to_human_string(), "0.0500 nM");
+        assert_eq!(SubstanceConcentration::from_mM(0.0).to_human_string(), "0 mM");
+
+        // Rounding up crosses into the next unit
+        assert_eq!(SubstanceConcentration::from_uM(999.9).to_human_string(), "1.00 mM");
+
+        // Negative changes keep their sign
+        assert_eq!(SubstanceConcentration::from_uM(-20.0).to_human_string(), "-20.0 µM");
+    }
+}
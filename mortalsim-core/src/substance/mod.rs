@@ -1,5 +1,8 @@
 mod change;
 mod concentration_tracker;
+mod display;
+mod rate;
+mod record;
 mod store;
 mod substance;
 pub mod substance_wrapper;
@@ -7,6 +10,9 @@ use std::sync::OnceLock;
 
 pub use change::SubstanceChange;
 pub use concentration_tracker::ConcentrationTracker;
+pub use display::ConcentrationDisplay;
+pub use rate::ConcentrationRate;
+pub use record::SubstanceChangeRecord;
 pub use store::SubstanceStore;
 pub use substance::Substance;
 
@@ -0,0 +1,42 @@
+use std::fmt;
+
+use super::SubstanceConcentration;
+use crate::SimTimeSpan;
+
+/// Rate of change of a Substance concentration, in mol/m^3 (mM) per second
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct ConcentrationRate {
+    pub molpm3ps: f64,
+}
+
+#[allow(non_snake_case)]
+impl ConcentrationRate {
+    /// Creates a new ConcentrationRate in mM per second
+    pub fn from_mM_per_s(rate: f64) -> Self {
+        Self { molpm3ps: rate }
+    }
+
+    /// Creates a new ConcentrationRate in mM per minute
+    pub fn from_mM_per_min(rate: f64) -> Self {
+        Self { molpm3ps: rate / 60.0 }
+    }
+
+    /// Returns the rate in mM per second
+    pub fn to_mM_per_s(&self) -> f64 {
+        self.molpm3ps
+    }
+
+    /// Concentration change over the given span of time at this rate
+    ///
+    /// ### Arguments
+    /// * `span` - span of time over which the rate applies
+    pub fn over(&self, span: SimTimeSpan) -> SubstanceConcentration {
+        SubstanceConcentration::from_mM(self.molpm3ps * span.to_s())
+    }
+}
+
+impl fmt::Display for ConcentrationRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} mM/s", self.molpm3ps)
+    }
+}
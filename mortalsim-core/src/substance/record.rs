@@ -0,0 +1,113 @@
+use anyhow::Result;
+
+use super::{SubstanceChange, SubstanceConcentration};
+use crate::math::BoundFn;
+use crate::sim::SimTime;
+use crate::SimTimeSpan;
+
+/// Plain data representation of a `SubstanceChange`, for recording
+/// scheduled changes to a log and replaying them later. With the `serde`
+/// feature, both the record and `SubstanceChange` itself serialize as e.g.
+/// `{"start_s":1.0,"amount":{"molpm3":0.5},"duration_s":10.0,"shape":{"Exponential":{"rate":2.0}}}`
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SubstanceChangeRecord {
+    pub start_s: f64,
+    pub amount: SubstanceConcentration,
+    pub duration_s: f64,
+    pub shape: BoundFn,
+}
+
+impl SubstanceChangeRecord {
+    /// Reconstructs the recorded change
+    ///
+    /// Returns an Err Result if the recorded duration isn't positive
+    pub fn to_change(&self) -> Result<SubstanceChange> {
+        if self.duration_s.is_nan() || self.duration_s <= 0.0 {
+            return Err(anyhow!("SubstanceChange duration must be greater than 0, got {}", self.duration_s));
+        }
+        Ok(SubstanceChange::new(
+            SimTime::from_s(self.start_s),
+            self.amount,
+            SimTimeSpan::from_s(self.duration_s),
+            self.shape,
+        ))
+    }
+}
+
+impl From<&SubstanceChange> for SubstanceChangeRecord {
+    fn from(change: &SubstanceChange) -> Self {
+        Self {
+            start_s: change.start_time().to_s(),
+            amount: change.amount(),
+            duration_s: change.duration().to_s(),
+            shape: change.bound_fn(),
+        }
+    }
+}
+
+impl From<SubstanceChange> for SubstanceChangeRecord {
+    fn from(change: SubstanceChange) -> Self {
+        Self::from(&change)
+    }
+}
+
+impl TryFrom<SubstanceChangeRecord> for SubstanceChange {
+    type Error = anyhow::Error;
+
+    fn try_from(record: SubstanceChangeRecord) -> Result<Self> {
+        record.to_change()
+    }
+}
+
+#[cfg(feature = "serde")]
+mod tests {
+    use crate::math::BoundFn;
+    use crate::substance::{SubstanceChange, SubstanceConcentration};
+    use crate::{SimTime, SimTimeSpan};
+
+    use super::SubstanceChangeRecord;
+
+    #[test]
+    fn json_round_trip() {
+        for shape in [
+            BoundFn::Linear,
+            BoundFn::Sigmoid,
+            BoundFn::Exponential { rate: -2.5 },
+            BoundFn::Logistic { steepness: 12.0, midpoint: 0.25 },
+        ] {
+            let change = SubstanceChange::new(
+                SimTime::from_s(12.5),
+                SubstanceConcentration::from_uM(-450.0),
+                SimTimeSpan::from_s(30.0),
+                shape,
+            );
+            let record = SubstanceChangeRecord::from(&change);
+
+            // SubstanceChange serializes through its record
+            let json = serde_json::to_string(&change).unwrap();
+            assert_eq!(json, serde_json::to_string(&record).unwrap());
+
+            let parsed: SubstanceChange = serde_json::from_str(&json).unwrap();
+            assert_eq!(SubstanceChangeRecord::from(&parsed), record);
+        }
+
+        let amount = SubstanceConcentration::from_mM(1.5);
+        let parsed: SubstanceConcentration = serde_json::from_str(&serde_json::to_string(&amount).unwrap()).unwrap();
+        assert_eq!(parsed, amount);
+    }
+
+    #[test]
+    fn json_errors() {
+        assert!(serde_json::from_str::<SubstanceChange>("[]").is_err());
+        assert!(serde_json::from_str::<SubstanceChange>(
+            r#"{"start_s":1,"amount":{"molpm3":1},"duration_s":1}"#
+        ).is_err());
+        assert!(serde_json::from_str::<SubstanceChange>(
+            r#"{"start_s":1,"amount":{"molpm3":1},"duration_s":1,"shape":"Custom"}"#
+        ).is_err());
+        assert!(serde_json::from_str::<SubstanceChange>(
+            r#"{"start_s":1,"amount":{"molpm3":1},"duration_s":0,"shape":"Linear"}"#
+        ).is_err());
+    }
+}
@@ -1,18 +1,25 @@
 use super::change::{self, DependentSubstanceChange, SubstanceChangeItem};
-use super::{SubstanceChange, SubstanceConcentration};
+use super::{ConcentrationRate, SubstanceChange, SubstanceConcentration};
 use crate::sim::SimTime;
 use crate::substance::Substance;
 use crate::id_gen::{IdGenerator, IdType};
 use crate::math::BoundFn;
 use crate::{secs, SimTimeSpan};
 use core::panic;
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt;
-use std::mem::swap;
+use std::mem::{swap, take};
 use std::sync::OnceLock;
 
 static ZERO_CONCENTRATION: OnceLock<SubstanceConcentration> = OnceLock::new();
 
+/// Number of samples taken when searching for a predicted crossing
+const PREDICTION_SAMPLES: usize = 100;
+/// Number of bisections used to refine a predicted crossing
+const PREDICTION_BISECTIONS: usize = 30;
+/// Number of advances for which concentration deltas are retained
+const STABILITY_HISTORY: usize = 256;
+
 #[derive(Clone)]
 /// A storage construct for Substance concentrations in a volume
 pub struct SubstanceStore {
@@ -27,13 +34,25 @@ pub struct SubstanceStore {
     /// Keep track of any Substances which are changing
     dependent_changes: HashMap<Substance, VecDeque<DependentSubstanceChange>>,
     /// Keep track of staged changes, which will be "new" on the next advance
-    staged_changes: HashMap<Substance, IdType>,
+    staged_changes: HashMap<Substance, Vec<IdType>>,
     /// Keep track of newly added change ids
-    new_changes: HashMap<Substance, IdType>,
+    new_changes: HashMap<Substance, Vec<IdType>>,
     /// Keep track of the solute percentage to ensure validity
     solute_pct: f64,
     /// whether to track new changes or not
     track_changes: bool,
+    /// Registered (min, max) concentration bounds for Substances
+    bounds: HashMap<Substance, (SubstanceConcentration, SubstanceConcentration)>,
+    /// Registered maximum rates of change for Substances
+    max_rates: HashMap<Substance, ConcentrationRate>,
+    /// Tags attached to scheduled changes
+    change_tags: HashMap<IdType, &'static str>,
+    /// Largest absolute concentration delta of each recent advance
+    recent_deltas: VecDeque<(SimTime, SubstanceConcentration)>,
+    /// Concentrations of each changing Substance before the last advance
+    advance_start: HashMap<Substance, SubstanceConcentration>,
+    /// Substances with direct changes scheduled since they were last taken
+    scheduled_substances: HashSet<Substance>,
 }
 
 impl fmt::Debug for SubstanceStore {
@@ -66,6 +85,20 @@ impl SubstanceStore {
         ZERO_CONCENTRATION.get_or_init(|| SubstanceConcentration::from_M(0.0))
     }
 
+    fn clamp_concentration(
+        val: SubstanceConcentration,
+        min: SubstanceConcentration,
+        max: SubstanceConcentration,
+    ) -> SubstanceConcentration {
+        if val < min {
+            min
+        } else if val > max {
+            max
+        } else {
+            val
+        }
+    }
+
     /// Constructs a new Substance store
     pub fn new() -> SubstanceStore {
         Self::new_impl(false)
@@ -88,6 +121,12 @@ impl SubstanceStore {
             new_changes: HashMap::new(),
             solute_pct: 0.0,
             track_changes,
+            bounds: HashMap::new(),
+            max_rates: HashMap::new(),
+            change_tags: HashMap::new(),
+            recent_deltas: VecDeque::new(),
+            advance_start: HashMap::new(),
+            scheduled_substances: HashSet::new(),
         }
     }
 
@@ -119,6 +158,108 @@ impl SubstanceStore {
         }
     }
 
+    /// Predicts the concentration of a given Substance at a future time
+    /// from the currently scheduled changes. Concentration bounds are not
+    /// taken into account.
+    ///
+    /// ### Arguments
+    /// * `substance` - Substance to predict
+    /// * `time`      - Simulation time to predict the concentration at
+    ///
+    /// Returns the predicted concentration of the substance
+    pub fn predict_concentration(&self, substance: &Substance, time: SimTime) -> SubstanceConcentration {
+        let mut conc = self.concentration_of(substance);
+        let direct = self.substance_changes.get(substance).into_iter().flat_map(|cm| cm.values());
+        for change in direct {
+            if change.start_time() < time {
+                conc += change.pending_amount(time);
+            }
+        }
+        for change in self.dependent_changes.get(substance).into_iter().flatten() {
+            if change.start_time() < time {
+                conc += change.pending_amount(time);
+            }
+        }
+        conc
+    }
+
+    /// Predicts the first time after the current simulation time at which
+    /// the concentration of a given Substance falls below a level, from the
+    /// currently scheduled changes.
+    ///
+    /// ### Arguments
+    /// * `substance` - Substance to predict
+    /// * `level`     - Concentration level to check against
+    ///
+    /// Returns the predicted crossing time, or `None` if the scheduled
+    /// changes never bring the concentration below the level
+    pub fn predict_time_below(&self, substance: &Substance, level: SubstanceConcentration) -> Option<SimTime> {
+        // Nothing changes after the last scheduled change completes
+        let direct = self.substance_changes.get(substance).into_iter()
+            .flat_map(|cm| cm.values())
+            .map(|c| c.start_time() + c.duration());
+        let dependent = self.dependent_changes.get(substance).into_iter()
+            .flatten()
+            .map(|c| c.start_time() + c.duration());
+        let end_time = direct.chain(dependent).max()?;
+        if end_time <= self.sim_time {
+            return None;
+        }
+
+        // Sample to find the first interval containing a crossing, then
+        // narrow it down by bisection
+        let below = |t: f64| self.predict_concentration(substance, SimTime::from_s(t)) < level;
+        let start = self.sim_time.to_s();
+        let step = (end_time.to_s() - start) / PREDICTION_SAMPLES as f64;
+        let mut lower = start;
+        let mut upper = (1..=PREDICTION_SAMPLES)
+            .map(|i| start + step * i as f64)
+            .find(|t| {
+                if below(*t) {
+                    return true;
+                }
+                lower = *t;
+                false
+            })?;
+
+        for _ in 0..PREDICTION_BISECTIONS {
+            let mid = (lower + upper) / 2.0;
+            if below(mid) {
+                upper = mid;
+            } else {
+                lower = mid;
+            }
+        }
+        Some(SimTime::from_s(upper))
+    }
+
+    /// Whether the composition of the store has settled, i.e. no scheduled
+    /// changes remain and no concentration has changed by more than the
+    /// given tolerance on any advance within the given span. Only the most
+    /// recent advances are retained, so very long spans are truncated.
+    ///
+    /// ### Arguments
+    /// * `tolerance` - Largest concentration delta considered stable
+    /// * `over`      - Span of recent simulation time to check
+    ///
+    /// Returns true if the store is stable
+    pub fn is_stable(&self, tolerance: SubstanceConcentration, over: SimTimeSpan) -> bool {
+        let has_direct = self.substance_changes.values().any(|cm| !cm.is_empty());
+        let has_dependent = self.dependent_changes.values()
+            .flatten()
+            .any(|c| !c.is_cancelled(self.sim_time));
+        if has_direct || has_dependent {
+            return false;
+        }
+
+        let since = self.sim_time - over;
+        self.recent_deltas
+            .iter()
+            .rev()
+            .take_while(|(time, _)| *time > since)
+            .all(|(_, delta)| *delta <= tolerance)
+    }
+
     /// sets the concentration of a given Substance in the store.
     ///
     /// ### Arguments
@@ -132,6 +273,16 @@ impl SubstanceStore {
         if concentration < SubstanceConcentration::from_M(0.0) {
             return Err(anyhow!("Concentration must be a positive value."));
         }
+        if let Some((min, max)) = self.bounds.get(&substance) {
+            if concentration < *min || concentration > *max {
+                return Err(anyhow!("Invalid concentration. {} of {} is outside of the registered bounds {} -> {}.",
+                    concentration,
+                    substance,
+                    min,
+                    max,
+                ));
+            }
+        }
         let concentration_change = concentration - self.concentration_of(&substance);
         let pct_change = concentration_change.molpm3*substance.molar_volume().m3_per_mol;
         if self.solute_pct + pct_change > 1.0 {
@@ -145,6 +296,116 @@ impl SubstanceStore {
         Ok(())
     }
 
+    /// Registers minimum and maximum concentration bounds for a Substance.
+    /// All scheduled changes on the Substance are clamped to these bounds
+    /// as they are executed. If the current concentration falls outside of
+    /// the new bounds, it is immediately clamped to the nearest bound.
+    ///
+    /// Changes which are in progress when the bounds change continue from
+    /// the (possibly clamped) current concentration. Any portion of a change
+    /// which was lost to clamping is not recovered if the bounds are later
+    /// relaxed.
+    ///
+    /// ### Arguments
+    /// * `substance` - Substance to bound
+    /// * `min`       - minimum allowed concentration
+    /// * `max`       - maximum allowed concentration
+    pub fn set_concentration_bounds(
+        &mut self,
+        substance: Substance,
+        min: SubstanceConcentration,
+        max: SubstanceConcentration,
+    ) -> anyhow::Result<()> {
+        if min < SubstanceConcentration::from_M(0.0) || min > max {
+            return Err(anyhow!("Invalid bounds {} -> {} for {}.", min, max, substance));
+        }
+        self.bounds.insert(substance, (min, max));
+
+        let cur_conc = self.concentration_of(&substance);
+        let clamped = Self::clamp_concentration(cur_conc, min, max);
+        if clamped != cur_conc {
+            log::debug!("Clamping {} from {} to {} for new bounds", substance, cur_conc, clamped);
+            self.solute_pct += (clamped - cur_conc).molpm3*substance.molar_volume().m3_per_mol;
+            self.composition.insert(substance, clamped);
+        }
+        Ok(())
+    }
+
+    /// Retrieves the registered concentration bounds for a Substance, if any.
+    ///
+    /// ### Arguments
+    /// * `substance` - Substance to retrieve bounds for
+    ///
+    /// Returns the (min, max) bounds of the Substance
+    pub fn concentration_bounds(&self, substance: &Substance) -> Option<(SubstanceConcentration, SubstanceConcentration)> {
+        self.bounds.get(substance).copied()
+    }
+
+    /// Removes any registered concentration bounds for a Substance.
+    ///
+    /// ### Arguments
+    /// * `substance` - Substance to remove bounds for
+    pub fn clear_concentration_bounds(&mut self, substance: &Substance) {
+        self.bounds.remove(substance);
+    }
+
+    /// Registers a maximum rate of change for a Substance. Any change
+    /// scheduled afterward whose peak rate would exceed the maximum is
+    /// stretched in duration so that it doesn't. Changes which were
+    /// already scheduled, and dependent changes, are unaffected.
+    ///
+    /// ### Arguments
+    /// * `substance` - Substance to limit
+    /// * `max_rate`  - maximum allowed rate of change, in either direction
+    pub fn set_max_rate(&mut self, substance: Substance, max_rate: ConcentrationRate) -> anyhow::Result<()> {
+        if max_rate.molpm3ps.is_nan() || max_rate.molpm3ps <= 0.0 {
+            return Err(anyhow!("Invalid maximum rate {} for {}.", max_rate, substance));
+        }
+        self.max_rates.insert(substance, max_rate);
+        Ok(())
+    }
+
+    /// Retrieves the registered maximum rate of change for a Substance, if any.
+    ///
+    /// ### Arguments
+    /// * `substance` - Substance to retrieve the maximum rate for
+    pub fn max_rate(&self, substance: &Substance) -> Option<ConcentrationRate> {
+        self.max_rates.get(substance).copied()
+    }
+
+    /// Removes any registered maximum rate of change for a Substance.
+    ///
+    /// ### Arguments
+    /// * `substance` - Substance to remove the maximum rate for
+    pub fn clear_max_rate(&mut self, substance: &Substance) {
+        self.max_rates.remove(substance);
+    }
+
+    /// Stretches the duration of a change whose peak rate would exceed the
+    /// registered maximum rate for the Substance, if any
+    fn limit_rate(&self, substance: Substance, change: SubstanceChange) -> SubstanceChange {
+        if let Some(max_rate) = self.max_rates.get(&substance) {
+            let min_duration = change.amount().molpm3.abs() * change.bound_fn().peak_slope() / max_rate.molpm3ps;
+            if min_duration > change.duration().to_s() {
+                log::debug!(
+                    "Stretching change of {} on {} from {} s to {} s to respect the maximum rate of {}",
+                    change.amount(),
+                    substance,
+                    change.duration().to_s(),
+                    min_duration,
+                    max_rate,
+                );
+                return SubstanceChange::new(
+                    change.start_time(),
+                    change.amount(),
+                    SimTimeSpan::from_s(min_duration),
+                    change.bound_fn(),
+                );
+            }
+        }
+        change
+    }
+
     /// Retrieves the current composition as a HashMap
     ///
     /// ### Arguments
@@ -173,6 +434,10 @@ impl SubstanceStore {
 
     /// Schedule a substance change on this store
     /// with a custom shape over the given duration.
+    /// The change is stretched if it would exceed the
+    /// Substance's maximum rate, if one is registered.
+    /// Changes on the same Substance are additive, so
+    /// overlapping changes superpose.
     ///
     /// Panics if `start_time < sim_time`
     ///
@@ -190,6 +455,7 @@ impl SubstanceStore {
         if change.start_time() < self.sim_time {
             panic!("start_time cannot be less than the current sim time!");
         }
+        let change = self.limit_rate(substance, change);
 
         log::debug!("Scheduling substance change for {}: {:?}", substance, change);
 
@@ -198,15 +464,66 @@ impl SubstanceStore {
             .entry(substance)
             .or_default()
             .insert(change_id, change);
+        self.scheduled_substances.insert(substance);
         
         if self.track_changes {
             log::debug!("Tracking changes to {}", substance);
-            self.staged_changes.insert(substance, change_id);
+            self.staged_changes.entry(substance).or_default().push(change_id);
         }
 
         change_id
     }
 
+    /// Schedule a substance change on this store which replaces all
+    /// other direct changes on the Substance, rather than adding to
+    /// them. The remainder of each replaced change is cancelled as of
+    /// the current time, while any portion already executed is kept.
+    /// Dependent changes are unaffected.
+    ///
+    /// Panics if `start_time < sim_time`
+    ///
+    /// ### Arguments
+    /// * `substance`  - the substance to change
+    /// * `change`     - change to execute
+    ///
+    /// Returns an id corresponding to this change
+    pub fn schedule_change_replace(
+        &mut self,
+        substance: Substance,
+        change: SubstanceChange,
+    ) -> IdType {
+        if let Some(change_map) = self.substance_changes.get_mut(&substance) {
+            for (change_id, replaced) in change_map.drain() {
+                log::debug!("Replacing change {} on substance {}", change_id, substance);
+                replaced.cancel(self.sim_time);
+                self.change_tags.remove(&change_id);
+            }
+        }
+        self.schedule_change(substance, change)
+    }
+
+    /// Schedule a substance change on this store with a tag,
+    /// which can later be used to cancel it with `cancel_tagged`.
+    ///
+    /// Panics if `start_time < sim_time`
+    ///
+    /// ### Arguments
+    /// * `substance`  - the substance to change
+    /// * `change`     - change to execute
+    /// * `tag`        - tag to attach to the change
+    ///
+    /// Returns an id corresponding to this change
+    pub fn schedule_tagged_change(
+        &mut self,
+        substance: Substance,
+        change: SubstanceChange,
+        tag: &'static str,
+    ) -> IdType {
+        let change_id = self.schedule_change(substance, change);
+        self.change_tags.insert(change_id, tag);
+        change_id
+    }
+
     /// Schedule a dependent substance change on this store
     /// equal to a change on a different store with a given delay.
     ///
@@ -224,6 +541,17 @@ impl SubstanceStore {
         start_time: SimTime,
         factor: f64,
         change: &SubstanceChange,
+    ) {
+        self.push_dependent_change(substance, start_time, factor, change, None);
+    }
+
+    fn push_dependent_change(
+        &mut self,
+        substance: Substance,
+        start_time: SimTime,
+        factor: f64,
+        change: &SubstanceChange,
+        tag: Option<&'static str>,
     ) {
         // Constrain the start time to a minimum of the current sim time
         if start_time < self.sim_time {
@@ -238,13 +566,80 @@ impl SubstanceStore {
             change
         );
 
-        let dep_change = DependentSubstanceChange::new(start_time, factor, change);
+        let mut dep_change = DependentSubstanceChange::new(start_time, factor, change);
+        if let Some(tag) = tag {
+            dep_change = dep_change.with_tag(tag);
+        }
         self.dependent_changes
             .entry(substance)
             .or_default()
             .push_back(dep_change);
     }
 
+    /// Schedule a dependent substance change on this store with a tag,
+    /// which can later be used to cancel it with `cancel_tagged`.
+    ///
+    /// Panics if `start_time < sim_time` or `start_time <= change.start_time()`
+    ///
+    /// ### Arguments
+    /// * `substance`  - the substance to change
+    /// * `start_time` - simulation time to start the change
+    /// * `factor`     - factor to apply to the original change
+    /// * `change`     - change to duplicate on this store
+    /// * `tag`        - tag to attach to the change
+    pub fn schedule_tagged_dependent_change(
+        &mut self,
+        substance: Substance,
+        start_time: SimTime,
+        factor: f64,
+        change: &SubstanceChange,
+        tag: &'static str,
+    ) {
+        self.push_dependent_change(substance, start_time, factor, change, Some(tag));
+    }
+
+    /// Cancels all pending changes on this store with the given tag,
+    /// leaving untagged changes and changes with other tags in place.
+    /// Cancelled direct changes also stop any dependent changes which
+    /// duplicate them elsewhere.
+    ///
+    /// ### Arguments
+    /// * `tag` - tag given when the changes were scheduled
+    ///
+    /// Returns the number of changes cancelled
+    pub fn cancel_tagged(&mut self, tag: &str) -> usize {
+        let mut cancelled = 0;
+
+        let tagged_ids: Vec<IdType> = self
+            .change_tags
+            .iter()
+            .filter(|(_, t)| **t == tag)
+            .map(|(id, _)| *id)
+            .collect();
+
+        for change_map in self.substance_changes.values_mut() {
+            for change_id in tagged_ids.iter() {
+                if let Some(change) = change_map.remove(change_id) {
+                    change.cancel(self.sim_time);
+                    cancelled += 1;
+                }
+            }
+        }
+
+        for change_id in tagged_ids {
+            self.change_tags.remove(&change_id);
+        }
+
+        for change_list in self.dependent_changes.values_mut() {
+            let prev_len = change_list.len();
+            change_list.retain(|c| c.tag() != Some(tag));
+            cancelled += prev_len - change_list.len();
+        }
+
+        log::debug!("Cancelled {} changes tagged {}", cancelled, tag);
+        cancelled
+    }
+
     /// Get a reference to a previously added `SubstanceChange`
     ///
     /// ### Arguments
@@ -275,6 +670,12 @@ impl SubstanceStore {
         !self.new_changes.is_empty()
     }
 
+    /// Takes the Substances which have had direct changes scheduled since
+    /// the last call, e.g. to attribute them to the component running
+    pub(crate) fn take_scheduled_substances(&mut self) -> impl Iterator<Item = Substance> + '_ {
+        self.scheduled_substances.drain()
+    }
+
 
     /// Get an iterator to all newly added `SubstanceChange`s
     /// since the last time the method was called
@@ -287,12 +688,17 @@ impl SubstanceStore {
         // Create a reference of the changes that the iterator can own
         let new_changes = &self.new_changes;
 
-        let results = self.substance_changes
+        // Only include the new changes, not those previously retrieved
+        self.substance_changes
             .iter()
-            .filter(move |(s, cm)| new_changes.get(s).is_some_and(|id| cm.contains_key(id)))
-            .map(|(s, cm)| cm.values().map(move |c| (*s, c))).flatten();
-
-        results
+            .flat_map(move |(s, cm)| {
+                new_changes
+                    .get(s)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(move |id| cm.get(id))
+                    .map(move |c| (*s, c))
+            })
     }
 
     /// Unschedule a substance change on this store
@@ -312,6 +718,7 @@ impl SubstanceStore {
             substance,
             change_id,
         );
+        self.change_tags.remove(change_id);
         self.substance_changes
             .entry(*substance)
             .or_default()
@@ -325,15 +732,67 @@ impl SubstanceStore {
 
     /// Clear all scheduled changes on this store
     pub fn clear_all_changes(&mut self) {
+        self.change_tags.clear();
         self.substance_changes.clear()
     }
 
+    /// Concentrations of each Substance which was changing before the
+    /// last advance, as they were at the start of it. The concentrations
+    /// of all other Substances were unchanged by the advance.
+    pub(crate) fn advance_start(&self) -> &HashMap<Substance, SubstanceConcentration> {
+        &self.advance_start
+    }
+
+    /// Restores any non-finite concentrations, e.g. from a change with a
+    /// NaN amount, to their values before the last advance, or to zero if
+    /// those weren't finite either. All scheduled changes on those
+    /// Substances are dropped so they can't recur, and the solute
+    /// percentage is recalculated afterward.
+    ///
+    /// Returns each affected Substance along with its non-finite concentration
+    pub(crate) fn reset_non_finite(&mut self) -> Vec<(Substance, SubstanceConcentration)> {
+        let invalid: Vec<(Substance, SubstanceConcentration)> = self.composition
+            .iter()
+            .filter(|(_, concentration)| !concentration.molpm3.is_finite())
+            .map(|(substance, concentration)| (*substance, *concentration))
+            .collect();
+
+        if invalid.is_empty() {
+            return invalid;
+        }
+
+        for (substance, _) in invalid.iter() {
+            if let Some(change_map) = self.substance_changes.remove(substance) {
+                for (change_id, change) in change_map {
+                    change.cancel(self.sim_time);
+                    self.change_tags.remove(&change_id);
+                }
+            }
+            self.dependent_changes.remove(substance);
+
+            let restored = self
+                .advance_start
+                .get(substance)
+                .copied()
+                .filter(|concentration| concentration.molpm3.is_finite())
+                .unwrap_or(*Self::zero_concentration());
+            self.composition.insert(*substance, restored);
+        }
+
+        self.solute_pct = self.composition
+            .iter()
+            .map(|(substance, concentration)| concentration.molpm3*substance.molar_volume().m3_per_mol)
+            .sum();
+        invalid
+    }
+
     fn process_change(
         sim_time: SimTime,
         substance: &Substance,
         change: &mut dyn SubstanceChangeItem,
         solute_pct: f64,
         composition: &mut HashMap<Substance, SubstanceConcentration>,
+        bounds: Option<&(SubstanceConcentration, SubstanceConcentration)>,
     ) -> f64 {
         if change.start_time() < sim_time {
             // Change we need to add is the function value at the current time
@@ -355,8 +814,16 @@ impl SubstanceStore {
                 new_conc = SubstanceConcentration::from_M(0.0);
             }
 
+            // Clamp to any registered bounds
+            if let Some((min, max)) = bounds {
+                if new_conc > *max || new_conc < *min {
+                    log::trace!("Clamping {} concentration {} to bounds {} -> {}", substance, new_conc, min, max);
+                    new_conc = Self::clamp_concentration(new_conc, *min, *max);
+                }
+            }
+
             // Check to make sure new concentration doesn't exceed possible solute volume
-            let change_pct = change_amt.molpm3*substance.molar_volume().m3_per_mol;
+            let change_pct = (new_conc - *prev_conc).molpm3*substance.molar_volume().m3_per_mol;
             if solute_pct + change_pct > 1.0 {
                 log::warn!(
                     "Substance change attempted to set an invalid solute concentration for {}: {}\n{}\n{}",
@@ -384,6 +851,14 @@ impl SubstanceStore {
     /// ### Arguments
     /// * `sim_time` - the new simulation time
     pub(crate) fn advance(&mut self, sim_time: SimTime) {
+        // Reuse the buffer from the previous advance for the starting values
+        let mut previous = take(&mut self.advance_start);
+        previous.clear();
+        previous.extend(
+            self.substance_changes.keys()
+                .chain(self.dependent_changes.keys())
+                .map(|s| (*s, self.concentration_of(s)))
+        );
 
         if self.track_changes {
             swap(&mut self.staged_changes, &mut self.new_changes);
@@ -398,7 +873,8 @@ impl SubstanceStore {
                     substance,
                     change,
                     self.solute_pct,
-                    &mut self.composition
+                    &mut self.composition,
+                    self.bounds.get(substance),
                 );
 
                 if sim_time > change.start_time() + change.duration() {
@@ -408,6 +884,7 @@ impl SubstanceStore {
 
             for change_id in ids_to_remove {
                 let removed = change_map.remove(&change_id);
+                self.change_tags.remove(&change_id);
                 self.id_gen.return_id(change_id).expect("Returning invalid id");
                 log::debug!(
                     "Change {} on substance {} has completed: {:?}",
@@ -425,7 +902,8 @@ impl SubstanceStore {
                     substance,
                     change,
                     self.solute_pct,
-                    &mut self.composition
+                    &mut self.composition,
+                    self.bounds.get(substance),
                 );
             }
 
@@ -434,6 +912,18 @@ impl SubstanceStore {
             }
         }
 
+        let max_delta = previous.iter()
+            .map(|(substance, prev)| {
+                let delta = self.concentration_of(substance) - *prev;
+                if delta < SubstanceConcentration::from_M(0.0) { -delta } else { delta }
+            })
+            .fold(SubstanceConcentration::from_M(0.0), |max, d| if d > max { d } else { max });
+        if self.recent_deltas.len() == STABILITY_HISTORY {
+            self.recent_deltas.pop_front();
+        }
+        self.recent_deltas.push_back((sim_time, max_delta));
+
+        self.advance_start = previous;
         self.sim_time = sim_time;
     }
 }
@@ -593,4 +1083,106 @@ mod tests {
             expected_atp2
         );
     }
+
+    #[test]
+    fn shaped_changes() {
+        let mut store = SubstanceStore::new();
+        let (rate, steepness, midpoint) = (4.0, 8.0, 0.4);
+        store.schedule_change(
+            Substance::ADP,
+            SubstanceChange::new(
+                secs!(0.0),
+                mmol_per_L!(1.0),
+                SimTimeSpan::from_s(10.0),
+                BoundFn::Exponential { rate },
+            ),
+        );
+        store.schedule_change(
+            Substance::ATP,
+            SubstanceChange::new(
+                secs!(0.0),
+                mmol_per_L!(1.0),
+                SimTimeSpan::from_s(10.0),
+                BoundFn::Logistic { steepness, midpoint },
+            ),
+        );
+
+        let exponential = |p: f64| (1.0 - f64::exp(-rate * p)) / (1.0 - f64::exp(-rate));
+        let logistic = |p: f64| 1.0 / (1.0 + f64::exp(-steepness * (p - midpoint)));
+        let logistic_norm = |p: f64| (logistic(p) - logistic(0.0)) / (logistic(1.0) - logistic(0.0));
+
+        for t in [1.0, 2.5, 4.0, 5.0, 7.5, 9.0, 10.0] {
+            store.advance(secs!(t));
+            let p = t / 10.0;
+
+            let adp_conc = store.concentration_of(&Substance::ADP);
+            let expected_adp = mmol_per_L!(exponential(p));
+            assert!(
+                (adp_conc - expected_adp).molpm3.abs() < 0.0001,
+                "Incorrect ADP concentration at {}s, found {:?}, expected {:?}",
+                t,
+                adp_conc,
+                expected_adp
+            );
+
+            let atp_conc = store.concentration_of(&Substance::ATP);
+            let expected_atp = mmol_per_L!(logistic_norm(p));
+            assert!(
+                (atp_conc - expected_atp).molpm3.abs() < 0.0001,
+                "Incorrect ATP concentration at {}s, found {:?}, expected {:?}",
+                t,
+                atp_conc,
+                expected_atp
+            );
+        }
+    }
+
+    #[test]
+    fn new_direct_changes() {
+        let mut store = SubstanceStore::new_tracking();
+        let change = |start: f64| {
+            SubstanceChange::new(secs!(start), mmol_per_L!(1.0), SimTimeSpan::from_s(1.0), BoundFn::Linear)
+        };
+        store.schedule_change(Substance::GLC, change(1.0));
+        store.advance(secs!(1.0));
+        assert_eq!(store.get_new_direct_changes().count(), 1);
+
+        // Only the changes scheduled since the previous advance are new,
+        // including each of several on the same Substance
+        store.schedule_change(Substance::GLC, change(2.0));
+        store.schedule_change(Substance::GLC, change(2.5));
+        store.advance(secs!(2.0));
+        let mut starts: Vec<f64> = store.get_new_direct_changes().map(|(_, c)| c.start_time().to_s()).collect();
+        starts.sort_by(f64::total_cmp);
+        assert_eq!(starts, vec![2.0, 2.5]);
+
+        store.advance(secs!(3.0));
+        assert!(!store.has_new_changes());
+        assert_eq!(store.get_new_direct_changes().count(), 0);
+    }
+
+    #[test]
+    fn stability() {
+        let mut store = SubstanceStore::new();
+        let tolerance = SubstanceConcentration::from_uM(1.0);
+        let over = SimTimeSpan::from_s(2.0);
+        store.schedule_change(
+            Substance::GLC,
+            SubstanceChange::new(secs!(0.0), mmol_per_L!(1.0), SimTimeSpan::from_s(10.0), BoundFn::Linear),
+        );
+        assert!(!store.is_stable(tolerance, over));
+
+        for t in 1..=10 {
+            store.advance(secs!(t as f64));
+            assert!(!store.is_stable(tolerance, over), "Stable during ramp at {}s", t);
+        }
+
+        // The last ramp delta remains within the window until 12s
+        store.advance(secs!(11.0));
+        assert!(!store.is_stable(tolerance, over));
+        store.advance(secs!(12.0));
+        assert!(store.is_stable(tolerance, over));
+        assert!(!store.is_stable(tolerance, SimTimeSpan::from_s(3.0)));
+        assert!(store.is_stable(mmol_per_L!(0.2), SimTimeSpan::from_s(3.0)));
+    }
 }
@@ -169,6 +169,11 @@ pub enum Substance {
     /// Glycogen (Glycogen)
     Glycogen,
 
+    /// Inert labeled tracer, with the physical properties of L-Glucose.
+    /// Tracers with different labels are independent of one another.
+    /// Components must never consume or transform tracers, so they can be
+    /// used to follow transport through the organism.
+    Tracer(u8),
 }
 
 impl fmt::Display for Substance {
@@ -271,6 +276,7 @@ impl Substance {
             Self::Cellulose => "Cellulose",
             Self::Glycogen => "Glycogen",
 
+            Self::Tracer(_) => "Tracer",
         }
     }
 
@@ -356,6 +362,7 @@ impl Substance {
             Self::Cellulose => 0,
             Self::Glycogen => 0,
 
+            Self::Tracer(_) => 0,
         }
     }
 
@@ -441,6 +448,7 @@ impl Substance {
             Self::Cellulose => MolarMass::from_gpmol(162000.0),
             Self::Glycogen => MolarMass::from_gpmol(5404680.0),
 
+            Self::Tracer(_) => MolarMass::from_gpmol(180.156),
         }
     }
 
@@ -526,6 +534,7 @@ impl Substance {
             Self::Cellulose => Density::from_gpcc(1.5),
             Self::Glycogen => Density::from_gpcc(1.6),
 
+            Self::Tracer(_) => Density::from_gpcc(1.54),
         }
     }
 
@@ -533,4 +542,9 @@ impl Substance {
     pub fn molar_volume(&self) -> crate::units::chemical::MolarVolume<f64> {
         self.molar_mass() / self.density()
     }
+
+    /// Whether the substance is an inert `Tracer`
+    pub fn is_tracer(&self) -> bool {
+        matches!(self, Self::Tracer(_))
+    }
 }
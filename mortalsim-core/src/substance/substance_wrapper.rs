@@ -69,7 +69,8 @@ macro_rules! substance_store_wrapper {
 
         /// Schedule a substance change on a given Vessel
         /// with a sigmoid shape over the given duration,
-        /// starting immediately.
+        /// starting immediately. Changes on the same Substance
+        /// are additive, so overlapping changes superpose.
         ///
         /// Panics if `duration <= 0`
         ///
@@ -98,6 +99,7 @@ macro_rules! substance_store_wrapper {
 
         /// Schedule a substance change on this store
         /// with a custom shape over the given duration.
+        /// Changes on the same Substance are additive.
         ///
         /// ### Arguments
         /// * `substance`  - the substance to change
@@ -117,6 +119,208 @@ macro_rules! substance_store_wrapper {
             id
         }
 
+        /// Schedule a substance change with a sigmoid shape over the
+        /// given duration, starting immediately, which replaces all other
+        /// changes scheduled directly on the store for the Substance,
+        /// including those from other components, rather than adding to
+        /// them. Any portion of the replaced changes already executed is
+        /// kept.
+        ///
+        /// Panics if `duration <= 0`
+        ///
+        /// ### Arguments
+        /// * `substance`  - the substance to change
+        /// * `amount`     - total concentration change to take place
+        /// * `duration`   - amount of time over which the change takes place
+        ///
+        /// Returns an id corresponding to this change
+        pub fn schedule_change_replace(
+            &mut self,
+            substance: crate::substance::Substance,
+            amount: crate::substance::SubstanceConcentration,
+            duration: crate::sim::SimTimeSpan
+        ) -> IdType {
+            let change = crate::substance::SubstanceChange::new(
+                self.$($field_path).+.sim_time(),
+                amount,
+                duration,
+                crate::math::BoundFn::Sigmoid
+            );
+            let id = self.$($field_path).+.schedule_change_replace(substance, change);
+            self.$($id_map_path).+.insert(substance, vec![id]);
+            id
+        }
+
+        /// Schedule a substance change on this store with a tag,
+        /// which can later be used to cancel it with `cancel_tagged`.
+        ///
+        /// ### Arguments
+        /// * `substance`  - the substance to change
+        /// * `change`     - change to execute
+        /// * `tag`        - tag to attach to the change
+        ///
+        /// Returns an id corresponding to this change
+        pub fn schedule_tagged_change(
+            &mut self,
+            substance: crate::substance::Substance,
+            change: crate::substance::SubstanceChange,
+            tag: &'static str,
+        ) -> IdType {
+            let id = self.$($field_path).+.schedule_tagged_change(
+                substance,
+                change,
+                tag,
+            );
+            self.$($id_map_path).+.entry(substance).or_default().push(id);
+            id
+        }
+
+        /// Cancels all pending changes on this store with the given tag,
+        /// leaving all other changes in place.
+        ///
+        /// ### Arguments
+        /// * `tag` - tag given when the changes were scheduled
+        ///
+        /// Returns the number of changes cancelled
+        pub fn cancel_tagged(&mut self, tag: &str) -> usize {
+            let cancelled = self.$($field_path).+.cancel_tagged(tag);
+            let store = &self.$($field_path).+;
+            for (substance, ids) in self.$($id_map_path).+.iter_mut() {
+                ids.retain(|id| store.get_substance_change(substance, id).is_some());
+            }
+            cancelled
+        }
+
+        /// Predicts the concentration of a given Substance at a future time
+        /// from the currently scheduled changes. Concentration bounds are not
+        /// taken into account.
+        ///
+        /// ### Arguments
+        /// * `substance` - Substance to predict
+        /// * `time`      - Simulation time to predict the concentration at
+        ///
+        /// Returns the predicted concentration of the substance
+        pub fn predict_concentration(
+            &self,
+            substance: &crate::substance::Substance,
+            time: crate::sim::SimTime,
+        ) -> crate::substance::SubstanceConcentration {
+            self.$($field_path).+.predict_concentration(substance, time)
+        }
+
+        /// Predicts the first time after the current simulation time at which
+        /// the concentration of a given Substance falls below a level, from the
+        /// currently scheduled changes.
+        ///
+        /// ### Arguments
+        /// * `substance` - Substance to predict
+        /// * `level`     - Concentration level to check against
+        ///
+        /// Returns the predicted crossing time, if any
+        pub fn predict_time_below(
+            &self,
+            substance: &crate::substance::Substance,
+            level: crate::substance::SubstanceConcentration,
+        ) -> Option<crate::sim::SimTime> {
+            self.$($field_path).+.predict_time_below(substance, level)
+        }
+
+        /// Whether the composition of the store has settled, i.e. no scheduled
+        /// changes remain and no concentration has changed by more than the
+        /// given tolerance on any advance within the given span.
+        ///
+        /// ### Arguments
+        /// * `tolerance` - Largest concentration delta considered stable
+        /// * `over`      - Span of recent simulation time to check
+        ///
+        /// Returns true if the store is stable
+        pub fn is_stable(
+            &self,
+            tolerance: crate::substance::SubstanceConcentration,
+            over: crate::SimTimeSpan,
+        ) -> bool {
+            self.$($field_path).+.is_stable(tolerance, over)
+        }
+
+        /// Registers minimum and maximum concentration bounds for a Substance.
+        /// All changes on the Substance are clamped to these bounds as they
+        /// are executed, and the current concentration is clamped immediately.
+        ///
+        /// ### Arguments
+        /// * `substance` - Substance to bound
+        /// * `min`       - minimum allowed concentration
+        /// * `max`       - maximum allowed concentration
+        pub fn set_concentration_bounds(
+            &mut self,
+            substance: crate::substance::Substance,
+            min: crate::substance::SubstanceConcentration,
+            max: crate::substance::SubstanceConcentration,
+        ) -> anyhow::Result<()> {
+            self.$($field_path).+.set_concentration_bounds(substance, min, max)
+        }
+
+        /// Retrieves the registered concentration bounds for a Substance, if any.
+        ///
+        /// ### Arguments
+        /// * `substance` - Substance to retrieve bounds for
+        ///
+        /// Returns the (min, max) bounds of the Substance
+        pub fn concentration_bounds(
+            &self,
+            substance: &crate::substance::Substance,
+        ) -> Option<(crate::substance::SubstanceConcentration, crate::substance::SubstanceConcentration)> {
+            self.$($field_path).+.concentration_bounds(substance)
+        }
+
+        /// Retrieves the registered maximum rate of change for a Substance, if any.
+        /// Scheduled changes which would exceed it are stretched in duration.
+        ///
+        /// ### Arguments
+        /// * `substance` - Substance to retrieve the maximum rate for
+        pub fn max_rate(
+            &self,
+            substance: &crate::substance::Substance,
+        ) -> Option<crate::substance::ConcentrationRate> {
+            self.$($field_path).+.max_rate(substance)
+        }
+
+        /// Schedule a ramp from the current concentration of a Substance
+        /// toward an absolute target concentration, starting immediately.
+        ///
+        /// The ramp respects any registered concentration bounds at every
+        /// step, so a target beyond a bound saturates at the bound partway
+        /// through the ramp. If the bounds change mid-ramp, the remainder of
+        /// the ramp continues from the current concentration under the new
+        /// bounds.
+        ///
+        /// Panics if `duration <= 0`
+        ///
+        /// ### Arguments
+        /// * `substance`  - the substance to change
+        /// * `target`     - concentration to ramp toward
+        /// * `duration`   - amount of time over which the ramp takes place
+        /// * `bound_fn`   - the shape of the ramp
+        ///
+        /// Returns an id corresponding to this change
+        pub fn schedule_ramp_clamped(
+            &mut self,
+            substance: crate::substance::Substance,
+            target: crate::substance::SubstanceConcentration,
+            duration: crate::sim::SimTimeSpan,
+            bound_fn: crate::math::BoundFn,
+        ) -> IdType {
+            let amount = target - self.$($field_path).+.concentration_of(&substance);
+            self.schedule_custom_change(
+                substance,
+                crate::substance::SubstanceChange::new(
+                    self.$($field_path).+.sim_time(),
+                    amount,
+                    duration,
+                    bound_fn,
+                )
+            )
+        }
+
         /// Unschedule a substance change on this store
         ///
         /// ### Arguments
@@ -174,6 +378,34 @@ macro_rules! substance_store_wrapper {
                 change,
             )
         }
+
+        /// Schedule a dependent substance change on this store with a tag,
+        /// which can later be used to cancel it with `cancel_tagged`.
+        ///
+        /// Panics if `start_time < sim_time` or `start_time <= change.start_time()`
+        ///
+        /// ### Arguments
+        /// * `substance`  - the substance to change
+        /// * `start_time` - simulation time to start the change
+        /// * `factor`     - factor to apply to the original change
+        /// * `change`     - change to duplicate on this store
+        /// * `tag`        - tag to attach to the change
+        pub fn schedule_tagged_dependent_change(
+            &mut self,
+            substance: crate::substance::Substance,
+            start_time: crate::SimTime,
+            factor: f64,
+            change: &crate::substance::SubstanceChange,
+            tag: &'static str,
+        ) {
+            self.$($field_path).+.schedule_tagged_dependent_change(
+                substance,
+                start_time,
+                factor,
+                change,
+                tag,
+            )
+        }
     };
 }
 
@@ -8,6 +8,8 @@ use mortalsim_core::{impl_sim, sim::layer::{nervous::{Nerve, NerveIter}, Anatomi
 use strum_macros::{Display, IntoStaticStr};
 
 use mortalsim_core::sim::{layer::circulation::{BloodVessel, BloodVesselType, VesselIter}, AnatomicalRegion, Organism};
+use mortalsim_core::units::base::Mass;
+use mortalsim_core::units::geometry::Volume;
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
 pub enum SampleAnatomicalRegion {
@@ -184,6 +186,15 @@ impl Organism for SampleOrganism {
     type VesselType = SampleBloodVessel;
     type NerveType = SampleNerve;
     type AnatomyType = SampleAnatomicalRegion;
+
+    // Roughly the size of a mouse
+    fn total_blood_volume() -> Volume<f64> {
+        Volume::from_L(0.002)
+    }
+
+    fn body_mass() -> Mass<f64> {
+        Mass::from_kg(0.025)
+    }
 }
 
 impl_sim!(SampleSim, SampleOrganism);
@@ -192,3 +203,12 @@ impl_sim!(SampleSim, SampleOrganism);
 fn test_sample_sim() {
     SampleSim::new();
 }
+
+#[test]
+fn test_sample_constants() {
+    assert_eq!(SampleOrganism::total_blood_volume(), Volume::from_L(0.002));
+    assert_eq!(SampleOrganism::body_mass(), Mass::from_kg(0.025));
+
+    // Constants which aren't overridden keep their defaults
+    assert_eq!(SampleOrganism::cardiac_index(), 3.0);
+}
@@ -8,6 +8,16 @@ use mortalsim_core::sim::organism::AnatomicalRegion;
 
 #[derive(Debug, Display, Hash, Clone, Copy, PartialEq, Eq, EnumString, IntoStaticStr)]
 pub enum HumanAnatomicalRegion {
+    Body,
+    Head,
+    Trunk,
+    Back,
+    Abdomen,
+    Pelvis,
+    LeftArm,
+    RightArm,
+    LeftLeg,
+    RightLeg,
     Cranial,
     Nasal,
     LeftFacial,
@@ -75,4 +85,105 @@ pub enum HumanAnatomicalRegion {
     RightPlantar
 }
 
-impl AnatomicalRegion for HumanAnatomicalRegion {}
+impl AnatomicalRegion for HumanAnatomicalRegion {
+    fn parent(&self) -> Option<Self> {
+        match self {
+            Self::Head => Some(Self::Body),
+            Self::Trunk => Some(Self::Body),
+            Self::Back => Some(Self::Trunk),
+            Self::Abdomen => Some(Self::Trunk),
+            Self::Pelvis => Some(Self::Trunk),
+            Self::LeftArm => Some(Self::Body),
+            Self::RightArm => Some(Self::Body),
+            Self::LeftLeg => Some(Self::Body),
+            Self::RightLeg => Some(Self::Body),
+            Self::Cranial => Some(Self::Head),
+            Self::Nasal => Some(Self::Head),
+            Self::LeftFacial => Some(Self::Head),
+            Self::RightFacial => Some(Self::Head),
+            Self::Cervical => Some(Self::Body),
+            Self::LeftScapular => Some(Self::Back),
+            Self::RightScapular => Some(Self::Back),
+            Self::Dorsal => Some(Self::Back),
+            Self::Lumbar => Some(Self::Back),
+            Self::Sacral => Some(Self::Back),
+            Self::Thoracic => Some(Self::Trunk),
+            Self::LeftMammary => Some(Self::Thoracic),
+            Self::RightMammary => Some(Self::Thoracic),
+            Self::LeftUpperAbdominal => Some(Self::Abdomen),
+            Self::LeftLowerAbdominal => Some(Self::Abdomen),
+            Self::RightUpperAbdominal => Some(Self::Abdomen),
+            Self::RightLowerAbdominal => Some(Self::Abdomen),
+            Self::Umbilical => Some(Self::Abdomen),
+            Self::LeftCoxal => Some(Self::Pelvis),
+            Self::RightCoxal => Some(Self::Pelvis),
+            Self::Pubic => Some(Self::Pelvis),
+            Self::LeftAxillary => Some(Self::LeftArm),
+            Self::LeftBrachial => Some(Self::LeftArm),
+            Self::LeftAntecubital => Some(Self::LeftArm),
+            Self::LeftOlecranal => Some(Self::LeftArm),
+            Self::LeftAntebrachial => Some(Self::LeftArm),
+            Self::LeftCarpal => Some(Self::LeftArm),
+            Self::LeftPalmar => Some(Self::LeftManus),
+            Self::LeftManus => Some(Self::LeftArm),
+            Self::LeftUpperPhalangeal => Some(Self::LeftManus),
+            Self::RightAxillary => Some(Self::RightArm),
+            Self::RightBrachial => Some(Self::RightArm),
+            Self::RightAntecubital => Some(Self::RightArm),
+            Self::RightOlecranal => Some(Self::RightArm),
+            Self::RightAntebrachial => Some(Self::RightArm),
+            Self::RightCarpal => Some(Self::RightArm),
+            Self::RightPalmar => Some(Self::RightManus),
+            Self::RightManus => Some(Self::RightArm),
+            Self::RightUpperPhalangeal => Some(Self::RightManus),
+            Self::LeftInguinal => Some(Self::Pelvis),
+            Self::LeftGluteal => Some(Self::LeftLeg),
+            Self::LeftFemoral => Some(Self::LeftLeg),
+            Self::LeftPatellar => Some(Self::LeftLeg),
+            Self::LeftPopliteal => Some(Self::LeftLeg),
+            Self::LeftCrural => Some(Self::LeftLeg),
+            Self::LeftSural => Some(Self::LeftLeg),
+            Self::LeftFibular => Some(Self::LeftLeg),
+            Self::LeftCalcaneal => Some(Self::LeftPedal),
+            Self::LeftTarsal => Some(Self::LeftPedal),
+            Self::LeftPedal => Some(Self::LeftLeg),
+            Self::LeftLowerPhalangeal => Some(Self::LeftPedal),
+            Self::LeftPlantar => Some(Self::LeftPedal),
+            Self::RightInguinal => Some(Self::Pelvis),
+            Self::RightGluteal => Some(Self::RightLeg),
+            Self::RightFemoral => Some(Self::RightLeg),
+            Self::RightPatellar => Some(Self::RightLeg),
+            Self::RightPopliteal => Some(Self::RightLeg),
+            Self::RightCrural => Some(Self::RightLeg),
+            Self::RightSural => Some(Self::RightLeg),
+            Self::RightFibular => Some(Self::RightLeg),
+            Self::RightCalcaneal => Some(Self::RightPedal),
+            Self::RightTarsal => Some(Self::RightPedal),
+            Self::RightPedal => Some(Self::RightLeg),
+            Self::RightLowerPhalangeal => Some(Self::RightPedal),
+            Self::RightPlantar => Some(Self::RightPedal),
+            _ => None,
+        }
+    }
+
+    fn children(&self) -> Vec<Self> {
+        match self {
+            Self::Body => vec![Self::Head, Self::Trunk, Self::LeftArm, Self::RightArm, Self::LeftLeg, Self::RightLeg, Self::Cervical],
+            Self::Head => vec![Self::Cranial, Self::Nasal, Self::LeftFacial, Self::RightFacial],
+            Self::Trunk => vec![Self::Back, Self::Abdomen, Self::Pelvis, Self::Thoracic],
+            Self::Back => vec![Self::LeftScapular, Self::RightScapular, Self::Dorsal, Self::Lumbar, Self::Sacral],
+            Self::Abdomen => vec![Self::LeftUpperAbdominal, Self::LeftLowerAbdominal, Self::RightUpperAbdominal, Self::RightLowerAbdominal, Self::Umbilical],
+            Self::Pelvis => vec![Self::LeftCoxal, Self::RightCoxal, Self::Pubic, Self::LeftInguinal, Self::RightInguinal],
+            Self::LeftArm => vec![Self::LeftAxillary, Self::LeftBrachial, Self::LeftAntecubital, Self::LeftOlecranal, Self::LeftAntebrachial, Self::LeftCarpal, Self::LeftManus],
+            Self::RightArm => vec![Self::RightAxillary, Self::RightBrachial, Self::RightAntecubital, Self::RightOlecranal, Self::RightAntebrachial, Self::RightCarpal, Self::RightManus],
+            Self::LeftLeg => vec![Self::LeftGluteal, Self::LeftFemoral, Self::LeftPatellar, Self::LeftPopliteal, Self::LeftCrural, Self::LeftSural, Self::LeftFibular, Self::LeftPedal],
+            Self::RightLeg => vec![Self::RightGluteal, Self::RightFemoral, Self::RightPatellar, Self::RightPopliteal, Self::RightCrural, Self::RightSural, Self::RightFibular, Self::RightPedal],
+            Self::Thoracic => vec![Self::LeftMammary, Self::RightMammary],
+            Self::LeftManus => vec![Self::LeftPalmar, Self::LeftUpperPhalangeal],
+            Self::RightManus => vec![Self::RightPalmar, Self::RightUpperPhalangeal],
+            Self::LeftPedal => vec![Self::LeftCalcaneal, Self::LeftTarsal, Self::LeftLowerPhalangeal, Self::LeftPlantar],
+            Self::RightPedal => vec![Self::RightCalcaneal, Self::RightTarsal, Self::RightLowerPhalangeal, Self::RightPlantar],
+            _ => Vec::new(),
+        }
+    }
+}
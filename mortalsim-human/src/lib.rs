@@ -7,10 +7,12 @@ extern crate anyhow;
 mod human_anatomy;
 mod human_circulation;
 mod human_nervous;
+mod patient;
 
 pub use human_circulation::HumanBloodVessel;
 pub use human_anatomy::HumanAnatomicalRegion;
 pub use human_nervous::HumanNerve;
+pub use patient::{Comorbidity, PatientProfile};
 
 use mortalsim_core::sim::{Organism, impl_sim};
 
@@ -24,3 +26,95 @@ impl Organism for HumanOrganism {
 }
 
 impl_sim!(HumanSim, HumanOrganism);
+
+#[cfg(test)]
+mod tests {
+    use mortalsim_core::sim::Organism;
+    use mortalsim_core::substance::Substance;
+
+    use crate::{HumanAnatomicalRegion, HumanNerve, HumanOrganism};
+    use crate::HumanAnatomicalRegion::*;
+
+    #[test]
+    fn organism_constants() {
+        let blood_volume = HumanOrganism::total_blood_volume().to_L();
+        let body_mass = HumanOrganism::body_mass().to_kg();
+        let surface_area = HumanOrganism::body_surface_area().to_m2();
+        assert!(blood_volume > 4.0 && blood_volume < 6.0, "{}", blood_volume);
+        assert!(body_mass > 50.0 && body_mass < 100.0, "{}", body_mass);
+        assert!(surface_area > 1.5 && surface_area < 2.2, "{}", surface_area);
+
+        // Resting cardiac output of around 5 L/min
+        let cardiac_output = HumanOrganism::cardiac_index() * surface_area;
+        assert!(cardiac_output > 4.0 && cardiac_output < 7.0, "{}", cardiac_output);
+    }
+
+    #[test]
+    fn reference_ranges() {
+        let (low, high) = HumanOrganism::reference_range(Substance::GLC).unwrap();
+        assert!(low.to_mM() > 3.0 && low.to_mM() < 4.5, "{}", low.to_mM());
+        assert!(high.to_mM() > 5.0 && high.to_mM() < 7.0, "{}", high.to_mM());
+
+        let (low, high) = HumanOrganism::reference_range(Substance::K).unwrap();
+        assert!(low < high);
+        assert!(HumanOrganism::reference_range(Substance::Cellulose).is_none());
+    }
+
+    #[test]
+    fn arm_hierarchy() {
+        // Walk from the fingers up to the root of the body
+        let mut path = vec![LeftUpperPhalangeal];
+        while let Some(parent) = HumanOrganism::region_parent(*path.last().unwrap()) {
+            path.push(parent);
+        }
+        assert_eq!(path, vec![LeftUpperPhalangeal, LeftManus, LeftArm, Body]);
+
+        // An injury to the arm reaches every region of the arm, and only the arm
+        let mut arm = Vec::new();
+        let mut pending = HumanOrganism::region_children(LeftArm);
+        while let Some(region) = pending.pop() {
+            pending.extend(HumanOrganism::region_children(region));
+            arm.push(region);
+        }
+        arm.sort_by_key(|r| <&'static str>::from(*r));
+        let mut expected: Vec<HumanAnatomicalRegion> = vec![
+            LeftAxillary,
+            LeftBrachial,
+            LeftAntecubital,
+            LeftOlecranal,
+            LeftAntebrachial,
+            LeftCarpal,
+            LeftPalmar,
+            LeftManus,
+            LeftUpperPhalangeal,
+        ];
+        expected.sort_by_key(|r| <&'static str>::from(*r));
+        assert_eq!(arm, expected);
+
+        // Parent and child links agree
+        assert!(HumanOrganism::region_children(LeftArm).contains(&LeftOlecranal));
+        assert_eq!(HumanOrganism::region_parent(LeftOlecranal), Some(LeftArm));
+        assert_eq!(HumanOrganism::region_children(LeftManus), vec![LeftPalmar, LeftUpperPhalangeal]);
+        assert_eq!(HumanOrganism::region_parent(Body), None);
+        assert!(HumanOrganism::region_children(LeftPalmar).is_empty());
+    }
+
+    #[test]
+    fn nerves_for_arm_regions() {
+        let nerves = |region| {
+            let mut nerves = HumanOrganism::nerves_for_region(region);
+            nerves.sort_by_key(|n| <&'static str>::from(*n));
+            nerves
+        };
+
+        // Only the nerves reaching the hand innervate it
+        assert_eq!(nerves(LeftManus), vec![HumanNerve::LeftMedian, HumanNerve::LeftRadial, HumanNerve::LeftUlnar]);
+
+        // while the whole arm includes those of the upper arm, but not the shoulder blade
+        let arm = nerves(LeftArm);
+        assert!(arm.contains(&HumanNerve::LeftAxillary) && arm.contains(&HumanNerve::LeftMusculocutaneous), "{:?}", arm);
+        assert!(nerves(LeftManus).iter().all(|n| arm.contains(n)));
+        assert!(!arm.contains(&HumanNerve::LeftSuprascapular), "{:?}", arm);
+        assert!(!arm.iter().any(|n| <&'static str>::from(*n).starts_with("Right")), "{:?}", arm);
+    }
+}
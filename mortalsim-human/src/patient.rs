@@ -0,0 +1,223 @@
+use mortalsim_core::event::{
+    AorticBloodPressure, BodyMass, CardiacOutput, CoreBodyTemp, Event, HeartRate, Height, PulmonaryBloodPressure,
+    RespiratoryRate,
+};
+use mortalsim_core::units::base::{Distance, Mass, Temperature};
+use mortalsim_core::units::geometry::{Area, Volume};
+use mortalsim_core::units::mechanical::{Frequency, Pressure};
+
+use crate::HumanSim;
+
+/// Age at which the profile's defaults apply without any age related change (years)
+const REFERENCE_AGE: f64 = 40.0;
+
+/// Chronic conditions which alter a patient's baseline physiology
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Comorbidity {
+    /// Elevated blood pressure with stiffer, more resistive vasculature
+    Hypertension,
+    /// Reduced left ventricular contractility and cardiac output
+    HeartFailure,
+}
+
+/// Description of a specific patient, from which initial conditions for
+/// the whole organism are derived. Set on the Sim state by
+/// `HumanSim::from_profile` so that components can adjust their own
+/// model constants for the patient.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PatientProfile {
+    /// Age (years)
+    pub age: f64,
+    pub body_mass: Mass<f64>,
+    pub height: Distance<f64>,
+    pub comorbidities: Vec<Comorbidity>,
+}
+
+impl PatientProfile {
+    /// Creates a new PatientProfile without any comorbidities
+    ///
+    /// ### Arguments
+    /// * `age`       - Age in years
+    /// * `body_mass` - Body mass of the patient
+    /// * `height`    - Height of the patient
+    pub fn new(age: f64, body_mass: Mass<f64>, height: Distance<f64>) -> Self {
+        Self {
+            age,
+            body_mass,
+            height,
+            comorbidities: Vec::new(),
+        }
+    }
+
+    /// Adds a comorbidity to the profile
+    ///
+    /// ### Arguments
+    /// * `comorbidity` - Condition the patient has
+    pub fn with_comorbidity(mut self, comorbidity: Comorbidity) -> Self {
+        if !self.has(comorbidity) {
+            self.comorbidities.push(comorbidity);
+        }
+        self
+    }
+
+    /// Whether the patient has the given comorbidity
+    pub fn has(&self, comorbidity: Comorbidity) -> bool {
+        self.comorbidities.contains(&comorbidity)
+    }
+
+    /// Body surface area, from the Mosteller formula
+    pub fn body_surface_area(&self) -> Area<f64> {
+        Area::from_m2((self.height.to_cm() * self.body_mass.to_kg() / 3600.0).sqrt())
+    }
+
+    /// Total blood volume, at 70 mL/kg of body mass
+    pub fn total_blood_volume(&self) -> Volume<f64> {
+        Volume::from_L(0.07 * self.body_mass.to_kg())
+    }
+
+    /// Resting cardiac output per unit of body surface area, in
+    /// L/min/m^2, which declines with age and heart failure
+    pub fn cardiac_index(&self) -> f64 {
+        let index = 3.0 - 0.008 * (self.age - REFERENCE_AGE).max(0.0);
+        if self.has(Comorbidity::HeartFailure) {
+            index * 0.65
+        } else {
+            index
+        }
+    }
+
+    /// Factor on ventricular contractility relative to a healthy adult
+    pub fn contractility_factor(&self) -> f64 {
+        if self.has(Comorbidity::HeartFailure) {
+            0.5
+        } else {
+            1.0
+        }
+    }
+
+    /// Factor on systemic vascular resistance relative to a healthy adult
+    pub fn vascular_resistance_factor(&self) -> f64 {
+        if self.has(Comorbidity::Hypertension) {
+            1.4
+        } else {
+            1.0
+        }
+    }
+
+    /// Factor on arterial stiffness relative to a healthy adult, which
+    /// increases with age and hypertension
+    pub fn arterial_stiffness_factor(&self) -> f64 {
+        let factor = 1.0 + 0.01 * (self.age - REFERENCE_AGE).max(0.0);
+        if self.has(Comorbidity::Hypertension) {
+            factor * 1.3
+        } else {
+            factor
+        }
+    }
+
+    /// `Event`s for the patient's body measurements, which stand in for
+    /// the organism's body size constants
+    pub fn organism_constants(&self) -> Vec<Box<dyn Event>> {
+        vec![Box::new(BodyMass(self.body_mass)), Box::new(Height(self.height))]
+    }
+
+    /// Resting vital sign `Event`s for the patient
+    pub fn baseline_vitals(&self) -> Vec<Box<dyn Event>> {
+        let heart_failure = self.has(Comorbidity::HeartFailure);
+
+        let (systolic, diastolic) = if self.has(Comorbidity::Hypertension) {
+            (150.0, 95.0)
+        } else if heart_failure {
+            (110.0, 75.0)
+        } else {
+            (120.0, 80.0)
+        };
+        // Congestion raises pulmonary pressures in heart failure
+        let (pa_systolic, pa_diastolic) = if heart_failure { (40.0, 20.0) } else { (25.0, 10.0) };
+        let (heart_rate, respiratory_rate) = if heart_failure { (85.0, 16.0) } else { (70.0, 12.0) };
+
+        vec![
+            Box::new(HeartRate(Frequency::from_Hz(heart_rate / 60.0))),
+            Box::new(RespiratoryRate(Frequency::from_Hz(respiratory_rate / 60.0))),
+            Box::new(AorticBloodPressure {
+                systolic: Pressure::from_mmHg(systolic),
+                diastolic: Pressure::from_mmHg(diastolic),
+            }),
+            Box::new(PulmonaryBloodPressure {
+                systolic: Pressure::from_mmHg(pa_systolic),
+                diastolic: Pressure::from_mmHg(pa_diastolic),
+            }),
+            Box::new(CoreBodyTemp(Temperature::from_celsius(37.0))),
+            Box::new(CardiacOutput(self.cardiac_index() * self.body_surface_area().to_m2())),
+        ]
+    }
+}
+
+impl Default for PatientProfile {
+    /// A healthy 40 year old adult of 70 kg and 175 cm
+    fn default() -> Self {
+        Self::new(REFERENCE_AGE, Mass::from_kg(70.0), Distance::from_cm(175.0))
+    }
+}
+
+impl Event for PatientProfile {
+    fn transient(&self) -> bool {
+        false
+    }
+}
+
+impl HumanSim {
+    /// Creates a new HumanSim with initial conditions for the given
+    /// patient. The profile's body measurements and baseline vitals are
+    /// set as defaults on the state, and the profile itself is set as a constant `Event` for
+    /// components which derive their own constants from it.
+    ///
+    /// ### Arguments
+    /// * `profile` - Patient to simulate
+    pub fn from_profile(profile: PatientProfile) -> Self {
+        // The profile is an input of the Sim, so must be saved along with it
+        #[cfg(feature = "serde")]
+        mortalsim_core::event::codec::register_event::<PatientProfile>();
+
+        let mut sim = Self::new();
+        sim.connector.set_default_events(profile.organism_constants());
+        sim.connector.set_default_events(profile.baseline_vitals());
+        sim.set_constant_event(profile);
+        sim
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mortalsim_core::event::{BodyMass, CardiacOutput, Height};
+    use mortalsim_core::units::base::{Distance, Mass};
+    use mortalsim_core::sim::Sim;
+
+    use crate::HumanSim;
+
+    use super::{Comorbidity, PatientProfile};
+
+    #[test]
+    fn profile_baseline() {
+        let healthy = PatientProfile::default();
+        assert!((healthy.body_surface_area().to_m2() - 1.845).abs() < 1e-3);
+        assert!((healthy.total_blood_volume().to_L() - 4.9).abs() < 1e-9);
+
+        let failing = PatientProfile::default().with_comorbidity(Comorbidity::HeartFailure);
+        let sim = HumanSim::from_profile(failing.clone());
+        assert_eq!(sim.state().get_state::<PatientProfile>(), Some(&failing));
+
+        let cardiac_output = sim.state().get_state::<CardiacOutput>().unwrap().0;
+        assert!(cardiac_output < 0.7 * healthy.cardiac_index() * healthy.body_surface_area().to_m2());
+    }
+
+    #[test]
+    fn profile_organism_constants() {
+        let profile = PatientProfile::new(65.0, Mass::from_kg(90.0), Distance::from_cm(160.0));
+        let sim = HumanSim::from_profile(profile);
+        assert_eq!(sim.state().get_state::<BodyMass>(), Some(&BodyMass(Mass::from_kg(90.0))));
+        assert_eq!(sim.state().get_state::<Height>(), Some(&Height(Distance::from_cm(160.0))));
+    }
+}
@@ -34,3 +34,35 @@ pub fn param_enum(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     debug_print_generated(&ast, &toks);
     toks.into()
 }
+
+/// Generates the `SimComponent` and layer component boilerplate for a
+/// struct, given its id and the layers it participates in. For example,
+///
+/// ```ignore
+/// #[mortalsim_component(id = "MyComponent", layers(core, circulation))]
+/// pub struct MyComponent<O: Organism> {
+///     core_connector: CoreConnector<O>,
+///     circ_connector: CirculationConnector<O>,
+/// }
+/// ```
+///
+/// Generates implementations of:
+/// - `SimComponent::id` and `SimComponent::attach`
+/// - `<layer>_connector` for each layer, returning the field of the matching
+///   connector type
+///
+/// The struct must provide inherent `run` and `<layer>_init` methods, which
+/// the generated trait implementations delegate to. A missing method is
+/// reported as an unconditional recursion error on the struct.
+#[proc_macro_attribute]
+pub fn mortalsim_component(args: proc_macro::TokenStream, input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let ast = syn::parse_macro_input!(input as DeriveInput);
+    // Keep the struct on error so that only the macro error is reported
+    let toks = macros::mortalsim_component::mortalsim_component_inner(args.into(), &ast)
+        .unwrap_or_else(|err| {
+            let err = err.to_compile_error();
+            quote::quote! { #ast #err }
+        });
+    debug_print_generated(&ast, &toks);
+    toks.into()
+}
@@ -1 +1,2 @@
 pub mod param_enum;
+pub mod mortalsim_component;
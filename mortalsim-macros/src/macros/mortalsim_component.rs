@@ -0,0 +1,181 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{Data, DeriveInput, Fields, GenericArgument, Ident, LitStr, PathArguments, Type};
+
+/// Layers in the order used by the `ComponentRegistry` method names,
+/// along with the connector type associated with each
+const LAYERS: [(&str, &str); 4] = [
+    ("core", "CoreConnector"),
+    ("circulation", "CirculationConnector"),
+    ("digestion", "DigestionConnector"),
+    ("nervous", "NervousConnector"),
+];
+
+/// Arguments given to the `mortalsim_component` attribute
+struct ComponentArgs {
+    id: LitStr,
+    layers: Vec<Ident>,
+}
+
+fn parse_args(args: TokenStream) -> syn::Result<ComponentArgs> {
+    let mut id = None;
+    let mut layers = Vec::new();
+
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("id") {
+            id = Some(meta.value()?.parse::<LitStr>()?);
+            Ok(())
+        } else if meta.path.is_ident("layers") {
+            meta.parse_nested_meta(|layer| {
+                let ident = layer.path.require_ident()?;
+                if !LAYERS.iter().any(|(name, _)| ident == name) {
+                    return Err(layer.error("Expected one of: core, circulation, digestion, nervous"));
+                }
+                if layers.contains(ident) {
+                    return Err(layer.error("Duplicate layer"));
+                }
+                layers.push(ident.clone());
+                Ok(())
+            })
+        } else {
+            Err(meta.error("Unsupported argument. Expected `id` or `layers`"))
+        }
+    });
+    syn::parse::Parser::parse2(parser, args)?;
+
+    let id = id.ok_or_else(|| syn::Error::new(Span::call_site(), "Missing `id = \"...\"` argument"))?;
+    if layers.is_empty() {
+        return Err(syn::Error::new(Span::call_site(), "Missing `layers(...)` argument"));
+    }
+    if layers.len() == LAYERS.len() {
+        return Err(syn::Error::new(Span::call_site(), "Components may have at most 3 layers"));
+    }
+
+    // Sort into registry order
+    layers.sort_by_key(|l| LAYERS.iter().position(|(name, _)| l == name));
+    Ok(ComponentArgs { id, layers })
+}
+
+/// Finds the connector field for the given connector type, returning the
+/// field name and the Organism type argument of the connector
+fn find_connector<'a>(ast: &'a DeriveInput, connector: &str) -> syn::Result<Option<(&'a Ident, &'a Type)>> {
+    let fields = match &ast.data {
+        Data::Struct(s) => match &s.fields {
+            Fields::Named(named) => &named.named,
+            _ => return Err(syn::Error::new_spanned(&ast.ident, "This macro only supports structs with named fields.")),
+        },
+        _ => return Err(syn::Error::new_spanned(&ast.ident, "This macro only supports structs.")),
+    };
+
+    for field in fields.iter() {
+        if let Type::Path(type_path) = &field.ty {
+            let segment = type_path.path.segments.last().unwrap();
+            if segment.ident != connector {
+                continue;
+            }
+            if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                if let Some(GenericArgument::Type(organism)) = args.args.first() {
+                    return Ok(Some((field.ident.as_ref().unwrap(), organism)));
+                }
+            }
+            return Err(syn::Error::new_spanned(&field.ty, "Expected a connector with an Organism type argument"));
+        }
+    }
+    Ok(None)
+}
+
+/// Generates a trait method which delegates to the inherent method of the
+/// same name. If the inherent method is missing, the call resolves to the
+/// trait method itself, so recursion is denied with the span of the struct
+/// to ensure the error is reported rather than suppressed as macro-generated.
+fn delegate(name: &Ident, method: &str, args: &[(Ident, TokenStream)]) -> TokenStream {
+    let method = Ident::new(method, name.span());
+    let arg_names = args.iter().map(|(arg, _)| arg);
+    let arg_types = args.iter().map(|(_, ty)| ty);
+    let call_names = arg_names.clone();
+    quote_spanned! {name.span()=>
+        #[deny(unconditional_recursion)]
+        fn #method(&mut self, #(#arg_names: #arg_types),*) {
+            Self::#method(self, #(#call_names),*)
+        }
+    }
+}
+
+pub(crate) fn mortalsim_component_inner(args: TokenStream, ast: &DeriveInput) -> syn::Result<TokenStream> {
+    let ComponentArgs { id, layers } = parse_args(args)?;
+
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let mut organism = None;
+    let mut layer_impls = Vec::new();
+
+    for (layer, connector) in LAYERS.iter() {
+        let field = find_connector(ast, connector)?;
+        let included = layers.iter().any(|l| l == layer);
+
+        let (field_name, organism_type) = match (field, included) {
+            (Some(field), true) => field,
+            (None, false) => continue,
+            (None, true) => {
+                return Err(syn::Error::new_spanned(
+                    &ast.ident,
+                    format!("Missing a {} field for the {} layer", connector, layer),
+                ))
+            }
+            (Some((field_name, _)), false) => {
+                return Err(syn::Error::new_spanned(
+                    field_name,
+                    format!("{} field given, but the {} layer is not listed", connector, layer),
+                ))
+            }
+        };
+        organism = organism.or(Some(organism_type));
+
+        let module = format_ident!("{}", layer);
+        let trait_name = format_ident!("{}Component", connector.trim_end_matches("Connector"));
+        let connector_type = format_ident!("{}", connector);
+        let initializer_type = format_ident!("{}Initializer", connector.trim_end_matches("Connector"));
+        let connector_fn = format_ident!("{}_connector", layer);
+
+        let init_delegate = delegate(name, &format!("{}_init", layer), &[(
+            format_ident!("initializer"),
+            quote! { &mut mortalsim_core::sim::layer::#module::#initializer_type<#organism_type> },
+        )]);
+
+        layer_impls.push(quote! {
+            impl #impl_generics mortalsim_core::sim::layer::#module::#trait_name<#organism_type> for #name #ty_generics #where_clause {
+                #init_delegate
+
+                fn #connector_fn(&mut self) -> &mut mortalsim_core::sim::layer::#module::#connector_type<#organism_type> {
+                    &mut self.#field_name
+                }
+            }
+        });
+    }
+
+    let organism = organism.unwrap();
+    let run_delegate = delegate(name, "run", &[]);
+    let add_fn = format_ident!(
+        "add_{}_component",
+        layers.iter().map(|l| l.to_string()).collect::<Vec<String>>().join("_")
+    );
+
+    Ok(quote! {
+        #ast
+
+        #(#layer_impls)*
+
+        impl #impl_generics mortalsim_core::sim::component::SimComponent<#organism> for #name #ty_generics #where_clause {
+            fn id(&self) -> &'static str {
+                #id
+            }
+
+            fn attach(self, registry: &mut mortalsim_core::sim::component::ComponentRegistry<#organism>) {
+                registry.#add_fn(self)
+            }
+
+            #run_delegate
+        }
+    })
+}
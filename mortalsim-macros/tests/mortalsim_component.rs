@@ -0,0 +1,91 @@
+use std::sync::{Arc, Mutex};
+
+use mortalsim_core::event::test::TestEventA;
+use mortalsim_core::sim::component::SimComponent;
+use mortalsim_core::sim::layer::circulation::{CirculationComponent, CirculationConnector, CirculationInitializer};
+use mortalsim_core::sim::layer::core::{CoreComponent, CoreConnector, CoreInitializer};
+use mortalsim_core::sim::organism::test::{TestBloodVessel, TestOrganism, TestSim};
+use mortalsim_core::sim::{Organism, Sim};
+use mortalsim_core::units::base::Distance;
+use mortalsim_core::SimTimeSpan;
+use mortalsim_macros::mortalsim_component;
+
+#[mortalsim_component(id = "CoreOnly", layers(core))]
+struct CoreOnly {
+    runs: Arc<Mutex<usize>>,
+    connector: CoreConnector<TestOrganism>,
+}
+
+impl CoreOnly {
+    fn core_init(&mut self, initializer: &mut CoreInitializer<TestOrganism>) {
+        initializer.notify::<TestEventA>();
+    }
+
+    fn run(&mut self) {
+        *self.runs.lock().unwrap() += 1;
+    }
+}
+
+#[mortalsim_component(id = "CoreCirculation", layers(circulation, core))]
+struct CoreCirculation<O: Organism> {
+    vessel: O::VesselType,
+    found_store: Arc<Mutex<bool>>,
+    core: CoreConnector<O>,
+    circulation: CirculationConnector<O>,
+}
+
+impl<O: Organism> CoreCirculation<O> {
+    fn core_init(&mut self, initializer: &mut CoreInitializer<O>) {
+        initializer.notify::<TestEventA>();
+    }
+
+    fn circulation_init(&mut self, initializer: &mut CirculationInitializer<O>) {
+        initializer.attach_vessel(self.vessel);
+    }
+
+    fn run(&mut self) {
+        let found = self.circulation.blood_store(&self.vessel).is_some();
+        *self.found_store.lock().unwrap() = found;
+    }
+}
+
+#[test]
+fn single_layer() {
+    let runs = Arc::new(Mutex::new(0));
+    let mut component = CoreOnly {
+        runs: runs.clone(),
+        connector: CoreConnector::new(),
+    };
+    assert_eq!(component.id(), "CoreOnly");
+    component.core_connector().unschedule_all(false);
+
+    let mut sim = TestSim::new();
+    sim.add_component(component).unwrap();
+    sim.advance_by(SimTimeSpan::from_s(1.0));
+    let initial_runs = *runs.lock().unwrap();
+
+    sim.schedule_event(SimTimeSpan::from_s(0.5), Box::new(TestEventA::new(Distance::from_m(1.0))));
+    sim.advance_by(SimTimeSpan::from_s(1.0));
+    assert_eq!(*runs.lock().unwrap(), initial_runs + 1);
+}
+
+#[test]
+fn multi_layer() {
+    let found_store = Arc::new(Mutex::new(false));
+    let mut component = CoreCirculation::<TestOrganism> {
+        vessel: TestBloodVessel::VenaCava,
+        found_store: found_store.clone(),
+        core: CoreConnector::new(),
+        circulation: CirculationConnector::new(),
+    };
+    assert_eq!(component.id(), "CoreCirculation");
+    component.circulation_connector().unschedule_all(false);
+
+    let mut sim = TestSim::new();
+    sim.add_component(component).unwrap();
+    assert!(sim.has_component("CoreCirculation"));
+
+    sim.schedule_event(SimTimeSpan::from_s(0.5), Box::new(TestEventA::new(Distance::from_m(1.0))));
+    sim.advance_by(SimTimeSpan::from_s(1.0));
+    assert!(*found_store.lock().unwrap());
+}
@@ -1,4 +1,7 @@
 use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::io::{self, Write};
 
 pub mod runge_kutta {
     pub mod fixed {
@@ -16,6 +19,37 @@ use crate::Vector;
 
 pub type NumType = f64;
 
+/// Errors which can occur while solving an ODE
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OdeError {
+    /// The solution state became NaN or infinite at the given value
+    /// of the independent variable
+    Diverged { at_time: NumType },
+}
+
+impl fmt::Display for OdeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OdeError::Diverged { at_time } => write!(f, "ODE solution diverged at x = {}", at_time),
+        }
+    }
+}
+
+impl std::error::Error for OdeError {}
+
+/// Values of the ODE variables at a single step of the solution, as
+/// passed to output functions registered with `OdeRunner::add_output`
+pub struct OdeStep<'a, T: Ode> {
+    /// Value of the independent variable
+    pub x: NumType,
+    /// Assignment parameters at this step
+    pub assignments: &'a ParamVec<T::AssignParam>,
+    /// Rate bound parameters at this step
+    pub rate_bound: &'a ParamVec<T::RateParam>,
+}
+
+type OutputFn<T> = Box<dyn Fn(&OdeStep<T>, &ParamVec<<T as Ode>::ConstParam>) -> NumType + Send>;
+
 /// Solution results for a set of explicit Ordinary Differential Equations
 /// 
 /// The struct includes the following properties:
@@ -23,6 +57,7 @@ pub type NumType = f64;
 /// `x_values`: the independent variable of integration (often time)
 /// `assignment_results`: variables assigned algebraically at each step of the solution
 /// `rate_bound_results`: dependent variables of the integration
+/// `outputs`: custom outputs registered on the runner, by name
 pub struct OdeResults<T: Ode> {
     /// Constants for the ODE
     pub constants: ParamVec<T::ConstParam>,
@@ -32,6 +67,8 @@ pub struct OdeResults<T: Ode> {
     pub assignment_results: Vec<ParamVec<T::AssignParam>>,
     /// Rate bound parameters at each step of the ODE
     pub rate_bound_results: Vec<ParamVec<T::RateParam>>,
+    /// Custom outputs at each step of the ODE, by name
+    pub outputs: HashMap<String, Vec<NumType>>,
 }
 
 impl<T: Ode> OdeResults<T> {
@@ -85,6 +122,122 @@ impl<T: Ode> OdeResults<T> {
             .unwrap();
         self.rate_bound_value(index, param)
     }
+
+    /// Values of a custom output at each step of the solution, or `None`
+    /// if no output was registered with the given name
+    ///
+    /// ### Arguments
+    /// * `name` - name the output was registered with
+    pub fn output(&self, name: &str) -> Option<&[NumType]> {
+        self.outputs.get(name).map(|values| values.as_slice())
+    }
+
+    /// Value of a custom output at the given index, or `None` if no
+    /// output was registered with the given name
+    ///
+    /// ### Arguments
+    /// * `index` - index of the solution step
+    /// * `name`  - name the output was registered with
+    pub fn output_value(&self, index: usize, name: &str) -> Option<NumType> {
+        self.output(name).and_then(|values| values.get(index).copied())
+    }
+
+    /// Iterator over the x values of each step of the solution
+    pub fn time_points(&self) -> impl Iterator<Item = NumType> + '_ {
+        self.x_values.iter().copied()
+    }
+
+    /// Iterator over the values of an assignment variable at each step
+    /// of the solution
+    pub fn column(&self, param: T::AssignParam) -> impl Iterator<Item = NumType> + '_ {
+        self.assignment_results.iter().map(move |values| values[param])
+    }
+
+    /// Value of the assignment variable at the given x value, linearly
+    /// interpolated between steps, or `None` if x is outside of the
+    /// solution's range
+    fn interpolated_assignment(&self, x: NumType, param: T::AssignParam) -> Option<NumType> {
+        let len = self.x_values.len().min(self.assignment_results.len());
+        let xs = &self.x_values[..len];
+        if len == 0 || x < xs[0] || x > xs[len - 1] {
+            return None;
+        }
+
+        let hi = xs.partition_point(|v| *v < x);
+        if xs[hi] == x || hi == 0 {
+            return Some(self.assignment_value(hi, param));
+        }
+        let lo = hi - 1;
+        let frac = (x - xs[lo]) / (xs[hi] - xs[lo]);
+        Some(self.assignment_value(lo, param) * (1.0 - frac) + self.assignment_value(hi, param) * frac)
+    }
+
+    /// Absolute differences of an assignment variable from another
+    /// solution at each x value of either solution within their common
+    /// range, interpolating where the steps don't line up
+    fn abs_diffs<'a>(&'a self, other: &'a Self, param: T::AssignParam) -> impl Iterator<Item = NumType> + 'a {
+        self.time_points()
+            .chain(other.time_points())
+            .filter_map(move |x| Some((self.interpolated_assignment(x, param)? - other.interpolated_assignment(x, param)?).abs()))
+    }
+
+    /// Largest absolute difference of an assignment variable from another
+    /// solution over their common range of x values, e.g. to check that a
+    /// change to the solver or constants didn't alter the trajectory
+    ///
+    /// ### Arguments
+    /// * `other` - solution to compare against
+    /// * `param` - assignment variable to compare
+    ///
+    /// Returns the largest difference, or NaN if the solutions don't overlap
+    pub fn max_abs_diff(&self, other: &Self, param: T::AssignParam) -> NumType {
+        self.abs_diffs(other, param).fold(NumType::NAN, NumType::max)
+    }
+
+    /// Root mean square difference of an assignment variable from another
+    /// solution over their common range of x values
+    ///
+    /// ### Arguments
+    /// * `other` - solution to compare against
+    /// * `param` - assignment variable to compare
+    ///
+    /// Returns the RMS difference, or NaN if the solutions don't overlap
+    pub fn rms_diff(&self, other: &Self, param: T::AssignParam) -> NumType {
+        let (sum, count) = self
+            .abs_diffs(other, param)
+            .fold((0.0, 0usize), |(sum, count), diff| (sum + diff * diff, count + 1));
+        (sum / count as NumType).sqrt()
+    }
+
+    /// Writes the given assignment variables to CSV, with a header row of
+    /// parameter names followed by one row per x value
+    ///
+    /// ### Arguments
+    /// * `writer` - destination for the CSV output
+    /// * `params` - assignment variables to include as columns
+    ///
+    /// Returns an Err Result if writing fails
+    pub fn write_csv<W: Write>(&self, mut writer: W, params: &[T::AssignParam]) -> io::Result<()>
+    where
+        T::AssignParam: Debug,
+    {
+        let header: Vec<String> = params.iter().map(|p| format!("{:?}", p)).collect();
+        writeln!(writer, "x,{}", header.join(","))?;
+
+        let mut columns: Vec<_> = params.iter().map(|p| self.column(*p)).collect();
+        for x in self.time_points() {
+            write!(writer, "{}", x)?;
+            for column in columns.iter_mut() {
+                // Missing values are left empty
+                match column.next() {
+                    Some(value) => write!(writer, ",{}", value)?,
+                    None => write!(writer, ",")?,
+                }
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
 }
 
 /// Representation of a set of explicit Ordinary Differential Equations
@@ -132,10 +285,15 @@ pub struct OdeRunner<T: Ode>
     ode: T,
     constants: ParamVec<T::ConstParam>,
     initial_rate_bound: ParamVec<T::RateParam>,
+    /// Custom outputs computed at each step of the solution
+    outputs: Vec<(String, OutputFn<T>)>,
     assignment_history: RefCell<Vec<ParamVec<T::AssignParam>>>,
     t_end: RefCell<NumType>,
     step_size: RefCell<NumType>,
     prev_x: RefCell<NumType>,
+    /// Value of the independent variable at which the current solution
+    /// became non-finite, if it has
+    diverged_at: RefCell<Option<NumType>>,
 }
 
 impl<T: Ode> OdeRunner<T> {
@@ -149,10 +307,12 @@ impl<T: Ode> OdeRunner<T> {
             ode: ode,
             constants: constants,
             initial_rate_bound,
+            outputs: Vec::new(),
             assignment_history: RefCell::new(vec![initial_assignments]),
             t_end: RefCell::new(0.0),
             step_size: RefCell::new(0.01),
             prev_x: RefCell::new(-1.0),
+            diverged_at: RefCell::new(None),
         }
     }
 
@@ -161,6 +321,11 @@ impl<T: Ode> OdeRunner<T> {
         self.constants[param] = value;
     }
 
+    /// Returns the current value of a constant
+    pub fn get_constant(&self, param: T::ConstParam) -> NumType {
+        self.constants[param]
+    }
+
     pub fn set_initial_value(&mut self, param: T::RateParam, value: NumType) {
         self.initial_rate_bound[param] = value;
     }
@@ -169,39 +334,150 @@ impl<T: Ode> OdeRunner<T> {
         self.initial_rate_bound = results;
     }
 
+    /// Registers a custom output, computed from the ODE variables at each
+    /// step of the solution and retrievable by name from `OdeResults`.
+    /// Registering an output with an existing name replaces it.
+    ///
+    /// ### Arguments
+    /// * `name`   - name to retrieve the output by
+    /// * `output` - computes the output from a solution step and the constants
+    pub fn add_output(
+        &mut self,
+        name: impl Into<String>,
+        output: impl Fn(&OdeStep<T>, &ParamVec<T::ConstParam>) -> NumType + Send + 'static,
+    ) {
+        let name = name.into();
+        self.outputs.retain(|(existing, _)| *existing != name);
+        self.outputs.push((name, Box::new(output)));
+    }
+
+    /// Solves the ODE with a fixed step size
+    ///
+    /// ### Arguments
+    /// * `t_start`   - initial value of the independent variable
+    /// * `t_end`     - final value of the independent variable
+    /// * `step_size` - step size of the solution
+    /// * `method`    - explicit Runge-Kutta method to solve with
+    ///
+    /// Returns the solution, or an `OdeError::Diverged` if the state became
+    /// NaN or infinite, in which case no further steps are taken. The next
+    /// solution then restarts from the initial values.
     pub fn solve_fixed(
         &self,
         t_start: NumType,
         t_end: NumType,
         step_size: NumType,
         method: &impl runge_kutta::fixed::ExplicitRKMethod<NumType>
-    ) -> OdeResults<T> {
+    ) -> Result<OdeResults<T>, OdeError> {
         *self.t_end.borrow_mut() = t_end;
+        *self.diverged_at.borrow_mut() = None;
         *self.step_size.borrow_mut() = step_size;
 
+        // If this solution doesn't continue on from the last one, restart
+        // the assignment history so it aligns with the new x values
+        if t_start <= *self.prev_x.borrow() {
+            self.assignment_history.borrow_mut().clear();
+            *self.prev_x.borrow_mut() = t_start - step_size;
+        }
+
+        // Stops stepping as soon as the solution diverges
+        let running = |x: &NumType, y: &Vector<NumType>| {
+            if self.diverged_at.borrow().is_none() && y.iter().any(|v| !v.is_finite()) {
+                *self.diverged_at.borrow_mut() = Some(*x);
+            }
+            self.diverged_at.borrow().is_none()
+        };
+
         let problem = ExplicitInitialValueProblemBuilder::new(
             self,
             t_start,
             self.initial_rate_bound.clone().into(),
         )
         .t_end(t_end)
+        .callback(&running)
         .build();
 
         let solver = runge_kutta::fixed::FixedStepper::new(step_size);
 
         let (x, y) = solver.solve(&problem, method).unwrap();
 
+        if let Some(at_time) = self.diverged_at.take() {
+            // Discard the history so the next solution restarts
+            self.assignment_history.borrow_mut().clear();
+            *self.prev_x.borrow_mut() = NumType::INFINITY;
+            return Err(OdeError::Diverged { at_time });
+        }
+
         let last_assign = vec![self.assignment_history.borrow().last().unwrap().clone()];
+        let assignment_results = self.assignment_history.replace(last_assign);
+        let rate_bound_results: Vec<ParamVec<T::RateParam>> = y.into_iter()
+            .map(|v| v.into())
+            .collect();
 
-        OdeResults {
+        let outputs = self.outputs
+            .iter()
+            .map(|(name, output)| {
+                let values = x.iter()
+                    .zip(assignment_results.iter())
+                    .zip(rate_bound_results.iter())
+                    .map(|((x, assignments), rate_bound)| {
+                        output(&OdeStep { x: *x, assignments, rate_bound }, &self.constants)
+                    })
+                    .collect();
+                (name.clone(), values)
+            })
+            .collect();
+
+        Ok(OdeResults {
             constants: self.constants.clone().into(),
             x_values: x,
-            assignment_results: self.assignment_history
-                .replace(last_assign),
-            rate_bound_results: y.into_iter()
-                .map(|v| v.into())
-                .collect(),
-        }
+            assignment_results,
+            rate_bound_results,
+            outputs,
+        })
+    }
+
+    /// Estimates the sensitivity of an output of the ODE to a constant by
+    /// central finite differences, i.e. the change in the output per unit
+    /// change in the constant. This is an analysis method which leaves the
+    /// constants and solution state of the runner unchanged.
+    ///
+    /// ### Arguments
+    /// * `param`  - constant to perturb
+    /// * `delta`  - amount to perturb the constant by in each direction
+    /// * `output` - solves the ODE and computes the output of interest
+    ///
+    /// Returns the estimated derivative of the output with respect to the
+    /// constant, or an Err if either perturbed solution fails
+    pub fn sensitivity(
+        &mut self,
+        param: T::ConstParam,
+        delta: NumType,
+        output: impl Fn(&Self) -> Result<NumType, OdeError>,
+    ) -> Result<NumType, OdeError> {
+        let value = self.constants[param];
+        let history = self.assignment_history.borrow().clone();
+        let prev_x = *self.prev_x.borrow();
+        let t_end = *self.t_end.borrow();
+        let step_size = *self.step_size.borrow();
+
+        // Each evaluation starts from the same solution state
+        let evaluate = |runner: &mut Self, constant: NumType| {
+            runner.constants[param] = constant;
+            *runner.assignment_history.borrow_mut() = history.clone();
+            *runner.prev_x.borrow_mut() = prev_x;
+            output(runner)
+        };
+
+        let upper = evaluate(self, value + delta);
+        let lower = evaluate(self, value - delta);
+
+        self.constants[param] = value;
+        self.assignment_history.replace(history);
+        self.prev_x.replace(prev_x);
+        self.t_end.replace(t_end);
+        self.step_size.replace(step_size);
+        Ok((upper? - lower?) / (2.0 * delta))
     }
 }
 
@@ -212,6 +488,12 @@ impl<T: Ode> ExplicitODE<NumType> for OdeRunner<T>
         let assignments = self.ode.calc_assignments(*x, &self.constants, &y_params);
         let rates = self.ode.calc_rates(*x, &self.constants, &assignments, &y_params);
 
+        // Stepping stops at the end of the current step once diverged
+        if y.iter().chain(assignments.iter()).chain(rates.iter()).any(|v| !v.is_finite()) {
+            self.diverged_at.borrow_mut().get_or_insert(*x);
+            return Vector::zero(y.dim().0);
+        }
+
         // this function will often be called between step sizes depending on the
         // method used. This ensures assignment_history aligns with the step sizes
         // which will appear in the output
@@ -0,0 +1,102 @@
+//! ODE which diverges in finite time: y' = y^2, with y(0) = 1, so that
+//! y = 1 / (1 - t) becomes infinite at t = 1
+
+extern crate mortalsim_macros;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use mortalsim_macros::ParamEnum;
+use mortalsim_math_routines::ode::runge_kutta::fixed::RungeKutta4;
+use mortalsim_math_routines::{
+    ode::{Ode, OdeError, OdeRunner},
+    params::ParamVec
+};
+
+#[derive(Clone, Copy, ParamEnum)]
+enum BlowupConstantParam {
+    Y0,
+}
+
+#[derive(Debug, Clone, Copy, ParamEnum)]
+enum BlowupAssignmentParam {
+    YSq,
+}
+
+#[derive(Clone, Copy, ParamEnum)]
+enum BlowupRateBoundParam {
+    Y,
+}
+
+struct BlowupOde {
+    /// Number of times the rates have been calculated
+    evaluations: Rc<Cell<usize>>,
+}
+
+impl Ode for BlowupOde {
+    type ConstParam = BlowupConstantParam;
+    type AssignParam = BlowupAssignmentParam;
+    type RateParam = BlowupRateBoundParam;
+
+    fn constants(&self) -> ParamVec<Self::ConstParam> {
+        let mut c = ParamVec::new();
+        c[BlowupConstantParam::Y0] = 1.0;
+        c
+    }
+
+    fn initial_values(
+        &self,
+        constants: &ParamVec<Self::ConstParam>,
+    ) -> ParamVec<Self::RateParam> {
+        let mut iv = ParamVec::new();
+        iv[BlowupRateBoundParam::Y] = constants[BlowupConstantParam::Y0];
+        iv
+    }
+
+    fn calc_assignments(
+        &self,
+        _x: f64,
+        _constants: &ParamVec<Self::ConstParam>,
+        ode_vars: &ParamVec<Self::RateParam>,
+    ) -> ParamVec<Self::AssignParam> {
+        let mut a = ParamVec::new();
+        a[BlowupAssignmentParam::YSq] = ode_vars[BlowupRateBoundParam::Y].powi(2);
+        a
+    }
+
+    fn calc_rates(
+        &self,
+        _x: f64,
+        _constants: &ParamVec<Self::ConstParam>,
+        assignments: &ParamVec<Self::AssignParam>,
+        _ode_vars: &ParamVec<Self::RateParam>,
+    ) -> ParamVec<Self::RateParam> {
+        self.evaluations.set(self.evaluations.get() + 1);
+        let mut dy_dt = ParamVec::new();
+        dy_dt[BlowupRateBoundParam::Y] = assignments[BlowupAssignmentParam::YSq];
+        dy_dt
+    }
+}
+
+#[test]
+fn stops_when_diverged() {
+    let evaluations = Rc::new(Cell::new(0));
+    let runner = OdeRunner::new(BlowupOde { evaluations: evaluations.clone() });
+
+    let step_size = 0.01;
+    let at_time = match runner.solve_fixed(0.0, 100.0, step_size, &RungeKutta4::default()) {
+        Err(OdeError::Diverged { at_time }) => at_time,
+        Ok(_) => panic!("Solution didn't diverge"),
+    };
+    assert!(at_time > 0.9 && at_time < 1.1, "{}", at_time);
+
+    // Four stages for each step up to the divergence, rather than for
+    // each step up to the end
+    let steps = (at_time / step_size).round() as usize + 1;
+    assert!(evaluations.get() <= 4 * steps, "{} evaluations for {} steps", evaluations.get(), steps);
+
+    // The next solution restarts from the initial values
+    let results = runner.solve_fixed(0.0, 0.5, step_size, &RungeKutta4::default()).unwrap();
+    let last = results.len() - 1;
+    assert!((results.rate_bound_value(last, BlowupRateBoundParam::Y) - 2.0).abs() < 1e-6);
+}
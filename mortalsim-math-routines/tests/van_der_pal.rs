@@ -27,7 +27,7 @@ enum VdpConstantParam {
     Mu,
 }
 
-#[derive(Clone, Copy, ParamEnum)]
+#[derive(Debug, Clone, Copy, ParamEnum)]
 enum VdpAssignmentParam {
     P1,
     P2,
@@ -191,7 +191,7 @@ macro_rules! vdp_explicit_tests {
             let x_start = 0.0;
             let x_end = 30.0;
 
-            let res = runner.solve_fixed(x_start, x_end, 0.01, $value);
+            let res = runner.solve_fixed(x_start, x_end, 0.01, $value).unwrap();
 
             assert!(res.len() > 0);
 
@@ -216,3 +216,58 @@ vdp_explicit_tests!(
     rk4: &RungeKutta4::default(),
     ssprk3: &Ssprk3::default(),
 );
+
+#[test]
+fn write_csv() {
+    let runner = OdeRunner::new(VdpOde::new());
+    let res = runner.solve_fixed(0.0, 1.0, 0.1, &RungeKutta4::default()).unwrap();
+
+    let mut buffer = Vec::new();
+    res.write_csv(&mut buffer, &[VdpAssignmentParam::P1, VdpAssignmentParam::P2]).unwrap();
+
+    let csv = String::from_utf8(buffer).unwrap();
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(rows[0], "x,P1,P2");
+    assert_eq!(rows.len(), res.len() + 1);
+    assert!(rows.iter().all(|row| row.split(',').count() == 3));
+
+    // First row holds the initial assignment values
+    let first: Vec<f64> = rows[1].split(',').map(|v| v.parse().unwrap()).collect();
+    assert_eq!(first, vec![res.x(0), res.column(VdpAssignmentParam::P1).next().unwrap(), res.column(VdpAssignmentParam::P2).next().unwrap()]);
+}
+
+#[test]
+fn custom_output() {
+    let mut runner = OdeRunner::new(VdpOde::new());
+    runner.add_output("Y1_SQ", |step, _| step.rate_bound[VdpRateBoundParam::Y1].powi(2));
+    runner.add_output("MU_P1", |step, constants| constants[VdpConstantParam::Mu] * step.assignments[VdpAssignmentParam::P1]);
+    let res = runner.solve_fixed(0.0, 1.0, 0.1, &RungeKutta4::default()).unwrap();
+
+    let y1_sq = res.output("Y1_SQ").unwrap();
+    assert_eq!(y1_sq.len(), res.len());
+    for (i, value) in y1_sq.iter().enumerate() {
+        assert_eq!(*value, res.rate_bound_value(i, VdpRateBoundParam::Y1).powi(2));
+    }
+    assert_eq!(res.output_value(3, "MU_P1"), Some(res.assignment_value(3, VdpAssignmentParam::P1)));
+    assert!(res.output("MAP").is_none());
+}
+
+#[test]
+fn compare_results() {
+    let solve = |mu: f64, x_end: f64, step_size: f64| {
+        let mut runner = OdeRunner::new(VdpOde::new());
+        runner.set_constant(VdpConstantParam::Mu, mu);
+        runner.solve_fixed(0.0, x_end, step_size, &RungeKutta4::default()).unwrap()
+    };
+
+    let base = solve(1.0, 5.0, 0.01);
+    let same = solve(1.0, 5.0, 0.01);
+    assert_eq!(base.max_abs_diff(&same, VdpAssignmentParam::P2), 0.0);
+    assert_eq!(base.rms_diff(&same, VdpAssignmentParam::P2), 0.0);
+
+    let perturbed = solve(1.05, 5.0, 0.01);
+    let max_diff = base.max_abs_diff(&perturbed, VdpAssignmentParam::P2);
+    let rms_diff = base.rms_diff(&perturbed, VdpAssignmentParam::P2);
+    assert!(rms_diff > 0.0 && rms_diff <= max_diff, "rms {} max {}", rms_diff, max_diff);
+    assert_eq!(perturbed.max_abs_diff(&base, VdpAssignmentParam::P2), max_diff);
+}
@@ -1,15 +1,17 @@
 use std::any::TypeId;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, OnceLock, RwLock};
 
 
-use mortalsim_core::sim::component::SimComponent;
-use mortalsim_core::sim::layer::circulation::{BloodVessel, CirculationComponent, CirculationConnector};
-use mortalsim_core::sim::layer::core::{CoreComponent, CoreConnector};
-use mortalsim_core::sim::Organism;
+use mortalsim_core::sim::layer::circulation::{BloodStore, BloodVessel, CirculationConnector};
+use mortalsim_core::sim::layer::core::CoreConnector;
+use mortalsim_core::sim::{Organism, SimTime};
 use mortalsim_core::event::{AorticBloodPressure, HeartRate};
+use mortalsim_core::math::BoundFn;
+use mortalsim_core::substance::{Substance, SubstanceChange, SubstanceConcentration};
 use mortalsim_core::units::base::Time;
 use mortalsim_core::SimTimeSpan;
+use mortalsim_macros::mortalsim_component;
 
 /// Mortalsim module for simple propagation of blood composition
 /// through a closed circulation system.
@@ -17,7 +19,8 @@ use mortalsim_core::SimTimeSpan;
 /// Major assumptions:
 /// - Blood composition is evenly mixed, meaning the concentration
 ///   at each vessel becomes equal to the average of all compositions
-///   from each preceding vessel.
+///   from each preceding vessel, weighted by their share of the flow
+///   by default (see `ConfluenceStrategy`).
 /// - Diffusion time across the vasculature is linearly proportional
 ///   to the number of vessels in the circulation tree
 /// - Time required for blood to pass through each vessel in the tree
@@ -26,7 +29,7 @@ use mortalsim_core::SimTimeSpan;
 ///   systemic circulation time
 
 struct VesselDistanceCache<T> {
-    map: HashMap<TypeId, HashMap<(&'static str, &'static str), Vec<(T, f64)>>>,
+    map: HashMap<TypeId, HashMap<DistanceKey, Vec<(T, f64)>>>,
 }
 
 impl<T> VesselDistanceCache<T> {
@@ -35,26 +38,110 @@ impl<T> VesselDistanceCache<T> {
             map: HashMap::new(),
         }
     }
-    pub fn get(&self, organism_type: &TypeId, a: &'static str, b: &'static str) -> Option<&Vec<(T, f64)>> {
-        self.map.get(organism_type)?.get(&(a, b))
+    pub fn get(&self, organism_type: &TypeId, strategy: &'static str, a: &'static str, b: &'static str) -> Option<&Vec<(T, f64)>> {
+        self.map.get(organism_type)?.get(&(strategy, a, b))
     }
-    pub fn insert(&mut self, organism_type: TypeId, a: &'static str, b: &'static str, val: Vec<(T, f64)>) {
-        self.map.entry(organism_type).or_default().insert((a, b), val);
+    pub fn insert(&mut self, organism_type: TypeId, strategy: &'static str, a: &'static str, b: &'static str, val: Vec<(T, f64)>) {
+        self.map.entry(organism_type).or_default().insert((strategy, a, b), val);
     }
 }
 
 static DIST_CACHE: OnceLock<Arc<RwLock<VesselDistanceCache<u32>>>> = OnceLock::new();
 
+type CapillaryExchangeFn<V> = Box<dyn FnMut(V, &mut BloodStore) + Send>;
+/// Tissue target concentration of a gas at a terminal vessel
+type CapillaryTargetFn<V> = Box<dyn FnMut(V, Substance) -> SubstanceConcentration + Send>;
+
+type EdgeModifierFn = Box<dyn Fn(&SubstanceChange) -> SubstanceChange + Send>;
+/// Confluence strategy and names of the vessel pair
+type DistanceKey = (&'static str, &'static str, &'static str);
+type ConfluenceWeightFn<V> = Box<dyn Fn(V, Option<V>) -> f64 + Send>;
+/// Pair of vessels, such as an edge blood flows directly across
+type VesselPair<V> = (V, V);
+/// Distance and flow factor of a path, with the given edges it crosses in order
+type EdgePath<V> = (u32, f64, Vec<VesselPair<V>>);
+/// Delay and flow factor of a path, with the modified edges it crosses in order
+type PathDelay<V> = (SimTimeSpan, f64, Vec<VesselPair<V>>);
+/// Delays at the base heart rate and flow factors for each path between a
+/// vessel pair, with the modified edges each path crosses in order, or
+/// `None` if the pair is unreachable
+type BaseDelays<V> = Option<Vec<(Time<f64>, f64, Vec<VesselPair<V>>)>>;
+
+/// How changes arriving at a confluence, where several vessels feed one
+/// (e.g. the vena cava), are combined
+pub enum ConfluenceStrategy<V: BloodVessel> {
+    /// Each upstream vessel contributes in proportion to its share of
+    /// the flow into the confluence, per `BloodVessel::flow_fractions`
+    FlowWeighted,
+    /// Each upstream vessel contributes equally
+    Average,
+    /// Changes pass through undiluted, as though the confluence takes
+    /// on the largest of its upstream changes
+    Max,
+    /// Custom weight for changes arriving at a confluence vessel (first
+    /// argument) from an upstream vessel (second argument), or `None`
+    /// for changes originating on the confluence vessel itself.
+    /// Distances computed with a custom strategy aren't cached.
+    Custom(ConfluenceWeightFn<V>),
+}
+
+impl<V: BloodVessel> ConfluenceStrategy<V> {
+    /// Key for cached distances computed with this strategy, if cacheable
+    fn cache_key(&self) -> Option<&'static str> {
+        match self {
+            Self::FlowWeighted => Some("FlowWeighted"),
+            Self::Average => Some("Average"),
+            Self::Max => Some("Max"),
+            Self::Custom(_) => None,
+        }
+    }
+}
+
+#[mortalsim_component(id = "SimpleBloodFlow", layers(core, circulation))]
 pub struct SimpleBloodFlow<O: Organism> {
     base_heart_rate: HeartRate,
     base_diffusion_time: Time<f64>,
     core_connector: CoreConnector<O>,
     circ_connector: CirculationConnector<O>,
+    /// Vessel pairs which have already been warned about being unreachable
+    unreachable_warned: HashSet<(O::VesselType, O::VesselType)>,
+    /// Tissue exchange to apply at terminal vessels before the loopback
+    capillary_exchange: Option<CapillaryExchangeFn<O::VesselType>>,
+    /// Time constants of gases equilibrating with the tissues at terminal vessels
+    capillary_time_constants: HashMap<Substance, SimTimeSpan>,
+    /// Tissue targets which gases equilibrate toward, if enabled
+    capillary_targets: Option<CapillaryTargetFn<O::VesselType>>,
+    /// Time gases last equilibrated with the tissues
+    last_gas_exchange: Option<SimTime>,
+    /// Time of the next periodic tissue exchange, if scheduled
+    next_exchange: Option<SimTime>,
+    /// Modifiers applied to changes propagating from one vessel to another
+    edge_modifiers: HashMap<(O::VesselType, O::VesselType), EdgeModifierFn>,
+    /// Subset of vessels to propagate changes between, if restricted
+    vessel_subset: Option<HashSet<O::VesselType>>,
+    /// Limits the heart rate is clamped to when calculating delays, if set
+    heart_rate_limits: Option<(HeartRate, HeartRate)>,
+    /// Whether the current heart rate is outside of the limits
+    heart_rate_clamped: bool,
+    /// How changes are combined where vessels join
+    confluence_strategy: ConfluenceStrategy<O::VesselType>,
+    /// Vessels to mark as reservoirs, with their mixing factors
+    reservoirs: HashMap<O::VesselType, f64>,
+    /// Delays at the base heart rate for each vessel pair, computed on first use
+    base_delays: HashMap<VesselPair<O::VesselType>, BaseDelays<O::VesselType>>,
+    /// Whether changes are spread over vessel volumes to conserve mass
+    conserve_mass: bool,
 }
 
 
 impl<O: Organism> SimpleBloodFlow<O> {
     pub const PULMONARY_RATIO: u32 = 12; // 1/12 of the max systemic length
+    /// Default lower heart rate limit, relative to the base heart rate
+    pub const MIN_HEART_RATE_RATIO: f64 = 0.25;
+    /// Default upper heart rate limit, relative to the base heart rate
+    pub const MAX_HEART_RATE_RATIO: f64 = 4.0;
+    /// Longest time between runs while tissue exchange is registered (s)
+    pub const CAPILLARY_EXCHANGE_PERIOD: f64 = 1.0;
 
     pub fn new(base_heart_rate: HeartRate, base_diffusion_time: Time<f64>) -> Self {
         Self {
@@ -62,7 +149,154 @@ impl<O: Organism> SimpleBloodFlow<O> {
             base_diffusion_time,
             core_connector: CoreConnector::new(),
             circ_connector: CirculationConnector::new(),
+            unreachable_warned: HashSet::new(),
+            capillary_exchange: None,
+            capillary_time_constants: HashMap::new(),
+            capillary_targets: None,
+            last_gas_exchange: None,
+            next_exchange: None,
+            edge_modifiers: HashMap::new(),
+            vessel_subset: None,
+            heart_rate_limits: None,
+            heart_rate_clamped: false,
+            confluence_strategy: ConfluenceStrategy::FlowWeighted,
+            reservoirs: HashMap::new(),
+            base_delays: HashMap::new(),
+            conserve_mass: false,
+        }
+    }
+
+    /// Conserves the mass of substances as changes propagate, rather than
+    /// replicating concentration changes downstream. A change on a vessel
+    /// with a known volume is spread over the volumes of every reachable
+    /// vessel, and each vessel's share washes out of the source as it
+    /// arrives, so the total amount of each Substance in the blood only
+    /// changes by the amount of the original change. Vessels without a
+    /// known volume are still changed as usual, but don't take a share.
+    pub fn with_mass_conservation(mut self) -> Self {
+        self.conserve_mass = true;
+        self
+    }
+
+    /// Sets how changes arriving at a confluence are combined, which
+    /// determines the factor applied to changes propagating through it.
+    /// Defaults to `ConfluenceStrategy::FlowWeighted`.
+    ///
+    /// ### Arguments
+    /// * `strategy` - Strategy for combining upstream changes
+    pub fn with_confluence_strategy(mut self, strategy: ConfluenceStrategy<O::VesselType>) -> Self {
+        self.confluence_strategy = strategy;
+        self.base_delays.clear();
+        self
+    }
+
+    /// Sets the limits the current heart rate is clamped to when calculating
+    /// delays, so that pathological heart rates (e.g. asystole) still yield
+    /// finite, nonzero delays. Defaults to `MIN_HEART_RATE_RATIO` and
+    /// `MAX_HEART_RATE_RATIO` times the base heart rate.
+    ///
+    /// ### Arguments
+    /// * `min` - Lowest heart rate used for delays
+    /// * `max` - Highest heart rate used for delays
+    pub fn with_heart_rate_limits(mut self, min: HeartRate, max: HeartRate) -> Self {
+        assert!(min.as_ref().Hz <= max.as_ref().Hz, "Minimum heart rate must not exceed the maximum");
+        self.heart_rate_limits = Some((min, max));
+        self
+    }
+
+    /// Marks the given vessel as a reservoir, such as a stagnant aneurysm
+    /// sac, which accumulates substances rather than fully participating
+    /// in the mix. Changes flowing into a reservoir which raise its
+    /// concentration apply in full, while those which wash it out, and
+    /// changes originating on the reservoir as they reach other vessels,
+    /// are scaled by `mixing_factor`. Reservoirs marked by any other
+    /// component via `CirculationInitializer::set_reservoir` behave the same.
+    ///
+    /// Panics if `mixing_factor` is not between 0 and 1
+    ///
+    /// ### Arguments
+    /// * `vessel`        - Vessel which acts as a reservoir
+    /// * `mixing_factor` - Fraction of normal mixing out of the vessel, where 0 retains everything
+    pub fn with_reservoir(mut self, vessel: O::VesselType, mixing_factor: f64) -> Self {
+        assert!((0.0..=1.0).contains(&mixing_factor), "Mixing factor must be between 0 and 1");
+        self.reservoirs.insert(vessel, mixing_factor);
+        self
+    }
+
+    /// Restricts blood flow to the given subset of vessels, for faster
+    /// focused studies on large organisms. Only changes on these vessels
+    /// are propagated, and only to other vessels in the subset. Changes
+    /// which would naturally reach vessels outside the subset are skipped.
+    /// Delays still follow the full vasculature between subset vessels.
+    ///
+    /// ### Arguments
+    /// * `vessels` - Vessels which participate in blood flow
+    pub fn restricted_to(mut self, vessels: HashSet<O::VesselType>) -> Self {
+        self.vessel_subset = Some(vessels);
+        self
+    }
+
+    /// Registers a tissue exchange function which is invoked on each
+    /// terminal vessel (where the systemic circulation loops back through
+    /// the pulmonary circulation) every time this component runs, which is
+    /// at least every `CAPILLARY_EXCHANGE_PERIOD` seconds. Any changes it
+    /// schedules are propagated along with all other changes.
+    ///
+    /// ### Arguments
+    /// * `exchange` - Function adjusting the composition of a terminal vessel
+    pub fn with_capillary_exchange(
+        mut self,
+        exchange: impl FnMut(O::VesselType, &mut BloodStore) + Send + 'static,
+    ) -> Self {
+        self.capillary_exchange = Some(Box::new(exchange));
+        self
+    }
+
+    /// Equilibrates gases with the tissues at each terminal vessel, like
+    /// `with_capillary_exchange`, as a first-order approach to their
+    /// tissue targets. Each gas has its own time constant, so e.g. CO2
+    /// can equilibrate faster than O2, and neither overshoots its target.
+    ///
+    /// Panics if any time constant isn't positive
+    ///
+    /// ### Arguments
+    /// * `time_constants` - Time constant of the approach for each gas
+    /// * `target`         - Tissue target of a gas at a terminal vessel
+    pub fn with_capillary_gas_exchange(
+        mut self,
+        time_constants: HashMap<Substance, SimTimeSpan>,
+        target: impl FnMut(O::VesselType, Substance) -> SubstanceConcentration + Send + 'static,
+    ) -> Self {
+        for (gas, time_constant) in time_constants.iter() {
+            assert!(time_constant.to_s() > 0.0, "{} time constant must be greater than 0", gas);
         }
+        self.capillary_time_constants = time_constants;
+        self.capillary_targets = Some(Box::new(target));
+        self
+    }
+
+    /// Registers a modifier for changes flowing from one vessel directly
+    /// into the next, e.g. to model a stenosis or valve between them. The
+    /// modifier applies to changes propagating to every vessel along the
+    /// paths which cross the edge, while paths around it are unaffected.
+    /// Use `SubstanceChange::scaled` to attenuate or block a change, or
+    /// `SubstanceChange::delayed` to delay it, so that cancelling the
+    /// original change cancels the modified change too. Modifiers along
+    /// the same path apply in the order they're crossed. Replaces any
+    /// modifier already registered for the pair.
+    ///
+    /// ### Arguments
+    /// * `from`     - Vessel upstream of the edge
+    /// * `to`       - Vessel immediately downstream of `from`
+    /// * `modifier` - Function returning the change to propagate in place of the original
+    pub fn add_edge_modifier(
+        &mut self,
+        from: O::VesselType,
+        to: O::VesselType,
+        modifier: impl Fn(&SubstanceChange) -> SubstanceChange + Send + 'static,
+    ) {
+        self.edge_modifiers.insert((from, to), Box::new(modifier));
+        self.base_delays.clear();
     }
 
     pub fn factory(base_heart_rate: HeartRate, base_diffusion_time: Time<f64>) -> impl Fn() -> Self {
@@ -71,16 +305,75 @@ impl<O: Organism> SimpleBloodFlow<O> {
         }
     }
 
-    fn calculate_blood_delays(&self, vessel_a: O::VesselType, vessel_b: O::VesselType) -> Vec<(SimTimeSpan, f64)> {
+    /// Ratio of the given heart rate to the base heart rate, with the heart
+    /// rate clamped to the configured limits. Invalid rates, including a
+    /// base heart rate which isn't positive, saturate to a ratio of 1.
+    ///
+    /// Returns the ratio and whether the heart rate was out of range
+    fn heart_rate_ratio(&self, heart_rate: &HeartRate) -> (f64, bool) {
+        let base = self.base_heart_rate.as_ref().Hz;
+        let hr = heart_rate.as_ref().Hz;
+        if !(base.is_finite() && base > 0.0) || hr.is_nan() {
+            return (1.0, true);
+        }
+
+        let (min, max) = match self.heart_rate_limits {
+            Some((min, max)) => (min.as_ref().Hz, max.as_ref().Hz),
+            None => (base * Self::MIN_HEART_RATE_RATIO, base * Self::MAX_HEART_RATE_RATIO),
+        };
+        let clamped = hr.clamp(min, max);
+        (clamped / base, clamped != hr)
+    }
+
+    /// Calculates the delays and factors for blood flowing from `vessel_a`
+    /// to `vessel_b` at the given heart rate, or `None` if `vessel_b` is
+    /// unreachable from `vessel_a`. Delays are inversely proportional to
+    /// the heart rate, so a faster heart shortens them. Delays at the base
+    /// heart rate are cached per vessel pair, so only the heart rate
+    /// scaling is applied on subsequent calls.
+    fn calculate_blood_delays(
+        &mut self,
+        heart_rate: &HeartRate,
+        vessel_a: O::VesselType,
+        vessel_b: O::VesselType,
+    ) -> Option<Vec<PathDelay<O::VesselType>>> {
+        let (hr_ratio, _) = self.heart_rate_ratio(heart_rate);
+
+        if !self.base_delays.contains_key(&(vessel_a, vessel_b)) {
+            let base = self.calculate_base_delays(vessel_a, vessel_b);
+            self.base_delays.insert((vessel_a, vessel_b), base);
+        }
+
+        Some(self.base_delays.get(&(vessel_a, vessel_b))?
+            .as_ref()?
+            .iter()
+            .map(|(delay, fact, edges)| (SimTimeSpan(*delay / hr_ratio), *fact, edges.clone()))
+            .collect())
+    }
+
+    /// Calculates the delays at the base heart rate and factors for blood
+    /// flowing from `vessel_a` to `vessel_b`, with the modified edges along
+    /// each path, or `None` if `vessel_b` is unreachable from `vessel_a`
+    fn calculate_base_delays(&self, vessel_a: O::VesselType, vessel_b: O::VesselType) -> BaseDelays<O::VesselType> {
         let reference_cycle = O::VesselType::max_cycle();
-        let heart_rate = self.core_connector.get::<HeartRate>().unwrap_or(&self.base_heart_rate);
 
-        Self::distance_factor_between(vessel_a, vessel_b)
+        // Paths are only split by the edges they cross if any are modified
+        let paths = if self.edge_modifiers.is_empty() {
+            Self::distance_factor_with(&self.confluence_strategy, vessel_a, vessel_b)?
+                .into_iter()
+                .map(|(dist, fact)| (dist, fact, Vec::new()))
+                .collect()
+        } else {
+            let edges = self.edge_modifiers.keys().copied().collect();
+            Self::reachable(Self::dist_calc_with(&self.confluence_strategy, vessel_a, vessel_b, &edges))?
+        };
+
+        Some(paths
             .into_iter()
-            .map(|(dist, fact)| {
-                let diffusion_delay = (f64::from(dist) / f64::from(reference_cycle)) * (heart_rate.as_ref() / self.base_heart_rate.as_ref()) * self.base_diffusion_time;
-                (SimTimeSpan(diffusion_delay), fact)
-            }).collect()
+            .map(|(dist, fact, edges)| {
+                let diffusion_delay = (f64::from(dist) / f64::from(reference_cycle)) * self.base_diffusion_time;
+                (diffusion_delay, fact, edges)
+            }).collect())
     }
 
     fn get_downstream_add(v: O::VesselType) -> (impl Iterator<Item=O::VesselType>, u32) {
@@ -94,13 +387,16 @@ impl<O: Organism> SimpleBloodFlow<O> {
         }
     }
 
-    // Internal recursive function to find the distance between any arbitrary vessel
+    // Internal recursive function to find the distance between any arbitrary
+    // vessel, along with which of the given edges each path crosses
     fn dist_calc(
         a: O::VesselType,
         b: O::VesselType,
         visited: &mut Vec<O::VesselType>,
-        factor: f64
-    ) -> Vec<(u32, f64)> {
+        factor: f64,
+        weight: &dyn Fn(O::VesselType, Option<O::VesselType>) -> f64,
+        edges: &HashSet<VesselPair<O::VesselType>>,
+    ) -> Vec<EdgePath<O::VesselType>> {
         // If we've hit a cycle, return immediately
         if visited.contains(&a) {
             log::trace!("Path: {:?} -> {:?}", visited, a);
@@ -108,16 +404,18 @@ impl<O: Organism> SimpleBloodFlow<O> {
         }
 
         let a_factor = {
-            if a.upstream().len() > 0 {
-                factor / a.upstream().len() as f64
+            if a.upstream().len() > 1 {
+                factor * weight(a, visited.last().copied())
             }
             else {
                 factor
             }
         };
         
+        let crossed = visited.last().map(|prev| (*prev, a)).filter(|edge| edges.contains(edge));
+
         if a == b {
-            return vec![(0, a_factor)];
+            return vec![(0, a_factor, crossed.into_iter().collect())];
         }
 
         // Add the current node to the list
@@ -127,9 +425,12 @@ impl<O: Organism> SimpleBloodFlow<O> {
 
         let res = downstream
             .map(|v| {
-                let mut items = Self::dist_calc(v, b, visited, a_factor);
-                for (x, _) in items.iter_mut() {
+                let mut items = Self::dist_calc(v, b, visited, a_factor, weight, edges);
+                for (x, _, path_edges) in items.iter_mut() {
                     *x += add_amount;
+                    if let Some(edge) = crossed {
+                        path_edges.insert(0, edge);
+                    }
                 }
                 items
             })
@@ -142,88 +443,328 @@ impl<O: Organism> SimpleBloodFlow<O> {
         res
     }
 
-    fn distance_factor_between(vessel_a: O::VesselType, vessel_b: O::VesselType) -> Vec<(u32, f64)> {
+    /// Distances and flow factors for each path from `vessel_a` to `vessel_b`
+    /// with the default confluence strategy, or `None` if there is no path
+    /// between them
+    #[cfg(test)]
+    fn distance_factor_between(vessel_a: O::VesselType, vessel_b: O::VesselType) -> Option<Vec<(u32, f64)>> {
+        Self::distance_factor_with(&ConfluenceStrategy::FlowWeighted, vessel_a, vessel_b)
+    }
+
+    /// Distances and flow factors for each path from `vessel_a` to `vessel_b`
+    /// with the given confluence strategy, or `None` if there is no path
+    /// between them
+    fn distance_factor_with(
+        strategy: &ConfluenceStrategy<O::VesselType>,
+        vessel_a: O::VesselType,
+        vessel_b: O::VesselType,
+    ) -> Option<Vec<(u32, f64)>> {
+        let strip = |paths: Vec<EdgePath<O::VesselType>>| -> Vec<(u32, f64)> {
+            paths.into_iter().map(|(dist, fact, _)| (dist, fact)).collect()
+        };
+        let Some(key) = strategy.cache_key() else {
+            return Self::reachable(strip(Self::dist_calc_with(strategy, vessel_a, vessel_b, &HashSet::new())));
+        };
+
         if let Some(d) = DIST_CACHE.get_or_init(|| {
             Arc::new(RwLock::new(VesselDistanceCache::new()))
-        }).read().unwrap().get(&TypeId::of::<O>(), vessel_a.into(), vessel_b.into()) {
-            return d.clone();
+        }).read().unwrap().get(&TypeId::of::<O>(), key, vessel_a.into(), vessel_b.into()) {
+            return Self::reachable(d.clone());
         }
 
-        let mut visited = Vec::new();
-        let result = Self::dist_calc(vessel_a, vessel_b, &mut visited, 1.0);
+        let result = strip(Self::dist_calc_with(strategy, vessel_a, vessel_b, &HashSet::new()));
 
         let cache = DIST_CACHE.get().unwrap();
 
         cache.write().unwrap().insert(
             TypeId::of::<O>(),
+            key,
             vessel_a.into(),
             vessel_b.into(),
             result
         );
 
-        cache.read()
+        Self::reachable(cache.read()
             .unwrap()
-            .get(&TypeId::of::<O>(), vessel_a.into(), vessel_b.into())
+            .get(&TypeId::of::<O>(), key, vessel_a.into(), vessel_b.into())
             .unwrap()
-            .clone()
+            .clone())
+    }
+
+    /// Runs `dist_calc` with the confluence weights of the given strategy,
+    /// combining paths of equal distance which cross the same edges
+    fn dist_calc_with(
+        strategy: &ConfluenceStrategy<O::VesselType>,
+        vessel_a: O::VesselType,
+        vessel_b: O::VesselType,
+        edges: &HashSet<VesselPair<O::VesselType>>,
+    ) -> Vec<EdgePath<O::VesselType>> {
+        // Changes originating on a confluence are attributed evenly
+        // to its upstream vessels by the built in strategies
+        let average = |v: O::VesselType| 1.0 / v.upstream().len() as f64;
+
+        let mut visited = Vec::new();
+        let paths = match strategy {
+            ConfluenceStrategy::FlowWeighted => {
+                let fractions = O::VesselType::flow_fractions();
+                let weight = |v: O::VesselType, from: Option<O::VesselType>| {
+                    let total = fractions.get(&v).copied().unwrap_or(0.0);
+                    match from {
+                        Some(from) if total > 0.0 => {
+                            fractions.get(&from).copied().unwrap_or(0.0) * from.flow_split(v) / total
+                        }
+                        _ => average(v),
+                    }
+                };
+                Self::dist_calc(vessel_a, vessel_b, &mut visited, 1.0, &weight, edges)
+            }
+            ConfluenceStrategy::Average => {
+                Self::dist_calc(vessel_a, vessel_b, &mut visited, 1.0, &|v, _| average(v), edges)
+            }
+            ConfluenceStrategy::Max => Self::dist_calc(vessel_a, vessel_b, &mut visited, 1.0, &|_, _| 1.0, edges),
+            ConfluenceStrategy::Custom(weight) => {
+                Self::dist_calc(vessel_a, vessel_b, &mut visited, 1.0, weight, edges)
+            }
+        };
+
+        // Group paths by the edges they cross, in the order first found
+        let mut groups: Vec<(Vec<_>, Vec<(u32, f64)>)> = Vec::new();
+        for (dist, fact, path_edges) in paths {
+            match groups.iter_mut().find(|(group_edges, _)| *group_edges == path_edges) {
+                Some((_, group)) => group.push((dist, fact)),
+                None => groups.push((path_edges, vec![(dist, fact)])),
+            }
+        }
+        groups
+            .into_iter()
+            .flat_map(|(group_edges, group)| {
+                Self::combine_paths(group)
+                    .into_iter()
+                    .map(move |(dist, fact)| (dist, fact, group_edges.clone()))
+            })
+            .collect()
+    }
+
+    /// Combines paths of equal distance into one, summing their factors.
+    /// Factors are summed from smallest to largest with compensated
+    /// summation, so that many tiny contributions aren't lost and the
+    /// result doesn't depend on the order paths were found in.
+    ///
+    /// Returns the combined paths in order of increasing distance
+    fn combine_paths(mut paths: Vec<(u32, f64)>) -> Vec<(u32, f64)> {
+        paths.sort_by(|(d1, f1), (d2, f2)| d1.cmp(d2).then(f1.abs().total_cmp(&f2.abs())));
+
+        let mut combined: Vec<(u32, f64)> = Vec::new();
+        // Running compensation for lost low-order bits of the current sum
+        let mut compensation = 0.0;
+        for (dist, factor) in paths {
+            match combined.last_mut() {
+                Some((last_dist, sum)) if *last_dist == dist => {
+                    let y = factor - compensation;
+                    let t = *sum + y;
+                    compensation = (t - *sum) - y;
+                    *sum = t;
+                }
+                _ => {
+                    combined.push((dist, factor));
+                    compensation = 0.0;
+                }
+            }
+        }
+        combined
+    }
+
+    // An empty set of paths means the vessels are disconnected
+    fn reachable<T>(paths: Vec<T>) -> Option<Vec<T>> {
+        if paths.is_empty() {
+            None
+        } else {
+            Some(paths)
+        }
     }
 }
 
-impl<O: Organism> CoreComponent<O> for SimpleBloodFlow<O> {
+impl<O: Organism> SimpleBloodFlow<O> {
     fn core_init(&mut self, initializer: &mut mortalsim_core::sim::layer::core::CoreInitializer<O>) {
         initializer.notify::<HeartRate>();
         initializer.notify::<AorticBloodPressure>();
     }
-    fn core_connector(&mut self) -> &mut CoreConnector<O> {
-        return &mut self.core_connector
-    }
-}
 
-impl<O: Organism> CirculationComponent<O> for SimpleBloodFlow<O> {
     fn circulation_init(&mut self, circulation_initializer: &mut mortalsim_core::sim::layer::circulation::CirculationInitializer<O>) {
-        circulation_initializer.notify_any_change();
+        match &self.vessel_subset {
+            // Only attach the subset, so everything else is ignored
+            Some(vessels) => {
+                for vessel in vessels.iter() {
+                    circulation_initializer.notify_scheduled_change(*vessel);
+                }
+            }
+            None => circulation_initializer.notify_any_change(),
+        }
+        for (vessel, mixing_factor) in self.reservoirs.iter() {
+            circulation_initializer.set_reservoir(*vessel, *mixing_factor);
+        }
     }
 
-    fn circulation_connector(&mut self) -> &mut CirculationConnector<O> {
-        return &mut self.circ_connector
-    }
-}
+    /// Schedules each gas on the terminal vessels to close the fraction of
+    /// the gap to its tissue target expected since the last exchange
+    fn exchange_gases(&mut self) {
+        let sim_time = self.circ_connector.sim_time();
+        let elapsed = self.last_gas_exchange.map(|t| SimTimeSpan::from_s((sim_time - t).to_s()));
+        self.last_gas_exchange = Some(sim_time);
+        let (Some(target), Some(elapsed)) = (self.capillary_targets.as_mut(), elapsed) else {
+            return;
+        };
+        if elapsed.to_s() <= 0.0 {
+            return;
+        }
 
-impl<O: Organism> SimComponent<O> for SimpleBloodFlow<O> {
-    fn id(&self) -> &'static str {
-        "SimpleBloodFlow"
-    }
-    fn attach(self, registry: &mut mortalsim_core::sim::component::ComponentRegistry<O>) {
-        registry.add_core_circulation_component(self)
+        let time_constants = &self.capillary_time_constants;
+        self.circ_connector.with_blood_stores(|vessel, store| {
+            if vessel.downstream().len() > 0 {
+                return;
+            }
+            for (gas, time_constant) in time_constants.iter() {
+                // Changes already pending count toward the gap, so it isn't closed twice
+                let expected = store.predict_concentration(gas, sim_time + elapsed);
+                let fraction = 1.0 - (-elapsed.to_s() / time_constant.to_s()).exp();
+                let gap = target(vessel, *gas) - expected;
+                if gap.to_mM() != 0.0 {
+                    store.schedule_custom_change(
+                        *gas,
+                        SubstanceChange::new(sim_time, gap * fraction, elapsed, BoundFn::Linear),
+                    );
+                }
+            }
+        });
     }
+
     fn run(&mut self) {
+        // Tissue exchange goes on even when the blood isn't changing
+        let sim_time = self.core_connector.sim_time();
+        if (self.capillary_exchange.is_some() || self.capillary_targets.is_some())
+            && self.next_exchange.map_or(true, |t| sim_time >= t)
+        {
+            let period = SimTimeSpan::from_s(Self::CAPILLARY_EXCHANGE_PERIOD);
+            self.core_connector.set_timer(period, 0);
+            self.next_exchange = Some(sim_time + period);
+        }
+
+        self.exchange_gases();
+        if let Some(exchange) = self.capillary_exchange.as_mut() {
+            self.circ_connector.with_blood_stores(|vessel, store| {
+                if vessel.downstream().len() == 0 {
+                    exchange(vessel, store);
+                }
+            });
+        }
+
+        let heart_rate = *self.core_connector.get::<HeartRate>().unwrap_or(&self.base_heart_rate);
+        let (_, clamped) = self.heart_rate_ratio(&heart_rate);
+        if clamped && !self.heart_rate_clamped {
+            log::warn!(
+                "Heart rate {:?} is outside of the supported range for base heart rate {:?}. Blood flow delays will be saturated.",
+                heart_rate,
+                self.base_heart_rate,
+            );
+        }
+        self.heart_rate_clamped = clamped;
+
+        // Attached vessels, and those with new changes to propagate, from a
+        // single pass over the stores
         let mut change_list = Vec::new();
         let mut all_list = Vec::new();
         self.circ_connector.with_blood_stores(|vessel, store| {
-            all_list.push(vessel);
             if store.has_new_changes() {
-                log::debug!("New changes on {:?}", vessel);
                 change_list.push(vessel);
             }
+            all_list.push(vessel);
         });
+        log::debug!("New changes on {:?}", change_list);
 
         for source in change_list.iter() {
+            let mut paths = Vec::new();
             for target in all_list.iter().filter(|v| *v != source) {
+                match self.calculate_blood_delays(&heart_rate, *source, *target) {
+                    Some(delays) => paths.push((*target, delays)),
+                    None => {
+                        if self.unreachable_warned.insert((*source, *target)) {
+                            log::warn!(
+                                "{:?} is unreachable from {:?}. Changes will not be propagated between them.",
+                                target,
+                                source,
+                            );
+                        }
+                    }
+                }
+            }
+
+            // When conserving mass, changes are spread over the volume of
+            // the source and every reachable vessel with a known volume
+            let source_volume = source.volume().filter(|_| self.conserve_mass).map(|volume| volume.to_L());
+            let total_volume = source_volume.map(|volume| {
+                volume + paths.iter().filter_map(|(target, _)| target.volume()).map(|v| v.to_L()).sum::<f64>()
+            });
+
+            for (target, delays) in paths {
+                let (spread, volume_ratio) = match (source_volume, total_volume) {
+                    (Some(source_volume), Some(total_volume)) => (
+                        source_volume / total_volume,
+                        target.volume().map_or(0.0, |volume| volume.to_L() / source_volume),
+                    ),
+                    _ => (1.0, 0.0),
+                };
+                let mut washouts = Vec::new();
+
                 let mut source_store = self.circ_connector.blood_store(source).unwrap();
-                let mut target_store = self.circ_connector.blood_store(target).unwrap();
+                let mut target_store = self.circ_connector.blood_store(&target).unwrap();
 
                 log::debug!("propagating changes from {:?} to {:?}", source, target);
 
-                for (delay, factor) in self.calculate_blood_delays(*source, *target) {
+                // Only the free fraction of protein bound substances diffuses
+                let bound_fractions = source_store.bound_fractions().clone();
+
+                // Reservoirs mix slowly with the rest of the circulation
+                let outbound_mixing = self.circ_connector.mixing_factor(source);
+                let washout_mixing = self.circ_connector.mixing_factor(&target);
+
+                for (delay, factor, edges) in delays {
                     for (substance, change) in source_store.get_new_direct_changes() {
-                        target_store.schedule_dependent_change(
-                            substance,
-                            self.circ_connector.sim_time() + delay,
-                            factor,
-                            change,
-                        )
+                        let free_fraction = 1.0 - bound_fractions.get(&substance).copied().unwrap_or(0.0);
+                        let mut start_time = self.circ_connector.sim_time() + delay;
+
+                        // Apply the modifiers of each edge along the path in turn
+                        let modified = edges.iter().fold(None, |modified: Option<SubstanceChange>, edge| {
+                            let modify = &self.edge_modifiers[edge];
+                            Some(modify(modified.as_ref().unwrap_or(change)))
+                        });
+                        let change = match modified.as_ref() {
+                            Some(modified) => {
+                                // Keep the propagation delay relative to the modified change
+                                start_time += change.start_time().span_to(&modified.start_time());
+                                modified
+                            }
+                            None => change,
+                        };
+
+                        let mixing = if change.amount().molpm3 < 0.0 {
+                            outbound_mixing * washout_mixing
+                        } else {
+                            outbound_mixing
+                        };
+
+                        let factor = factor * free_fraction * mixing * spread;
+                        target_store.schedule_dependent_change(substance, start_time, factor, change);
+
+                        // The target's share of the change leaves the source as it arrives
+                        if volume_ratio > 0.0 {
+                            washouts.push((substance, start_time, -factor * volume_ratio, change.clone()));
+                        }
                     }
                 }
+
+                for (substance, start_time, factor, change) in washouts {
+                    source_store.schedule_dependent_change(substance, start_time, factor, &change);
+                }
             }
         }
     }
@@ -234,58 +775,144 @@ mod test;
 
 #[cfg(test)]
 mod tests {
+    use mortalsim_core::components::{BloodGasComponent, LymphaticComponent, MetabolismComponent, RenalClearanceComponent, VentilationComponent};
     use mortalsim_core::math::BoundFn;
-    use mortalsim_core::sim::organism::test::{TestBloodVessel, TestOrganism};
+    use mortalsim_core::sim::organism::test::{FistulaOrganism, FistulaTestVessel, FistulaVessel, TestBloodVessel, TestOrganism};
     use mortalsim_core::substance::{Substance, SubstanceChange, SubstanceConcentration};
     use mortalsim_core::units::mechanical::Frequency;
-    use mortalsim_core::event::HeartRate;
+    use mortalsim_core::event::{ArterialBloodGas, HeartRate, MetabolicRate, RespiratoryRate};
+    use mortalsim_core::units::mechanical::Power;
     use mortalsim_core::sim::organism::test::TestSim;
-    use mortalsim_core::sim::Sim;
+    use mortalsim_core::sim::{LayerType, Sim};
     use mortalsim_core::SimTime;
 
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+
+    use mortalsim_human::{HumanBloodVessel, HumanOrganism, HumanSim};
+
     use super::*;
     use super::test::*;
 
     #[test_log::test]
     fn distance_factor_ao_ab() {
         let res = SimpleBloodFlow::<TestOrganism>::distance_factor_between(TestBloodVessel::Aorta, TestBloodVessel::AbdominalAorta);
-        for (dist, fact) in res {
+        for (dist, fact) in res.unwrap() {
             assert_eq!(dist, 1);
             assert_eq!(fact, 1.0);
         }
     }
     #[test_log::test]
     fn distance_factor_ao_vc() {
+        // Paths through each leg are combined
         let res = SimpleBloodFlow::<TestOrganism>::distance_factor_between(TestBloodVessel::Aorta, TestBloodVessel::InferiorVenaCava);
-        for (dist, fact) in res {
-            assert_eq!(dist, 4);
-            assert_eq!(fact, 0.5);
-        }
+        assert_eq!(res, Some(vec![(4, 1.0)]));
     }
     #[test_log::test]
     fn distance_factor_rf_lf() {
         let res = SimpleBloodFlow::<TestOrganism>::distance_factor_between(TestBloodVessel::RightFemoralArtery, TestBloodVessel::LeftFemoralArtery);
-        for (dist, fact) in res {
+        for (dist, fact) in res.unwrap() {
             assert_eq!(dist, 6);
             assert_eq!(fact, 0.5);
         }
     }
 
+    #[test_log::test]
+    fn combine_paths_precision() {
+        // One major path alongside a million minor paths of the same
+        // length, each of which is lost when naively added to the major one
+        let mut paths = vec![(3, 1.0)];
+        paths.extend(std::iter::repeat((3, 1.0e-16)).take(1_000_000));
+        paths.push((5, 0.25));
+        let reference = 1.0 + 1.0e-10;
+
+        let naive = paths.iter().filter(|(d, _)| *d == 3).fold(0.0, |acc, (_, f)| acc + f);
+        assert_eq!(naive, 1.0);
+
+        let combined = SimpleBloodFlow::<TestOrganism>::combine_paths(paths.clone());
+        assert_eq!(combined.len(), 2);
+        assert_eq!(combined[0].0, 3);
+        assert!((combined[0].1 - reference).abs() < 1.0e-15, "{} != {}", combined[0].1, reference);
+        assert_eq!(combined[1], (5, 0.25));
+
+        // Order in which paths are found doesn't matter
+        paths.reverse();
+        assert_eq!(SimpleBloodFlow::<TestOrganism>::combine_paths(paths), combined);
+    }
+
+    #[test_log::test]
+    fn distance_factor_confluence() {
+        let factors = |strategy: ConfluenceStrategy<TestBloodVessel>| {
+            SimpleBloodFlow::<TestOrganism>::distance_factor_with(
+                &strategy,
+                TestBloodVessel::RightAxillaryArtery,
+                TestBloodVessel::VenaCava,
+            )
+        };
+
+        // The vena cava is fed by the jugular (10 mL arteries each) and
+        // axillary (15 mL arteries each) veins, so the right axillary vein
+        // carries 0.15 / 0.5 of its inflow rather than a quarter
+        let flow_weighted = factors(ConfluenceStrategy::FlowWeighted).unwrap();
+        assert_eq!(flow_weighted.len(), 1);
+        assert_eq!(flow_weighted[0].0, 2);
+        assert!((flow_weighted[0].1 - 0.3).abs() < 1e-9, "{:?}", flow_weighted);
+
+        assert_eq!(factors(ConfluenceStrategy::Average), Some(vec![(2, 0.25)]));
+        assert_eq!(factors(ConfluenceStrategy::Max), Some(vec![(2, 1.0)]));
+        assert_eq!(factors(ConfluenceStrategy::Custom(Box::new(|_, _| 0.5))), Some(vec![(2, 0.5)]));
+
+        // Flow weighting is the default
+        assert_eq!(
+            SimpleBloodFlow::<TestOrganism>::distance_factor_between(TestBloodVessel::RightAxillaryArtery, TestBloodVessel::VenaCava),
+            Some(flow_weighted)
+        );
+    }
+
+    #[test_log::test]
+    fn distance_factor_fistula() {
+        let artery = FistulaTestVessel::Base(TestBloodVessel::LeftAxillaryArtery);
+        let fistula = FistulaTestVessel::Ext(FistulaVessel::LeftArteriovenousFistula);
+        let vein = FistulaTestVessel::Base(TestBloodVessel::LeftAxillaryVein);
+
+        // Base vasculature only has the direct capillary path
+        assert_eq!(
+            SimpleBloodFlow::<TestOrganism>::distance_factor_between(TestBloodVessel::LeftAxillaryArtery, TestBloodVessel::LeftAxillaryVein),
+            Some(vec![(1, 1.0)])
+        );
+
+        assert_eq!(SimpleBloodFlow::<FistulaOrganism>::distance_factor_between(artery, fistula), Some(vec![(1, 1.0)]));
+
+        // Flow into the vein is now split between the capillaries and the fistula
+        let mut res = SimpleBloodFlow::<FistulaOrganism>::distance_factor_between(artery, vein).unwrap();
+        res.sort_by_key(|(d, _)| *d);
+        assert_eq!(res, vec![(1, 0.5), (2, 0.5)]);
+    }
+
+    #[test_log::test]
+    fn distance_factor_disconnected() {
+        // No path leads into the isolated vessel
+        assert!(SimpleBloodFlow::<DisconnectedOrganism>::distance_factor_between(DisconnectedVessel::Aorta, DisconnectedVessel::Isolated).is_none());
+        assert!(SimpleBloodFlow::<DisconnectedOrganism>::distance_factor_between(DisconnectedVessel::VenaCava, DisconnectedVessel::Isolated).is_none());
+        assert!(SimpleBloodFlow::<DisconnectedOrganism>::distance_factor_between(DisconnectedVessel::Aorta, DisconnectedVessel::VenaCava).is_some());
+    }
+
     #[test_log::test]
     fn blood_delay() {
-        let sbf = SimpleBloodFlow::<TestOrganism>::new(
+        let mut sbf = SimpleBloodFlow::<TestOrganism>::new(
             HeartRate(Frequency::from_Hz(60.0)),
             Time::from_s(60.0),
         );
 
-        for (d1, _f) in sbf.calculate_blood_delays(TestBloodVessel::Aorta, TestBloodVessel::AbdominalAorta) {
+        let hr = HeartRate(Frequency::from_Hz(60.0));
+        for (d1, _f, _) in sbf.calculate_blood_delays(&hr, TestBloodVessel::Aorta, TestBloodVessel::AbdominalAorta).unwrap() {
             assert!(
                 d1 < SimTimeSpan::from_s(60.0) && d1 > SimTimeSpan::from_s(1.0),
                 "Aorta->AbdominalAorta delay {d1} is not in a reasonable range."
             );
         }
         
-        for (d2, _f) in sbf.calculate_blood_delays(TestBloodVessel::Aorta, TestBloodVessel::VenaCava) {
+        for (d2, _f, _) in sbf.calculate_blood_delays(&hr, TestBloodVessel::Aorta, TestBloodVessel::VenaCava).unwrap() {
             assert!(
                 d2 < SimTimeSpan::from_s(60.0) && d2 > SimTimeSpan::from_s(20.0),
                 "Aorta->VenaCava delay {d2} is not in a reasonable range."
@@ -293,7 +920,87 @@ mod tests {
         }
     }
 
-    fn blood_component_aorta(time_factor: f64) -> TestBloodCheckerComponent {
+    #[test_log::test]
+    fn blood_delay_extreme_heart_rate() {
+        let delays = |sbf: &mut SimpleBloodFlow<TestOrganism>, hz: f64| -> Vec<f64> {
+            sbf.calculate_blood_delays(&HeartRate(Frequency::from_Hz(hz)), TestBloodVessel::Aorta, TestBloodVessel::VenaCava)
+                .unwrap()
+                .into_iter()
+                .map(|(d, _, _)| d.to_s())
+                .collect()
+        };
+        let sane = |ds: &Vec<f64>| ds.iter().all(|d| d.is_finite() && *d > 0.0);
+
+        let mut sbf = SimpleBloodFlow::<TestOrganism>::new(HeartRate(Frequency::from_Hz(60.0)), Time::from_s(60.0));
+        let normal = delays(&mut sbf, 60.0);
+
+        // Asystole is clamped to a quarter of the base heart rate
+        let asystole = delays(&mut sbf, 0.0);
+        assert!(sane(&asystole));
+        assert_eq!(asystole, delays(&mut sbf, 15.0));
+
+        // Extreme heart rates saturate at four times the base
+        let extreme = delays(&mut sbf, 1.0e12);
+        assert!(sane(&extreme));
+        assert_eq!(extreme, delays(&mut sbf, 240.0));
+        assert!(sane(&delays(&mut sbf, f64::NAN)));
+
+        // Limits can be configured
+        let mut limited = SimpleBloodFlow::<TestOrganism>::new(HeartRate(Frequency::from_Hz(60.0)), Time::from_s(60.0))
+            .with_heart_rate_limits(HeartRate(Frequency::from_Hz(30.0)), HeartRate(Frequency::from_Hz(120.0)));
+        assert_eq!(delays(&mut limited, 0.0), delays(&mut limited, 30.0));
+        assert_eq!(delays(&mut limited, 60.0), normal);
+
+        // A zero base heart rate leaves delays unscaled
+        let mut no_base = SimpleBloodFlow::<TestOrganism>::new(HeartRate(Frequency::from_Hz(0.0)), Time::from_s(60.0));
+        assert_eq!(delays(&mut no_base, 0.0), normal);
+    }
+
+    #[test_log::test]
+    fn blood_delay_varying_heart_rate() {
+        let mut sbf = SimpleBloodFlow::<HumanOrganism>::new(HeartRate(Frequency::from_Hz(1.2)), Time::from_s(60.0));
+        let vessels: Vec<HumanBloodVessel> = HumanBloodVessel::arteries().take(10)
+            .chain(HumanBloodVessel::veins().take(10))
+            .collect();
+        let pairs: Vec<(HumanBloodVessel, HumanBloodVessel)> = vessels.iter()
+            .flat_map(|a| vessels.iter().filter(move |b| *b != a).map(move |b| (*a, *b)))
+            .collect();
+
+        // Delays and factors of every pair at the given heart rate
+        let run = |sbf: &mut SimpleBloodFlow<HumanOrganism>, hz: f64| {
+            pairs.iter()
+                .map(|(a, b)| {
+                    sbf.calculate_blood_delays(&HeartRate(Frequency::from_Hz(hz)), *a, *b)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|(delay, factor, _)| (delay.to_s(), factor))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let base = run(&mut sbf, 1.2);
+        assert!(base.iter().any(|paths| !paths.is_empty()));
+        assert_eq!(sbf.base_delays.len(), pairs.len());
+
+        // Varying the heart rate reuses the cached base delays, scaling
+        // them by exactly the heart rate ratio, so a faster heart shortens
+        // the delays without changing the factors
+        for i in 0..20 {
+            let hz = 0.6 + 0.1 * f64::from(i);
+            let scaled = run(&mut sbf, hz);
+            assert_eq!(sbf.base_delays.len(), pairs.len());
+            for (base_paths, paths) in base.iter().zip(scaled.iter()) {
+                assert_eq!(base_paths.len(), paths.len());
+                for ((base_delay, base_factor), (delay, factor)) in base_paths.iter().zip(paths.iter()) {
+                    assert!((delay - base_delay * 1.2 / hz).abs() < 1e-9, "{} {} at {} Hz", base_delay, delay, hz);
+                    assert_eq!(base_factor, factor);
+                }
+            }
+        }
+    }
+
+    fn blood_component_aorta(time_factor: f64) -> TestBloodCheckerComponent<TestOrganism> {
         TestBloodCheckerComponent::new(
             TestBloodVessel::Aorta,
             vec![
@@ -317,7 +1024,7 @@ mod tests {
         )
     }
 
-    fn blood_component_left_arm(time_factor: f64) -> TestBloodCheckerComponent {
+    fn blood_component_left_arm(time_factor: f64) -> TestBloodCheckerComponent<TestOrganism> {
         TestBloodCheckerComponent::new(
             TestBloodVessel::LeftAxillaryVein,
             vec![
@@ -342,7 +1049,7 @@ mod tests {
         )
     }
 
-    fn blood_component_right_leg(time_factor: f64) -> TestBloodCheckerComponent {
+    fn blood_component_right_leg(time_factor: f64) -> TestBloodCheckerComponent<TestOrganism> {
         TestBloodCheckerComponent::new(
             TestBloodVessel::RightFemoralVein,
             vec![
@@ -367,6 +1074,607 @@ mod tests {
         )
     }
 
+    #[test_log::test]
+    fn test_disconnected_blood_flow() {
+        let mut sim = DisconnectedSim::new();
+        sim.add_component(SimpleBloodFlow::new(
+            HeartRate(Frequency::from_Hz(60.0)),
+            Time::from_s(60.0),
+        )).unwrap();
+        sim.add_component(TestBloodCheckerComponent::<DisconnectedOrganism>::new(
+            DisconnectedVessel::Aorta,
+            vec![
+                (
+                    SimTime::from_s(0.0),
+                    Substance::O2,
+                    SubstanceChange::new(
+                        SimTime::from_s(1.0),
+                        SubstanceConcentration::from_uM(100.0),
+                        SimTimeSpan::from_s(10.0),
+                        BoundFn::Linear,
+                    ),
+                )
+            ],
+            vec![],
+        )).unwrap();
+        sim.add_component(TestBloodCheckerComponent::<DisconnectedOrganism>::new(
+            DisconnectedVessel::VenaCava,
+            vec![],
+            vec![
+                (SimTime::from_s(60.0), Substance::O2, SubstanceConcentrationRange::new(99.9, 100.1)),
+            ],
+        )).unwrap();
+        sim.add_component(TestBloodCheckerComponent::<DisconnectedOrganism>::new(
+            DisconnectedVessel::Isolated,
+            vec![],
+            vec![
+                (SimTime::from_s(30.0), Substance::O2, SubstanceConcentrationRange::new(-0.1, 0.1)),
+                (SimTime::from_s(60.0), Substance::O2, SubstanceConcentrationRange::new(-0.1, 0.1)),
+            ],
+        )).unwrap();
+
+        for _ in 1..70 {
+            sim.advance_by(SimTimeSpan::from_s(1.0));
+        }
+    }
+
+    #[test_log::test]
+    fn test_total_substance() {
+        let mut sim = TestSim::new();
+        sim.add_component(SimpleBloodFlow::new(
+            HeartRate(Frequency::from_Hz(60.0)),
+            Time::from_s(60.0),
+        ).with_mass_conservation()).unwrap();
+
+        // Tissue compartment exchanging with the 20 mL LeftFemoralArtery
+        sim.add_component(
+            LymphaticComponent::new(vec![Substance::O2])
+                .with_compartment_times(
+                    TestBloodVessel::LeftFemoralArtery,
+                    TestBloodVessel::LeftFemoralVein,
+                    SimTimeSpan::from_s(30.0),
+                    SimTimeSpan::from_s(60.0),
+                )
+                .with_update_period(SimTimeSpan::from_s(1.0)),
+        ).unwrap();
+
+        // 100 uM tracer injected into the 100 mL Aorta
+        sim.add_component(TestBloodCheckerComponent::<TestOrganism>::new(
+            TestBloodVessel::Aorta,
+            vec![
+                (
+                    SimTime::from_s(0.0),
+                    Substance::O2,
+                    SubstanceChange::new(
+                        SimTime::from_s(1.0),
+                        SubstanceConcentration::from_uM(100.0),
+                        SimTimeSpan::from_s(5.0),
+                        BoundFn::Linear,
+                    ),
+                )
+            ],
+            vec![],
+        )).unwrap();
+        for vessel in [
+            TestBloodVessel::RightCarotidArtery,
+            TestBloodVessel::LeftCarotidArtery,
+            TestBloodVessel::RightAxillaryArtery,
+            TestBloodVessel::LeftAxillaryArtery,
+            TestBloodVessel::AbdominalAorta,
+            TestBloodVessel::RightFemoralArtery,
+            TestBloodVessel::LeftFemoralArtery,
+            TestBloodVessel::VenaCava,
+            TestBloodVessel::RightAxillaryVein,
+            TestBloodVessel::LeftAxillaryVein,
+            TestBloodVessel::RightJugularVein,
+            TestBloodVessel::LeftJugularVein,
+            TestBloodVessel::InferiorVenaCava,
+            TestBloodVessel::RightFemoralVein,
+            TestBloodVessel::LeftFemoralVein,
+        ] {
+            sim.add_component(TestBloodCheckerComponent::<TestOrganism>::new(vessel, vec![], vec![])).unwrap();
+        }
+
+        let total_umol = |sim: &TestSim| sim.total_substance(&Substance::O2).to_umol();
+        let o2_um = |sim: &mut TestSim, vessel| {
+            let mut value = f64::NAN;
+            sim.inspect_circulation("SimpleBloodFlow", |connector| {
+                value = connector.blood_store(&vessel).unwrap().concentration_of(&Substance::O2).to_uM();
+            }).unwrap();
+            value
+        };
+
+        // The injected amount is held constant across every vessel and the
+        // tissue as the tracer spreads through the circulation
+        for _ in 0..300 {
+            sim.advance_by(SimTimeSpan::from_s(1.0));
+            if sim.time() >= SimTime::from_s(6.0) {
+                assert!((total_umol(&sim) - 10.0).abs() < 1e-6, "Unexpected total {} at {}", total_umol(&sim), sim.time());
+            }
+        }
+
+        // rather than replicating the injected concentration downstream
+        let aorta = o2_um(&mut sim, TestBloodVessel::Aorta);
+        let vein = o2_um(&mut sim, TestBloodVessel::LeftFemoralVein);
+        assert!(aorta > 0.0 && aorta < 50.0, "{}", aorta);
+        assert!(vein > 0.0 && vein < 50.0, "{}", vein);
+    }
+
+    #[test_log::test]
+    fn test_tracer_transit() {
+        let tracer_totals = |clearance: bool| {
+            let mut sim = TestSim::new();
+            sim.add_component(SimpleBloodFlow::new(
+                HeartRate(Frequency::from_Hz(60.0)),
+                Time::from_s(60.0),
+            )).unwrap();
+            if clearance {
+                sim.add_component(
+                    RenalClearanceComponent::new(vec![TestBloodVessel::LeftFemoralArtery, TestBloodVessel::RightFemoralArtery])
+                        .with_substance(Substance::Tracer(1), 0.8)
+                        .with_update_period(SimTimeSpan::from_s(1.0)),
+                ).unwrap();
+            }
+
+            // 100 uM of a tracer injected into the Aorta
+            sim.add_component(TestBloodCheckerComponent::<TestOrganism>::new(
+                TestBloodVessel::Aorta,
+                vec![
+                    (
+                        SimTime::from_s(0.0),
+                        Substance::Tracer(1),
+                        SubstanceChange::new(
+                            SimTime::from_s(1.0),
+                            SubstanceConcentration::from_uM(100.0),
+                            SimTimeSpan::from_s(5.0),
+                            BoundFn::Linear,
+                        ),
+                    )
+                ],
+                vec![],
+            )).unwrap();
+
+            // Spreads through the limbs to the venous return
+            sim.add_component(TestBloodCheckerComponent::<TestOrganism>::new(
+                TestBloodVessel::VenaCava,
+                vec![],
+                vec![(SimTime::from_s(100.0), Substance::Tracer(1), SubstanceConcentrationRange::new(50.0, 150.0))],
+            )).unwrap();
+            for vessel in [
+                TestBloodVessel::InferiorVenaCava,
+                TestBloodVessel::LeftAxillaryVein,
+                TestBloodVessel::LeftFemoralArtery,
+                TestBloodVessel::RightFemoralArtery,
+                TestBloodVessel::RightFemoralVein,
+            ] {
+                sim.add_component(TestBloodCheckerComponent::<TestOrganism>::new(vessel, vec![], vec![])).unwrap();
+            }
+
+            let mut totals = Vec::new();
+            for _ in 0..150 {
+                sim.advance_by(SimTimeSpan::from_s(1.0));
+                totals.push(sim.total_substance(&Substance::Tracer(1)).to_umol());
+                assert_eq!(sim.total_substance(&Substance::Tracer(2)).to_umol(), 0.0);
+            }
+            totals
+        };
+
+        // Tracers aren't cleared, so transit is identical with renal
+        // clearance running, and the total holds once propagation is done
+        let totals = tracer_totals(false);
+        for (with_clearance, without) in tracer_totals(true).iter().zip(totals.iter()) {
+            assert!((with_clearance - without).abs() < 1e-6, "{} != {}", with_clearance, without);
+        }
+        for total in totals[100..].iter() {
+            assert!((total - totals[100]).abs() < 1e-6, "Total {} drifted from {}", total, totals[100]);
+        }
+        assert!(Substance::Tracer(1).is_tracer() && !Substance::GLCL.is_tracer());
+    }
+
+    #[test_log::test]
+    fn test_ventilation() {
+        // Arterial PaCO2 (mmHg) over time at the given respiratory rate (breaths/min)
+        let pa_co2 = |breaths_per_min: f64| {
+            let mut sim = TestSim::new();
+            sim.add_component(SimpleBloodFlow::new(
+                HeartRate(Frequency::from_Hz(60.0)),
+                Time::from_s(60.0),
+            )).unwrap();
+            sim.add_component(MetabolismComponent::whole_body(
+                vec![TestBloodVessel::VenaCava, TestBloodVessel::InferiorVenaCava],
+                SimTimeSpan::from_s(1.0),
+            )).unwrap();
+            sim.add_component(VentilationComponent::new(RespiratoryRate(Frequency::from_Hz(12.0 / 60.0)))).unwrap();
+            sim.add_component(BloodGasComponent::new(TestBloodVessel::Aorta)).unwrap();
+            sim.schedule_event(SimTimeSpan::from_s(0.0), Box::new(MetabolicRate(Power::from_W(400.0))));
+            sim.schedule_event(SimTimeSpan::from_s(0.0), Box::new(RespiratoryRate(Frequency::from_Hz(breaths_per_min / 60.0))));
+
+            let mut values = Vec::new();
+            let mut current = 0.0;
+            for _ in 0..300 {
+                sim.force_layer_run(Some(LayerType::Circulation));
+                sim.advance_by(SimTimeSpan::from_s(1.0));
+                if let Some(abg) = sim.drain_active().filter_map(|e| e.downcast_ref::<ArterialBloodGas>().copied()).last() {
+                    current = abg.pa_co2.to_mmHg();
+                }
+                values.push(current);
+            }
+            values
+        };
+
+        // Faster breathing clears more CO2, so arterial CO2 settles lower
+        let normal = pa_co2(12.0);
+        let fast = pa_co2(30.0);
+        assert!(normal[299] > 40.0, "{}", normal[299]);
+        assert!(fast[299] < normal[299] - 10.0, "{} vs {}", fast[299], normal[299]);
+        for (fast, normal) in fast.iter().zip(normal.iter()) {
+            assert!(fast <= normal, "{} > {}", fast, normal);
+        }
+    }
+
+    #[test_log::test]
+    fn test_edge_modifier() {
+        let mut sbf = SimpleBloodFlow::new(
+            HeartRate(Frequency::from_Hz(60.0)),
+            Time::from_s(60.0),
+        );
+        // Complete stenosis between the Aorta and Abdominal Aorta
+        sbf.add_edge_modifier(TestBloodVessel::Aorta, TestBloodVessel::AbdominalAorta, |change| change.scaled(0.0));
+
+        let mut sim = TestSim::new();
+        sim.add_component(sbf).unwrap();
+        sim.add_component(TestBloodCheckerComponent::<TestOrganism>::new(
+            TestBloodVessel::Aorta,
+            vec![
+                (
+                    SimTime::from_s(0.0),
+                    Substance::O2,
+                    SubstanceChange::new(
+                        SimTime::from_s(1.0),
+                        SubstanceConcentration::from_uM(100.0),
+                        SimTimeSpan::from_s(10.0),
+                        BoundFn::Linear,
+                    ),
+                )
+            ],
+            vec![],
+        )).unwrap();
+        sim.add_component(TestBloodCheckerComponent::<TestOrganism>::new(
+            TestBloodVessel::AbdominalAorta,
+            vec![],
+            vec![
+                (SimTime::from_s(60.0), Substance::O2, SubstanceConcentrationRange::new(-0.1, 0.1)),
+            ],
+        )).unwrap();
+
+        // Vessels further downstream are blocked as well
+        for vessel in [TestBloodVessel::RightFemoralArtery, TestBloodVessel::LeftFemoralArtery] {
+            sim.add_component(TestBloodCheckerComponent::<TestOrganism>::new(
+                vessel,
+                vec![],
+                vec![
+                    (SimTime::from_s(60.0), Substance::O2, SubstanceConcentrationRange::new(-0.1, 0.1)),
+                ],
+            )).unwrap();
+        }
+
+        // Vessels reached around the stenosis are unaffected
+        sim.add_component(TestBloodCheckerComponent::<TestOrganism>::new(
+            TestBloodVessel::RightCarotidArtery,
+            vec![],
+            vec![
+                (SimTime::from_s(60.0), Substance::O2, SubstanceConcentrationRange::new(99.9, 100.1)),
+            ],
+        )).unwrap();
+
+        for _ in 0..61 {
+            sim.force_layer_run(Some(LayerType::Circulation));
+            sim.advance_by(SimTimeSpan::from_s(1.0));
+        }
+    }
+
+    #[test_log::test]
+    fn test_reservoir() {
+        let sbf = SimpleBloodFlow::new(
+            HeartRate(Frequency::from_Hz(60.0)),
+            Time::from_s(60.0),
+        ).with_reservoir(TestBloodVessel::LeftFemoralArtery, 0.1);
+
+        let mut sim = TestSim::new();
+        sim.add_component(sbf).unwrap();
+
+        // A 100 uM bolus passes through the Aorta
+        sim.add_component(TestBloodCheckerComponent::<TestOrganism>::new(
+            TestBloodVessel::Aorta,
+            vec![
+                (
+                    SimTime::from_s(0.0),
+                    Substance::O2,
+                    SubstanceChange::new(
+                        SimTime::from_s(1.0),
+                        SubstanceConcentration::from_uM(100.0),
+                        SimTimeSpan::from_s(5.0),
+                        BoundFn::Linear,
+                    ),
+                ),
+                (
+                    SimTime::from_s(39.0),
+                    Substance::O2,
+                    SubstanceChange::new(
+                        SimTime::from_s(40.0),
+                        SubstanceConcentration::from_uM(-100.0),
+                        SimTimeSpan::from_s(5.0),
+                        BoundFn::Linear,
+                    ),
+                ),
+            ],
+            vec![],
+        )).unwrap();
+
+        // Ordinary vessels are washed out once the bolus has passed,
+        // while the reservoir retains most of it
+        sim.add_component(TestBloodCheckerComponent::<TestOrganism>::new(
+            TestBloodVessel::RightFemoralArtery,
+            vec![],
+            vec![
+                (SimTime::from_s(40.0), Substance::O2, SubstanceConcentrationRange::new(99.9, 100.1)),
+                (SimTime::from_s(100.0), Substance::O2, SubstanceConcentrationRange::new(-0.1, 0.1)),
+            ],
+        )).unwrap();
+        sim.add_component(TestBloodCheckerComponent::<TestOrganism>::new(
+            TestBloodVessel::LeftFemoralArtery,
+            vec![],
+            vec![
+                (SimTime::from_s(40.0), Substance::O2, SubstanceConcentrationRange::new(99.9, 100.1)),
+                (SimTime::from_s(100.0), Substance::O2, SubstanceConcentrationRange::new(89.9, 90.1)),
+            ],
+        )).unwrap();
+
+        for _ in 0..101 {
+            sim.force_layer_run(Some(LayerType::Circulation));
+            sim.advance_by(SimTimeSpan::from_s(1.0));
+        }
+    }
+
+    #[test_log::test]
+    fn test_baseline_heart_rate() {
+        // The baseline heart rate of 70 bpm is twice the base heart rate,
+        // so the O2 reaches the Vena Cava well before it would at the base
+        let mut sim = TestSim::new();
+        sim.add_component(SimpleBloodFlow::new(
+            HeartRate(Frequency::from_Hz(35.0 / 60.0)),
+            Time::from_s(60.0),
+        )).unwrap();
+        sim.add_component(TestBloodCheckerComponent::<TestOrganism>::new(
+            TestBloodVessel::Aorta,
+            vec![
+                (
+                    SimTime::from_s(0.0),
+                    Substance::O2,
+                    SubstanceChange::new(
+                        SimTime::from_s(1.0),
+                        SubstanceConcentration::from_uM(100.0),
+                        SimTimeSpan::from_s(10.0),
+                        BoundFn::Linear,
+                    ),
+                )
+            ],
+            vec![],
+        )).unwrap();
+        sim.add_component(TestBloodCheckerComponent::<TestOrganism>::new(
+            TestBloodVessel::VenaCava,
+            vec![],
+            vec![
+                (SimTime::from_s(35.0), Substance::O2, SubstanceConcentrationRange::new(99.9, 100.1)),
+            ],
+        )).unwrap();
+
+        sim.initialize_baseline_vitals();
+        for _ in 0..61 {
+            sim.force_layer_run(Some(LayerType::Circulation));
+            sim.advance_by(SimTimeSpan::from_s(1.0));
+        }
+    }
+
+    #[test_log::test]
+    fn test_protein_binding() {
+        let free = Arc::new(Mutex::new(SubstanceConcentration::from_mM(0.0)));
+        let mut sim = TestSim::new();
+        sim.add_component(SimpleBloodFlow::new(
+            HeartRate(Frequency::from_Hz(60.0)),
+            Time::from_s(60.0),
+        )).unwrap();
+
+        // 90% of Retinol is bound, so only 10 uM of the 100 uM is free
+        sim.add_component(TestProteinBindingComponent::<TestOrganism>::new(
+            TestBloodVessel::Aorta,
+            Substance::Retinol,
+            0.9,
+            free.clone(),
+        )).unwrap();
+        sim.add_component(TestBloodCheckerComponent::<TestOrganism>::new(
+            TestBloodVessel::Aorta,
+            vec![
+                (
+                    SimTime::from_s(0.0),
+                    Substance::Retinol,
+                    SubstanceChange::new(
+                        SimTime::from_s(1.0),
+                        SubstanceConcentration::from_uM(100.0),
+                        SimTimeSpan::from_s(5.0),
+                        BoundFn::Linear,
+                    ),
+                )
+            ],
+            vec![
+                // The bound fraction stays behind, so the total is unchanged
+                (SimTime::from_s(60.0), Substance::Retinol, SubstanceConcentrationRange::new(99.9, 100.1)),
+            ],
+        )).unwrap();
+
+        // Only the free fraction reaches downstream vessels
+        sim.add_component(TestBloodCheckerComponent::<TestOrganism>::new(
+            TestBloodVessel::AbdominalAorta,
+            vec![],
+            vec![
+                (SimTime::from_s(60.0), Substance::Retinol, SubstanceConcentrationRange::new(9.9, 10.1)),
+            ],
+        )).unwrap();
+
+        for _ in 0..61 {
+            sim.force_layer_run(Some(LayerType::Circulation));
+            sim.advance_by(SimTimeSpan::from_s(1.0));
+        }
+
+        let free = free.lock().unwrap().to_uM();
+        assert!((free - 10.0).abs() < 1e-6, "Unexpected free concentration {}", free);
+    }
+
+    #[test_log::test]
+    fn test_capillary_exchange() {
+        let exchanged = Arc::new(Mutex::new(HashSet::new()));
+        let exchanged_ref = exchanged.clone();
+
+        let sbf = SimpleBloodFlow::new(
+            HeartRate(Frequency::from_Hz(60.0)),
+            Time::from_s(60.0),
+        ).with_capillary_exchange(move |vessel: TestBloodVessel, store| {
+            let o2 = store.concentration_of(&Substance::O2);
+            // Tissues consume some of the delivered O2 once, producing CO2
+            if o2 > SubstanceConcentration::from_uM(40.0) && exchanged_ref.lock().unwrap().insert(vessel) {
+                store.schedule_change(Substance::O2, SubstanceConcentration::from_uM(-20.0), SimTimeSpan::from_s(2.0));
+                store.schedule_change(Substance::CO2, SubstanceConcentration::from_uM(20.0), SimTimeSpan::from_s(2.0));
+            }
+        });
+
+        let mut sim = TestSim::new();
+        sim.add_component(sbf).unwrap();
+        sim.add_component(TestBloodCheckerComponent::<TestOrganism>::new(
+            TestBloodVessel::Aorta,
+            vec![
+                (
+                    SimTime::from_s(0.0),
+                    Substance::O2,
+                    SubstanceChange::new(
+                        SimTime::from_s(1.0),
+                        SubstanceConcentration::from_uM(100.0),
+                        SimTimeSpan::from_s(5.0),
+                        BoundFn::Linear,
+                    ),
+                )
+            ],
+            vec![
+                // CO2 produced at the capillaries loops back around
+                (SimTime::from_s(30.0), Substance::CO2, SubstanceConcentrationRange::new(-0.1, 0.1)),
+                (SimTime::from_s(150.0), Substance::CO2, SubstanceConcentrationRange::new(14.9, 15.1)),
+            ],
+        )).unwrap();
+        sim.add_component(TestBloodCheckerComponent::<TestOrganism>::new(
+            TestBloodVessel::VenaCava,
+            vec![],
+            vec![
+                (SimTime::from_s(30.0), Substance::CO2, SubstanceConcentrationRange::new(-0.1, 0.1)),
+                (SimTime::from_s(60.0), Substance::CO2, SubstanceConcentrationRange::new(19.9, 20.1)),
+                (SimTime::from_s(60.0), Substance::O2, SubstanceConcentrationRange::new(79.9, 80.1)),
+            ],
+        )).unwrap();
+        sim.add_component(TestBloodCheckerComponent::<TestOrganism>::new(
+            TestBloodVessel::InferiorVenaCava,
+            vec![],
+            vec![
+                (SimTime::from_s(80.0), Substance::CO2, SubstanceConcentrationRange::new(19.9, 20.1)),
+                (SimTime::from_s(80.0), Substance::O2, SubstanceConcentrationRange::new(79.9, 80.1)),
+            ],
+        )).unwrap();
+
+        for _ in 0..150 {
+            sim.advance_by(SimTimeSpan::from_s(1.0));
+        }
+
+        // Exchange only takes place at the terminal vessels
+        assert_eq!(
+            *exchanged.lock().unwrap(),
+            HashSet::from([TestBloodVessel::VenaCava, TestBloodVessel::InferiorVenaCava])
+        );
+    }
+
+    #[test_log::test]
+    fn test_capillary_exchange_periodic() {
+        let exchanges = Arc::new(Mutex::new(0));
+        let exchanges_ref = exchanges.clone();
+
+        let sbf = SimpleBloodFlow::new(
+            HeartRate(Frequency::from_Hz(60.0)),
+            Time::from_s(60.0),
+        ).with_capillary_exchange(move |_: TestBloodVessel, _| *exchanges_ref.lock().unwrap() += 1);
+
+        let mut sim = TestSim::new();
+        sim.add_component(sbf).unwrap();
+        // Stores only exist for vessels something else is attached to
+        for vessel in [TestBloodVessel::VenaCava, TestBloodVessel::InferiorVenaCava] {
+            sim.add_component(TestBloodCheckerComponent::<TestOrganism>::new(vessel, vec![], vec![])).unwrap();
+        }
+
+        // The exchange keeps running on each terminal vessel with nothing changing
+        for _ in 0..10 {
+            sim.advance_by(SimTimeSpan::from_s(1.0));
+        }
+        assert_eq!(*exchanges.lock().unwrap(), 20);
+
+        // Forced runs exchange in between
+        sim.force_layer_run(Some(LayerType::Circulation));
+        sim.advance_by(SimTimeSpan::from_s(0.5));
+        assert_eq!(*exchanges.lock().unwrap(), 22);
+        sim.advance_by(SimTimeSpan::from_s(0.5));
+        assert_eq!(*exchanges.lock().unwrap(), 24);
+    }
+
+    #[test_log::test]
+    fn test_capillary_gas_exchange() {
+        let o2_target = SubstanceConcentration::from_uM(40.0);
+        let co2_target = SubstanceConcentration::from_uM(60.0);
+
+        // Only the vena cava participates, so nothing flows back into it
+        let sbf = SimpleBloodFlow::new(
+            HeartRate(Frequency::from_Hz(60.0)),
+            Time::from_s(60.0),
+        ).restricted_to(HashSet::from([TestBloodVessel::VenaCava]))
+        .with_capillary_gas_exchange(
+            HashMap::from([
+                (Substance::CO2, SimTimeSpan::from_s(5.0)),
+                (Substance::O2, SimTimeSpan::from_s(20.0)),
+            ]),
+            move |_, gas| if gas == Substance::O2 { o2_target } else { co2_target },
+        );
+
+        let mut sim = TestSim::new();
+        sim.add_component(sbf).unwrap();
+
+        // Fraction of the way to each target
+        let mut progress = Vec::new();
+        for _ in 0..60 {
+            sim.force_layer_run(Some(LayerType::Circulation));
+            sim.advance_by(SimTimeSpan::from_s(1.0));
+            sim.inspect_circulation("SimpleBloodFlow", |connector| {
+                let store = connector.blood_store(&TestBloodVessel::VenaCava).unwrap();
+                progress.push((
+                    store.concentration_of(&Substance::CO2) / co2_target,
+                    store.concentration_of(&Substance::O2) / o2_target,
+                ));
+            }).unwrap();
+        }
+
+        // CO2 equilibrates faster than O2
+        let (co2_progress, o2_progress) = progress[10];
+        assert!(co2_progress > 0.75 && o2_progress < 0.5, "{:?}", progress[10]);
+        assert!(progress[59].0 > 0.99 && progress[59].1 > 0.9, "{:?}", progress[59]);
+
+        // and neither overshoots its target
+        for (co2_progress, o2_progress) in progress.iter() {
+            assert!(*co2_progress <= 1.0 + 1e-9 && *o2_progress <= 1.0 + 1e-9, "{:?}", progress);
+        }
+    }
+
     #[test_log::test]
     fn test_blood_flow() {
         let bhr = HeartRate(Frequency::from_Hz(60.0));
@@ -381,4 +1689,139 @@ mod tests {
             sim.advance_by(SimTimeSpan::from_s(1.0));
         }
     }
+
+    /// Runs blood flow on a HumanSim for a minute with a series of O2
+    /// changes in the Aorta, returning the time spent and the number of
+    /// vessels the O2 reached
+    fn human_blood_flow(sbf: SimpleBloodFlow<HumanOrganism>, outside_o2: SubstanceConcentrationRange) -> (Duration, usize) {
+        let reached = Arc::new(Mutex::new(0));
+        let mut sim = HumanSim::new();
+        sim.add_component(sbf).unwrap();
+        sim.add_component(TestReachComponent::<HumanOrganism>::new(Substance::O2, reached.clone())).unwrap();
+        // 100 uM in total, over 50 separate changes
+        sim.add_component(TestBloodCheckerComponent::<HumanOrganism>::new(
+            HumanBloodVessel::Aorta,
+            (0..50).map(|i| (
+                SimTime::from_s(i as f64),
+                Substance::O2,
+                SubstanceChange::new(
+                    SimTime::from_s(i as f64 + 1.0),
+                    SubstanceConcentration::from_uM(2.0),
+                    SimTimeSpan::from_s(1.0),
+                    BoundFn::Linear,
+                ),
+            )).collect(),
+            vec![],
+        )).unwrap();
+        sim.add_component(TestBloodCheckerComponent::<HumanOrganism>::new(
+            HumanBloodVessel::AbdominalAorta,
+            vec![],
+            vec![
+                (SimTime::from_s(60.0), Substance::O2, SubstanceConcentrationRange::new(99.9, 100.1)),
+            ],
+        )).unwrap();
+        sim.add_component(TestBloodCheckerComponent::<HumanOrganism>::new(
+            HumanBloodVessel::LeftSubclavianArtery,
+            vec![],
+            vec![(SimTime::from_s(60.0), Substance::O2, outside_o2)],
+        )).unwrap();
+
+        let start = Instant::now();
+        for _ in 0..61 {
+            // Make sure the checkers read even when nothing has changed
+            sim.force_layer_run(Some(LayerType::Circulation));
+            sim.advance_by(SimTimeSpan::from_s(1.0));
+        }
+        let elapsed = start.elapsed();
+        let reached = *reached.lock().unwrap();
+        (elapsed, reached)
+    }
+
+    #[test_log::test]
+    fn test_restricted_to() {
+        let sbf = || SimpleBloodFlow::new(
+            HeartRate(Frequency::from_Hz(60.0)),
+            Time::from_s(60.0),
+        );
+
+        // Compare the best of several runs, so that other tests running
+        // concurrently don't swamp the difference
+        let mut restricted_time = Duration::MAX;
+        let mut full_time = Duration::MAX;
+        for _ in 0..3 {
+            // Vessels outside the subset never receive the change
+            let (elapsed, restricted_reach) = human_blood_flow(
+                sbf().restricted_to(HashSet::from([
+                    HumanBloodVessel::Aorta,
+                    HumanBloodVessel::ThoracicAorta,
+                    HumanBloodVessel::AbdominalAorta,
+                ])),
+                SubstanceConcentrationRange::new(-0.1, 0.1),
+            );
+            assert_eq!(restricted_reach, 3);
+            restricted_time = restricted_time.min(elapsed);
+
+            let (elapsed, full_reach) = human_blood_flow(sbf(), SubstanceConcentrationRange::new(99.9, 100.1));
+            assert!(full_reach > 10 * restricted_reach, "O2 only reached {} vessels", full_reach);
+            full_time = full_time.min(elapsed);
+        }
+
+        log::info!("Restricted: {:?}, full: {:?}", restricted_time, full_time);
+        assert!(
+            restricted_time * 2 < full_time,
+            "Restricted {:?} was not substantially faster than full {:?}",
+            restricted_time,
+            full_time,
+        );
+    }
+
+    /// Markers around the output of a run in `run_writes_nothing_to_stdout`
+    const RUN_START: &str = "<<run start>>";
+    const RUN_END: &str = "<<run end>>";
+
+    #[test]
+    fn run_writes_nothing_to_stdout() {
+        // The test harness captures stdout within the test process, so the
+        // run happens in a child process running only this test
+        if std::env::var_os("MORTALSIM_STDOUT_CHILD").is_some() {
+            println!("{}", RUN_START);
+            let mut sim = TestSim::new();
+            sim.add_component(SimpleBloodFlow::new(
+                HeartRate(Frequency::from_Hz(60.0)),
+                Time::from_s(60.0),
+            )).unwrap();
+            sim.add_component(TestBloodCheckerComponent::<TestOrganism>::new(
+                TestBloodVessel::Aorta,
+                vec![(
+                    SimTime::from_s(0.0),
+                    Substance::GLC,
+                    SubstanceChange::new(
+                        SimTime::from_s(1.0),
+                        SubstanceConcentration::from_mM(1.0),
+                        SimTimeSpan::from_s(5.0),
+                        BoundFn::Linear,
+                    ),
+                )],
+                vec![],
+            )).unwrap();
+            sim.add_component(TestBloodCheckerComponent::<TestOrganism>::new(TestBloodVessel::VenaCava, vec![], vec![])).unwrap();
+            for _ in 0..30 {
+                sim.advance_by(SimTimeSpan::from_s(1.0));
+            }
+            println!("{}", RUN_END);
+            return;
+        }
+
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args(["tests::run_writes_nothing_to_stdout", "--exact", "--nocapture", "--test-threads=1"])
+            .env("MORTALSIM_STDOUT_CHILD", "1")
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(output.status.success(), "{}", stdout);
+
+        let start = stdout.find(RUN_START).expect("Run didn't start") + RUN_START.len();
+        let end = stdout.find(RUN_END).expect("Run didn't end");
+        assert_eq!(stdout[start..end].trim(), "");
+    }
 }
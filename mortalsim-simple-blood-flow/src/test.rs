@@ -1,10 +1,16 @@
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex, OnceLock};
 
+use anyhow::anyhow;
+use mortalsim_core::impl_sim;
 use mortalsim_core::sim::component::SimComponent;
-use mortalsim_core::sim::layer::circulation::{CirculationComponent, CirculationConnector};
-use mortalsim_core::sim::organism::test::{TestBloodVessel, TestOrganism};
-use mortalsim_core::substance::{Substance, SubstanceChange, SubstanceConcentration};
-use mortalsim_core::SimTime;
+use mortalsim_core::sim::layer::circulation::{BloodVessel, BloodVesselType, CirculationComponent, CirculationConnector, VesselIter};
+use mortalsim_core::sim::layer::core::{CoreComponent, CoreConnector, CoreInitializer};
+use mortalsim_core::sim::layer::AnatomicalRegionIter;
+use mortalsim_core::sim::organism::test::{TestAnatomicalRegion, TestNerve};
+use mortalsim_core::sim::Organism;
+use mortalsim_core::substance::{ConcentrationDisplay, Substance, SubstanceChange, SubstanceConcentration};
+use mortalsim_core::{SimTime, SimTimeSpan};
 use rand::distributions::{Alphanumeric, DistString};
 
 pub struct SubstanceConcentrationRange {
@@ -22,40 +28,44 @@ impl SubstanceConcentrationRange {
     }
     pub fn check(&self, val: SubstanceConcentration) {
         log::info!("Concentration {}. Expected range {} -> {}",
-            val,
-            self.min,
-            self.max
+            val.to_human_string(),
+            self.min.to_human_string(),
+            self.max.to_human_string()
         );
 
         assert!(
             val >= self.min && val <= self.max,
             "Concentration {} is not within expected range {} -> {}",
-            val,
-            self.min,
-            self.max
+            val.to_human_string(),
+            self.min.to_human_string(),
+            self.max.to_human_string()
         );
     }
 }
 
 
-pub struct TestBloodCheckerComponent {
+pub struct TestBloodCheckerComponent<O: Organism> {
     /// Generated ID
     id: &'static str,
     /// Which vessel to associate with
-    vessel: TestBloodVessel,
+    vessel: O::VesselType,
     /// List of time to execute, substance to change, how much
     pending_writes: VecDeque<(SimTime, Substance, SubstanceChange)>,
     /// List of time to read, substance to check, expected value
     pending_reads: VecDeque<(SimTime, Substance, SubstanceConcentrationRange)>,
     /// Circulation connector
-    circ_connector: CirculationConnector<TestOrganism>,
+    circ_connector: CirculationConnector<O>,
+    /// Core connector, for timers to run at each pending time
+    core_connector: CoreConnector<O>,
+    /// Time of the latest timer set
+    next_timer: Option<SimTime>,
     /// Prev
     prev: HashMap<Substance, SubstanceConcentration>,
 }
 
-impl TestBloodCheckerComponent {
+impl<O: Organism> TestBloodCheckerComponent<O> {
     pub fn new(
-        vessel: TestBloodVessel,
+        vessel: O::VesselType,
         mut writes: Vec<(SimTime, Substance, SubstanceChange)>,
         mut reads: Vec<(SimTime, Substance, SubstanceConcentrationRange)>,
     ) -> Self {
@@ -68,17 +78,27 @@ impl TestBloodCheckerComponent {
             pending_writes: writes.into(),
             pending_reads: reads.into(),
             circ_connector: CirculationConnector::new(),
+            core_connector: CoreConnector::new(),
+            next_timer: None,
             prev: HashMap::new(),
         }
     }
 }
 
-impl CirculationComponent<TestOrganism> for TestBloodCheckerComponent {
-    fn circulation_connector(&mut self) -> &mut CirculationConnector<TestOrganism> {
+impl<O: Organism> CoreComponent<O> for TestBloodCheckerComponent<O> {
+    fn core_init(&mut self, _initializer: &mut CoreInitializer<O>) {}
+
+    fn core_connector(&mut self) -> &mut CoreConnector<O> {
+        &mut self.core_connector
+    }
+}
+
+impl<O: Organism> CirculationComponent<O> for TestBloodCheckerComponent<O> {
+    fn circulation_connector(&mut self) -> &mut CirculationConnector<O> {
         &mut self.circ_connector
     }
 
-    fn circulation_init(&mut self, circulation_initializer: &mut mortalsim_core::sim::layer::circulation::CirculationInitializer<TestOrganism>) {
+    fn circulation_init(&mut self, circulation_initializer: &mut mortalsim_core::sim::layer::circulation::CirculationInitializer<O>) {
         log::info!("Circ init {}", self.id);
         circulation_initializer.attach_vessel(self.vessel);
         for substance in self.pending_reads.iter().map(|(_, s, _e)| *s).collect::<HashSet<Substance>>() {
@@ -91,13 +111,13 @@ impl CirculationComponent<TestOrganism> for TestBloodCheckerComponent {
     }
 }
 
-impl SimComponent<TestOrganism> for TestBloodCheckerComponent {
+impl<O: Organism> SimComponent<O> for TestBloodCheckerComponent<O> {
     fn id(&self) -> &'static str {
         self.id
     }
 
-    fn attach(self, registry: &mut mortalsim_core::sim::component::ComponentRegistry<TestOrganism>) {
-        registry.add_circulation_component(self)
+    fn attach(self, registry: &mut mortalsim_core::sim::component::ComponentRegistry<O>) {
+        registry.add_core_circulation_component(self)
     }
 
     fn run(&mut self) {
@@ -119,7 +139,7 @@ impl SimComponent<TestOrganism> for TestBloodCheckerComponent {
                 .unwrap()
                 .concentration_of(&substance);
             if !self.prev.contains_key(substance) || (&val - self.prev.get(substance).unwrap()).molpm3 > 0.000001 {
-                log::info!("{}: {} {:?}: {}", self.circ_connector.sim_time(), self.id(), self.vessel, val);
+                log::info!("{}: {} {:?}: {}", self.circ_connector.sim_time(), self.id(), self.vessel, val.to_human_string());
             }
 
             self.prev.insert(*substance, val);
@@ -135,5 +155,215 @@ impl SimComponent<TestOrganism> for TestBloodCheckerComponent {
 
             expected.check(conc);
         }
+
+        // Run again in time for the next pending write or read
+        let next_time = [self.pending_writes.front().map(|w| w.0), self.pending_reads.front().map(|r| r.0)]
+            .into_iter()
+            .flatten()
+            .min();
+        if let Some(next_time) = next_time {
+            if self.next_timer != Some(next_time) {
+                self.core_connector.set_timer(SimTimeSpan::from_s((next_time - sim_time).to_s()), 0);
+                self.next_timer = Some(next_time);
+            }
+        }
+    }
+}
+
+/// Counts how many vessels have received any of a given Substance.
+/// Every vessel is attached individually, so each has a blood store
+/// for changes to propagate to.
+pub struct TestReachComponent<O: Organism> {
+    substance: Substance,
+    /// Number of vessels with a nonzero concentration as of the last run
+    reached: Arc<Mutex<usize>>,
+    circ_connector: CirculationConnector<O>,
+}
+
+impl<O: Organism> TestReachComponent<O> {
+    pub fn new(substance: Substance, reached: Arc<Mutex<usize>>) -> Self {
+        Self {
+            substance,
+            reached,
+            circ_connector: CirculationConnector::new(),
+        }
+    }
+}
+
+impl<O: Organism> CirculationComponent<O> for TestReachComponent<O> {
+    fn circulation_connector(&mut self) -> &mut CirculationConnector<O> {
+        &mut self.circ_connector
+    }
+
+    fn circulation_init(&mut self, circulation_initializer: &mut mortalsim_core::sim::layer::circulation::CirculationInitializer<O>) {
+        for vessel in O::VesselType::arteries().chain(O::VesselType::veins()) {
+            circulation_initializer.attach_vessel(vessel);
+        }
+    }
+}
+
+impl<O: Organism> SimComponent<O> for TestReachComponent<O> {
+    fn id(&self) -> &'static str {
+        "TestReachComponent"
+    }
+
+    fn attach(self, registry: &mut mortalsim_core::sim::component::ComponentRegistry<O>) {
+        registry.add_circulation_component(self)
+    }
+
+    fn run(&mut self) {
+        let mut reached = 0;
+        self.circ_connector.with_blood_stores(|_, store| {
+            if store.concentration_of(&self.substance) > SubstanceConcentration::from_mM(0.0) {
+                reached += 1;
+            }
+        });
+        *self.reached.lock().unwrap() = reached;
+    }
+}
+
+/// Marks a Substance as protein bound, and records its free
+/// concentration on a vessel each time it changes
+pub struct TestProteinBindingComponent<O: Organism> {
+    vessel: O::VesselType,
+    substance: Substance,
+    bound_fraction: f64,
+    /// Free concentration as of the last run
+    free: Arc<Mutex<SubstanceConcentration>>,
+    circ_connector: CirculationConnector<O>,
+}
+
+impl<O: Organism> TestProteinBindingComponent<O> {
+    pub fn new(
+        vessel: O::VesselType,
+        substance: Substance,
+        bound_fraction: f64,
+        free: Arc<Mutex<SubstanceConcentration>>,
+    ) -> Self {
+        Self {
+            vessel,
+            substance,
+            bound_fraction,
+            free,
+            circ_connector: CirculationConnector::new(),
+        }
+    }
+}
+
+impl<O: Organism> CirculationComponent<O> for TestProteinBindingComponent<O> {
+    fn circulation_connector(&mut self) -> &mut CirculationConnector<O> {
+        &mut self.circ_connector
+    }
+
+    fn circulation_init(&mut self, circulation_initializer: &mut mortalsim_core::sim::layer::circulation::CirculationInitializer<O>) {
+        circulation_initializer.set_protein_binding(self.substance, self.bound_fraction);
+        circulation_initializer.notify_composition_change(self.vessel, self.substance, SubstanceConcentration::from_mM(0.0));
+    }
+}
+
+impl<O: Organism> SimComponent<O> for TestProteinBindingComponent<O> {
+    fn id(&self) -> &'static str {
+        "TestProteinBindingComponent"
+    }
+
+    fn attach(self, registry: &mut mortalsim_core::sim::component::ComponentRegistry<O>) {
+        registry.add_circulation_component(self)
+    }
+
+    fn run(&mut self) {
+        let store = self.circ_connector.blood_store(&self.vessel).unwrap();
+        *self.free.lock().unwrap() = store.free_concentration_of(&self.substance);
+    }
+}
+
+/// Organism with a vessel which is deliberately disconnected
+/// from the rest of the circulation
+#[derive(Debug, Clone, Copy)]
+pub struct DisconnectedOrganism;
+
+impl Organism for DisconnectedOrganism {
+    type VesselType = DisconnectedVessel;
+    type NerveType = TestNerve;
+    type AnatomyType = TestAnatomicalRegion;
+}
+
+impl_sim!(DisconnectedSim, DisconnectedOrganism);
+
+#[derive(Debug, Hash, Clone, Copy, PartialEq, Eq)]
+pub enum DisconnectedVessel {
+    Aorta,
+    VenaCava,
+    Isolated,
+}
+
+impl From<DisconnectedVessel> for &'static str {
+    fn from(value: DisconnectedVessel) -> Self {
+        match value {
+            DisconnectedVessel::Aorta => "Aorta",
+            DisconnectedVessel::VenaCava => "VenaCava",
+            DisconnectedVessel::Isolated => "Isolated",
+        }
+    }
+}
+
+static AORTA_SET: OnceLock<HashSet<DisconnectedVessel>> = OnceLock::new();
+static VENA_CAVA_SET: OnceLock<HashSet<DisconnectedVessel>> = OnceLock::new();
+static EMPTY_VESSEL_SET: OnceLock<HashSet<DisconnectedVessel>> = OnceLock::new();
+static EMPTY_REGION_SET: OnceLock<HashSet<TestAnatomicalRegion>> = OnceLock::new();
+
+fn vessel_set(
+    lock: &'static OnceLock<HashSet<DisconnectedVessel>>,
+    vessels: &[DisconnectedVessel],
+) -> VesselIter<'static, DisconnectedVessel> {
+    VesselIter(lock.get_or_init(|| vessels.iter().copied().collect()).iter())
+}
+
+impl BloodVessel for DisconnectedVessel {
+    type AnatomyType = TestAnatomicalRegion;
+
+    fn max_arterial_depth() -> u32 {
+        1
+    }
+    fn max_venous_depth() -> u32 {
+        1
+    }
+    fn max_cycle() -> u32 {
+        2
+    }
+    fn start_vessels<'a>() -> VesselIter<'a, Self> {
+        vessel_set(&AORTA_SET, &[Self::Aorta])
+    }
+    fn arteries<'a>() -> VesselIter<'a, Self> {
+        vessel_set(&AORTA_SET, &[Self::Aorta])
+    }
+    fn veins<'a>() -> VesselIter<'a, Self> {
+        vessel_set(&VENA_CAVA_SET, &[Self::VenaCava])
+    }
+    fn pre_capillaries<'a>() -> VesselIter<'a, Self> {
+        vessel_set(&AORTA_SET, &[Self::Aorta])
+    }
+    fn post_capillaries<'a>() -> VesselIter<'a, Self> {
+        vessel_set(&VENA_CAVA_SET, &[Self::VenaCava])
+    }
+    fn vessel_type(&self) -> BloodVesselType {
+        match self {
+            Self::VenaCava => BloodVesselType::Vein,
+            _ => BloodVesselType::Artery,
+        }
+    }
+    fn upstream<'a>(&self) -> VesselIter<'a, Self> {
+        match self {
+            Self::VenaCava => vessel_set(&AORTA_SET, &[Self::Aorta]),
+            _ => vessel_set(&EMPTY_VESSEL_SET, &[]),
+        }
+    }
+    fn downstream<'a>(&self) -> VesselIter<'a, Self> {
+        match self {
+            Self::Aorta => vessel_set(&VENA_CAVA_SET, &[Self::VenaCava]),
+            _ => vessel_set(&EMPTY_VESSEL_SET, &[]),
+        }
+    }
+    fn regions<'a>(&self) -> AnatomicalRegionIter<'_, Self::AnatomyType> {
+        AnatomicalRegionIter(EMPTY_REGION_SET.get_or_init(HashSet::new).iter())
     }
 }
@@ -3,19 +3,36 @@ extern crate mortalsim_macros;
 
 use model::Smith2004CvsOde;
 use mortalsim_core::{
-    event::{AorticBloodPressure, Event, HeartRate, PulmonaryBloodPressure},
+    event::{AorticBloodPressure, CardiacOutput, Event, HeartRate, Hemodynamics, PulmonaryBloodPressure},
     sim::{
         component::SimComponent,
-        layer::core::{CoreComponent, CoreConnector}
+        layer::core::{CoreComponent, CoreConnector},
+        Organism,
     }, units::mechanical::Pressure, SimTimeSpan,
 };
-use mortalsim_human::HumanOrganism;
-use mortalsim_math_routines::ode::{runge_kutta::fixed::RungeKutta4, OdeRunner};
-use params::{Smith2004CvsAssignmentParam, Smith2004CvsConstantParam};
+use mortalsim_human::{HumanOrganism, PatientProfile};
+use mortalsim_math_routines::ode::{runge_kutta::fixed::RungeKutta4, OdeError, OdeResults, OdeRunner};
+use params::{Smith2004CvsAssignmentParam, Smith2004CvsConstantParam, Smith2004CvsRateBoundParam};
 
 pub mod params;
 pub mod model;
 
+/// Duration of each ODE solution (s)
+const T_END: f64 = 10.0;
+/// Fixed step size of each ODE solution (s)
+const STEP_SIZE: f64 = 0.01;
+
+/// How diastolic pressure is measured from each solution
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiastolicMeasure {
+    /// Minimum pressure over the measurement window
+    WindowMinimum,
+    /// Pressure just before the last opening of the downstream valve
+    /// in the measurement window, i.e. at the start of systole. Falls
+    /// back to the window minimum if the valve never opens.
+    EndDiastolic,
+}
+
 #[derive(Debug)]
 pub struct Smith2004CvsParamChanges {
     changes: Vec<(Smith2004CvsConstantParam, f64)>,
@@ -47,7 +64,9 @@ impl Event for Smith2004CvsParamChanges {
 
 /// This is a Mortalsim component to simulate cardiovascular dynamics
 /// 
-/// Populates `AorticBloodPressure` and `PulmonaryBloodPressure` events
+/// Populates `AorticBloodPressure`, `PulmonaryBloodPressure` and
+/// `CardiacOutput` events, along with a `Hemodynamics` event bundling
+/// all three
 /// 
 /// This component exhibits a model from the article:
 /// 
@@ -87,6 +106,11 @@ pub struct Smith2004CvsComponent {
     connector: CoreConnector<HumanOrganism>,
     ao_init: AorticBloodPressure,
     pa_init: PulmonaryBloodPressure,
+    diastolic_measure: DiastolicMeasure,
+    /// Last successfully solved output, retained if a later solution diverges
+    last_output: Option<Hemodynamics>,
+    /// Patient profile which the constants were last adjusted for
+    profile: Option<PatientProfile>,
 }
 
 impl Smith2004CvsComponent {
@@ -106,6 +130,9 @@ impl Smith2004CvsComponent {
                 systolic: Pressure::from_mmHg(25.0),
                 diastolic: Pressure::from_mmHg(4.0),
             },
+            diastolic_measure: DiastolicMeasure::WindowMinimum,
+            last_output: None,
+            profile: None,
         }
     }
     
@@ -117,53 +144,59 @@ impl Smith2004CvsComponent {
             connector: CoreConnector::new(),
             ao_init,
             pa_init,
+            diastolic_measure: DiastolicMeasure::WindowMinimum,
+            last_output: None,
+            profile: None,
         }
     }
 
+    /// Sets how diastolic pressures are measured (default is the window minimum)
+    ///
+    /// ### Arguments
+    /// * `measure` - diastolic measurement method
+    pub fn with_diastolic_measure(mut self, measure: DiastolicMeasure) -> Self {
+        self.diastolic_measure = measure;
+        self
+    }
+
     /// Sets a constant value for the simulation before it executes
     pub fn set_constant(&mut self, param: Smith2004CvsConstantParam, value: f64) {
         self.runner.set_constant(param, value)
     }
-}
-
-impl CoreComponent<HumanOrganism> for Smith2004CvsComponent {
-    fn core_connector(&mut self) -> &mut CoreConnector<HumanOrganism> {
-        &mut self.connector
-    }
-
-    fn core_init(&mut self, initializer: &mut mortalsim_core::sim::layer::core::CoreInitializer<HumanOrganism>) {
-        initializer.notify::<HeartRate>();
-        initializer.notify::<Smith2004CvsParamChanges>();
-
-        initializer.set_output(self.ao_init);
-        initializer.set_output(self.pa_init);
-    }
-}
-
-impl SimComponent<HumanOrganism> for Smith2004CvsComponent {
-    fn id(&self) -> &'static str {
-        "Smith2004CvsComponent"
-    }
 
-    fn attach(self, registry: &mut mortalsim_core::sim::component::ComponentRegistry<HumanOrganism>) {
-        registry.add_core_component(self)
+    /// Constants adjusted for a patient, with the factor on each
+    fn profile_factors(profile: &PatientProfile) -> [(Smith2004CvsConstantParam, f64); 3] {
+        [
+            (Smith2004CvsConstantParam::E_es_lvf, profile.contractility_factor()),
+            (Smith2004CvsConstantParam::R_sys, profile.vascular_resistance_factor()),
+            (Smith2004CvsConstantParam::E_es_ao, profile.arterial_stiffness_factor()),
+        ]
     }
 
-    fn run(&mut self) {
-        if let Some(hr) = self.connector.get::<HeartRate>() {
-            self.runner.set_constant(Smith2004CvsConstantParam::period, 1.0/hr.as_ref().Hz);
+    /// Scales the currently set constants for the given patient's
+    /// contractility, vascular resistance and arterial stiffness. Any
+    /// scaling for a previously applied profile is undone first, so
+    /// values set through `set_constant` are kept relative to the patient.
+    ///
+    /// ### Arguments
+    /// * `profile` - patient to adjust the constants for
+    fn apply_profile(&mut self, profile: PatientProfile) {
+        let previous = self.profile.as_ref().map(Self::profile_factors);
+        for (idx, (param, factor)) in Self::profile_factors(&profile).into_iter().enumerate() {
+            let previous_factor = previous.map_or(1.0, |factors| factors[idx].1);
+            let value = self.runner.get_constant(param) / previous_factor * factor;
+            self.runner.set_constant(param, value);
         }
+        self.profile = Some(profile);
+    }
 
-        if let Some(evt) = self.connector.get::<Smith2004CvsParamChanges>() {
-            for (param, value) in evt.changes.iter() {
-                self.runner.set_constant(*param, *value)
-            }
-        }
-
-        let t_end = 10.0;
-        let step_size = 0.01;
-
-        let results = self.runner.solve_fixed(0.0, t_end, step_size, &RungeKutta4::default());
+    /// Solves the ODE and measures the resulting aortic and pulmonary
+    /// blood pressures, or returns an Err if the solution diverges
+    fn solve_pressures(
+        runner: &OdeRunner<Smith2004CvsOde>,
+        diastolic_measure: DiastolicMeasure,
+    ) -> Result<(OdeResults<Smith2004CvsOde>, AorticBloodPressure, PulmonaryBloodPressure), OdeError> {
+        let results = runner.solve_fixed(0.0, T_END, STEP_SIZE, &RungeKutta4::default())?;
 
         let mut bp_ao = AorticBloodPressure {
             systolic: Pressure::from_mmHg(-10000.0),
@@ -178,7 +211,7 @@ impl SimComponent<HumanOrganism> for Smith2004CvsComponent {
         // Go to the halfway point, after giving some time
         // for the model to stabilize before pulling the
         // results
-        let measure_start_idx = ((t_end/2.0)*step_size) as usize;
+        let measure_start_idx = ((T_END/2.0)*STEP_SIZE) as usize;
 
         for idx in measure_start_idx..results.len() {
             let bp_ao_x = results.assignment_value(idx, Smith2004CvsAssignmentParam::P_ao);
@@ -198,25 +231,372 @@ impl SimComponent<HumanOrganism> for Smith2004CvsComponent {
             }
         }
 
+        if diastolic_measure == DiastolicMeasure::EndDiastolic {
+            let end_diastolic = |valve_flow, pressure| {
+                (measure_start_idx.max(1)..results.len())
+                    .rev()
+                    .find(|idx| {
+                        results.rate_bound_value(idx - 1, valve_flow) <= 0.0
+                            && results.rate_bound_value(*idx, valve_flow) > 0.0
+                    })
+                    .map(|idx| Pressure::from_mmHg(results.assignment_value(idx - 1, pressure)))
+            };
+
+            if let Some(pressure) = end_diastolic(Smith2004CvsRateBoundParam::Q_av, Smith2004CvsAssignmentParam::P_ao) {
+                bp_ao.diastolic = pressure;
+            }
+            if let Some(pressure) = end_diastolic(Smith2004CvsRateBoundParam::Q_pv, Smith2004CvsAssignmentParam::P_pa) {
+                bp_pa.diastolic = pressure;
+            }
+        }
+
+        Ok((results, bp_ao, bp_pa))
+    }
+
+    /// Mean flow through the aortic valve over the second half of a solution
+    ///
+    /// ### Arguments
+    /// * `results` - solution to measure
+    ///
+    /// Returns the cardiac output
+    fn cardiac_output(results: &OdeResults<Smith2004CvsOde>) -> CardiacOutput {
+        let window = (results.len() / 2)..results.len();
+        let count = window.len().max(1) as f64;
+        let mean_flow: f64 = window
+            .map(|idx| results.rate_bound_value(idx, Smith2004CvsRateBoundParam::Q_av).max(0.0))
+            .sum::<f64>() / count;
+
+        // mL/s to L/min
+        CardiacOutput(mean_flow * 60.0 / 1000.0)
+    }
+
+    /// Estimates the sensitivity of aortic systolic pressure to a constant
+    /// by finite differences, for calibration. The component is unchanged.
+    ///
+    /// ### Arguments
+    /// * `param` - constant to perturb
+    /// * `delta` - amount to perturb the constant by in each direction
+    ///
+    /// Returns the change in systolic pressure (mmHg) per unit change in the
+    /// constant, or an Err if either perturbed solution diverges
+    pub fn systolic_sensitivity(&mut self, param: Smith2004CvsConstantParam, delta: f64) -> Result<f64, OdeError> {
+        let measure = self.diastolic_measure;
+        self.runner.sensitivity(param, delta, |runner| Ok(Self::solve_pressures(runner, measure)?.1.systolic.to_mmHg()))
+    }
+
+    /// Estimates the sensitivity of aortic diastolic pressure to a constant
+    /// by finite differences, for calibration. The component is unchanged.
+    ///
+    /// ### Arguments
+    /// * `param` - constant to perturb
+    /// * `delta` - amount to perturb the constant by in each direction
+    ///
+    /// Returns the change in diastolic pressure (mmHg) per unit change in the
+    /// constant, or an Err if either perturbed solution diverges
+    pub fn diastolic_sensitivity(&mut self, param: Smith2004CvsConstantParam, delta: f64) -> Result<f64, OdeError> {
+        let measure = self.diastolic_measure;
+        self.runner.sensitivity(param, delta, |runner| Ok(Self::solve_pressures(runner, measure)?.1.diastolic.to_mmHg()))
+    }
+}
+
+impl CoreComponent<HumanOrganism> for Smith2004CvsComponent {
+    fn core_connector(&mut self) -> &mut CoreConnector<HumanOrganism> {
+        &mut self.connector
+    }
+
+    fn core_init(&mut self, initializer: &mut mortalsim_core::sim::layer::core::CoreInitializer<HumanOrganism>) {
+        initializer.notify::<HeartRate>();
+        initializer.notify::<Smith2004CvsParamChanges>();
+
+        let co_init = CardiacOutput(HumanOrganism::cardiac_index() * HumanOrganism::body_surface_area().to_m2());
+        initializer.set_output(self.ao_init);
+        initializer.set_output(self.pa_init);
+        initializer.set_output(co_init);
+        initializer.set_output(Hemodynamics {
+            aortic: self.ao_init,
+            pulmonary: self.pa_init,
+            cardiac_output: co_init,
+        });
+    }
+}
+
+impl SimComponent<HumanOrganism> for Smith2004CvsComponent {
+    fn id(&self) -> &'static str {
+        "Smith2004CvsComponent"
+    }
+
+    fn attach(self, registry: &mut mortalsim_core::sim::component::ComponentRegistry<HumanOrganism>) {
+        registry.add_core_component(self)
+    }
+
+    fn run(&mut self) {
+        if let Some(profile) = self.connector.get::<PatientProfile>() {
+            if self.profile.as_ref() != Some(profile) {
+                let profile = profile.clone();
+                self.apply_profile(profile);
+            }
+        }
+
+        if let Some(hr) = self.connector.get::<HeartRate>() {
+            self.runner.set_constant(Smith2004CvsConstantParam::period, 1.0/hr.as_ref().Hz);
+        }
+
+        if let Some(evt) = self.connector.get::<Smith2004CvsParamChanges>() {
+            for (param, value) in evt.changes.iter() {
+                self.runner.set_constant(*param, *value)
+            }
+        }
+
         let effect_time = SimTimeSpan::from_s(
-            results.constant_value(Smith2004CvsConstantParam::period)*(t_end/2.0)
+            self.runner.get_constant(Smith2004CvsConstantParam::period)*(T_END/2.0)
         );
 
-        self.connector.schedule_event(effect_time, bp_ao);
-        self.connector.schedule_event(effect_time, bp_pa);
+        let output = match Self::solve_pressures(&self.runner, self.diastolic_measure) {
+            Ok((results, bp_ao, bp_pa)) => {
+                let output = Hemodynamics {
+                    aortic: bp_ao,
+                    pulmonary: bp_pa,
+                    cardiac_output: Self::cardiac_output(&results),
+                };
+                self.last_output = Some(output);
+                output
+            }
+            Err(err) => {
+                log::warn!("{}: {}, retaining the last good pressures", self.id(), err);
+
+                // Previously scheduled outputs are unscheduled on each run,
+                // so any last good output is scheduled again in their place
+                match self.last_output {
+                    Some(output) => output,
+                    None => return,
+                }
+            }
+        };
 
+        self.connector.schedule_event(effect_time, output.aortic);
+        self.connector.schedule_event(effect_time, output.pulmonary);
+        self.connector.schedule_event(effect_time, output.cardiac_output);
+        self.connector.schedule_event(effect_time, output);
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use mortalsim_core::sim::component::SimComponent;
+    use std::any::TypeId;
+
+    use mortalsim_core::event::{AorticBloodPressure, CardiacOutput, HeartRate, Hemodynamics, PulmonaryBloodPressure};
+    use mortalsim_core::sim::component::{ComponentRegistry, SimComponent};
+    use mortalsim_core::sim::layer::core::{CoreComponent, CoreConnector, CoreInitializer};
+    use mortalsim_core::sim::Sim;
+    use mortalsim_core::units::mechanical::Frequency;
+    use mortalsim_core::SimTimeSpan;
+    use mortalsim_human::{Comorbidity, HumanOrganism, HumanSim, PatientProfile};
+    use mortalsim_math_routines::ode::OdeError;
+
+    use crate::params::Smith2004CvsConstantParam;
+    use crate::{DiastolicMeasure, T_END, Smith2004CvsComponent, Smith2004CvsParamChanges};
+
+    struct HeartRateComponent {
+        connector: CoreConnector<HumanOrganism>,
+    }
+
+    impl CoreComponent<HumanOrganism> for HeartRateComponent {
+        fn core_connector(&mut self) -> &mut CoreConnector<HumanOrganism> {
+            &mut self.connector
+        }
+
+        fn core_init(&mut self, initializer: &mut CoreInitializer<HumanOrganism>) {
+            initializer.produces::<HeartRate>();
+        }
+    }
+
+    impl SimComponent<HumanOrganism> for HeartRateComponent {
+        fn id(&self) -> &'static str {
+            "HeartRateComponent"
+        }
+
+        fn attach(self, registry: &mut ComponentRegistry<HumanOrganism>) {
+            registry.add_core_component(self)
+        }
 
-    use crate::Smith2004CvsComponent;
+        fn run(&mut self) {}
+    }
 
     #[test]
     fn component_run() {
         let mut comp = Smith2004CvsComponent::new();
         comp.run();
     }
+
+    #[test]
+    fn systolic_sensitivity() {
+        let mut comp = Smith2004CvsComponent::new();
+        let sensitivity = comp.systolic_sensitivity(Smith2004CvsConstantParam::R_sys, 0.1).unwrap();
+        assert!(sensitivity > 0.0, "{}", sensitivity);
+
+        // The analysis leaves the component unchanged
+        assert_eq!(comp.systolic_sensitivity(Smith2004CvsConstantParam::R_sys, 0.1).unwrap(), sensitivity);
+    }
+
+    #[test]
+    fn divergent_constants() {
+        let mut comp = Smith2004CvsComponent::new();
+        comp.run();
+        let good = comp.last_output.unwrap();
+
+        // A tiny aortic valve inertance is too stiff for the fixed step,
+        // so the solution blows up partway through
+        comp.set_constant(Smith2004CvsConstantParam::L_av, 1e-9);
+        match Smith2004CvsComponent::solve_pressures(&comp.runner, comp.diastolic_measure) {
+            Err(OdeError::Diverged { at_time }) => assert!(at_time > 0.0 && at_time < T_END, "{}", at_time),
+            Ok((_, bp_ao, _)) => panic!("Expected divergence, got {:?}", bp_ao),
+        }
+        assert!(comp.systolic_sensitivity(Smith2004CvsConstantParam::R_sys, 0.1).is_err());
+
+        // The last good output is retained
+        comp.run();
+        assert_eq!(comp.last_output, Some(good));
+
+        // And the next solution restarts once the constants are sane again
+        comp.set_constant(Smith2004CvsConstantParam::L_av, 0.00012189);
+        comp.run();
+        let bp_ao = comp.last_output.unwrap().aortic;
+        assert!((bp_ao.systolic.to_mmHg() - good.aortic.systolic.to_mmHg()).abs() < 5.0, "{:?} {:?}", bp_ao, good);
+    }
+
+    #[test]
+    fn end_diastolic_pressure() {
+        let comp = Smith2004CvsComponent::new().with_diastolic_measure(DiastolicMeasure::EndDiastolic);
+        let (_, ao_min, pa_min) = Smith2004CvsComponent::solve_pressures(&comp.runner, DiastolicMeasure::WindowMinimum).unwrap();
+        let (_, ao_ed, pa_ed) = Smith2004CvsComponent::solve_pressures(&comp.runner, comp.diastolic_measure).unwrap();
+
+        // The window minimum picks up dips below the pressure at the
+        // start of systole, which is ~80/4 mmHg once stabilized
+        assert!(ao_ed.diastolic.to_mmHg() > ao_min.diastolic.to_mmHg() + 1.0, "{:?} {:?}", ao_ed, ao_min);
+        assert!(pa_ed.diastolic.to_mmHg() > pa_min.diastolic.to_mmHg(), "{:?} {:?}", pa_ed, pa_min);
+        assert!((ao_ed.diastolic.to_mmHg() - 80.0).abs() < 2.0, "{:?}", ao_ed);
+        assert!((pa_ed.diastolic.to_mmHg() - 4.0).abs() < 1.0, "{:?}", pa_ed);
+
+        // Systolic pressures are unaffected
+        assert_eq!(ao_ed.systolic, ao_min.systolic);
+        assert_eq!(pa_ed.systolic, pa_min.systolic);
+    }
+
+    #[test]
+    fn missing_heart_rate_producer() {
+        let mut sim = HumanSim::new();
+        sim.add_component(Smith2004CvsComponent::new()).unwrap();
+
+        let missing = sim.validate_event_graph().unwrap_err();
+        assert!(missing
+            .iter()
+            .any(|d| d.component_id == "Smith2004CvsComponent" && d.event_type == TypeId::of::<HeartRate>()));
+    }
+
+    /// Times at which the component emits `AorticBloodPressure`
+    fn pressure_times(sim: &mut HumanSim, until: f64) -> Vec<f64> {
+        let mut times = Vec::new();
+        while sim.time().to_s() < until {
+            sim.advance_by(SimTimeSpan::from_s(0.5));
+            if sim.drain_active().any(|e| e.is::<AorticBloodPressure>()) {
+                times.push(sim.time().to_s());
+            }
+        }
+        times
+    }
+
+    #[test]
+    fn constant_heart_rate() {
+        let mut sim = HumanSim::new();
+        sim.add_component(Smith2004CvsComponent::new()).unwrap();
+        sim.set_constant_event(HeartRate(Frequency::from_Hz(2.0)));
+
+        // The constant satisfies the HeartRate dependency
+        let missing = sim.validate_event_graph().unwrap_err();
+        assert!(missing.iter().all(|d| d.event_type != TypeId::of::<HeartRate>()));
+
+        // Pressures are emitted after five periods of 0.5s
+        assert_eq!(pressure_times(&mut sim, 3.0), vec![2.5]);
+
+        // The constant remains in place for later runs, with the forced
+        // run taking place on the next advance at 3.5s
+        sim.force_layer_run(None);
+        assert_eq!(pressure_times(&mut sim, 7.0), vec![6.0]);
+
+        // A later HeartRate overrides the constant, with periods of 1s
+        sim.schedule_event(SimTimeSpan::from_s(1.0), Box::new(HeartRate(Frequency::from_Hz(1.0))));
+        assert_eq!(pressure_times(&mut sim, 14.0), vec![13.0]);
+    }
+
+    #[test]
+    fn hemodynamics() {
+        let mut sim = HumanSim::new();
+        sim.add_component(Smith2004CvsComponent::new()).unwrap();
+        sim.set_constant_event(HeartRate(Frequency::from_Hz(1.0)));
+
+        let mut active = Vec::new();
+        while active.is_empty() {
+            sim.advance_by(SimTimeSpan::from_s(0.5));
+            active = sim.drain_active().collect();
+        }
+
+        let find = |type_id: TypeId| active.iter().find(|e| e.as_ref().as_any().type_id() == type_id).unwrap();
+        let composite = *find(TypeId::of::<Hemodynamics>()).downcast_ref::<Hemodynamics>().unwrap();
+        assert_eq!(Some(&composite.aortic), find(TypeId::of::<AorticBloodPressure>()).downcast_ref());
+        assert_eq!(Some(&composite.pulmonary), find(TypeId::of::<PulmonaryBloodPressure>()).downcast_ref());
+        assert_eq!(Some(&composite.cardiac_output), find(TypeId::of::<CardiacOutput>()).downcast_ref());
+
+        // Roughly 5 L/min at rest
+        let cardiac_output = composite.cardiac_output.0;
+        assert!(cardiac_output > 3.0 && cardiac_output < 8.0, "{}", cardiac_output);
+    }
+
+    /// Cardiac output (L/min) from the first solution for the patient
+    fn profile_cardiac_output(profile: PatientProfile) -> f64 {
+        let mut sim = HumanSim::from_profile(profile);
+        sim.add_component(Smith2004CvsComponent::new()).unwrap();
+
+        loop {
+            sim.advance_by(SimTimeSpan::from_s(0.5));
+            if let Some(hemodynamics) = sim.drain_active().find_map(|e| e.downcast_ref::<Hemodynamics>().copied()) {
+                return hemodynamics.cardiac_output.0;
+            }
+        }
+    }
+
+    #[test]
+    fn patient_profile() {
+        let healthy = profile_cardiac_output(PatientProfile::default());
+        let heart_failure = profile_cardiac_output(PatientProfile::default().with_comorbidity(Comorbidity::HeartFailure));
+        assert!(heart_failure < healthy, "{} vs {}", heart_failure, healthy);
+    }
+
+    #[test]
+    fn profile_scales_set_constants() {
+        let mut component = Smith2004CvsComponent::new();
+        let default_r_sys = component.runner.get_constant(Smith2004CvsConstantParam::R_sys);
+        let default_e_es_lvf = component.runner.get_constant(Smith2004CvsConstantParam::E_es_lvf);
+        component.set_constant(Smith2004CvsConstantParam::R_sys, default_r_sys * 2.0);
+
+        let hypertensive = PatientProfile::default().with_comorbidity(Comorbidity::Hypertension);
+        component.apply_profile(hypertensive);
+        let r_sys = component.runner.get_constant(Smith2004CvsConstantParam::R_sys);
+        assert!((r_sys - default_r_sys * 2.0 * 1.4).abs() < 1e-9, "{}", r_sys);
+        assert_eq!(component.runner.get_constant(Smith2004CvsConstantParam::E_es_lvf), default_e_es_lvf);
+
+        // Switching patients undoes the previous scaling, keeping the set value
+        component.apply_profile(PatientProfile::default());
+        let r_sys = component.runner.get_constant(Smith2004CvsConstantParam::R_sys);
+        assert!((r_sys - default_r_sys * 2.0).abs() < 1e-9, "{}", r_sys);
+    }
+
+    #[test]
+    fn complete_event_graph() {
+        let mut sim = HumanSim::new();
+        sim.add_component(Smith2004CvsComponent::new()).unwrap();
+        sim.add_component(HeartRateComponent { connector: CoreConnector::new() }).unwrap();
+        sim.schedule_event(SimTimeSpan::from_s(1.0), Box::new(Smith2004CvsParamChanges::new()));
+
+        assert!(sim.validate_event_graph().is_ok());
+    }
 }
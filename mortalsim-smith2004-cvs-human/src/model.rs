@@ -293,7 +293,7 @@ mod tests {
 
         let runner = OdeRunner::new(Smith2004CvsOde::new());
 
-        let results = runner.solve_fixed(x_start, x_end, 0.01, &RungeKutta4::default());
+        let results = runner.solve_fixed(x_start, x_end, 0.01, &RungeKutta4::default()).unwrap();
 
         // Create chart
         let mut graph_x1: Vec<(f64, f64)> = Vec::with_capacity(results.len());
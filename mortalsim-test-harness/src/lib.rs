@@ -0,0 +1,54 @@
+//! Utilities for testing MortalSim simulations
+
+use mortalsim_core::sim::Sim;
+use mortalsim_core::SimTimeSpan;
+
+/// Advances a Sim by a number of fixed steps
+///
+/// ### Arguments
+/// * `sim`       - Sim to advance
+/// * `steps`     - Number of steps to take
+/// * `step_span` - Time to advance on each step
+///
+/// Returns a snapshot of the final `SimState`
+fn run_snapshot(mut sim: Box<dyn Sim>, steps: usize, step_span: SimTimeSpan) -> Vec<String> {
+    for _ in 0..steps {
+        sim.advance_by(step_span);
+    }
+    sim.state().snapshot()
+}
+
+/// Builds and runs a Sim twice, asserting that both runs end in an
+/// identical `SimState`. Panics on any difference.
+///
+/// ### Arguments
+/// * `build_sim` - Builds a fresh Sim with all of its components
+/// * `steps`     - Number of steps to run
+/// * `step_span` - Time to advance on each step
+pub fn assert_deterministic(build_sim: impl Fn() -> Box<dyn Sim>, steps: usize, step_span: SimTimeSpan) {
+    let first = run_snapshot(build_sim(), steps, step_span);
+    let second = run_snapshot(build_sim(), steps, step_span);
+    assert_eq!(first, second, "Sim state differs between runs");
+}
+
+/// Same as `assert_deterministic`, but also runs a threaded build of
+/// the same Sim and asserts that it ends in the same `SimState`
+///
+/// ### Arguments
+/// * `build_sim`          - Builds a fresh Sim with all of its components
+/// * `build_threaded_sim` - Builds the same Sim in threaded mode
+/// * `steps`              - Number of steps to run
+/// * `step_span`          - Time to advance on each step
+pub fn assert_deterministic_threaded(
+    build_sim: impl Fn() -> Box<dyn Sim>,
+    build_threaded_sim: impl Fn() -> Box<dyn Sim>,
+    steps: usize,
+    step_span: SimTimeSpan,
+) {
+    let first = run_snapshot(build_sim(), steps, step_span);
+    let second = run_snapshot(build_sim(), steps, step_span);
+    assert_eq!(first, second, "Sim state differs between runs");
+
+    let threaded = run_snapshot(build_threaded_sim(), steps, step_span);
+    assert_eq!(first, threaded, "Sim state differs between sequential and threaded runs");
+}
@@ -0,0 +1,51 @@
+use mortalsim_core::components::{BloodGasComponent, MetabolismComponent};
+use mortalsim_core::event::MetabolicRate;
+use mortalsim_core::sim::Sim;
+use mortalsim_core::units::mechanical::Power;
+use mortalsim_core::SimTimeSpan;
+use mortalsim_human::{HumanBloodVessel, HumanSim};
+use mortalsim_test_harness::{assert_deterministic, assert_deterministic_threaded};
+
+/// Minimal running sim, with resting metabolism drawing from the aorta
+/// and a blood gas panel reported from the same vessel
+fn minimal_sim(mut sim: HumanSim) -> Box<dyn Sim> {
+    sim.add_component(MetabolismComponent::whole_body(
+        vec![HumanBloodVessel::Aorta],
+        SimTimeSpan::from_s(1.0),
+    )).unwrap();
+    sim.add_component(BloodGasComponent::new(HumanBloodVessel::Aorta)).unwrap();
+    sim.set_constant_event(MetabolicRate(Power::from_W(80.0)));
+    Box::new(sim)
+}
+
+#[test]
+fn minimal_sim_is_deterministic() {
+    assert_deterministic(|| minimal_sim(HumanSim::new()), 30, SimTimeSpan::from_s(1.0));
+}
+
+#[test]
+fn minimal_sim_is_deterministic_threaded() {
+    assert_deterministic_threaded(
+        || minimal_sim(HumanSim::new()),
+        || minimal_sim(HumanSim::new_threaded()),
+        30,
+        SimTimeSpan::from_s(1.0),
+    );
+}
+
+
+#[test]
+fn minimal_sim_state_report() {
+    let mut sim = minimal_sim(HumanSim::new());
+    for _ in 0..5 {
+        sim.advance_by(SimTimeSpan::from_s(1.0));
+    }
+
+    let report = sim.state_report();
+    assert!(report.starts_with("Sim state at 5.000 s"), "{}", report);
+    for section in ["== State ==", "== Blood composition ==", "== Digestion =="] {
+        assert!(report.contains(section), "Missing {} in {}", section, report);
+    }
+    assert!(report.contains("MetabolicRate"), "{}", report);
+    assert!(report.contains("Aorta: "), "{}", report);
+}
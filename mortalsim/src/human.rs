@@ -0,0 +1,119 @@
+use mortalsim_core::components::{MetabolismComponent, SinoatrialNodeComponent, VentilationComponent};
+use mortalsim_core::event::{HeartRate, MetabolicRate, RespiratoryRate};
+use mortalsim_core::sim::component::ComponentFactory;
+use mortalsim_core::sim::layer::circulation::BloodVessel;
+use mortalsim_core::units::base::Time;
+use mortalsim_core::units::mechanical::{Frequency, Power};
+use mortalsim_core::SimTimeSpan;
+use mortalsim_human::{HumanBloodVessel, HumanOrganism, HumanSim, PatientProfile};
+use mortalsim_simple_blood_flow::SimpleBloodFlow;
+use mortalsim_smith2004_cvs_human::Smith2004CvsComponent;
+
+use crate::{DefaultComponents, WithDefaults};
+
+/// Resting heart rate of a healthy adult (bpm)
+const RESTING_HEART_RATE: f64 = 70.0;
+
+/// Resting respiratory rate of a healthy adult (breaths per minute)
+const RESTING_RESPIRATORY_RATE: f64 = 12.0;
+
+/// Basal metabolic rate of a healthy adult (W)
+const BASAL_METABOLIC_RATE: f64 = 80.0;
+
+/// Time for substances to diffuse through the circulation at the resting
+/// heart rate (s)
+const BASE_DIFFUSION_TIME: f64 = 60.0;
+
+/// Time constant for blood gases to equilibrate in the lungs (s)
+const PULMONARY_EXCHANGE_TIME: f64 = 10.0;
+
+/// Time between metabolic consumption updates (s)
+const METABOLISM_PERIOD: f64 = 1.0;
+
+impl DefaultComponents for HumanOrganism {
+    /// Baseline human physiology, consisting of
+    /// * a `SinoatrialNodeComponent` pacing the heart at a resting rate
+    /// * the `Smith2004CvsComponent` model of the cardiovascular system
+    /// * `SimpleBloodFlow` carrying substances through the circulation
+    /// * whole body metabolism consuming O2 at the pre-capillary vessels
+    /// * ventilation exchanging O2 and CO2 where blood returns to the lungs
+    fn default_components() -> Vec<ComponentFactory<'static, Self>> {
+        let heart_rate = HeartRate(Frequency::from_Hz(RESTING_HEART_RATE / 60.0));
+        let respiratory_rate = RespiratoryRate(Frequency::from_Hz(RESTING_RESPIRATORY_RATE / 60.0));
+
+        vec![
+            ComponentFactory::new(move || SinoatrialNodeComponent::new(heart_rate)),
+            ComponentFactory::new(Smith2004CvsComponent::new),
+            ComponentFactory::new(SimpleBloodFlow::factory(heart_rate, Time::from_s(BASE_DIFFUSION_TIME))),
+            ComponentFactory::new(|| {
+                MetabolismComponent::whole_body(
+                    HumanBloodVessel::pre_capillaries().collect(),
+                    SimTimeSpan::from_s(METABOLISM_PERIOD),
+                )
+            }),
+            ComponentFactory::new(move || {
+                VentilationComponent::new(respiratory_rate).with_pulmonary_exchange(
+                    SimTimeSpan::from_s(PULMONARY_EXCHANGE_TIME),
+                    SimTimeSpan::from_s(PULMONARY_EXCHANGE_TIME),
+                )
+            }),
+        ]
+    }
+}
+
+impl WithDefaults for HumanSim {
+    /// Creates a new HumanSim for a healthy adult, with the baseline vitals
+    /// of `PatientProfile::default` and a basal `MetabolicRate`
+    fn with_defaults() -> Self {
+        let mut sim = HumanSim::from_profile(PatientProfile::default());
+        sim.set_constant_event(MetabolicRate(Power::from_W(BASAL_METABOLIC_RATE)));
+        for mut factory in HumanOrganism::default_components() {
+            sim.add_component_from_factory(&mut factory).unwrap();
+        }
+        sim
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mortalsim_core::event::{AorticBloodPressure, CardiacOutput, HeartRate};
+    use mortalsim_core::sim::Sim;
+    use mortalsim_core::SimTimeSpan;
+    use mortalsim_human::HumanSim;
+
+    use crate::WithDefaults;
+
+    #[test]
+    fn default_human_vitals_stable() {
+        let mut sim = HumanSim::with_defaults();
+        assert_eq!(sim.active_components().len(), 5);
+
+        // Heart rate (bpm), systolic and diastolic pressures (mmHg) and
+        // cardiac output (L/min) each second for a minute
+        let mut vitals = Vec::new();
+        for _ in 0..60 {
+            sim.advance_by(SimTimeSpan::from_s(1.0));
+            let state = sim.state();
+            let bp = state.get_state::<AorticBloodPressure>().unwrap();
+            vitals.push([
+                state.get_state::<HeartRate>().unwrap().as_ref().Hz * 60.0,
+                bp.systolic.to_mmHg(),
+                bp.diastolic.to_mmHg(),
+                state.get_state::<CardiacOutput>().unwrap().0,
+            ]);
+        }
+
+        // Every vital is within resting limits once the model has settled
+        let limits = [(60.0, 100.0), (100.0, 150.0), (60.0, 95.0), (4.0, 7.0)];
+        let settled = &vitals[10..];
+        for (i, (low, high)) in limits.iter().enumerate() {
+            let values: Vec<f64> = settled.iter().map(|v| v[i]).collect();
+            let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+            let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            assert!(min >= *low && max <= *high, "vital {}: {} - {}", i, min, max);
+
+            // and doesn't drift
+            assert!(max - min <= 0.01 * max, "vital {}: {} - {}", i, min, max);
+        }
+    }
+}
@@ -0,0 +1,24 @@
+//! Whole-organism models assembled from the MortalSim component crates
+
+use mortalsim_core::sim::component::ComponentFactory;
+use mortalsim_core::sim::Organism;
+
+mod human;
+
+/// Curated set of components which together form a runnable baseline
+/// model of an `Organism`
+pub trait DefaultComponents: Organism {
+    /// Factories for the default components of the `Organism`. Each
+    /// factory produces a component with a distinct id.
+    fn default_components() -> Vec<ComponentFactory<'static, Self>>;
+}
+
+/// Sims which can be created with the default components of their
+/// `Organism` already registered
+pub trait WithDefaults: Sized {
+    /// Creates a new Sim at resting baseline conditions with the default
+    /// components of its `Organism`. Defaults attached with `set_default`
+    /// are registered as well, so they must not share ids with the
+    /// default components.
+    fn with_defaults() -> Self;
+}
@@ -0,0 +1,67 @@
+use mortalsim::WithDefaults;
+use mortalsim_core::event::{CoreBodyTemp, Event};
+use mortalsim_core::sim::Sim;
+use mortalsim_core::substance::Substance;
+use mortalsim_core::units::base::Temperature;
+use mortalsim_core::SimTimeSpan;
+use mortalsim_human::HumanSim;
+
+#[test]
+fn save_and_load_mid_run() {
+    let path = std::env::temp_dir().join(format!("mortalsim_save_load_{}.json", std::process::id()));
+
+    // Run partway, with a fever starting before and peaking after the save
+    let mut sim = HumanSim::with_defaults();
+    for _ in 0..10 {
+        sim.advance_by(SimTimeSpan::from_s(1.0));
+    }
+    sim.schedule_event(SimTimeSpan::from_s(2.0), Box::new(CoreBodyTemp(Temperature::from_celsius(38.0))));
+    sim.schedule_event(SimTimeSpan::from_s(8.0), Box::new(CoreBodyTemp(Temperature::from_celsius(39.0))));
+    sim.advance_by_stepping(SimTimeSpan::from_s(5.0), &mut |_, _| {});
+    sim.save_to_file(&path).unwrap();
+
+    let mut loaded = HumanSim::with_defaults();
+    loaded.load_from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.time(), sim.time());
+    assert_eq!(loaded.state().snapshot(), sim.state().snapshot());
+
+    // Both continue identically
+    for _ in 0..20 {
+        sim.advance_by(SimTimeSpan::from_s(1.0));
+        loaded.advance_by(SimTimeSpan::from_s(1.0));
+        assert_eq!(loaded.state().snapshot(), sim.state().snapshot(), "at {:?}", sim.time());
+        assert_eq!(loaded.total_substance(&Substance::O2), sim.total_substance(&Substance::O2));
+    }
+}
+
+#[test]
+fn load_requires_same_construction() {
+    let path = std::env::temp_dir().join(format!("mortalsim_load_mismatch_{}.json", std::process::id()));
+
+    let mut sim = HumanSim::with_defaults();
+    sim.advance_by(SimTimeSpan::from_s(1.0));
+    sim.save_to_file(&path).unwrap();
+
+    let mut loaded = HumanSim::new();
+    let result = loaded.load_from_file(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert!(result.is_err());
+}
+
+#[test]
+fn save_rejects_unpersistable_inputs() {
+    #[derive(Debug)]
+    struct Unregistered;
+    impl Event for Unregistered {}
+
+    let path = std::env::temp_dir().join(format!("mortalsim_save_unpersistable_{}.json", std::process::id()));
+
+    let mut sim = HumanSim::with_defaults();
+    sim.schedule_event(SimTimeSpan::from_s(1.0), Box::new(Unregistered));
+    sim.advance_by(SimTimeSpan::from_s(2.0));
+
+    let result = sim.save_to_file(&path);
+    assert!(result.unwrap_err().to_string().contains("Unregistered"));
+    assert!(!path.exists());
+}
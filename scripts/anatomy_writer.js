@@ -13,6 +13,37 @@ fs.readdirSync(configPath)
         writeAnatomyFile(dir, config);
     });
 
+function writeHierarchy(config) {
+    let parents = config.parents || {};
+    let children = {};
+    config.regions.filter(r => parents[r]).forEach(r => {
+        children[parents[r]] = (children[parents[r]] || []).concat([r]);
+    });
+
+    let parentArms = config.regions
+        .filter(r => parents[r])
+        .map(r => `            Self::${r} => Some(Self::${parents[r]}),\n`)
+        .join('');
+    let childArms = config.regions
+        .filter(r => children[r])
+        .map(r => `            Self::${r} => vec![${children[r].map(c => 'Self::' + c).join(', ')}],\n`)
+        .join('');
+
+    return ` {
+    fn parent(&self) -> Option<Self> {
+        match self {
+${parentArms}            _ => None,
+        }
+    }
+
+    fn children(&self) -> Vec<Self> {
+        match self {
+${childArms}            _ => Vec::new(),
+        }
+    }
+}`;
+}
+
 function writeAnatomyFile(namespace, config) {
     let namespaceCapitalized = namespace.charAt(0).toUpperCase() + namespace.slice(1);
 
@@ -31,6 +62,6 @@ pub enum ${namespaceCapitalized}AnatomicalRegion {
     ${config.regions.join(',\n    ')}
 }
 
-impl AnatomicalRegion for ${namespaceCapitalized}AnatomicalRegion {}
+impl AnatomicalRegion for ${namespaceCapitalized}AnatomicalRegion${config.parents ? writeHierarchy(config) : ' {}'}
 `)
 }
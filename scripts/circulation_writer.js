@@ -27,6 +27,7 @@ function writeCircFile(namespace, config) {
         let artery = {
             id: entry.id,
             regions: entry.regions,
+            volume: entry.volume,
             upstream: upstream ? [upstream.id] : [],
             downstream: [...(entry.links || []).map(e => e.id), ...(entry.bridges || []).map(e => e)]
         }
@@ -47,6 +48,7 @@ function writeCircFile(namespace, config) {
         let vein = {
             id: entry.id,
             regions: entry.regions,
+            volume: entry.volume,
             downstream: downstream ? [downstream.id] : [],
             upstream: [...(entry.links || []).map(e => e.id), ...(bridges[entry.id] || [])]
         }
@@ -61,6 +63,7 @@ function writeCircFile(namespace, config) {
     config.venous.forEach(e => processVein(e, null, 1));
 
     let allVessels = [...Object.values(arteries), ...Object.values(veins)];
+    let volumeVessels = allVessels.filter(v => v.volume !== undefined);
 
     fs.writeFileSync(path.join(configPath, '..', config.path), `
 /*
@@ -70,7 +73,8 @@ function writeCircFile(namespace, config) {
 use std::collections::HashSet;
 use std::sync::OnceLock;
 use ${namespace == 'test' ? 'crate' : 'mortalsim_core'}::sim::layer::circulation::{BloodVesselType, BloodVessel, VesselIter};
-use ${namespace == 'test' ? 'crate' : 'mortalsim_core'}::sim::layer::AnatomicalRegionIter;
+use ${namespace == 'test' ? 'crate' : 'mortalsim_core'}::sim::layer::AnatomicalRegionIter;${volumeVessels.length ? `
+use ${namespace == 'test' ? 'crate' : 'mortalsim_core'}::units::geometry::Volume;` : ''}
 use super::${namespaceCapitalized}AnatomicalRegion;
 
 #[derive(Debug, Display, Hash, Clone, Copy, PartialEq, Eq, EnumString, IntoStaticStr)]
@@ -192,7 +196,14 @@ impl BloodVessel for ${vesselEnum} {
             }).iter())`
             )}
         }
-    }
+    }${volumeVessels.length ? `
+    fn volume(&self) -> Option<Volume<f64>> {
+        match self {
+            ${volumeVessels.map(v => `${vesselEnum}::${v.id} => Some(Volume::from_mL(${Number.isInteger(v.volume) ? v.volume.toFixed(1) : v.volume})),`)
+                .join('\n            ')}${volumeVessels.length < allVessels.length ? `
+            _ => None,` : ''}
+        }
+    }` : ''}
 }
 `)
 }
@@ -110,7 +110,7 @@ ${layerList.map(l =>
 `    ${l}::{${l.cap()}Component, ${l.cap()}Initializer, ${l.cap()}Connector}`)
 .join(',\n')},
 };
-use super::SimComponent;
+use super::{Phase, SimComponent};
 
 pub trait ComponentWrapper<O: Organism>: SimComponent<O> + ${layerList.map(l => `${l.cap()}Component<O>`).join(' + ')} {
 ${layerList.map(layer => `
@@ -140,6 +140,12 @@ impl<O: Organism> SimComponent<O> for Box<dyn ComponentWrapper<O>> {
     fn run(&mut self) {
         self.as_mut().run()     
     }
+    fn reset(&mut self) {
+        self.as_mut().reset()
+    }
+    fn phase(&self) -> Phase {
+        self.as_ref().phase()
+    }
 }
 ${layerList.map(layer => `
 impl<O: Organism> ${layer.cap()}Component<O> for Box<dyn ComponentWrapper<O>> {
@@ -166,6 +172,12 @@ impl<O: Organism, T: Send + ${layersToBounds(items)}> SimComponent<O> for ${wrap
     fn run(&mut self) {
         self.0.run();
     }
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+    fn phase(&self) -> Phase {
+        self.0.phase()
+    }
 }
 ${layerImpl(wrapperName, items, layerList.filter(l => !items.includes(l)))}
 impl<O: Organism, T: Send + ${layersToBounds(items)}> ComponentWrapper<O> for ${wrapperName}<O,T> {
@@ -209,6 +221,7 @@ impl<O: Organism> ComponentRegistry<O> {
 
     pub(crate) fn remove_component(&mut self, component_id: &str) -> anyhow::Result<Box<dyn ComponentWrapper<O>>> {
         if let Some(index) = self.components.iter().position(|x| x.id() == component_id) {
+            self.id_set.remove(component_id);
             return Ok(self.components.remove(index))
         }
         Err(anyhow!("component not found"))
@@ -223,6 +236,10 @@ impl<O: Organism> ComponentRegistry<O> {
     pub(crate) fn all_components_mut(&mut self) -> impl Iterator<Item = &mut Box<dyn ComponentWrapper<O>>> {
         self.components.iter_mut()
     }
+
+    pub(crate) fn add_wrapped_component(&mut self, component: Box<dyn ComponentWrapper<O>>) {
+        self.components.push(component)
+    }
 ${layerCombos.map(items => `
     pub fn add_${items.join('_')}_component(&mut self, component: impl ${layersToBounds(items)} + 'static) {
         self.components.push(Box::new(${getWrapperName(items)}(component, PhantomData)))
@@ -39,6 +39,11 @@ ${Object.entries(substanceConfigs).map(([sid, cfg]) =>
 `    /// ${cfg.name} (${sid}${stringifyCharge(cfg.charge)})
     ${sid},
 `).join('')}
+    /// Inert labeled tracer, with the physical properties of L-Glucose.
+    /// Tracers with different labels are independent of one another.
+    /// Components must never consume or transform tracers, so they can be
+    /// used to follow transport through the organism.
+    Tracer(u8),
 }
 
 impl fmt::Display for Substance {
@@ -65,6 +70,7 @@ impl Substance {
 ${Object.entries(substanceConfigs).map(([sid, cfg]) =>
 `            Self::${sid} => "${cfg.name}",
 `).join('')}
+            Self::Tracer(_) => "Tracer",
         }
     }
 
@@ -74,6 +80,7 @@ ${Object.entries(substanceConfigs).map(([sid, cfg]) =>
 ${Object.entries(substanceConfigs).map(([sid, cfg]) =>
 `            Self::${sid} => ${cfg.charge},
 `).join('')}
+            Self::Tracer(_) => 0,
         }
     }
 
@@ -83,6 +90,7 @@ ${Object.entries(substanceConfigs).map(([sid, cfg]) =>
 ${Object.entries(substanceConfigs).map(([sid, cfg]) =>
 `            Self::${sid} => MolarMass::from_gpmol(${fmtValue(cfg.molar_mass)}),
 `).join('')}
+            Self::Tracer(_) => MolarMass::from_gpmol(180.156),
         }
     }
 
@@ -92,6 +100,7 @@ ${Object.entries(substanceConfigs).map(([sid, cfg]) =>
 ${Object.entries(substanceConfigs).map(([sid, cfg]) =>
 `            Self::${sid} => Density::from_gpcc(${fmtValue(cfg.density)}),
 `).join('')}
+            Self::Tracer(_) => Density::from_gpcc(1.54),
         }
     }
 
@@ -99,5 +108,10 @@ ${Object.entries(substanceConfigs).map(([sid, cfg]) =>
     pub fn molar_volume(&self) -> crate::units::chemical::MolarVolume<f64> {
         self.molar_mass() / self.density()
     }
+
+    /// Whether the substance is an inert \`Tracer\`
+    pub fn is_tracer(&self) -> bool {
+        matches!(self, Self::Tracer(_))
+    }
 }
 `);